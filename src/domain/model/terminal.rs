@@ -1,6 +1,15 @@
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use crate::domain::primitive::{NotificationEvent, TerminalId, TerminalStatus};
+use crate::domain::primitive::restart_policy::STABLE_AFTER;
+use crate::domain::primitive::{
+    ForegroundProcess, NotificationEvent, RestartPolicy, SpawnOptions, StatusBadge, StatusFilter, TerminalFilter,
+    TerminalId, TerminalStatus,
+};
+
+/// How long a running terminal has to go without output before it is shown
+/// as quiet.
+pub const QUIET_AFTER: Duration = Duration::from_secs(5 * 60);
 
 pub struct ManagedTerminal {
     id: TerminalId,
@@ -10,6 +19,21 @@ pub struct ManagedTerminal {
     last_notification: Option<NotificationEvent>,
     has_unread_notification: bool,
     memo: String,
+    badge: Option<StatusBadge>,
+    /// Sidebar group, `None` when ungrouped.
+    group: Option<String>,
+    /// Marked to receive synchronized input.
+    marked: bool,
+    /// How the process was spawned; a respawn starts it the same way.
+    spawn: SpawnOptions,
+    foreground: Option<ForegroundProcess>,
+    /// When the current process was started.
+    started_at: Instant,
+    last_output: Option<Instant>,
+    /// Automatic restarts in a row, for the restart backoff.
+    restarts: u32,
+    /// When the restart policy starts the exited process again.
+    restart_at: Option<Instant>,
 }
 
 impl ManagedTerminal {
@@ -22,6 +46,15 @@ impl ManagedTerminal {
             last_notification: None,
             has_unread_notification: false,
             memo: String::new(),
+            badge: None,
+            group: None,
+            marked: false,
+            spawn: SpawnOptions::default(),
+            foreground: None,
+            started_at: Instant::now(),
+            last_output: None,
+            restarts: 0,
+            restart_at: None,
         }
     }
 
@@ -81,12 +114,159 @@ impl ManagedTerminal {
     pub fn has_memo(&self) -> bool {
         !self.memo.is_empty()
     }
+
+    /// Status badge set through `set-status`, `None` when cleared.
+    pub fn badge(&self) -> Option<&StatusBadge> {
+        self.badge.as_ref()
+    }
+
+    pub fn set_badge(&mut self, badge: Option<StatusBadge>) {
+        self.badge = badge;
+    }
+
+    /// The group the terminal is listed under in the sidebar.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    pub fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+
+    /// Whether synchronized input goes to this terminal.
+    pub fn is_marked(&self) -> bool {
+        self.marked
+    }
+
+    pub fn set_marked(&mut self, marked: bool) {
+        self.marked = marked;
+    }
+
+    /// When the terminal last produced output, `None` if it never has.
+    pub fn last_output(&self) -> Option<Instant> {
+        self.last_output
+    }
+
+    pub fn record_output(&mut self, at: Instant) {
+        self.last_output = Some(at);
+    }
+
+    /// Time since the terminal last produced output, or since its process
+    /// was started if it has not yet.
+    pub fn quiet_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_output.unwrap_or(self.started_at))
+    }
+
+    /// Command the terminal was spawned with, `None` for an interactive shell.
+    pub fn command(&self) -> Option<&str> {
+        self.spawn.command.as_deref()
+    }
+
+    pub fn spawn_options(&self) -> &SpawnOptions {
+        &self.spawn
+    }
+
+    pub fn set_spawn_options(&mut self, spawn: SpawnOptions) {
+        self.spawn = spawn;
+    }
+
+    /// After the process exited, decide when the restart policy starts it
+    /// again. The delay grows with each restart in a row and starts over
+    /// once a process stayed up for `STABLE_AFTER`.
+    pub fn schedule_restart(&mut self, now: Instant) {
+        let TerminalStatus::Exited(code) = self.status else {
+            return;
+        };
+        if !self.spawn.restart.should_restart(code) {
+            return;
+        }
+        if now.saturating_duration_since(self.started_at) >= STABLE_AFTER {
+            self.restarts = 0;
+        }
+        self.restart_at = Some(now + RestartPolicy::backoff(self.restarts));
+    }
+
+    /// When the restart policy starts the process again, if it will.
+    pub fn restart_at(&self) -> Option<Instant> {
+        self.restart_at
+    }
+
+    /// A new process was started in this terminal. `automatic` restarts
+    /// count towards the backoff; a manual respawn resets it.
+    pub fn mark_respawned(&mut self, now: Instant, automatic: bool) {
+        self.status = TerminalStatus::Running;
+        self.foreground = None;
+        self.started_at = now;
+        self.last_output = None;
+        self.restart_at = None;
+        self.restarts = if automatic { self.restarts + 1 } else { 0 };
+    }
+
+    /// The program in the foreground, `None` if unknown.
+    pub fn foreground(&self) -> Option<&ForegroundProcess> {
+        self.foreground.as_ref()
+    }
+
+    /// Record the foreground program. A live terminal becomes `Idle` when
+    /// its interactive shell is back in the foreground, `Running` otherwise.
+    pub fn set_foreground(&mut self, foreground: Option<ForegroundProcess>) {
+        if self.status.is_running() {
+            let at_prompt = self.command().is_none()
+                && foreground.as_ref().is_some_and(|fg| fg.is_terminal_process);
+            self.status = if at_prompt { TerminalStatus::Idle } else { TerminalStatus::Running };
+        }
+        self.foreground = foreground;
+    }
+
+    /// What the terminal is doing, e.g. "vim running", "idle" or
+    /// "exited (1)", plus how long it has been quiet once that reaches
+    /// `QUIET_AFTER` or when it restarts.
+    pub fn activity_text(&self, now: Instant) -> String {
+        let text = match (&self.status, &self.foreground) {
+            (TerminalStatus::Running, Some(fg)) => format!("{} running", fg.name),
+            (status, _) => status.status_text(),
+        };
+        if let Some(at) = self.restart_at {
+            let secs = at.saturating_duration_since(now).as_millis().div_ceil(1000);
+            return format!("{} · restart in {}s", text, secs);
+        }
+        match self.quiet_minutes(now) {
+            Some(mins) => format!("{} · quiet {}m", text, mins),
+            None => text,
+        }
+    }
+
+    /// Whole minutes a live terminal has gone without output, once that
+    /// reaches `QUIET_AFTER`.
+    pub fn quiet_minutes(&self, now: Instant) -> Option<u64> {
+        let quiet = self.quiet_for(now);
+        (self.status.is_running() && quiet >= QUIET_AFTER).then_some(quiet.as_secs() / 60)
+    }
+
+    /// Whether the sidebar shows this terminal under `filter`. `cwd` is the
+    /// current directory as displayed, which may differ from `cwd()`.
+    pub fn matches_filter(&self, filter: &TerminalFilter, cwd: &str) -> bool {
+        let status_ok = match filter.status {
+            StatusFilter::All => true,
+            StatusFilter::Running => self.status.is_running(),
+            StatusFilter::Exited => !self.status.is_running(),
+            StatusFilter::Notified => self.has_unread_notification,
+        };
+        status_ok
+            && filter.matches_text(
+                [self.name.as_str(), cwd, self.memo.as_str()]
+                    .into_iter()
+                    .chain(self.badge.as_ref().map(|b| b.text.as_str()))
+                    .chain(self.group.as_deref())
+                    .chain(self.foreground.as_ref().map(|fg| fg.name.as_str())),
+            )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::primitive::NotificationEvent;
+    use crate::domain::primitive::{Color, NotificationEvent};
 
     fn make_terminal() -> ManagedTerminal {
         ManagedTerminal::new(
@@ -263,4 +443,220 @@ mod tests {
         assert!(!terminal.has_memo());
         assert_eq!(terminal.memo(), "");
     }
+
+    // =========================================================================
+    // Tests: badge
+    // =========================================================================
+
+    #[test]
+    fn set_badge_sets_and_clears_the_badge() {
+        let mut terminal = make_terminal();
+        assert!(terminal.badge().is_none());
+
+        let badge = StatusBadge { text: "thinking".to_string(), icon: None, color: Color::Indexed(3) };
+        terminal.set_badge(Some(badge.clone()));
+        assert_eq!(terminal.badge(), Some(&badge));
+
+        terminal.set_badge(None);
+        assert!(terminal.badge().is_none());
+    }
+
+    // =========================================================================
+    // Tests: output time
+    // =========================================================================
+
+    #[test]
+    fn new_terminal_has_no_output_time() {
+        assert!(make_terminal().last_output().is_none());
+    }
+
+    #[test]
+    fn record_output_keeps_latest_time() {
+        let mut terminal = make_terminal();
+        let first = Instant::now();
+        let later = first + std::time::Duration::from_millis(5);
+        terminal.record_output(first);
+        terminal.record_output(later);
+        assert_eq!(terminal.last_output(), Some(later));
+    }
+
+    #[test]
+    fn quiet_for_counts_from_last_output_or_creation() {
+        let mut terminal = make_terminal();
+        let now = Instant::now() + Duration::from_secs(90);
+        assert!(terminal.quiet_for(now) >= Duration::from_secs(90));
+
+        terminal.record_output(now - Duration::from_secs(10));
+        assert_eq!(terminal.quiet_for(now), Duration::from_secs(10));
+    }
+
+    // =========================================================================
+    // Tests: foreground process and activity
+    // =========================================================================
+
+    fn process(name: &str, is_terminal_process: bool) -> ForegroundProcess {
+        ForegroundProcess {
+            pid: 42,
+            name: name.to_string(),
+            cmdline: name.to_string(),
+            is_terminal_process,
+        }
+    }
+
+    #[test]
+    fn shell_in_foreground_makes_terminal_idle() {
+        let mut terminal = make_terminal();
+        terminal.set_foreground(Some(process("zsh", true)));
+        assert_eq!(terminal.status(), &TerminalStatus::Idle);
+
+        terminal.set_foreground(Some(process("vim", false)));
+        assert_eq!(terminal.status(), &TerminalStatus::Running);
+        assert_eq!(terminal.foreground().unwrap().name, "vim");
+
+        // Unknown foreground: assume it is busy
+        terminal.set_foreground(None);
+        assert_eq!(terminal.status(), &TerminalStatus::Running);
+    }
+
+    #[test]
+    fn command_terminal_is_never_idle() {
+        let mut terminal = make_terminal();
+        terminal.set_spawn_options(SpawnOptions {
+            command: Some("cargo watch".to_string()),
+            ..Default::default()
+        });
+        terminal.set_foreground(Some(process("cargo", true)));
+        assert_eq!(terminal.status(), &TerminalStatus::Running);
+    }
+
+    #[test]
+    fn set_foreground_keeps_exit_status() {
+        let mut terminal = make_terminal();
+        terminal.mark_exited(1);
+        terminal.set_foreground(Some(process("zsh", true)));
+        assert_eq!(terminal.status(), &TerminalStatus::Exited(1));
+    }
+
+    #[test]
+    fn activity_text_names_program_and_quiet_time() {
+        let mut terminal = make_terminal();
+        let now = Instant::now();
+        terminal.record_output(now);
+        assert_eq!(terminal.activity_text(now), "running");
+
+        terminal.set_foreground(Some(process("claude", false)));
+        assert_eq!(terminal.activity_text(now), "claude running");
+
+        terminal.set_foreground(Some(process("zsh", true)));
+        assert_eq!(terminal.activity_text(now), "idle");
+        assert_eq!(terminal.activity_text(now + QUIET_AFTER + Duration::from_secs(70)), "idle · quiet 6m");
+
+        terminal.mark_exited(0);
+        assert_eq!(terminal.activity_text(now + QUIET_AFTER), "exited (0)");
+    }
+
+    // =========================================================================
+    // Tests: restart policy
+    // =========================================================================
+
+    fn terminal_with_policy(restart: RestartPolicy) -> ManagedTerminal {
+        let mut terminal = make_terminal();
+        terminal.set_spawn_options(SpawnOptions { restart, ..Default::default() });
+        terminal
+    }
+
+    #[test]
+    fn schedule_restart_follows_the_policy() {
+        let now = Instant::now();
+        let mut terminal = terminal_with_policy(RestartPolicy::OnFailure);
+        terminal.mark_exited(0);
+        terminal.schedule_restart(now);
+        assert!(terminal.restart_at().is_none());
+
+        terminal.mark_exited(2);
+        terminal.schedule_restart(now);
+        assert_eq!(terminal.restart_at(), Some(now + Duration::from_secs(1)));
+
+        let mut terminal = terminal_with_policy(RestartPolicy::Never);
+        terminal.mark_exited(2);
+        terminal.schedule_restart(now);
+        assert!(terminal.restart_at().is_none());
+    }
+
+    #[test]
+    fn schedule_restart_backs_off_until_the_process_stays_up() {
+        let mut terminal = terminal_with_policy(RestartPolicy::Always);
+        let mut now = Instant::now();
+        for expected in [1, 2, 4] {
+            terminal.mark_exited(1);
+            terminal.schedule_restart(now);
+            assert_eq!(terminal.restart_at(), Some(now + Duration::from_secs(expected)));
+            terminal.mark_respawned(now, true);
+            assert_eq!(terminal.status(), &TerminalStatus::Running);
+            assert!(terminal.restart_at().is_none());
+        }
+
+        // Ran long enough: back to the first delay
+        now += STABLE_AFTER;
+        terminal.mark_exited(1);
+        terminal.schedule_restart(now);
+        assert_eq!(terminal.restart_at(), Some(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn manual_respawn_resets_backoff() {
+        let mut terminal = terminal_with_policy(RestartPolicy::Always);
+        let now = Instant::now();
+        terminal.mark_respawned(now, true);
+        terminal.mark_respawned(now, false);
+        terminal.mark_exited(1);
+        terminal.schedule_restart(now);
+        assert_eq!(terminal.restart_at(), Some(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn activity_text_shows_pending_restart() {
+        let mut terminal = terminal_with_policy(RestartPolicy::OnFailure);
+        let now = Instant::now();
+        terminal.mark_exited(1);
+        terminal.schedule_restart(now);
+        assert_eq!(terminal.activity_text(now), "exited (1) · restart in 1s");
+    }
+
+    // =========================================================================
+    // Tests: sidebar filter
+    // =========================================================================
+
+    #[test]
+    fn matches_filter_by_status() {
+        let mut terminal = make_terminal();
+        let mut filter = TerminalFilter { status: StatusFilter::Running, text: String::new() };
+        assert!(terminal.matches_filter(&filter, "/tmp"));
+
+        filter.status = StatusFilter::Notified;
+        assert!(!terminal.matches_filter(&filter, "/tmp"));
+        terminal.set_notification(NotificationEvent::Bell);
+        assert!(terminal.matches_filter(&filter, "/tmp"));
+
+        filter.status = StatusFilter::Exited;
+        assert!(!terminal.matches_filter(&filter, "/tmp"));
+        terminal.mark_exited(0);
+        assert!(terminal.matches_filter(&filter, "/tmp"));
+    }
+
+    #[test]
+    fn matches_filter_by_text_in_any_field() {
+        let mut terminal = make_terminal();
+        let filter = |text: &str| TerminalFilter { status: StatusFilter::All, text: text.to_string() };
+        assert!(terminal.matches_filter(&filter("TEST"), "/tmp"));
+        assert!(terminal.matches_filter(&filter("src/api"), "/home/src/api"));
+        assert!(!terminal.matches_filter(&filter("deploy"), "/tmp"));
+
+        terminal.set_memo("deploy notes".to_string());
+        assert!(terminal.matches_filter(&filter("deploy"), "/tmp"));
+        terminal.set_badge(Some(StatusBadge { text: "failing".to_string(), icon: None, color: Color::Indexed(1) }));
+        assert!(terminal.matches_filter(&filter("fail"), "/tmp"));
+        terminal.set_group(Some("backend".to_string()));
+        assert!(terminal.matches_filter(&filter("backend"), "/tmp"));
+    }
 }
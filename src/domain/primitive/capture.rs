@@ -0,0 +1,66 @@
+/// How `capture-pane` renders the captured lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// Plain characters, one line per row.
+    #[default]
+    Text,
+    /// Characters with SGR escape sequences for colors and attributes
+    /// (tmux `capture-pane -e`).
+    Ansi,
+    /// The cells themselves, with their style.
+    Cells,
+}
+
+impl CaptureFormat {
+    /// Parse the wire/CLI name of a format.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(CaptureFormat::Text),
+            "ansi" => Some(CaptureFormat::Ansi),
+            "cells" => Some(CaptureFormat::Cells),
+            _ => None,
+        }
+    }
+}
+
+/// Which lines `capture-pane` returns and how.
+///
+/// Lines are numbered like tmux: 0 is the top line of the screen and
+/// negative numbers go back into the scrollback (-1 is the newest line
+/// that scrolled off). Out-of-range lines are clamped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureOptions {
+    /// First line. `None` is line 0, or the oldest scrollback line with
+    /// `include_scrollback`.
+    pub start: Option<i64>,
+    /// Last line, inclusive. `None` is the bottom line of the screen.
+    pub end: Option<i64>,
+    pub include_scrollback: bool,
+    pub format: CaptureFormat,
+    /// Return at most this many lines; the response says where the next
+    /// page starts.
+    pub limit: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_options_capture_the_screen_as_text() {
+        let opts = CaptureOptions::default();
+        assert_eq!(opts.start, None);
+        assert_eq!(opts.end, None);
+        assert!(!opts.include_scrollback);
+        assert_eq!(opts.format, CaptureFormat::Text);
+        assert_eq!(opts.limit, None);
+    }
+
+    #[test]
+    fn format_names_parse() {
+        assert_eq!(CaptureFormat::parse("text"), Some(CaptureFormat::Text));
+        assert_eq!(CaptureFormat::parse("ansi"), Some(CaptureFormat::Ansi));
+        assert_eq!(CaptureFormat::parse("cells"), Some(CaptureFormat::Cells));
+        assert_eq!(CaptureFormat::parse("html"), None);
+    }
+}
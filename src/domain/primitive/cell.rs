@@ -5,7 +5,7 @@ pub enum Color {
     Rgb(u8, u8, u8),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Cell {
     pub ch: char,
     pub fg: Color,
@@ -38,7 +38,7 @@ impl Default for Cell {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct CursorPos {
     pub row: u16,
     pub col: u16,
@@ -58,6 +58,41 @@ pub enum CursorStyle {
     SteadyBar,
 }
 
+/// Mouse reporting requested by the application running in a terminal
+/// (DECSET 9 / 1000 / 1002 / 1003).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseMode {
+    /// Mouse reporting is off; the host handles the mouse itself.
+    #[default]
+    Off,
+    /// Button presses only (X10).
+    Press,
+    /// Button presses and releases.
+    PressRelease,
+    /// Presses, releases and motion while a button is held.
+    ButtonMotion,
+    /// Presses, releases and all motion.
+    AnyMotion,
+}
+
+impl MouseMode {
+    pub fn is_enabled(self) -> bool {
+        self != MouseMode::Off
+    }
+
+    pub fn reports_release(self) -> bool {
+        matches!(self, MouseMode::PressRelease | MouseMode::ButtonMotion | MouseMode::AnyMotion)
+    }
+
+    pub fn reports_drag(self) -> bool {
+        matches!(self, MouseMode::ButtonMotion | MouseMode::AnyMotion)
+    }
+
+    pub fn reports_motion(self) -> bool {
+        self == MouseMode::AnyMotion
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,4 +162,21 @@ mod tests {
         assert_eq!(cell.ch, 'あ');
         assert_eq!(cell.width, 2);
     }
+
+    #[test]
+    fn mouse_mode_default_is_off() {
+        assert_eq!(MouseMode::default(), MouseMode::Off);
+        assert!(!MouseMode::Off.is_enabled());
+    }
+
+    #[test]
+    fn mouse_mode_reporting_levels() {
+        assert!(MouseMode::Press.is_enabled());
+        assert!(!MouseMode::Press.reports_release());
+        assert!(MouseMode::PressRelease.reports_release());
+        assert!(!MouseMode::PressRelease.reports_drag());
+        assert!(MouseMode::ButtonMotion.reports_drag());
+        assert!(!MouseMode::ButtonMotion.reports_motion());
+        assert!(MouseMode::AnyMotion.reports_motion());
+    }
 }
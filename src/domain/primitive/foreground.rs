@@ -0,0 +1,13 @@
+/// The program in the foreground of a terminal: the leader of its pty's
+/// foreground process group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForegroundProcess {
+    pub pid: u32,
+    /// Short program name, e.g. "vim".
+    pub name: String,
+    /// Full command line, arguments separated by spaces.
+    pub cmdline: String,
+    /// Whether it is the process the terminal was spawned with. For an
+    /// interactive shell this means the shell is waiting at its prompt.
+    pub is_terminal_process: bool,
+}
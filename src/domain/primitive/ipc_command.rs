@@ -1,3 +1,8 @@
+use super::{
+    CaptureOptions, Cell, ForegroundProcess, LogMode, LogStatus, NotificationEvent, RestartPolicy, ScreenFrame, Signal,
+    SortKey, StatusBadge, TargetSpec, TerminalFilter,
+};
+
 /// IPC command types for external control of CLI Manager.
 ///
 /// These commands represent the protocol for inter-process communication,
@@ -5,9 +10,11 @@
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IpcCommand {
     /// Send keystrokes to a specific terminal.
-    SendKeys { target: u32, keys: Vec<String> },
-    /// Capture the visible pane content of a terminal.
-    CapturePane { target: u32, include_scrollback: bool },
+    /// With `literal`, the keys are sent as text without key-name lookup.
+    SendKeys { target: u32, keys: Vec<String>, literal: bool },
+    /// Capture a terminal's content: the visible screen by default, or a
+    /// range of lines reaching into the scrollback.
+    CapturePane { target: u32, options: CaptureOptions },
     /// List all terminal windows.
     ListWindows,
     /// Paste the yank buffer content into a terminal.
@@ -17,18 +24,198 @@ pub enum IpcCommand {
     /// Show the current yank buffer content.
     ShowBuffer,
     /// Create a new terminal window.
+    ///
+    /// `command` runs via `$SHELL -c` instead of an interactive shell;
+    /// `cwd` and `env` override the working directory and environment.
     CreateWindow {
         name: Option<String>,
         command: Option<String>,
+        cwd: Option<String>,
+        env: Vec<(String, String)>,
+        restart: RestartPolicy,
+        /// Sidebar group to list the terminal under.
+        group: Option<String>,
     },
     /// Kill (close) a terminal window.
     KillWindow { target: u32 },
+    /// Start a terminal's process again in the same slot, keeping its id,
+    /// name, memo and scrollback. A live process is only replaced when
+    /// `kill` is set.
+    RespawnWindow { target: u32, kill: bool },
+    /// Send a signal to a terminal's foreground process group.
+    SendSignal { target: u32, signal: Signal },
     /// Select (activate) a terminal window.
     SelectWindow { target: u32 },
     /// Rename a terminal window.
     RenameWindow { target: u32, name: String },
-    /// Send a desktop notification via CLI Manager.
-    Notify { title: Option<String>, body: String },
+    /// Move a terminal window to another place in the sidebar.
+    MoveWindow { target: u32, to: WindowMove },
+    /// Sort the sidebar once by `key`.
+    SortWindows { key: SortKey },
+    /// Set the sidebar filter; the default filter shows every terminal.
+    FilterWindows { filter: TerminalFilter },
+    /// Put a terminal in a sidebar group, or take it out with `None`.
+    SetGroup { target: u32, group: Option<String> },
+    /// Collapse or expand a sidebar group; `None` toggles it.
+    ToggleGroup { group: String, collapsed: Option<bool> },
+    /// Mark a terminal for synchronized input, or unmark it; `None`
+    /// toggles the mark.
+    MarkWindow { target: u32, marked: Option<bool> },
+    /// Turn synchronize mode on or off; `None` toggles it.
+    SyncInput { enabled: Option<bool> },
+    /// Send a notification. With a target it is attributed to that
+    /// terminal like an OSC 9/777 notification (sidebar mark and a desktop
+    /// notification under its name); without one it is only shown on the
+    /// desktop.
+    Notify { target: Option<u32>, title: Option<String>, body: String },
+    /// Set the status badge shown for a terminal in the sidebar, or clear it
+    /// with `None`.
+    SetStatus { target: u32, status: Option<StatusBadge> },
+    /// Write raw input (already encoded, e.g. by a key handler) to a
+    /// terminal, like keystrokes typed into it: in synchronize mode it also
+    /// goes to the other synchronized terminals.
+    Input { target: u32, data: String },
+    /// Subscribe to screen updates (`cm attach`). Keeps the connection open
+    /// and streams `IpcEvent::Frame`s; sending it again updates the client's
+    /// pane size.
+    Attach { cols: u16, rows: u16 },
+    /// Stop `cm server`, closing every terminal.
+    KillServer,
+    /// Start logging a terminal's output to disk. `max_bytes` defaults to
+    /// `DEFAULT_LOG_MAX_BYTES`.
+    LogStart {
+        target: u32,
+        mode: LogMode,
+        max_bytes: Option<u64>,
+    },
+    /// Stop logging a terminal.
+    LogStop { target: u32 },
+    /// Query whether and where a terminal is being logged.
+    LogStatus { target: u32 },
+    /// Start recording a terminal as an asciicast file. `path` overrides the
+    /// default location.
+    RecordStart { target: u32, path: Option<String> },
+    /// Stop recording a terminal.
+    RecordStop { target: u32 },
+    /// Subscribe to session events (`cm ctl watch`). Keeps the connection
+    /// open and streams lifecycle, notification, cwd and focus events, plus
+    /// the output of the terminals listed in `output`.
+    Subscribe { output: Vec<u32> },
+    /// Hold the reply until `condition` holds for a terminal, or fail once
+    /// `timeout_ms` has passed (`None` waits indefinitely).
+    WaitFor {
+        target: u32,
+        condition: WaitCondition,
+        timeout_ms: Option<u64>,
+    },
+    /// `command` aimed at terminals given by name, glob or specifier rather
+    /// than by id. The runner resolves `target` and runs `command` with each
+    /// resulting id put in its `target`. `caller` is the id of the terminal
+    /// the client runs in, for `@self`.
+    Resolve {
+        target: TargetSpec,
+        caller: Option<u32>,
+        command: Box<IpcCommand>,
+    },
+}
+
+impl IpcCommand {
+    /// Whether the command is aimed at a terminal.
+    pub fn has_target(&self) -> bool {
+        self.clone().target_mut().is_some()
+    }
+
+    /// The command aimed at terminal `id` instead. Commands without a
+    /// target are returned unchanged.
+    pub fn with_target(mut self, id: u32) -> Self {
+        if let Some(target) = self.target_mut() {
+            *target = id;
+        }
+        self
+    }
+
+    fn target_mut(&mut self) -> Option<&mut u32> {
+        match self {
+            IpcCommand::SendKeys { target, .. }
+            | IpcCommand::CapturePane { target, .. }
+            | IpcCommand::PasteBuffer { target }
+            | IpcCommand::KillWindow { target }
+            | IpcCommand::RespawnWindow { target, .. }
+            | IpcCommand::SendSignal { target, .. }
+            | IpcCommand::SelectWindow { target }
+            | IpcCommand::RenameWindow { target, .. }
+            | IpcCommand::MoveWindow { target, .. }
+            | IpcCommand::SetGroup { target, .. }
+            | IpcCommand::MarkWindow { target, .. }
+            | IpcCommand::SetStatus { target, .. }
+            | IpcCommand::Input { target, .. }
+            | IpcCommand::LogStart { target, .. }
+            | IpcCommand::LogStop { target }
+            | IpcCommand::LogStatus { target }
+            | IpcCommand::RecordStart { target, .. }
+            | IpcCommand::RecordStop { target }
+            | IpcCommand::WaitFor { target, .. } => Some(target),
+            IpcCommand::Notify { target, .. } => target.as_mut(),
+            IpcCommand::ListWindows
+            | IpcCommand::SetBuffer { .. }
+            | IpcCommand::ShowBuffer
+            | IpcCommand::CreateWindow { .. }
+            | IpcCommand::SortWindows { .. }
+            | IpcCommand::FilterWindows { .. }
+            | IpcCommand::ToggleGroup { .. }
+            | IpcCommand::SyncInput { .. }
+            | IpcCommand::Attach { .. }
+            | IpcCommand::KillServer
+            | IpcCommand::Subscribe { .. }
+            | IpcCommand::Resolve { .. } => None,
+        }
+    }
+}
+
+/// What a `wait-for` request waits for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitCondition {
+    /// Output printed after the wait starts matching `pattern`, a regular
+    /// expression when `regex` is set and a plain substring otherwise.
+    Match { pattern: String, regex: bool },
+    /// No output from the terminal for `ms` milliseconds.
+    Idle { ms: u64 },
+    /// The terminal's process exits.
+    Exit,
+}
+
+/// Where `move-window` moves a terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowMove {
+    /// Places up (negative) or down among the terminals the sidebar shows.
+    By(isize),
+    /// Index in the full sidebar order, clamped to the last place.
+    To(usize),
+    /// Trade places with another terminal. `caller` is for `@self`.
+    Swap { with: TargetSpec, caller: Option<u32> },
+}
+
+/// Events pushed to streaming (attached or subscribed) connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcEvent {
+    /// The session's current screen.
+    Frame(ScreenFrame),
+    /// A terminal was created.
+    TerminalCreated { id: u32, name: String },
+    /// A terminal was closed and removed from the session.
+    TerminalClosed { id: u32 },
+    /// A terminal was renamed.
+    TerminalRenamed { id: u32, name: String },
+    /// A terminal's process exited.
+    TerminalExited { id: u32, exit_code: i32 },
+    /// A terminal raised a notification (BEL, OSC 9, OSC 777).
+    Notification { terminal: String, event: NotificationEvent },
+    /// A terminal reported a new working directory (OSC 7).
+    CwdChanged { id: u32, cwd: String },
+    /// The active terminal changed (`None` when no terminal is left).
+    FocusChanged { id: Option<u32> },
+    /// Output read from a terminal's pty, decoded as UTF-8.
+    Output { id: u32, data: String },
 }
 
 /// IPC response types returned to external clients.
@@ -46,8 +233,17 @@ pub enum IpcResponse {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IpcResponseData {
     /// Captured pane content with metadata.
+    ///
+    /// `text` holds the lines as text or ANSI; for `CaptureFormat::Cells`
+    /// it is empty and `cells` holds the rows instead. `start..=end` are
+    /// the lines returned (numbered like `CaptureOptions`), and
+    /// `next_start` is set when `limit` cut the capture short.
     CapturePane {
         text: String,
+        cells: Option<Vec<Vec<Cell>>>,
+        start: i64,
+        end: i64,
+        next_start: Option<i64>,
         cursor_row: usize,
         cursor_col: usize,
         size_rows: usize,
@@ -62,6 +258,22 @@ pub enum IpcResponseData {
     Buffer { text: Option<String> },
     /// Created terminal window ID.
     CreateWindow { id: u32 },
+    /// Log state of a terminal, `None` if it is not being logged.
+    Log { status: Option<LogStatus> },
+    /// Recording file that was started or finished.
+    Recording { path: String },
+    /// Text found by `wait-for`: the matched `text`, the whole `line` it is
+    /// on, and its position (`row` 0 = top of the scrollback, `col` in cells).
+    WaitMatch {
+        text: String,
+        line: String,
+        row: usize,
+        col: usize,
+    },
+    /// Exit code of the process `wait-for` waited on.
+    Exited { exit_code: i32 },
+    /// One response per terminal matched by a glob target, in sidebar order.
+    Targets { results: Vec<(u32, IpcResponse)> },
 }
 
 /// Information about a single terminal window.
@@ -71,7 +283,17 @@ pub struct WindowInfo {
     pub name: String,
     pub cwd: Option<String>,
     pub is_active: bool,
+    /// Whether the process is alive (running or idle).
     pub is_running: bool,
+    /// Whether the shell is waiting at its prompt.
+    pub is_idle: bool,
+    /// The program in the foreground, if known.
+    pub process: Option<ForegroundProcess>,
+    /// Seconds since the terminal last produced output.
+    pub quiet_secs: u64,
+    pub group: Option<String>,
+    /// Marked for synchronized input.
+    pub marked: bool,
 }
 
 #[cfg(test)]
@@ -87,8 +309,9 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: 1,
             keys: vec!["Enter".to_string(), "q".to_string()],
+            literal: false,
         };
-        if let IpcCommand::SendKeys { target, keys } = &cmd {
+        if let IpcCommand::SendKeys { target, keys, .. } = &cmd {
             assert_eq!(*target, 1);
             assert_eq!(keys.len(), 2);
             assert_eq!(keys[0], "Enter");
@@ -103,8 +326,9 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: 0,
             keys: vec![],
+            literal: false,
         };
-        if let IpcCommand::SendKeys { target, keys } = &cmd {
+        if let IpcCommand::SendKeys { target, keys, .. } = &cmd {
             assert_eq!(*target, 0);
             assert!(keys.is_empty());
         } else {
@@ -116,15 +340,15 @@ mod tests {
     fn capture_pane_with_scrollback() {
         let cmd = IpcCommand::CapturePane {
             target: 5,
-            include_scrollback: true,
+            options: CaptureOptions { include_scrollback: true, ..CaptureOptions::default() },
         };
         if let IpcCommand::CapturePane {
             target,
-            include_scrollback,
+            options,
         } = &cmd
         {
             assert_eq!(*target, 5);
-            assert!(*include_scrollback);
+            assert!(options.include_scrollback);
         } else {
             panic!("Expected CapturePane variant");
         }
@@ -134,15 +358,15 @@ mod tests {
     fn capture_pane_without_scrollback() {
         let cmd = IpcCommand::CapturePane {
             target: 2,
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         if let IpcCommand::CapturePane {
             target,
-            include_scrollback,
+            options,
         } = &cmd
         {
             assert_eq!(*target, 2);
-            assert!(!*include_scrollback);
+            assert!(!options.include_scrollback);
         } else {
             panic!("Expected CapturePane variant");
         }
@@ -239,6 +463,10 @@ mod tests {
     fn capture_pane_data_construction() {
         let data = IpcResponseData::CapturePane {
             text: "$ ls\nfoo bar\n".to_string(),
+            cells: None,
+            start: 0,
+            end: 23,
+            next_start: None,
             cursor_row: 2,
             cursor_col: 0,
             size_rows: 24,
@@ -256,6 +484,7 @@ mod tests {
             name,
             cwd,
             scrollback_total,
+            ..
         } = &data
         {
             assert_eq!(text, "$ ls\nfoo bar\n");
@@ -275,6 +504,10 @@ mod tests {
     fn capture_pane_data_without_cwd() {
         let data = IpcResponseData::CapturePane {
             text: String::new(),
+            cells: None,
+            start: 0,
+            end: 23,
+            next_start: None,
             cursor_row: 0,
             cursor_col: 0,
             size_rows: 24,
@@ -299,6 +532,11 @@ mod tests {
                 cwd: Some("/home".to_string()),
                 is_active: true,
                 is_running: true,
+                is_idle: false,
+                process: None,
+                quiet_secs: 0,
+                group: None,
+                marked: false,
             },
             WindowInfo {
                 id: 2,
@@ -306,6 +544,11 @@ mod tests {
                 cwd: None,
                 is_active: false,
                 is_running: false,
+                is_idle: false,
+                process: None,
+                quiet_secs: 0,
+                group: None,
+                marked: false,
             },
         ];
         let data = IpcResponseData::ListWindows {
@@ -364,6 +607,11 @@ mod tests {
             cwd: Some("/tmp".to_string()),
             is_active: false,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_eq!(info.id, 3);
         assert_eq!(info.name, "editor");
@@ -380,6 +628,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert!(info.is_active);
         assert!(info.is_running);
@@ -393,6 +646,11 @@ mod tests {
             cwd: None,
             is_active: false,
             is_running: false,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert!(!info.is_active);
         assert!(!info.is_running);
@@ -406,6 +664,11 @@ mod tests {
             cwd: Some("/home".to_string()),
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -413,6 +676,11 @@ mod tests {
             cwd: Some("/home".to_string()),
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_eq!(a, b);
     }
@@ -425,6 +693,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 2,
@@ -432,6 +705,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -444,6 +722,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -451,6 +734,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -463,6 +751,11 @@ mod tests {
             cwd: Some("/a".to_string()),
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -470,6 +763,11 @@ mod tests {
             cwd: Some("/b".to_string()),
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -482,6 +780,11 @@ mod tests {
             cwd: Some("/a".to_string()),
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -489,6 +792,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -501,6 +809,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -508,6 +821,11 @@ mod tests {
             cwd: None,
             is_active: false,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -520,6 +838,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let b = WindowInfo {
             id: 1,
@@ -527,6 +850,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: false,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         assert_ne!(a, b);
     }
@@ -540,6 +868,7 @@ mod tests {
         let original = IpcCommand::SendKeys {
             target: 1,
             keys: vec!["a".to_string(), "b".to_string()],
+            literal: false,
         };
         let cloned = original.clone();
         assert_eq!(original, cloned);
@@ -562,6 +891,11 @@ mod tests {
             cwd: Some("/usr".to_string()),
             is_active: false,
             is_running: true,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let cloned = original.clone();
         assert_eq!(original, cloned);
@@ -593,6 +927,11 @@ mod tests {
             cwd: None,
             is_active: true,
             is_running: false,
+            is_idle: false,
+            process: None,
+            quiet_secs: 0,
+            group: None,
+            marked: false,
         };
         let debug = format!("{:?}", info);
         assert!(debug.contains("WindowInfo"));
@@ -624,10 +963,12 @@ mod tests {
         let a = IpcCommand::SendKeys {
             target: 1,
             keys: vec![],
+            literal: false,
         };
         let b = IpcCommand::SendKeys {
             target: 2,
             keys: vec![],
+            literal: false,
         };
         assert_ne!(a, b);
     }
@@ -637,10 +978,12 @@ mod tests {
         let a = IpcCommand::SendKeys {
             target: 1,
             keys: vec!["a".to_string()],
+            literal: false,
         };
         let b = IpcCommand::SendKeys {
             target: 1,
             keys: vec!["b".to_string()],
+            literal: false,
         };
         assert_ne!(a, b);
     }
@@ -654,8 +997,12 @@ mod tests {
         let cmd = IpcCommand::CreateWindow {
             name: Some("my-term".to_string()),
             command: Some("/bin/bash".to_string()),
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
-        if let IpcCommand::CreateWindow { name, command } = &cmd {
+        if let IpcCommand::CreateWindow { name, command, .. } = &cmd {
             assert_eq!(name.as_deref(), Some("my-term"));
             assert_eq!(command.as_deref(), Some("/bin/bash"));
         } else {
@@ -668,8 +1015,12 @@ mod tests {
         let cmd = IpcCommand::CreateWindow {
             name: None,
             command: None,
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
-        if let IpcCommand::CreateWindow { name, command } = &cmd {
+        if let IpcCommand::CreateWindow { name, command, .. } = &cmd {
             assert!(name.is_none());
             assert!(command.is_none());
         } else {
@@ -682,8 +1033,12 @@ mod tests {
         let cmd = IpcCommand::CreateWindow {
             name: Some("editor".to_string()),
             command: None,
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
-        if let IpcCommand::CreateWindow { name, command } = &cmd {
+        if let IpcCommand::CreateWindow { name, command, .. } = &cmd {
             assert_eq!(name.as_deref(), Some("editor"));
             assert!(command.is_none());
         } else {
@@ -696,8 +1051,12 @@ mod tests {
         let cmd = IpcCommand::CreateWindow {
             name: None,
             command: Some("vim".to_string()),
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
-        if let IpcCommand::CreateWindow { name, command } = &cmd {
+        if let IpcCommand::CreateWindow { name, command, .. } = &cmd {
             assert!(name.is_none());
             assert_eq!(command.as_deref(), Some("vim"));
         } else {
@@ -818,6 +1177,10 @@ mod tests {
         let a = IpcCommand::CreateWindow {
             name: None,
             command: None,
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
         let b = IpcCommand::ListWindows;
         assert_ne!(a, b);
@@ -861,6 +1224,10 @@ mod tests {
         let original = IpcCommand::CreateWindow {
             name: Some("test".to_string()),
             command: Some("bash".to_string()),
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
         let cloned = original.clone();
         assert_eq!(original, cloned);
@@ -878,6 +1245,10 @@ mod tests {
         let cmd = IpcCommand::CreateWindow {
             name: Some("dbg-test".to_string()),
             command: None,
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: None,
         };
         let debug = format!("{:?}", cmd);
         assert!(debug.contains("CreateWindow"));
@@ -925,10 +1296,11 @@ mod tests {
     #[test]
     fn notify_with_title_and_body() {
         let cmd = IpcCommand::Notify {
+            target: None,
             title: Some("Claude Code".to_string()),
             body: "Response complete".to_string(),
         };
-        if let IpcCommand::Notify { title, body } = &cmd {
+        if let IpcCommand::Notify { title, body, .. } = &cmd {
             assert_eq!(title.as_deref(), Some("Claude Code"));
             assert_eq!(body, "Response complete");
         } else {
@@ -939,10 +1311,11 @@ mod tests {
     #[test]
     fn notify_without_title() {
         let cmd = IpcCommand::Notify {
+            target: None,
             title: None,
             body: "Task done".to_string(),
         };
-        if let IpcCommand::Notify { title, body } = &cmd {
+        if let IpcCommand::Notify { title, body, .. } = &cmd {
             assert!(title.is_none());
             assert_eq!(body, "Task done");
         } else {
@@ -953,6 +1326,7 @@ mod tests {
     #[test]
     fn notify_clone_equals_original() {
         let original = IpcCommand::Notify {
+            target: None,
             title: Some("Test".to_string()),
             body: "Hello".to_string(),
         };
@@ -963,6 +1337,7 @@ mod tests {
     #[test]
     fn notify_debug_includes_variant_name() {
         let cmd = IpcCommand::Notify {
+            target: None,
             title: Some("Debug".to_string()),
             body: "test".to_string(),
         };
@@ -975,6 +1350,7 @@ mod tests {
     #[test]
     fn notify_not_equal_to_other_variants() {
         let notify = IpcCommand::Notify {
+            target: None,
             title: None,
             body: "msg".to_string(),
         };
@@ -985,10 +1361,12 @@ mod tests {
     #[test]
     fn notify_different_bodies_not_equal() {
         let a = IpcCommand::Notify {
+            target: None,
             title: None,
             body: "a".to_string(),
         };
         let b = IpcCommand::Notify {
+            target: None,
             title: None,
             body: "b".to_string(),
         };
@@ -998,13 +1376,132 @@ mod tests {
     #[test]
     fn notify_different_titles_not_equal() {
         let a = IpcCommand::Notify {
+            target: None,
             title: Some("X".to_string()),
             body: "same".to_string(),
         };
         let b = IpcCommand::Notify {
+            target: None,
             title: Some("Y".to_string()),
             body: "same".to_string(),
         };
         assert_ne!(a, b);
     }
+
+    // =========================================================================
+    // Tests: attach / input / kill-server and IpcEvent
+    // =========================================================================
+
+    #[test]
+    fn attach_with_different_sizes_not_equal() {
+        let a = IpcCommand::Attach { cols: 80, rows: 24 };
+        let b = IpcCommand::Attach { cols: 120, rows: 40 };
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn input_keeps_raw_data() {
+        let cmd = IpcCommand::Input {
+            target: 2,
+            data: "\x1b[A".to_string(),
+        };
+        if let IpcCommand::Input { target, data } = &cmd {
+            assert_eq!(*target, 2);
+            assert_eq!(data.as_bytes(), b"\x1b[A");
+        } else {
+            panic!("Expected Input variant");
+        }
+    }
+
+    #[test]
+    fn kill_server_not_equal_to_other_variants() {
+        assert_ne!(IpcCommand::KillServer, IpcCommand::ListWindows);
+    }
+
+    #[test]
+    fn subscribe_with_different_outputs_not_equal() {
+        let a = IpcCommand::Subscribe { output: vec![] };
+        let b = IpcCommand::Subscribe { output: vec![1] };
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn lifecycle_events_are_distinct_per_terminal() {
+        assert_ne!(
+            IpcEvent::TerminalClosed { id: 1 },
+            IpcEvent::TerminalClosed { id: 2 }
+        );
+        assert_ne!(
+            IpcEvent::TerminalExited { id: 1, exit_code: 0 },
+            IpcEvent::TerminalExited { id: 1, exit_code: 1 }
+        );
+    }
+
+    #[test]
+    fn frame_event_clone_equals_original() {
+        let event = IpcEvent::Frame(ScreenFrame {
+            active: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(event.clone(), event);
+    }
+
+    // =========================================================================
+    // Tests: log commands
+    // =========================================================================
+
+    #[test]
+    fn log_commands_are_distinct_per_target() {
+        assert_ne!(
+            IpcCommand::LogStop { target: 1 },
+            IpcCommand::LogStatus { target: 1 }
+        );
+        assert_ne!(
+            IpcCommand::LogStop { target: 1 },
+            IpcCommand::LogStop { target: 2 }
+        );
+    }
+
+    #[test]
+    fn log_response_without_status() {
+        let data = IpcResponseData::Log { status: None };
+        assert_eq!(data.clone(), data);
+        assert_ne!(data, IpcResponseData::Buffer { text: None });
+    }
+
+    // =========================================================================
+    // Tests: wait-for
+    // =========================================================================
+
+    #[test]
+    fn wait_for_conditions_are_distinct() {
+        let wait = |condition| IpcCommand::WaitFor { target: 1, condition, timeout_ms: None };
+        assert_ne!(wait(WaitCondition::Exit), wait(WaitCondition::Idle { ms: 0 }));
+        assert_ne!(
+            wait(WaitCondition::Match { pattern: "ok".to_string(), regex: false }),
+            wait(WaitCondition::Match { pattern: "ok".to_string(), regex: true })
+        );
+    }
+
+    // =========================================================================
+    // Tests: targets
+    // =========================================================================
+
+    #[test]
+    fn with_target_replaces_the_target() {
+        let cmd = IpcCommand::RenameWindow { target: 0, name: "x".to_string() };
+        assert_eq!(
+            cmd.with_target(4),
+            IpcCommand::RenameWindow { target: 4, name: "x".to_string() }
+        );
+    }
+
+    #[test]
+    fn commands_without_target_are_left_alone() {
+        assert!(!IpcCommand::ListWindows.has_target());
+        assert_eq!(IpcCommand::ListWindows.with_target(4), IpcCommand::ListWindows);
+        assert!(IpcCommand::LogStop { target: 0 }.has_target());
+        assert!(IpcCommand::MarkWindow { target: 0, marked: None }.has_target());
+        assert!(!IpcCommand::SyncInput { enabled: None }.has_target());
+    }
 }
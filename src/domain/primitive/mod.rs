@@ -5,11 +5,39 @@ pub mod cell;
 pub mod notification;
 pub mod search_match;
 pub mod ipc_command;
+pub mod spawn_options;
+pub mod session_spec;
+pub mod screen_frame;
+pub mod terminal_log;
+pub mod pane;
+pub mod capture;
+pub mod target;
+pub mod status_badge;
+pub mod foreground;
+pub mod restart_policy;
+pub mod signal;
+pub mod sort_key;
+pub mod terminal_filter;
+pub mod terminal_group;
 
 pub use terminal_id::TerminalId;
 pub use terminal_status::TerminalStatus;
 pub use terminal_size::TerminalSize;
-pub use cell::{Cell, Color, CursorPos, CursorStyle};
+pub use cell::{Cell, Color, CursorPos, CursorStyle, MouseMode};
 pub use notification::NotificationEvent;
 pub use search_match::SearchMatch;
-pub use ipc_command::{IpcCommand, IpcResponse, IpcResponseData, WindowInfo};
+pub use ipc_command::{IpcCommand, IpcEvent, IpcResponse, IpcResponseData, WaitCondition, WindowInfo, WindowMove};
+pub use spawn_options::SpawnOptions;
+pub use session_spec::{SessionSpec, TerminalSpec};
+pub use screen_frame::{FrameWindow, ScreenFrame};
+pub use terminal_log::{LogMode, LogOptions, LogStatus, DEFAULT_LOG_MAX_BYTES};
+pub use pane::{PaneDirection, SplitDirection};
+pub use capture::{CaptureFormat, CaptureOptions};
+pub use target::TargetSpec;
+pub use status_badge::StatusBadge;
+pub use foreground::ForegroundProcess;
+pub use restart_policy::RestartPolicy;
+pub use signal::Signal;
+pub use sort_key::SortKey;
+pub use terminal_filter::{StatusFilter, TerminalFilter};
+pub use terminal_group::{group_name, GroupHeader};
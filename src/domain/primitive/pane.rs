@@ -0,0 +1,17 @@
+/// How a pane is divided when it is split (tmux naming).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// Side by side: left and right halves (`Ctrl+b %`).
+    Horizontal,
+    /// Stacked: top and bottom halves (`Ctrl+b "`).
+    Vertical,
+}
+
+/// Direction used to move focus to a neighbouring pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
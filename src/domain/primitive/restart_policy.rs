@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// Delay before the first automatic restart; doubled for each restart in a row.
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound of the restart delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A process that ran at least this long before exiting is considered to
+/// have started fine, so its next restart starts over at `FIRST_BACKOFF`.
+pub const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+/// Whether a terminal's process is started again when it exits, e.g. for
+/// dev servers and watchers that should stay up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    /// Restart only after a non-zero exit code.
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    /// Parse `never`, `on-failure` or `always`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "never" => Ok(Self::Never),
+            "on-failure" => Ok(Self::OnFailure),
+            "always" => Ok(Self::Always),
+            _ => Err(format!("invalid restart policy: {s} (expected never, on-failure or always)")),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Never => "never",
+            Self::OnFailure => "on-failure",
+            Self::Always => "always",
+        }
+    }
+
+    /// Whether a process that exited with `exit_code` should be restarted.
+    pub fn should_restart(&self, exit_code: i32) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnFailure => exit_code != 0,
+            Self::Always => true,
+        }
+    }
+
+    /// Delay before a restart when `attempt` restarts already happened in
+    /// a row: 1s, 2s, 4s, ... up to 60s.
+    pub fn backoff(attempt: u32) -> Duration {
+        FIRST_BACKOFF.saturating_mul(1 << attempt.min(16)).min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trips_every_policy() {
+        for policy in [RestartPolicy::Never, RestartPolicy::OnFailure, RestartPolicy::Always] {
+            assert_eq!(RestartPolicy::parse(policy.as_str()), Ok(policy));
+        }
+        let err = RestartPolicy::parse("sometimes").unwrap_err();
+        assert!(err.starts_with("invalid restart policy"), "got: {err}");
+    }
+
+    #[test]
+    fn should_restart_depends_on_exit_code() {
+        assert!(!RestartPolicy::Never.should_restart(1));
+        assert!(!RestartPolicy::OnFailure.should_restart(0));
+        assert!(RestartPolicy::OnFailure.should_restart(-1));
+        assert!(RestartPolicy::Always.should_restart(0));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(RestartPolicy::backoff(0), Duration::from_secs(1));
+        assert_eq!(RestartPolicy::backoff(3), Duration::from_secs(8));
+        assert_eq!(RestartPolicy::backoff(6), Duration::from_secs(60));
+        assert_eq!(RestartPolicy::backoff(u32::MAX), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Cell, CursorPos, GroupHeader, StatusBadge, TerminalFilter, TerminalStatus};
+
+/// Sidebar entry of a `ScreenFrame`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameWindow {
+    pub id: u32,
+    pub name: String,
+    /// OSC 7 cwd if reported, otherwise the cwd the terminal was spawned in.
+    pub cwd: String,
+    pub status: TerminalStatus,
+    pub memo: String,
+    pub badge: Option<StatusBadge>,
+    pub group: Option<String>,
+    /// Marked for synchronized input.
+    pub marked: bool,
+    /// Name of the program in the foreground, if known.
+    pub process: Option<String>,
+    /// Whole minutes without output once that reaches `QUIET_AFTER`,
+    /// otherwise 0.
+    pub quiet_mins: u64,
+    /// Summary of the unread notification, if any.
+    pub notification: Option<String>,
+}
+
+/// Everything an attached client needs to draw the session: the sidebar
+/// entries and the visible screen of the active terminal.
+///
+/// The server compares consecutive frames and only pushes a new one to a
+/// client when something changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScreenFrame {
+    /// The terminals the sidebar shows under `filter`.
+    pub windows: Vec<FrameWindow>,
+    /// Group headers drawn among `windows`.
+    pub groups: Vec<GroupHeader>,
+    pub filter: TerminalFilter,
+    /// Whether synchronize mode is on.
+    pub sync: bool,
+    /// ID of the active terminal. `None` when there are no terminals.
+    pub active: Option<u32>,
+    /// Visible cell grid of the active terminal.
+    pub cells: Vec<Vec<Cell>>,
+    pub cursor: CursorPos,
+    pub cursor_visible: bool,
+    /// DECCKM state of the active terminal, needed to encode arrow keys.
+    pub application_cursor_keys: bool,
+    /// Bracketed paste state of the active terminal, needed to wrap pastes.
+    pub bracketed_paste: bool,
+    /// Kitty keyboard protocol flags of the active terminal (0 = legacy).
+    pub keyboard_flags: u16,
+}
+
+impl ScreenFrame {
+    /// The active window entry, if any.
+    pub fn active_window(&self) -> Option<&FrameWindow> {
+        let id = self.active?;
+        self.windows.iter().find(|w| w.id == id)
+    }
+
+    /// Index of the active window in `windows`.
+    pub fn active_index(&self) -> Option<usize> {
+        let id = self.active?;
+        self.windows.iter().position(|w| w.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32) -> FrameWindow {
+        FrameWindow {
+            id,
+            name: format!("term-{id}"),
+            cwd: "/tmp".to_string(),
+            status: TerminalStatus::Running,
+            memo: String::new(),
+            badge: None,
+            process: None,
+            quiet_mins: 0,
+            notification: None,
+            group: None,
+            marked: false,
+        }
+    }
+
+    #[test]
+    fn default_frame_has_no_active_window() {
+        let frame = ScreenFrame::default();
+        assert!(frame.active_window().is_none());
+        assert!(frame.active_index().is_none());
+    }
+
+    #[test]
+    fn active_window_is_found_by_id() {
+        let frame = ScreenFrame {
+            windows: vec![window(3), window(7)],
+            active: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(frame.active_window().unwrap().name, "term-7");
+        assert_eq!(frame.active_index(), Some(1));
+    }
+
+    #[test]
+    fn active_id_without_matching_window() {
+        let frame = ScreenFrame {
+            windows: vec![window(1)],
+            active: Some(9),
+            ..Default::default()
+        };
+        assert!(frame.active_window().is_none());
+        assert!(frame.active_index().is_none());
+    }
+}
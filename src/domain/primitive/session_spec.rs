@@ -0,0 +1,28 @@
+use super::SpawnOptions;
+
+/// Declarative definition of a single terminal in a session file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalSpec {
+    /// Display name. `None` falls back to the generated `term-N` name.
+    pub name: Option<String>,
+    /// Command, cwd and env used to spawn the terminal's process.
+    pub spawn: SpawnOptions,
+    /// Initial memo text.
+    pub memo: Option<String>,
+    /// Sidebar group.
+    pub group: Option<String>,
+    /// Output from a previous run, shown dimmed above the new prompt.
+    pub scrollback: Vec<String>,
+}
+
+/// Declarative definition of a workspace: the terminals to create at startup,
+/// in sidebar order, and which of them starts active.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSpec {
+    pub terminals: Vec<TerminalSpec>,
+    /// Index into `terminals` of the terminal to activate. `None` keeps the
+    /// last created terminal active.
+    pub active: Option<usize>,
+    /// Whether the mini terminal is open.
+    pub mini_terminal_visible: bool,
+}
@@ -0,0 +1,59 @@
+/// A signal that can be sent to a terminal's foreground process group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Int,
+    Term,
+    Hup,
+    Kill,
+    Stop,
+    Cont,
+}
+
+impl Signal {
+    pub const ALL: [Signal; 6] = [Self::Int, Self::Term, Self::Hup, Self::Kill, Self::Stop, Self::Cont];
+
+    /// Parse a signal name such as `INT`, `sigterm` or `SIGKILL`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let upper = s.to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        Self::ALL
+            .into_iter()
+            .find(|signal| &signal.name()[3..] == name)
+            .ok_or_else(|| format!("invalid signal: {s} (expected INT, TERM, HUP, KILL, STOP or CONT)"))
+    }
+
+    /// Full name, e.g. "SIGINT".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Int => "SIGINT",
+            Self::Term => "SIGTERM",
+            Self::Hup => "SIGHUP",
+            Self::Kill => "SIGKILL",
+            Self::Stop => "SIGSTOP",
+            Self::Cont => "SIGCONT",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_short_and_full_names_in_any_case() {
+        assert_eq!(Signal::parse("INT"), Ok(Signal::Int));
+        assert_eq!(Signal::parse("sigterm"), Ok(Signal::Term));
+        assert_eq!(Signal::parse("SIGKILL"), Ok(Signal::Kill));
+        for signal in Signal::ALL {
+            assert_eq!(Signal::parse(signal.name()), Ok(signal));
+        }
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names() {
+        for s in ["USR1", "SIG", "9", ""] {
+            let err = Signal::parse(s).unwrap_err();
+            assert!(err.starts_with("invalid signal"), "got: {err}");
+        }
+    }
+}
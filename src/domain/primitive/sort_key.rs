@@ -0,0 +1,45 @@
+/// Order the sidebar can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Cwd,
+    /// Most recent output first; terminals without output last.
+    Activity,
+    /// Terminals with an unread notification first, otherwise unchanged.
+    Unread,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 4] = [Self::Name, Self::Cwd, Self::Activity, Self::Unread];
+
+    /// Parse `name`, `cwd`, `activity` or `unread`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.as_str() == s)
+            .ok_or_else(|| format!("invalid sort key: {s} (expected name, cwd, activity or unread)"))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Cwd => "cwd",
+            Self::Activity => "activity",
+            Self::Unread => "unread",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trips_every_key() {
+        for key in SortKey::ALL {
+            assert_eq!(SortKey::parse(key.as_str()), Ok(key));
+        }
+        let err = SortKey::parse("size").unwrap_err();
+        assert!(err.starts_with("invalid sort key"), "got: {err}");
+    }
+}
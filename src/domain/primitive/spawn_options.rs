@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use super::RestartPolicy;
+
+/// Overrides applied when spawning the process of a new terminal.
+///
+/// The default value starts an interactive `$SHELL` in the usecase's cwd,
+/// which matches the behaviour of creating a terminal from the TUI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpawnOptions {
+    /// Command line to run instead of an interactive shell.
+    /// Executed via `$SHELL -c` so pipes, quoting and `&&` work as typed.
+    pub command: Option<String>,
+    /// Working directory for the process. `None` uses the default cwd.
+    pub cwd: Option<PathBuf>,
+    /// Extra environment variables, applied after the built-in ones.
+    pub env: Vec<(String, String)>,
+    /// Whether the process is started again when it exits.
+    pub restart: RestartPolicy,
+}
+
+impl SpawnOptions {
+    /// Build the argv to execute with the given shell.
+    ///
+    /// - No command: `[shell]` (interactive shell)
+    /// - With command: `[shell, "-c", command]`
+    pub fn argv(&self, shell: &str) -> Vec<String> {
+        match &self.command {
+            Some(command) => vec![shell.to_string(), "-c".to_string(), command.clone()],
+            None => vec![shell.to_string()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_has_no_overrides() {
+        let opts = SpawnOptions::default();
+        assert!(opts.command.is_none());
+        assert!(opts.cwd.is_none());
+        assert!(opts.env.is_empty());
+        assert_eq!(opts.restart, RestartPolicy::Never);
+    }
+
+    #[test]
+    fn argv_without_command_is_interactive_shell() {
+        let opts = SpawnOptions::default();
+        assert_eq!(opts.argv("/bin/zsh"), vec!["/bin/zsh".to_string()]);
+    }
+
+    #[test]
+    fn argv_with_command_runs_through_shell() {
+        let opts = SpawnOptions {
+            command: Some("cargo watch -x test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.argv("/bin/bash"),
+            vec![
+                "/bin/bash".to_string(),
+                "-c".to_string(),
+                "cargo watch -x test".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn argv_keeps_command_as_single_argument() {
+        let opts = SpawnOptions {
+            command: Some("echo 'a b' && ls | wc -l".to_string()),
+            ..Default::default()
+        };
+        let argv = opts.argv("/bin/sh");
+        assert_eq!(argv.len(), 3);
+        assert_eq!(argv[2], "echo 'a b' && ls | wc -l");
+    }
+}
@@ -0,0 +1,71 @@
+use super::Color;
+
+/// State an agent or hook publishes for its terminal (`set-status`), such
+/// as "thinking", "waiting for approval" or "tests failing". Shown in the
+/// sidebar next to the terminal and searchable in the quick switcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusBadge {
+    pub text: String,
+    /// Short symbol shown after the terminal name, e.g. "⏳".
+    pub icon: Option<String>,
+    pub color: Color,
+}
+
+impl StatusBadge {
+    /// Icon and text as one line, e.g. "⏳ thinking".
+    pub fn label(&self) -> String {
+        match &self.icon {
+            Some(icon) => format!("{} {}", icon, self.text),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Parse a badge color: one of the eight ANSI color names (plus
+    /// `gray`), a palette index or `#rrggbb`.
+    pub fn parse_color(s: &str) -> Result<Color, String> {
+        let names = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+        if let Some(i) = names.iter().position(|n| s.eq_ignore_ascii_case(n)) {
+            return Ok(Color::Indexed(i as u8));
+        }
+        if s.eq_ignore_ascii_case("gray") || s.eq_ignore_ascii_case("grey") {
+            return Ok(Color::Indexed(8));
+        }
+        if let Ok(i) = s.parse::<u8>() {
+            return Ok(Color::Indexed(i));
+        }
+        let invalid = || format!("invalid color: {s} (expected a color name, 0-255 or #rrggbb)");
+        let digits = s.strip_prefix('#').filter(|d| d.len() == 6).ok_or_else(invalid)?;
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+        Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_puts_icon_before_text() {
+        let mut badge = StatusBadge { text: "thinking".to_string(), icon: None, color: Color::Default };
+        assert_eq!(badge.label(), "thinking");
+        badge.icon = Some("⏳".to_string());
+        assert_eq!(badge.label(), "⏳ thinking");
+    }
+
+    #[test]
+    fn parse_color_names_indexes_and_hex() {
+        assert_eq!(StatusBadge::parse_color("red"), Ok(Color::Indexed(1)));
+        assert_eq!(StatusBadge::parse_color("Green"), Ok(Color::Indexed(2)));
+        assert_eq!(StatusBadge::parse_color("gray"), Ok(Color::Indexed(8)));
+        assert_eq!(StatusBadge::parse_color("208"), Ok(Color::Indexed(208)));
+        assert_eq!(StatusBadge::parse_color("#ff8000"), Ok(Color::Rgb(255, 128, 0)));
+    }
+
+    #[test]
+    fn parse_color_rejects_unknown() {
+        for s in ["purple", "256", "#fff", "#gg0000", ""] {
+            let err = StatusBadge::parse_color(s).unwrap_err();
+            assert!(err.starts_with("invalid color"), "got: {err}");
+        }
+    }
+}
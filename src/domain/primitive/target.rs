@@ -0,0 +1,180 @@
+use std::fmt;
+
+/// Which terminal(s) an IPC command is aimed at.
+///
+/// Parsed from the `-t` argument: a numeric id, a terminal name, a glob
+/// over names (`agent-*`, fans out to every match), a group (`#api`, fans
+/// out to its terminals) or one of the relative specifiers `@active`,
+/// `@self` and `@last`. Repeating `-t` gives a list, which selects every
+/// terminal any of its items selects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetSpec {
+    Id(u32),
+    Name(String),
+    Pattern(String),
+    /// Every terminal in a group (`#name`).
+    Group(String),
+    /// The active terminal (`@active`).
+    Active,
+    /// The terminal the caller runs in (`@self`).
+    Caller,
+    /// The terminal that was active before the current one (`@last`).
+    Last,
+    /// Several targets at once (repeated `-t`). Never nested.
+    List(Vec<TargetSpec>),
+}
+
+impl TargetSpec {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Ok(id) = s.parse::<u32>() {
+            return Ok(TargetSpec::Id(id));
+        }
+        match s {
+            "@active" => Ok(TargetSpec::Active),
+            "@self" => Ok(TargetSpec::Caller),
+            "@last" => Ok(TargetSpec::Last),
+            // Reject what looks like a mistyped specifier or a stray flag
+            _ if s.is_empty() || s == "#" || s.starts_with('@') || s.starts_with('-') => {
+                Err(format!("invalid target: {s} (expected an id, a name, a glob, #group, @active, @self or @last)"))
+            }
+            _ if s.starts_with('#') => Ok(TargetSpec::Group(s[1..].to_string())),
+            _ if s.contains(['*', '?']) => Ok(TargetSpec::Pattern(s.to_string())),
+            _ => Ok(TargetSpec::Name(s.to_string())),
+        }
+    }
+
+    /// Whether a terminal named `name` is selected by this spec. Only
+    /// meaningful for `Name` and `Pattern`.
+    pub fn matches_name(&self, name: &str) -> bool {
+        match self {
+            TargetSpec::Name(n) => n == name,
+            TargetSpec::Pattern(p) => glob_match(p, name),
+            _ => false,
+        }
+    }
+
+    /// Whether the spec selects a set of terminals (a glob, a group or a
+    /// list), which is answered per terminal even when it matches just one.
+    pub fn fans_out(&self) -> bool {
+        matches!(self, TargetSpec::Pattern(_) | TargetSpec::Group(_) | TargetSpec::List(_))
+    }
+}
+
+impl fmt::Display for TargetSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetSpec::Id(id) => write!(f, "{id}"),
+            TargetSpec::Name(s) | TargetSpec::Pattern(s) => f.write_str(s),
+            TargetSpec::Group(g) => write!(f, "#{g}"),
+            TargetSpec::Active => f.write_str("@active"),
+            TargetSpec::Caller => f.write_str("@self"),
+            TargetSpec::Last => f.write_str("@last"),
+            TargetSpec::List(specs) => {
+                for (i, spec) in specs.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{spec}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Shell-style glob: `*` matches any run of characters, `?` exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the `*` swallow one more character
+                Some((after_star, tried)) => {
+                    p = after_star;
+                    t = tried + 1;
+                    star = Some((after_star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ids_names_patterns_and_specifiers() {
+        assert_eq!(TargetSpec::parse("3"), Ok(TargetSpec::Id(3)));
+        assert_eq!(TargetSpec::parse("api-server"), Ok(TargetSpec::Name("api-server".to_string())));
+        assert_eq!(TargetSpec::parse("agent-*"), Ok(TargetSpec::Pattern("agent-*".to_string())));
+        assert_eq!(TargetSpec::parse("#api"), Ok(TargetSpec::Group("api".to_string())));
+        assert_eq!(TargetSpec::parse("@active"), Ok(TargetSpec::Active));
+        assert_eq!(TargetSpec::parse("@self"), Ok(TargetSpec::Caller));
+        assert_eq!(TargetSpec::parse("@last"), Ok(TargetSpec::Last));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_specifier_and_flags() {
+        for s in ["@nope", "-1", "", "#"] {
+            let err = TargetSpec::parse(s).unwrap_err();
+            assert!(err.starts_with("invalid target"), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in ["7", "build", "agent-?", "#api", "@active", "@self", "@last"] {
+            assert_eq!(TargetSpec::parse(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn glob_match_star_and_question_mark() {
+        assert!(glob_match("agent-*", "agent-1"));
+        assert!(glob_match("agent-*", "agent-"));
+        assert!(glob_match("*-server", "api-server"));
+        assert!(glob_match("a*b*c", "axxbyybc"));
+        assert!(glob_match("term-?", "term-3"));
+        assert!(!glob_match("term-?", "term-12"));
+        assert!(!glob_match("agent-*", "my-agent-1"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn matches_name_is_exact_for_names() {
+        let spec = TargetSpec::Name("build".to_string());
+        assert!(spec.matches_name("build"));
+        assert!(!spec.matches_name("build-2"));
+        assert!(!TargetSpec::Active.matches_name("build"));
+    }
+
+    #[test]
+    fn globs_and_groups_fan_out() {
+        assert!(TargetSpec::Pattern("agent-*".to_string()).fans_out());
+        assert!(TargetSpec::Group("api".to_string()).fans_out());
+        assert!(!TargetSpec::Name("api".to_string()).fans_out());
+        assert!(!TargetSpec::Id(1).fans_out());
+    }
+
+    #[test]
+    fn lists_fan_out_and_display_comma_separated() {
+        let spec = TargetSpec::List(vec![TargetSpec::Id(1), TargetSpec::Name("build".to_string())]);
+        assert!(spec.fans_out());
+        assert_eq!(spec.to_string(), "1,build");
+    }
+}
@@ -0,0 +1,120 @@
+/// Which terminals the sidebar shows by status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    /// Running or idle.
+    Running,
+    Exited,
+    /// With an unread notification.
+    Notified,
+}
+
+impl StatusFilter {
+    /// Parse `all`, `running`, `exited` or `notified`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "all" => Ok(Self::All),
+            "running" => Ok(Self::Running),
+            "exited" => Ok(Self::Exited),
+            "notified" => Ok(Self::Notified),
+            _ => Err(format!("invalid status filter: {s} (expected all, running, exited or notified)")),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Running => "running",
+            Self::Exited => "exited",
+            Self::Notified => "notified",
+        }
+    }
+
+    /// The next filter when cycling through them with Tab.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::All => Self::Running,
+            Self::Running => Self::Exited,
+            Self::Exited => Self::Notified,
+            Self::Notified => Self::All,
+        }
+    }
+}
+
+/// Narrows the sidebar to the terminals with a status and/or containing
+/// some text. The default filter shows every terminal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalFilter {
+    pub status: StatusFilter,
+    /// Case-insensitive text matched against the name, cwd, memo, badge,
+    /// group and foreground process. Empty matches everything.
+    pub text: String,
+}
+
+impl TerminalFilter {
+    /// Whether the filter hides anything at all.
+    pub fn is_active(&self) -> bool {
+        self.status != StatusFilter::All || !self.text.is_empty()
+    }
+
+    /// Whether any of `fields` contains the filter text.
+    pub fn matches_text<'a>(&self, fields: impl IntoIterator<Item = &'a str>) -> bool {
+        if self.text.is_empty() {
+            return true;
+        }
+        let needle = self.text.to_lowercase();
+        fields.into_iter().any(|field| field.to_lowercase().contains(&needle))
+    }
+
+    /// Short description for the sidebar, e.g. "running · api".
+    pub fn label(&self) -> String {
+        match (self.status, self.text.is_empty()) {
+            (StatusFilter::All, _) => self.text.clone(),
+            (status, true) => status.as_str().to_string(),
+            (status, false) => format!("{} · {}", status.as_str(), self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_filter_parse_and_cycle() {
+        let mut status = StatusFilter::All;
+        for _ in 0..4 {
+            assert_eq!(StatusFilter::parse(status.as_str()), Ok(status));
+            status = status.next();
+        }
+        assert_eq!(status, StatusFilter::All);
+        assert!(StatusFilter::parse("idle").is_err());
+    }
+
+    #[test]
+    fn default_filter_is_inactive_and_matches_everything() {
+        let filter = TerminalFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches_text(["anything"]));
+        assert_eq!(filter.label(), "");
+    }
+
+    #[test]
+    fn text_matches_any_field_ignoring_case() {
+        let filter = TerminalFilter { status: StatusFilter::All, text: "API".to_string() };
+        assert!(filter.is_active());
+        assert!(filter.matches_text(["web", "/src/api"]));
+        assert!(!filter.matches_text(["web", "/src/ui"]));
+    }
+
+    #[test]
+    fn label_combines_status_and_text() {
+        let mut filter = TerminalFilter { status: StatusFilter::Running, text: String::new() };
+        assert_eq!(filter.label(), "running");
+        filter.text = "api".to_string();
+        assert_eq!(filter.label(), "running · api");
+        filter.status = StatusFilter::All;
+        assert_eq!(filter.label(), "api");
+    }
+}
@@ -0,0 +1,32 @@
+/// Normalize a group name as typed: surrounding whitespace and a leading
+/// `#` (the target syntax) are dropped. Empty means no group.
+pub fn group_name(s: &str) -> Option<String> {
+    let name = s.trim();
+    let name = name.strip_prefix('#').unwrap_or(name).trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// A group heading in the sidebar, drawn above the group's terminals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupHeader {
+    pub name: String,
+    /// Terminals in the group, shown or not.
+    pub count: usize,
+    /// Whether the group's terminals are hidden (except the active one).
+    pub collapsed: bool,
+    /// Position in the shown terminals of the first one below the header.
+    pub at: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_name_trims_and_drops_hash() {
+        assert_eq!(group_name("api"), Some("api".to_string()));
+        assert_eq!(group_name("  #web ui "), Some("web ui".to_string()));
+        assert_eq!(group_name(""), None);
+        assert_eq!(group_name(" # "), None);
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+/// Default size at which a terminal log file is rotated (10 MiB).
+pub const DEFAULT_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// What is written to a terminal's log file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogMode {
+    /// Pty output exactly as received, escape sequences included.
+    /// Replays faithfully with `cat`.
+    Raw,
+    /// Printable text only: ANSI escape sequences and carriage returns
+    /// are stripped so the file is greppable.
+    #[default]
+    Text,
+}
+
+impl LogMode {
+    /// Wire/CLI name of the mode.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogMode::Raw => "raw",
+            LogMode::Text => "text",
+        }
+    }
+
+    /// Parse the wire/CLI name of a mode.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "raw" => Some(LogMode::Raw),
+            "text" => Some(LogMode::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for starting to log a terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogOptions {
+    pub mode: LogMode,
+    /// The file is rotated once it grows past this size.
+    pub max_bytes: u64,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            mode: LogMode::default(),
+            max_bytes: DEFAULT_LOG_MAX_BYTES,
+        }
+    }
+}
+
+/// State of an active terminal log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogStatus {
+    /// Current log file (rotated files get a `.1`, `.2`, ... suffix).
+    pub path: PathBuf,
+    pub mode: LogMode,
+    pub max_bytes: u64,
+    /// Bytes written to the current file.
+    pub bytes_written: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_options_are_text_with_default_limit() {
+        let opts = LogOptions::default();
+        assert_eq!(opts.mode, LogMode::Text);
+        assert_eq!(opts.max_bytes, DEFAULT_LOG_MAX_BYTES);
+    }
+
+    #[test]
+    fn mode_name_roundtrip() {
+        for mode in [LogMode::Raw, LogMode::Text] {
+            assert_eq!(LogMode::parse(mode.as_str()), Some(mode));
+        }
+    }
+
+    #[test]
+    fn parse_unknown_mode_is_none() {
+        assert_eq!(LogMode::parse("ansi"), None);
+        assert_eq!(LogMode::parse(""), None);
+    }
+}
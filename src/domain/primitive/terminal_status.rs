@@ -1,17 +1,22 @@
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TerminalStatus {
     Running,
+    /// The process is alive but idle: an interactive shell waiting at its
+    /// prompt.
+    Idle,
     Exited(i32),
 }
 
 impl TerminalStatus {
+    /// Whether the process is still alive (running or idle).
     pub fn is_running(&self) -> bool {
-        matches!(self, Self::Running)
+        matches!(self, Self::Running | Self::Idle)
     }
 
     pub fn icon(&self) -> &str {
         match self {
             Self::Running => "●",
+            Self::Idle => "○",
             Self::Exited(_) => "✗",
         }
     }
@@ -19,6 +24,7 @@ impl TerminalStatus {
     pub fn status_text(&self) -> String {
         match self {
             Self::Running => "running".to_string(),
+            Self::Idle => "idle".to_string(),
             Self::Exited(code) => format!("exited ({code})"),
         }
     }
@@ -0,0 +1,224 @@
+//! Line ranges and rendering for `capture-pane`.
+//!
+//! Lines are read through `ScreenPort::get_row_cells`, so the screen and
+//! the scrollback are captured the same way, and rendered as plain text,
+//! as text with SGR escapes rebuilt from the cell styles, or as cells.
+
+use crate::domain::primitive::{CaptureFormat, CaptureOptions, Cell, Color, TerminalId};
+use crate::interface_adapter::port::ScreenPort;
+use crate::shared::error::AppError;
+
+/// Captured lines, ready for `IpcResponseData::CapturePane`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    pub text: String,
+    pub cells: Option<Vec<Vec<Cell>>>,
+    pub start: i64,
+    pub end: i64,
+    pub next_start: Option<i64>,
+}
+
+/// Read and render the lines `options` asks for.
+pub fn capture<S: ScreenPort>(
+    screen: &mut S,
+    id: TerminalId,
+    options: &CaptureOptions,
+) -> Result<Capture, AppError> {
+    let rows = screen.get_cells(id)?.len() as i64;
+    let history = screen.get_max_scrollback(id)? as i64;
+    let (start, end, next_start) = line_range(options, history, rows);
+
+    let mut lines = Vec::new();
+    for line in start..=end {
+        lines.push(screen.get_row_cells(id, (line + history) as usize)?);
+    }
+
+    let (text, cells) = match options.format {
+        CaptureFormat::Text => (join_lines(lines.iter().map(|row| row_text(row))), None),
+        CaptureFormat::Ansi => (join_lines(lines.iter().map(|row| row_ansi(row))), None),
+        CaptureFormat::Cells => (String::new(), Some(lines)),
+    };
+    Ok(Capture { text, cells, start, end, next_start })
+}
+
+/// First and last line of the page to return, and the first line of the
+/// next page. Lines are clamped to `-history..rows`; a reversed range is
+/// swapped, as in tmux.
+fn line_range(options: &CaptureOptions, history: i64, rows: i64) -> (i64, i64, Option<i64>) {
+    let first = -history;
+    let last = (rows - 1).max(first);
+    let default_start = if options.include_scrollback { first } else { 0 };
+    let start = options.start.unwrap_or(default_start).clamp(first, last);
+    let end = options.end.unwrap_or(last).clamp(first, last);
+    let (start, end) = (start.min(end), start.max(end));
+    match options.limit {
+        Some(limit) if limit > 0 && end - start >= limit as i64 => {
+            let page_end = start + limit as i64 - 1;
+            (start, page_end, Some(page_end + 1))
+        }
+        _ => (start, end, None),
+    }
+}
+
+/// Join rendered lines, dropping empty lines at the bottom.
+fn join_lines(lines: impl Iterator<Item = String>) -> String {
+    let mut lines: Vec<String> = lines.collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// A row's characters without trailing blanks.
+fn row_text(row: &[Cell]) -> String {
+    let line: String = row.iter().filter(|c| c.width != 0).map(|c| c.ch).collect();
+    line.trim_end().to_string()
+}
+
+/// A row's characters with an SGR sequence wherever the style changes.
+/// The line starts from the default style and is reset at its end.
+fn row_ansi(row: &[Cell]) -> String {
+    let blank = Cell::default();
+    let len = row.len() - row.iter().rev().take_while(|c| **c == blank).count();
+
+    let mut out = String::new();
+    let mut style = String::new();
+    for cell in row[..len].iter().filter(|c| c.width != 0) {
+        let params = sgr_params(cell);
+        if params != style {
+            out.push_str(&format!("\x1b[0{}m", params));
+            style = params;
+        }
+        out.push(cell.ch);
+    }
+    if !style.is_empty() {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+/// SGR parameters (each with a leading `;`) that set a cell's style on
+/// top of a reset. Empty for the default style.
+fn sgr_params(cell: &Cell) -> String {
+    let mut params = String::new();
+    for (set, code) in [
+        (cell.bold, 1),
+        (cell.dim, 2),
+        (cell.italic, 3),
+        (cell.underline, 4),
+        (cell.reverse, 7),
+        (cell.hidden, 8),
+        (cell.strikethrough, 9),
+    ] {
+        if set {
+            params.push_str(&format!(";{code}"));
+        }
+    }
+    push_color(&mut params, cell.fg, 30, 90, 38);
+    push_color(&mut params, cell.bg, 40, 100, 48);
+    params
+}
+
+/// Append a color: `base + n` for the 8 standard colors, `bright + n - 8`
+/// for the bright ones, `extended;5;n` or `extended;2;r;g;b` otherwise.
+fn push_color(params: &mut String, color: Color, base: u8, bright: u8, extended: u8) {
+    match color {
+        Color::Default => {}
+        Color::Indexed(n @ 0..=7) => params.push_str(&format!(";{}", base + n)),
+        Color::Indexed(n @ 8..=15) => params.push_str(&format!(";{}", bright + n - 8)),
+        Color::Indexed(n) => params.push_str(&format!(";{extended};5;{n}")),
+        Color::Rgb(r, g, b) => params.push_str(&format!(";{extended};2;{r};{g};{b}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(start: Option<i64>, end: Option<i64>) -> CaptureOptions {
+        CaptureOptions { start, end, ..CaptureOptions::default() }
+    }
+
+    fn cells(text: &str) -> Vec<Cell> {
+        let mut row: Vec<Cell> = text.chars().map(|ch| Cell { ch, ..Cell::default() }).collect();
+        row.resize(10, Cell::default());
+        row
+    }
+
+    // =========================================================================
+    // Tests: line_range
+    // =========================================================================
+
+    #[test]
+    fn line_range_defaults_to_the_screen() {
+        assert_eq!(line_range(&options(None, None), 100, 24), (0, 23, None));
+    }
+
+    #[test]
+    fn line_range_with_scrollback_starts_at_oldest_line() {
+        let opts = CaptureOptions { include_scrollback: true, ..CaptureOptions::default() };
+        assert_eq!(line_range(&opts, 100, 24), (-100, 23, None));
+        // An explicit start wins
+        let opts = CaptureOptions { start: Some(-5), ..opts };
+        assert_eq!(line_range(&opts, 100, 24), (-5, 23, None));
+    }
+
+    #[test]
+    fn line_range_clamps_and_swaps() {
+        assert_eq!(line_range(&options(Some(-500), Some(99)), 100, 24), (-100, 23, None));
+        assert_eq!(line_range(&options(Some(5), Some(-2)), 100, 24), (-2, 5, None));
+    }
+
+    #[test]
+    fn line_range_pages_by_limit() {
+        let opts = CaptureOptions { limit: Some(10), ..options(Some(-20), None) };
+        assert_eq!(line_range(&opts, 100, 24), (-20, -11, Some(-10)));
+        // The last page fits and has no next page
+        let opts = CaptureOptions { limit: Some(10), ..options(Some(14), None) };
+        assert_eq!(line_range(&opts, 100, 24), (14, 23, None));
+    }
+
+    // =========================================================================
+    // Tests: rendering
+    // =========================================================================
+
+    #[test]
+    fn row_text_skips_wide_continuation_and_trailing_blanks() {
+        let mut row = cells("a");
+        row[1] = Cell { ch: '漢', width: 2, ..Cell::default() };
+        row[2] = Cell { ch: ' ', width: 0, ..Cell::default() };
+        row[3].ch = 'b';
+        assert_eq!(row_text(&row), "a漢b");
+    }
+
+    #[test]
+    fn row_ansi_of_plain_row_has_no_escapes() {
+        assert_eq!(row_ansi(&cells("plain text")), "plain text");
+    }
+
+    #[test]
+    fn row_ansi_emits_sgr_on_style_changes_and_resets_at_end() {
+        let mut row = cells("ok: 1");
+        for cell in &mut row[..2] {
+            cell.fg = Color::Indexed(2);
+            cell.bold = true;
+        }
+        row[4].bg = Color::Rgb(255, 0, 128);
+        assert_eq!(
+            row_ansi(&row),
+            "\x1b[0;1;32mok\x1b[0m: \x1b[0;48;2;255;0;128m1\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn sgr_params_cover_bright_and_256_colors() {
+        let cell = Cell { fg: Color::Indexed(9), bg: Color::Indexed(200), underline: true, ..Cell::default() };
+        assert_eq!(sgr_params(&cell), ";4;91;48;5;200");
+    }
+
+    #[test]
+    fn join_lines_drops_empty_lines_at_the_bottom() {
+        let lines = ["a", "", "b", "", ""].iter().map(|s| s.to_string());
+        assert_eq!(join_lines(lines), "a\n\nb");
+    }
+}
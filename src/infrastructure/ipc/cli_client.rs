@@ -2,32 +2,56 @@ use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
 use std::process;
 
+use crate::domain::primitive::{RestartPolicy, Signal, SortKey, StatusBadge, StatusFilter, TargetSpec};
+use crate::infrastructure::ipc::socket_discovery;
+use crate::usecase::terminal_usecase::TERMINAL_ID_ENV;
+
 /// Entry point for `cm ctl <subcommand>`.
 ///
 /// Parses CLI arguments, connects to the IPC socket, sends a JSON command,
 /// reads the response, and outputs to stdout/stderr.
 pub fn run(args: &[String]) -> ! {
-    if args.len() < 3 {
+    let (instance, args) = match take_instance_option(&args[2..]) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+    };
+    if args.is_empty() {
         print_usage();
         process::exit(1);
     }
 
-    let subcommand = &args[2];
-    let sub_args = &args[3..];
+    let subcommand = &args[0];
+    let sub_args = &args[1..];
 
-    let json = match build_request(subcommand, sub_args) {
-        Ok(j) => j,
-        Err(e) => {
-            eprintln!("error: {}", e);
-            process::exit(1);
+    let response = if subcommand == "list-instances" {
+        // Answered from the registry, without connecting to an instance
+        serde_json::json!({"ok": true, "data": socket_discovery::list_instances()}).to_string()
+    } else {
+        let json = match build_request(subcommand, sub_args) {
+            Ok(j) => j,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        };
+
+        if subcommand == "watch" {
+            if let Err(e) = watch(&json, instance.as_deref()) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+            process::exit(0);
         }
-    };
 
-    let response = match send_request(&json) {
-        Ok(r) => r,
-        Err(e) => {
-            eprintln!("error: {}", e);
-            process::exit(1);
+        match send_request(&json, instance.as_deref()) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
         }
     };
 
@@ -66,7 +90,12 @@ pub fn run(args: &[String]) -> ! {
 fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
     match subcommand {
         "send-keys" => {
-            let (target, keys) = parse_target_and_rest(args, "send-keys")?;
+            let (target, mut keys) = parse_target_and_rest(args, "send-keys")?;
+            // -l: send the keys as literal text (tmux `send-keys -l`)
+            let literal = keys.first().is_some_and(|k| k == "-l");
+            if literal {
+                keys.remove(0);
+            }
             if keys.is_empty() {
                 return Err("send-keys requires at least one key argument".to_string());
             }
@@ -78,18 +107,63 @@ fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
                 "cmd": "send-keys",
                 "target": target,
                 "keys": keys_json,
+                "literal": literal,
             })
             .to_string())
         }
         "capture-pane" => {
             let (target, rest) = parse_target_and_rest(args, "capture-pane")?;
-            let scrollback = rest.contains(&"-S".to_string());
-            Ok(serde_json::json!({
+            let mut obj = serde_json::json!({
                 "cmd": "capture-pane",
                 "target": target,
-                "scrollback": scrollback,
-            })
-            .to_string())
+                "scrollback": false,
+            });
+            let mut i = 0;
+            while i < rest.len() {
+                // -S/-E take a line number or `-` (tmux: start of history /
+                // end of screen); a bare -S keeps meaning "whole scrollback"
+                let line = rest.get(i + 1).map(String::as_str);
+                let line_value = match line {
+                    Some("-") => Some(None),
+                    Some(v) => v.parse::<i64>().ok().map(Some),
+                    None => None,
+                };
+                match rest[i].as_str() {
+                    "-S" => {
+                        match line_value {
+                            Some(Some(n)) => obj["start"] = serde_json::json!(n),
+                            _ => obj["scrollback"] = serde_json::json!(true),
+                        }
+                        i += 1 + usize::from(line_value.is_some());
+                    }
+                    "-E" => {
+                        match line_value {
+                            Some(Some(n)) => obj["end"] = serde_json::json!(n),
+                            Some(None) => {}
+                            None => return Err("-E requires a line number or -".to_string()),
+                        }
+                        i += 2;
+                    }
+                    "-e" => {
+                        obj["format"] = serde_json::json!("ansi");
+                        i += 1;
+                    }
+                    "--cells" => {
+                        obj["format"] = serde_json::json!("cells");
+                        i += 1;
+                    }
+                    "--limit" => {
+                        let limit = line
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .filter(|n| *n > 0)
+                            .ok_or("--limit requires a positive number")?;
+                        obj["limit"] = serde_json::json!(limit);
+                        i += 2;
+                    }
+                    other => return Err(format!("unknown option: {}", other)),
+                }
+            }
+            Ok(obj.to_string())
         }
         "list-windows" => Ok(serde_json::json!({"cmd": "list-windows"}).to_string()),
         "paste-buffer" => {
@@ -134,6 +208,58 @@ fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
                             return Err("--cmd requires a value".to_string());
                         }
                     }
+                    "--cwd" => {
+                        if i + 1 < args.len() {
+                            // Resolve relative to the caller's cwd, not the server's.
+                            let dir = std::path::Path::new(&args[i + 1]);
+                            let dir = if dir.is_absolute() {
+                                dir.to_path_buf()
+                            } else {
+                                std::env::current_dir()
+                                    .map_err(|e| format!("cannot resolve --cwd: {}", e))?
+                                    .join(dir)
+                            };
+                            obj["cwd"] = serde_json::json!(dir.to_string_lossy());
+                            i += 2;
+                        } else {
+                            return Err("--cwd requires a value".to_string());
+                        }
+                    }
+                    "--env" => {
+                        if i + 1 < args.len() {
+                            let (key, value) = args[i + 1]
+                                .split_once('=')
+                                .filter(|(k, _)| !k.is_empty())
+                                .ok_or_else(|| {
+                                    format!("--env expects KEY=VALUE, got: {}", args[i + 1])
+                                })?;
+                            if obj.get("env").is_none() {
+                                obj["env"] = serde_json::json!({});
+                            }
+                            obj["env"][key] = serde_json::json!(value);
+                            i += 2;
+                        } else {
+                            return Err("--env requires a value".to_string());
+                        }
+                    }
+                    "--restart" => {
+                        if i + 1 < args.len() {
+                            // Validate here so a typo fails before anything is spawned
+                            RestartPolicy::parse(&args[i + 1])?;
+                            obj["restart"] = serde_json::json!(&args[i + 1]);
+                            i += 2;
+                        } else {
+                            return Err("--restart requires a value".to_string());
+                        }
+                    }
+                    "--group" => {
+                        if i + 1 < args.len() {
+                            obj["group"] = serde_json::json!(&args[i + 1]);
+                            i += 2;
+                        } else {
+                            return Err("--group requires a value".to_string());
+                        }
+                    }
                     other => {
                         return Err(format!("unknown option: {}", other));
                     }
@@ -145,6 +271,127 @@ fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
             let (target, _) = parse_target_and_rest(args, "kill-window")?;
             Ok(serde_json::json!({"cmd": "kill-window", "target": target}).to_string())
         }
+        "respawn-window" => {
+            let (target, rest) = parse_target_and_rest(args, "respawn-window")?;
+            let mut obj = serde_json::json!({"cmd": "respawn-window", "target": target});
+            for arg in &rest {
+                match arg.as_str() {
+                    "-k" | "--kill" => obj["kill"] = serde_json::json!(true),
+                    other => return Err(format!("unknown option: {}", other)),
+                }
+            }
+            Ok(obj.to_string())
+        }
+        "send-signal" => {
+            let (target, rest) = parse_target_and_rest(args, "send-signal")?;
+            let [signal] = rest.as_slice() else {
+                return Err("send-signal requires one signal, e.g. INT, TERM or KILL".to_string());
+            };
+            let signal = Signal::parse(signal)?;
+            Ok(serde_json::json!({"cmd": "send-signal", "target": target, "signal": signal.name()}).to_string())
+        }
+        "move-window" => {
+            let (target, rest) = parse_target_and_rest(args, "move-window")?;
+            let mut obj = serde_json::json!({"cmd": "move-window", "target": target});
+            let value = |i: usize, flag: &str| rest.get(i + 1).ok_or_else(|| format!("{} requires a value", flag));
+            match rest.first().map(String::as_str) {
+                Some("--up") if rest.len() == 1 => obj["by"] = serde_json::json!(-1),
+                Some("--down") if rest.len() == 1 => obj["by"] = serde_json::json!(1),
+                Some(flag @ "--by") if rest.len() <= 2 => {
+                    let by: i64 = value(0, flag)?.parse().map_err(|_| format!("invalid --by: {}", rest[1]))?;
+                    obj["by"] = serde_json::json!(by);
+                }
+                Some(flag @ "--to") if rest.len() <= 2 => {
+                    let to: u64 = value(0, flag)?.parse().map_err(|_| format!("invalid --to: {}", rest[1]))?;
+                    obj["to"] = serde_json::json!(to);
+                }
+                Some(flag @ "--swap") if rest.len() <= 2 => obj["swap"] = target_value(value(0, flag)?)?,
+                _ => return Err("move-window requires one of --up, --down, --by <n>, --to <index> or --swap <t>".to_string()),
+            }
+            Ok(obj.to_string())
+        }
+        "sort-windows" => {
+            let [key] = args else {
+                return Err("sort-windows requires one key: name, cwd, activity or unread".to_string());
+            };
+            // Validate here so a typo is reported before connecting
+            SortKey::parse(key)?;
+            Ok(serde_json::json!({"cmd": "sort-windows", "key": key}).to_string())
+        }
+        "filter-windows" => {
+            // No arguments clear the filter
+            let mut obj = serde_json::json!({"cmd": "filter-windows"});
+            let mut words: Vec<&str> = Vec::new();
+            let mut i = 0;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--status" => {
+                        let status = args.get(i + 1).ok_or("--status requires a value")?;
+                        StatusFilter::parse(status)?;
+                        obj["status"] = serde_json::json!(status);
+                        i += 2;
+                    }
+                    other if other.starts_with("--") => return Err(format!("unknown option: {}", other)),
+                    word => {
+                        words.push(word);
+                        i += 1;
+                    }
+                }
+            }
+            if !words.is_empty() {
+                obj["text"] = serde_json::json!(words.join(" "));
+            }
+            Ok(obj.to_string())
+        }
+        "set-group" => {
+            // No group takes the terminal out of its group
+            let (target, rest) = parse_target_and_rest(args, "set-group")?;
+            let mut obj = serde_json::json!({"cmd": "set-group", "target": target});
+            match rest.as_slice() {
+                [] => {}
+                [group] => obj["group"] = serde_json::json!(group),
+                _ => return Err("set-group takes one group name".to_string()),
+            }
+            Ok(obj.to_string())
+        }
+        "toggle-group" => {
+            let mut obj = serde_json::json!({"cmd": "toggle-group"});
+            for arg in args {
+                match arg.as_str() {
+                    "--collapse" => obj["collapsed"] = serde_json::json!(true),
+                    "--expand" => obj["collapsed"] = serde_json::json!(false),
+                    other if other.starts_with("--") => return Err(format!("unknown option: {}", other)),
+                    _ if obj.get("group").is_some() => return Err("toggle-group takes one group name".to_string()),
+                    group => obj["group"] = serde_json::json!(group),
+                }
+            }
+            if obj.get("group").is_none() {
+                return Err("toggle-group requires a group name".to_string());
+            }
+            Ok(obj.to_string())
+        }
+        "mark-window" => {
+            // Neither flag toggles the mark
+            let (target, rest) = parse_target_and_rest(args, "mark-window")?;
+            let mut obj = serde_json::json!({"cmd": "mark-window", "target": target});
+            match rest.as_slice() {
+                [] => {}
+                [flag] if flag == "--on" => obj["marked"] = serde_json::json!(true),
+                [flag] if flag == "--off" => obj["marked"] = serde_json::json!(false),
+                _ => return Err("mark-window takes --on or --off".to_string()),
+            }
+            Ok(obj.to_string())
+        }
+        "sync-input" => {
+            let mut obj = serde_json::json!({"cmd": "sync-input"});
+            match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                [] => {}
+                ["on"] => obj["enabled"] = serde_json::json!(true),
+                ["off"] => obj["enabled"] = serde_json::json!(false),
+                _ => return Err("sync-input takes on or off".to_string()),
+            }
+            Ok(obj.to_string())
+        }
         "select-window" => {
             let (target, _) = parse_target_and_rest(args, "select-window")?;
             Ok(serde_json::json!({"cmd": "select-window", "target": target}).to_string())
@@ -176,12 +423,71 @@ fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
                     .to_string(),
             )
         }
+        "set-status" => {
+            let (target, rest) = parse_target_and_rest(args, "set-status")?;
+            let mut words: Vec<String> = Vec::new();
+            let mut icon: Option<String> = None;
+            let mut color: Option<String> = None;
+            let mut clear = false;
+            let mut i = 0;
+            while i < rest.len() {
+                match rest[i].as_str() {
+                    "--icon" => {
+                        icon = Some(rest.get(i + 1).ok_or("--icon requires a value")?.clone());
+                        i += 2;
+                    }
+                    "--color" => {
+                        let value = rest.get(i + 1).ok_or("--color requires a value")?;
+                        StatusBadge::parse_color(value)?;
+                        color = Some(value.clone());
+                        i += 2;
+                    }
+                    "--clear" => {
+                        clear = true;
+                        i += 1;
+                    }
+                    other if other.starts_with("--") => {
+                        return Err(format!("unknown option: {}", other));
+                    }
+                    _ => {
+                        words.push(rest[i].clone());
+                        i += 1;
+                    }
+                }
+            }
+            let mut obj = serde_json::json!({"cmd": "set-status", "target": target});
+            match (clear, words.is_empty()) {
+                // No text clears the badge
+                (true, true) => {}
+                (true, false) => return Err("set-status takes either a text or --clear".to_string()),
+                (false, true) => return Err("set-status requires a text argument (or --clear)".to_string()),
+                (false, false) => {
+                    obj["text"] = serde_json::json!(words.join(" "));
+                    if let Some(icon) = icon {
+                        obj["icon"] = serde_json::json!(icon);
+                    }
+                    if let Some(color) = color {
+                        obj["color"] = serde_json::json!(color);
+                    }
+                }
+            }
+            Ok(obj.to_string())
+        }
         "notify" => {
+            // Without -t the instance attributes it to the caller's terminal
+            let mut target: Option<serde_json::Value> = None;
             let mut title: Option<String> = None;
             let mut body: Option<String> = None;
             let mut i = 0;
             while i < args.len() {
                 match args[i].as_str() {
+                    "-t" => {
+                        let value = args
+                            .get(i + 1)
+                            .ok_or_else(|| "notify -t requires a target".to_string())?;
+                        target = Some(target_value(value)?);
+                        i += 2;
+                    }
                     "--title" => {
                         if i + 1 < args.len() {
                             title = Some(args[i + 1].clone());
@@ -210,31 +516,178 @@ fn build_request(subcommand: &str, args: &[String]) -> Result<String, String> {
                 }
             };
             let mut obj = serde_json::json!({"cmd": "notify", "body": body});
+            if let Some(t) = target {
+                obj["target"] = t;
+            }
             if let Some(t) = title {
                 obj["title"] = serde_json::json!(t);
             }
             Ok(obj.to_string())
         }
+        "kill-server" => Ok(serde_json::json!({"cmd": "kill-server"}).to_string()),
+        "log-start" => {
+            let (target, rest) = parse_target_and_rest(args, "log-start")?;
+            let mut obj = serde_json::json!({"cmd": "log-start", "target": target});
+            let mut i = 0;
+            while i < rest.len() {
+                match rest[i].as_str() {
+                    "--mode" => {
+                        if i + 1 < rest.len() {
+                            obj["mode"] = serde_json::json!(&rest[i + 1]);
+                            i += 2;
+                        } else {
+                            return Err("--mode requires a value (raw or text)".to_string());
+                        }
+                    }
+                    "--max-size" => {
+                        if i + 1 < rest.len() {
+                            let bytes = parse_size(&rest[i + 1])?;
+                            obj["max_size"] = serde_json::json!(bytes);
+                            i += 2;
+                        } else {
+                            return Err("--max-size requires a value".to_string());
+                        }
+                    }
+                    other => {
+                        return Err(format!("unknown option: {}", other));
+                    }
+                }
+            }
+            Ok(obj.to_string())
+        }
+        "record-start" => {
+            let (target, rest) = parse_target_and_rest(args, "record-start")?;
+            let mut obj = serde_json::json!({"cmd": "record-start", "target": target});
+            let mut i = 0;
+            while i < rest.len() {
+                match rest[i].as_str() {
+                    "--file" => {
+                        if i + 1 < rest.len() {
+                            // Resolve relative to the caller's cwd, not the server's.
+                            let file = std::path::Path::new(&rest[i + 1]);
+                            let file = if file.is_absolute() {
+                                file.to_path_buf()
+                            } else {
+                                std::env::current_dir()
+                                    .map_err(|e| format!("cannot resolve --file: {}", e))?
+                                    .join(file)
+                            };
+                            obj["path"] = serde_json::json!(file.to_string_lossy());
+                            i += 2;
+                        } else {
+                            return Err("--file requires a value".to_string());
+                        }
+                    }
+                    other => {
+                        return Err(format!("unknown option: {}", other));
+                    }
+                }
+            }
+            Ok(obj.to_string())
+        }
+        "record-stop" => {
+            let (target, _) = parse_target_and_rest(args, "record-stop")?;
+            Ok(serde_json::json!({"cmd": "record-stop", "target": target}).to_string())
+        }
+        "watch" => {
+            let mut output = Vec::new();
+            let mut i = 0;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--output" => {
+                        let id = args
+                            .get(i + 1)
+                            .ok_or_else(|| "--output requires a terminal ID".to_string())?;
+                        output.push(
+                            id.parse::<u32>()
+                                .map_err(|_| format!("invalid terminal ID: {}", id))?,
+                        );
+                        i += 2;
+                    }
+                    other => return Err(format!("unknown option: {}", other)),
+                }
+            }
+            Ok(serde_json::json!({"cmd": "subscribe", "output": output}).to_string())
+        }
+        "wait-for" => {
+            let (target, rest) = parse_target_and_rest(args, "wait-for")?;
+            let mut obj = serde_json::json!({"cmd": "wait-for", "target": target});
+            let mut conditions = 0;
+            let mut i = 0;
+            while i < rest.len() {
+                let value = rest.get(i + 1);
+                match rest[i].as_str() {
+                    "--regex" => {
+                        let re = value.ok_or_else(|| "--regex requires a pattern".to_string())?;
+                        obj["pattern"] = serde_json::json!(re);
+                        obj["regex"] = serde_json::json!(true);
+                        conditions += 1;
+                        i += 2;
+                    }
+                    "--idle" => {
+                        let d = value.ok_or_else(|| "--idle requires a duration".to_string())?;
+                        obj["idle_ms"] = serde_json::json!(parse_duration(d)?);
+                        conditions += 1;
+                        i += 2;
+                    }
+                    "--exit" => {
+                        obj["exit"] = serde_json::json!(true);
+                        conditions += 1;
+                        i += 1;
+                    }
+                    "--timeout" => {
+                        let d = value.ok_or_else(|| "--timeout requires a duration".to_string())?;
+                        obj["timeout_ms"] = serde_json::json!(parse_duration(d)?);
+                        i += 2;
+                    }
+                    other if other.starts_with("--") => {
+                        return Err(format!("unknown option: {}", other));
+                    }
+                    text => {
+                        obj["pattern"] = serde_json::json!(text);
+                        conditions += 1;
+                        i += 1;
+                    }
+                }
+            }
+            if conditions != 1 {
+                return Err(
+                    "wait-for requires exactly one of <text>, --regex, --idle or --exit".to_string(),
+                );
+            }
+            Ok(obj.to_string())
+        }
+        "log-stop" => {
+            let (target, _) = parse_target_and_rest(args, "log-stop")?;
+            Ok(serde_json::json!({"cmd": "log-stop", "target": target}).to_string())
+        }
+        "log-status" => {
+            let (target, _) = parse_target_and_rest(args, "log-status")?;
+            Ok(serde_json::json!({"cmd": "log-status", "target": target}).to_string())
+        }
         _ => Err(format!("unknown subcommand: {}", subcommand)),
     }
 }
 
-/// Parse `-t <id>` from args and return (target_id, remaining_args).
-fn parse_target_and_rest(args: &[String], cmd_name: &str) -> Result<(u32, Vec<String>), String> {
-    let mut target: Option<u32> = None;
+/// Parse `-t <target>` from args and return (target, remaining_args).
+///
+/// The target is sent as a number when it is an id and as a string
+/// otherwise (a name, a glob or `@active`/`@self`/`@last`). A repeated `-t`
+/// sends them all as an array. It defaults to `@self`.
+fn parse_target_and_rest(
+    args: &[String],
+    cmd_name: &str,
+) -> Result<(serde_json::Value, Vec<String>), String> {
+    let mut targets: Vec<serde_json::Value> = Vec::new();
     let mut rest = Vec::new();
     let mut i = 0;
     while i < args.len() {
         if args[i] == "-t" {
             i += 1;
             if i >= args.len() {
-                return Err(format!("{} -t requires a terminal ID", cmd_name));
+                return Err(format!("{} -t requires a target", cmd_name));
             }
-            target = Some(
-                args[i]
-                    .parse::<u32>()
-                    .map_err(|_| format!("invalid terminal ID: {}", args[i]))?,
-            );
+            targets.push(target_value(&args[i])?);
         } else if args[i] == "--raw" {
             // Skip --raw, it's handled at the response level
         } else {
@@ -242,26 +695,113 @@ fn parse_target_and_rest(args: &[String], cmd_name: &str) -> Result<(u32, Vec<St
         }
         i += 1;
     }
-    match target {
-        Some(t) => Ok((t, rest)),
-        None => Err(format!("{} requires -t <id>", cmd_name)),
+    match targets.len() {
+        // Without -t, act on the terminal cm ctl runs in
+        0 => Ok((TargetSpec::Caller.to_string().into(), rest)),
+        1 => Ok((targets.remove(0), rest)),
+        _ => Ok((targets.into(), rest)),
+    }
+}
+
+/// The JSON `target` for a `-t` argument, validated up front.
+fn target_value(arg: &str) -> Result<serde_json::Value, String> {
+    match TargetSpec::parse(arg)? {
+        TargetSpec::Id(id) => Ok(id.into()),
+        _ => Ok(arg.into()),
+    }
+}
+
+/// Add the caller's terminal id to a request, for `@self` targets.
+pub(crate) fn with_caller(json: &str, caller: Option<u32>) -> String {
+    let Some(caller) = caller else {
+        return json.to_string();
+    };
+    match serde_json::from_str::<serde_json::Value>(json) {
+        Ok(serde_json::Value::Object(mut obj)) => {
+            obj.insert("caller".to_string(), caller.into());
+            serde_json::Value::Object(obj).to_string()
+        }
+        _ => json.to_string(),
+    }
+}
+
+/// Id of the cm terminal this process runs in, from the environment the
+/// terminal was spawned with.
+pub(crate) fn caller_terminal_id() -> Option<u32> {
+    std::env::var(TERMINAL_ID_ENV).ok()?.parse().ok()
+}
+
+/// The caller's terminal id for a request to `socket_path`. Only the
+/// instance the terminal belongs to (`CLI_MANAGER_SOCK`) gets it; any other
+/// instance would take it for one of its own terminals.
+pub(crate) fn caller_for_socket(socket_path: &str) -> Option<u32> {
+    own_instance_caller(
+        caller_terminal_id(),
+        std::env::var("CLI_MANAGER_SOCK").ok().as_deref(),
+        socket_path,
+    )
+}
+
+fn own_instance_caller(
+    caller: Option<u32>,
+    own_socket: Option<&str>,
+    socket_path: &str,
+) -> Option<u32> {
+    caller.filter(|_| own_socket == Some(socket_path))
+}
+
+/// Parse a byte size: plain bytes or with a `K`, `M` or `G` suffix (`10M`).
+fn parse_size(value: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid size: {} (e.g. 1048576, 512K, 10M)", value);
+    let (digits, unit) = match value.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&value[..i], 1024),
+        Some((i, 'M' | 'm')) => (&value[..i], 1024 * 1024),
+        Some((i, 'G' | 'g')) => (&value[..i], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    let n: u64 = digits.parse().map_err(|_| invalid())?;
+    match n.checked_mul(unit) {
+        Some(bytes) if bytes > 0 => Ok(bytes),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse a duration into milliseconds: `500ms`, `30s`, `2m`, or plain
+/// seconds (`30`).
+fn parse_duration(value: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid duration: {} (e.g. 500ms, 30s, 2m)", value);
+    let (digits, unit) = if let Some(d) = value.strip_suffix("ms") {
+        (d, 1)
+    } else if let Some(d) = value.strip_suffix('s') {
+        (d, 1000)
+    } else if let Some(d) = value.strip_suffix('m') {
+        (d, 60 * 1000)
+    } else {
+        (value, 1000)
+    };
+    let n: u64 = digits.parse().map_err(|_| invalid())?;
+    n.checked_mul(unit).ok_or_else(invalid)
+}
+
+/// Split a leading `--instance <name|pid>` off the `cm ctl` arguments.
+fn take_instance_option(args: &[String]) -> Result<(Option<String>, &[String]), String> {
+    if args.first().is_some_and(|a| a == "--instance") {
+        let selector = args
+            .get(1)
+            .ok_or_else(|| "--instance requires a name or pid".to_string())?;
+        return Ok((Some(selector.clone()), &args[2..]));
     }
+    Ok((None, args))
 }
 
-fn send_request(json: &str) -> Result<String, String> {
-    // Try CLI_MANAGER_SOCK env var first, then fall back to discovery file
-    let socket_path = std::env::var("CLI_MANAGER_SOCK").or_else(|_| {
-        crate::infrastructure::ipc::socket_discovery::read_socket_path()
-            .map_err(|_| ())
-    }).map_err(|_| {
-        "No running cli-manager instance found. Is cli-manager running?".to_string()
-    })?;
+fn send_request(json: &str, instance: Option<&str>) -> Result<String, String> {
+    let socket_path = socket_discovery::resolve_socket_path(instance)?;
 
     let mut stream = UnixStream::connect(&socket_path)
         .map_err(|e| format!("cannot connect to {}: {}", socket_path, e))?;
 
     // Send request + newline
-    let mut request = json.to_string();
+    let mut request = with_caller(json, caller_for_socket(&socket_path));
     request.push('\n');
     stream
         .write_all(request.as_bytes())
@@ -281,21 +821,97 @@ fn send_request(json: &str) -> Result<String, String> {
     Ok(response.trim().to_string())
 }
 
+/// `cm ctl watch`: subscribe and print every event line (JSONL) until the
+/// instance goes away.
+fn watch(json: &str, instance: Option<&str>) -> Result<(), String> {
+    let socket_path = socket_discovery::resolve_socket_path(instance)?;
+    let mut stream = UnixStream::connect(&socket_path)
+        .map_err(|e| format!("cannot connect to {}: {}", socket_path, e))?;
+    let json = with_caller(json, caller_for_socket(&socket_path));
+    stream
+        .write_all(format!("{json}\n").as_bytes())
+        .map_err(|e| format!("write error: {}", e))?;
+
+    let mut lines = BufReader::new(&stream).lines();
+    let response = lines
+        .next()
+        .ok_or_else(|| "empty response from server".to_string())?
+        .map_err(|e| format!("read error: {}", e))?;
+    let parsed: serde_json::Value = serde_json::from_str(&response)
+        .map_err(|e| format!("error parsing response: {}", e))?;
+    if parsed.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+        let error_msg = parsed.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error");
+        return Err(error_msg.to_string());
+    }
+
+    let stdout = std::io::stdout();
+    for line in lines {
+        let line = line.map_err(|e| format!("read error: {}", e))?;
+        let mut out = stdout.lock();
+        // Stop quietly when the reader of our output goes away
+        if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
 fn print_usage() {
-    eprintln!("Usage: cm ctl <subcommand> [options]");
+    eprintln!("Usage: cm ctl [--instance <name|pid>] <subcommand> [options]");
     eprintln!();
     eprintln!("Subcommands:");
-    eprintln!("  send-keys -t <id> <keys...>      Send keys to terminal");
-    eprintln!("  capture-pane -t <id> [-S]         Capture terminal content");
+    eprintln!("  send-keys [-t <t>] [-l] <keys...> Send keys to terminal (-l: literal text)");
+    eprintln!("  capture-pane [-t <t>] [-S [<n>|-]] [-E <n>|-] [-e | --cells] [--limit <n>]");
+    eprintln!("                                    Capture terminal content (lines: 0 = top of");
+    eprintln!("                                    screen, negative = scrollback; -e: ANSI colors)");
     eprintln!("  list-windows                      List all terminals");
-    eprintln!("  paste-buffer -t <id>              Paste yank buffer to terminal");
+    eprintln!("  paste-buffer [-t <t>]             Paste yank buffer to terminal");
     eprintln!("  set-buffer <text>                 Set yank buffer text");
     eprintln!("  show-buffer                       Show yank buffer content");
-    eprintln!("  create-window [--name <n>] [--cmd <c>]  Create a new terminal");
-    eprintln!("  kill-window -t <id>               Kill a terminal");
-    eprintln!("  select-window -t <id>             Select (focus) a terminal");
-    eprintln!("  rename-window -t <id> --name <n>  Rename a terminal");
-    eprintln!("  notify --body <b> [--title <t>]   Send a desktop notification");
+    eprintln!("  create-window [--name <n>] [--cmd <c>] [--cwd <dir>] [--env K=V]...");
+    eprintln!("                [--restart never|on-failure|always] [--group <g>]");
+    eprintln!("                                    Create a new terminal");
+    eprintln!("  kill-window [-t <t>]              Kill a terminal");
+    eprintln!("  respawn-window [-t <t>] [-k]      Restart an exited terminal's process in place");
+    eprintln!("                                    (-k: kill a running one first)");
+    eprintln!("  send-signal [-t <t>] <signal>     Signal the foreground process group");
+    eprintln!("                                    (INT, TERM, HUP, KILL, STOP or CONT)");
+    eprintln!("  select-window [-t <t>]            Select (focus) a terminal");
+    eprintln!("  rename-window [-t <t>] --name <n> Rename a terminal");
+    eprintln!("  move-window [-t <t>] --up | --down | --by <n> | --to <index> | --swap <t>");
+    eprintln!("                                    Move a terminal in the sidebar (index 0 = top)");
+    eprintln!("  sort-windows <key>                Sort the sidebar by name, cwd, activity or unread");
+    eprintln!("  filter-windows [--status <s>] [<text>]");
+    eprintln!("                                    Show only matching terminals in the sidebar (status:");
+    eprintln!("                                    running, exited or notified; no arguments: show all)");
+    eprintln!("  set-group [-t <t>] [<group>]      Put a terminal in a sidebar group (none: ungroup)");
+    eprintln!("  toggle-group <group> [--collapse | --expand]");
+    eprintln!("                                    Collapse or expand a group in the sidebar");
+    eprintln!("  mark-window [-t <t>] [--on | --off]");
+    eprintln!("                                    Mark a terminal for synchronized input (toggle)");
+    eprintln!("  sync-input [on | off]             Type into every marked terminal at once (toggle)");
+    eprintln!("  set-status [-t <t>] <text> [--icon <i>] [--color <c>] | --clear");
+    eprintln!("                                    Show a status badge in the sidebar (color: name,");
+    eprintln!("                                    0-255 or #rrggbb)");
+    eprintln!("  notify [-t <t>] --body <b> [--title <t>]");
+    eprintln!("                                    Notify on a terminal's sidebar entry and desktop");
+    eprintln!("  kill-server                       Stop cm server and its terminals");
+    eprintln!("  list-instances                    List running cm instances");
+    eprintln!("  log-start [-t <t>] [--mode raw|text] [--max-size <n>[K|M|G]]");
+    eprintln!("                                    Log terminal output to a file");
+    eprintln!("  log-stop [-t <t>]                 Stop logging a terminal");
+    eprintln!("  log-status [-t <t>]               Show a terminal's log file and size");
+    eprintln!("  record-start [-t <t>] [--file <f>] Record terminal as asciicast (cm play)");
+    eprintln!("  record-stop [-t <t>]              Stop recording a terminal");
+    eprintln!("  watch [--output <id>]...          Stream session events as JSON lines");
+    eprintln!("  wait-for [-t <t>] <text> | --regex <re> | --idle <dur> | --exit [--timeout <dur>]");
+    eprintln!("                                    Wait for new output, quiet or exit (dur: 500ms, 30s, 2m)");
+    eprintln!();
+    eprintln!("Targets (-t, default @self):");
+    eprintln!("  <id>, <name>, <glob> (e.g. agent-*, runs on every match), #<group> (every");
+    eprintln!("  terminal in the group; quote it in the shell),");
+    eprintln!("  @active, @self (the terminal cm ctl runs in) or @last (previously active).");
+    eprintln!("  Repeat -t to target several at once (e.g. send-keys -t api -t web ...)");
     eprintln!();
     eprintln!("Options:");
     eprintln!("  --raw    Output raw JSON response");
@@ -344,10 +960,22 @@ mod tests {
     }
 
     #[test]
-    fn build_request_send_keys_missing_target() {
+    fn build_request_send_keys_literal_flag() {
+        let args = s(&["-t", "1", "-l", "Enter", "-l"]);
+        let json_str = build_request("send-keys", &args).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["literal"], true);
+        let keys = v["keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], "Enter");
+        assert_eq!(keys[1], "-l");
+    }
+
+    #[test]
+    fn build_request_send_keys_defaults_to_caller_terminal() {
         let args = s(&["cargo test", "Enter"]);
-        let err = build_request("send-keys", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("send-keys", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
@@ -364,7 +992,7 @@ mod tests {
     fn build_request_send_keys_empty_args() {
         let args = s(&[]);
         let err = build_request("send-keys", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        assert!(err.contains("requires at least one key argument"), "got: {err}");
     }
 
     // ========================================================================
@@ -392,10 +1020,80 @@ mod tests {
     }
 
     #[test]
-    fn build_request_capture_pane_missing_target() {
+    fn build_request_capture_pane_with_line_range() {
+        let args = s(&["-t", "1", "-S", "-100", "-E", "-1"]);
+        let v: Value = serde_json::from_str(&build_request("capture-pane", &args).unwrap()).unwrap();
+        assert_eq!(v["start"], -100);
+        assert_eq!(v["end"], -1);
+        assert_eq!(v["scrollback"], false);
+    }
+
+    #[test]
+    fn build_request_capture_pane_dash_means_history_start_and_screen_end() {
+        let args = s(&["-t", "1", "-S", "-", "-E", "-"]);
+        let v: Value = serde_json::from_str(&build_request("capture-pane", &args).unwrap()).unwrap();
+        assert_eq!(v["scrollback"], true);
+        assert!(v.get("start").is_none());
+        assert!(v.get("end").is_none());
+    }
+
+    #[test]
+    fn build_request_capture_pane_bare_scrollback_before_other_flags() {
+        let args = s(&["-t", "1", "-S", "-e"]);
+        let v: Value = serde_json::from_str(&build_request("capture-pane", &args).unwrap()).unwrap();
+        assert_eq!(v["scrollback"], true);
+        assert_eq!(v["format"], "ansi");
+    }
+
+    #[test]
+    fn build_request_capture_pane_cells_with_limit() {
+        let args = s(&["-t", "1", "--cells", "--limit", "50"]);
+        let v: Value = serde_json::from_str(&build_request("capture-pane", &args).unwrap()).unwrap();
+        assert_eq!(v["format"], "cells");
+        assert_eq!(v["limit"], 50);
+    }
+
+    #[test]
+    fn build_request_capture_pane_rejects_bad_options() {
+        let err = build_request("capture-pane", &s(&["-t", "1", "--limit", "0"])).unwrap_err();
+        assert!(err.contains("--limit"), "got: {err}");
+        let err = build_request("capture-pane", &s(&["-t", "1", "-E"])).unwrap_err();
+        assert!(err.contains("-E requires"), "got: {err}");
+        let err = build_request("capture-pane", &s(&["-t", "1", "-x"])).unwrap_err();
+        assert!(err.contains("unknown option"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_capture_pane_defaults_to_caller_terminal() {
         let args = s(&["-S"]);
-        let err = build_request("capture-pane", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("capture-pane", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
+    }
+
+    // ========================================================================
+    // Tests: take_instance_option
+    // ========================================================================
+
+    #[test]
+    fn take_instance_option_splits_leading_selector() {
+        let args = s(&["--instance", "work", "list-windows"]);
+        let (instance, rest) = take_instance_option(&args).unwrap();
+        assert_eq!(instance.as_deref(), Some("work"));
+        assert_eq!(rest, &s(&["list-windows"])[..]);
+    }
+
+    #[test]
+    fn take_instance_option_leaves_other_args_alone() {
+        let args = s(&["send-keys", "--instance", "Enter"]);
+        let (instance, rest) = take_instance_option(&args).unwrap();
+        assert_eq!(instance, None);
+        assert_eq!(rest, &args[..]);
+    }
+
+    #[test]
+    fn take_instance_option_requires_value() {
+        let err = take_instance_option(&s(&["--instance"])).unwrap_err();
+        assert!(err.contains("requires a name or pid"), "got: {err}");
     }
 
     // ========================================================================
@@ -424,10 +1122,10 @@ mod tests {
     }
 
     #[test]
-    fn build_request_paste_buffer_missing_target() {
+    fn build_request_paste_buffer_defaults_to_caller_terminal() {
         let args = s(&[]);
-        let err = build_request("paste-buffer", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("paste-buffer", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     // ========================================================================
@@ -471,6 +1169,188 @@ mod tests {
         assert_eq!(v["cmd"], "show-buffer");
     }
 
+    #[test]
+    fn build_request_kill_server_roundtrip_with_protocol() {
+        let json_str = build_request("kill-server", &s(&[])).unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json_str).unwrap();
+        assert_eq!(cmd, crate::domain::primitive::IpcCommand::KillServer);
+    }
+
+    // ========================================================================
+    // Tests: build_request — log-start / log-stop / log-status
+    // ========================================================================
+
+    #[test]
+    fn build_request_log_start_defaults() {
+        let json_str = build_request("log-start", &s(&["-t", "2"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "log-start", "target": 2}));
+    }
+
+    #[test]
+    fn build_request_log_start_with_options_roundtrip_with_protocol() {
+        let json_str =
+            build_request("log-start", &s(&["-t", "1", "--mode", "raw", "--max-size", "2M"]))
+                .unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json_str).unwrap();
+        assert_eq!(
+            cmd,
+            crate::domain::primitive::IpcCommand::LogStart {
+                target: 1,
+                mode: crate::domain::primitive::LogMode::Raw,
+                max_bytes: Some(2 * 1024 * 1024),
+            }
+        );
+    }
+
+    #[test]
+    fn build_request_log_start_defaults_to_caller_terminal() {
+        let v: Value = serde_json::from_str(&build_request("log-start", &s(&["--mode", "raw"])).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
+    }
+
+    #[test]
+    fn build_request_log_start_option_missing_value() {
+        assert!(build_request("log-start", &s(&["-t", "1", "--mode"])).is_err());
+        assert!(build_request("log-start", &s(&["-t", "1", "--max-size"])).is_err());
+    }
+
+    #[test]
+    fn build_request_log_start_unknown_option() {
+        let err = build_request("log-start", &s(&["-t", "1", "--gzip"])).unwrap_err();
+        assert!(err.contains("unknown option"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_log_stop_and_status() {
+        let v: Value =
+            serde_json::from_str(&build_request("log-stop", &s(&["-t", "3"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "log-stop", "target": 3}));
+        let v: Value =
+            serde_json::from_str(&build_request("log-status", &s(&["-t", "3"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "log-status", "target": 3}));
+    }
+
+    // ========================================================================
+    // Tests: build_request — watch
+    // ========================================================================
+
+    #[test]
+    fn build_request_watch_without_output() {
+        let json_str = build_request("watch", &[]).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["cmd"], "subscribe");
+        assert!(v["output"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn build_request_watch_with_output_targets() {
+        let args = s(&["--output", "2", "--output", "5"]);
+        let json_str = build_request("watch", &args).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["output"], serde_json::json!([2, 5]));
+    }
+
+    #[test]
+    fn build_request_watch_invalid_output_id() {
+        let args = s(&["--output", "x"]);
+        let err = build_request("watch", &args).unwrap_err();
+        assert!(err.contains("invalid terminal ID: x"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: build_request — record-start / record-stop
+    // ========================================================================
+
+    #[test]
+    fn build_request_record_start_default_path() {
+        let v: Value =
+            serde_json::from_str(&build_request("record-start", &s(&["-t", "1"])).unwrap())
+                .unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "record-start", "target": 1}));
+    }
+
+    #[test]
+    fn build_request_record_start_relative_file_is_resolved() {
+        let json_str = build_request("record-start", &s(&["-t", "1", "--file", "out.cast"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        let expected = std::env::current_dir().unwrap().join("out.cast");
+        assert_eq!(v["path"], expected.to_string_lossy().as_ref());
+    }
+
+    #[test]
+    fn build_request_record_start_file_missing_value() {
+        let err = build_request("record-start", &s(&["-t", "1", "--file"])).unwrap_err();
+        assert!(err.contains("--file requires a value"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_record_stop_roundtrip_with_protocol() {
+        let json_str = build_request("record-stop", &s(&["-t", "2"])).unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json_str).unwrap();
+        assert_eq!(cmd, crate::domain::primitive::IpcCommand::RecordStop { target: 2 });
+    }
+
+    #[test]
+    fn build_request_wait_for_regex_with_timeout() {
+        let args = s(&["-t", "3", "--regex", "PASS|FAIL", "--timeout", "60s"]);
+        let json = build_request("wait-for", &args).unwrap();
+        assert_eq!(
+            crate::infrastructure::ipc::protocol::parse_command(&json).unwrap(),
+            crate::domain::primitive::IpcCommand::WaitFor {
+                target: 3,
+                condition: crate::domain::primitive::WaitCondition::Match {
+                    pattern: "PASS|FAIL".to_string(),
+                    regex: true,
+                },
+                timeout_ms: Some(60_000),
+            }
+        );
+    }
+
+    #[test]
+    fn build_request_wait_for_text_idle_and_exit() {
+        let v: Value = serde_json::from_str(&build_request("wait-for", &s(&["-t", "1", "$ "])).unwrap()).unwrap();
+        assert_eq!(v["pattern"], "$ ");
+        assert!(v.get("regex").is_none());
+        let v: Value = serde_json::from_str(&build_request("wait-for", &s(&["-t", "1", "--idle", "500ms"])).unwrap()).unwrap();
+        assert_eq!(v["idle_ms"], 500);
+        let v: Value = serde_json::from_str(&build_request("wait-for", &s(&["-t", "1", "--exit"])).unwrap()).unwrap();
+        assert_eq!(v["exit"], true);
+    }
+
+    #[test]
+    fn build_request_wait_for_requires_one_condition() {
+        assert!(build_request("wait-for", &s(&["-t", "1"])).is_err());
+        assert!(build_request("wait-for", &s(&["-t", "1", "--exit", "--idle", "1s"])).is_err());
+        assert!(build_request("wait-for", &s(&["-t", "1", "--exit", "--timeout"])).is_err());
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("250ms"), Ok(250));
+        assert_eq!(parse_duration("30s"), Ok(30_000));
+        assert_eq!(parse_duration("2m"), Ok(120_000));
+        assert_eq!(parse_duration("5"), Ok(5_000));
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("100").unwrap(), 100);
+        assert_eq!(parse_size("512K").unwrap(), 512 * 1024);
+        assert_eq!(parse_size("10m").unwrap(), 10 * 1024 * 1024);
+        assert_eq!(parse_size("1G").unwrap(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn parse_size_rejects_invalid() {
+        for value in ["", "M", "0", "-1", "1.5M", "10T", "99999999999999999999G"] {
+            assert!(parse_size(value).is_err(), "accepted: {value}");
+        }
+    }
+
     // ========================================================================
     // Tests: build_request — unknown subcommand
     // ========================================================================
@@ -516,28 +1396,98 @@ mod tests {
     fn parse_target_and_rest_missing_id() {
         let args = s(&["-t"]);
         let err = parse_target_and_rest(&args, "test-cmd").unwrap_err();
-        assert!(err.contains("-t requires a terminal ID"), "got: {err}");
+        assert!(err.contains("-t requires a target"), "got: {err}");
     }
 
     #[test]
-    fn parse_target_and_rest_invalid_id() {
-        let args = s(&["-t", "abc"]);
+    fn parse_target_and_rest_invalid_specifier() {
+        let args = s(&["-t", "@bogus"]);
         let err = parse_target_and_rest(&args, "test-cmd").unwrap_err();
-        assert!(err.contains("invalid terminal ID: abc"), "got: {err}");
+        assert!(err.contains("invalid target: @bogus"), "got: {err}");
     }
 
     #[test]
     fn parse_target_and_rest_negative_id() {
         let args = s(&["-t", "-1"]);
         let err = parse_target_and_rest(&args, "test-cmd").unwrap_err();
-        assert!(err.contains("invalid terminal ID: -1"), "got: {err}");
+        assert!(err.contains("invalid target: -1"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_target_and_rest_names_and_specifiers_are_strings() {
+        for spec in ["api-server", "agent-*", "@active", "@self", "@last"] {
+            let args = s(&["-t", spec]);
+            let (target, _) = parse_target_and_rest(&args, "test-cmd").unwrap();
+            assert_eq!(target, spec);
+        }
     }
 
     #[test]
-    fn parse_target_and_rest_no_target_flag() {
+    fn with_caller_adds_caller_id() {
+        let json = r#"{"cmd":"kill-window","target":"@self"}"#;
+        let v: Value = serde_json::from_str(&with_caller(json, Some(4))).unwrap();
+        assert_eq!(v["caller"], 4);
+        assert_eq!(v["target"], "@self");
+        assert_eq!(with_caller(json, None), json);
+    }
+
+    #[test]
+    fn caller_terminal_id_comes_from_the_spawn_env() {
+        let saved = std::env::var(TERMINAL_ID_ENV).ok();
+        unsafe {
+            std::env::set_var(TERMINAL_ID_ENV, "3");
+        }
+        let inside = caller_terminal_id();
+        unsafe {
+            std::env::remove_var(TERMINAL_ID_ENV);
+        }
+        let outside = caller_terminal_id();
+        if let Some(val) = saved {
+            unsafe {
+                std::env::set_var(TERMINAL_ID_ENV, val);
+            }
+        }
+        assert_eq!(inside, Some(3));
+        assert_eq!(outside, None);
+    }
+
+    #[test]
+    fn request_without_target_goes_to_the_caller() {
+        let json = build_request("kill-window", &[]).unwrap();
+        let v: Value = serde_json::from_str(&with_caller(&json, Some(3))).unwrap();
+        assert_eq!(v["target"], "@self");
+        assert_eq!(v["caller"], 3);
+        // Outside a cm terminal the server is left to reject @self
+        let v: Value = serde_json::from_str(&with_caller(&json, None)).unwrap();
+        assert_eq!(v["target"], "@self");
+        assert!(v.get("caller").is_none());
+    }
+
+    #[test]
+    fn explicit_target_is_kept_alongside_the_caller() {
+        let json = build_request("kill-window", &s(&["-t", "api"])).unwrap();
+        let v: Value = serde_json::from_str(&with_caller(&json, Some(3))).unwrap();
+        assert_eq!(v["target"], "api");
+        assert_eq!(v["caller"], 3);
+    }
+
+    #[test]
+    fn caller_is_only_sent_to_its_own_instance() {
+        let own = "/tmp/cm-100.sock";
+        assert_eq!(own_instance_caller(Some(3), Some(own), own), Some(3));
+        // `--instance` picked another instance: terminal 3 there is not us
+        assert_eq!(own_instance_caller(Some(3), Some(own), "/tmp/cm-200.sock"), None);
+        // Not running inside any instance
+        assert_eq!(own_instance_caller(Some(3), None, own), None);
+        assert_eq!(own_instance_caller(None, Some(own), own), None);
+    }
+
+    #[test]
+    fn parse_target_and_rest_no_target_flag_defaults_to_self() {
         let args = s(&["foo", "bar"]);
-        let err = parse_target_and_rest(&args, "test-cmd").unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let (target, rest) = parse_target_and_rest(&args, "test-cmd").unwrap();
+        assert_eq!(target, "@self");
+        assert_eq!(rest, s(&["foo", "bar"]));
     }
 
     #[test]
@@ -548,6 +1498,14 @@ mod tests {
         assert_eq!(rest, vec!["key1".to_string()]);
     }
 
+    #[test]
+    fn parse_target_and_rest_repeated_flag_is_a_list() {
+        let args = s(&["-t", "2", "-t", "api", "ls"]);
+        let (target, rest) = parse_target_and_rest(&args, "test-cmd").unwrap();
+        assert_eq!(target, serde_json::json!([2, "api"]));
+        assert_eq!(rest, s(&["ls"]));
+    }
+
     #[test]
     fn parse_target_and_rest_zero_id() {
         let args = s(&["-t", "0"]);
@@ -605,7 +1563,7 @@ mod tests {
         let saved_discovery = std::fs::read_to_string(&discovery_path).ok();
         let _ = std::fs::remove_file(&discovery_path);
 
-        let result = send_request("{}");
+        let result = send_request("{}", None);
 
         // Restore env var
         if let Some(val) = saved {
@@ -631,7 +1589,7 @@ mod tests {
         unsafe {
             std::env::set_var("CLI_MANAGER_SOCK", "/tmp/nonexistent-cli-manager-test.sock");
         }
-        let result = send_request("{}");
+        let result = send_request("{}", None);
         // Restore
         if let Some(val) = saved {
             unsafe {
@@ -715,6 +1673,236 @@ mod tests {
         assert!(err.contains("unknown option: --foo"), "got: {err}");
     }
 
+    #[test]
+    fn build_request_create_window_with_absolute_cwd() {
+        let args = s(&["--cwd", "/var/tmp"]);
+        let json_str = build_request("create-window", &args).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["cwd"], "/var/tmp");
+    }
+
+    #[test]
+    fn build_request_create_window_relative_cwd_is_resolved() {
+        let args = s(&["--cwd", "sub/dir"]);
+        let json_str = build_request("create-window", &args).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        let cwd = v["cwd"].as_str().unwrap();
+        assert!(std::path::Path::new(cwd).is_absolute(), "got: {cwd}");
+        assert!(cwd.ends_with("sub/dir"), "got: {cwd}");
+    }
+
+    #[test]
+    fn build_request_create_window_cwd_missing_value() {
+        let args = s(&["--cwd"]);
+        let err = build_request("create-window", &args).unwrap_err();
+        assert!(err.contains("--cwd requires a value"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_create_window_with_multiple_env() {
+        let args = s(&["--env", "RUST_LOG=debug", "--env", "URL=a=b"]);
+        let json_str = build_request("create-window", &args).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["env"]["RUST_LOG"], "debug");
+        assert_eq!(v["env"]["URL"], "a=b");
+    }
+
+    #[test]
+    fn build_request_create_window_env_without_equals() {
+        let args = s(&["--env", "RUST_LOG"]);
+        let err = build_request("create-window", &args).unwrap_err();
+        assert!(err.contains("KEY=VALUE"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_create_window_env_empty_key() {
+        let args = s(&["--env", "=x"]);
+        let err = build_request("create-window", &args).unwrap_err();
+        assert!(err.contains("KEY=VALUE"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_create_window_full_roundtrip_with_protocol() {
+        let args = s(&["--name", "w", "--cmd", "cargo watch", "--cwd", "/w", "--env", "A=1", "--restart", "always"]);
+        let json_str = build_request("create-window", &args).unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json_str).unwrap();
+        assert_eq!(
+            cmd,
+            crate::domain::primitive::IpcCommand::CreateWindow {
+                name: Some("w".to_string()),
+                command: Some("cargo watch".to_string()),
+                cwd: Some("/w".to_string()),
+                env: vec![("A".to_string(), "1".to_string())],
+                restart: RestartPolicy::Always,
+                group: None,
+            }
+        );
+    }
+
+    #[test]
+    fn build_request_create_window_invalid_restart_policy() {
+        let args = s(&["--cmd", "npm start", "--restart", "forever"]);
+        let err = build_request("create-window", &args).unwrap_err();
+        assert!(err.contains("invalid restart policy"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: build_request — respawn-window
+    // ========================================================================
+
+    #[test]
+    fn build_request_respawn_window() {
+        let json_str = build_request("respawn-window", &s(&["-t", "3"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["cmd"], "respawn-window");
+        assert_eq!(v["target"], 3);
+        assert!(v.get("kill").is_none());
+
+        let json_str = build_request("respawn-window", &s(&["-t", "3", "-k"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["kill"], true);
+
+        let err = build_request("respawn-window", &s(&["--force"])).unwrap_err();
+        assert!(err.contains("unknown option: --force"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: build_request — send-signal
+    // ========================================================================
+
+    #[test]
+    fn build_request_send_signal() {
+        let json_str = build_request("send-signal", &s(&["-t", "dev", "int"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["cmd"], "send-signal");
+        assert_eq!(v["target"], "dev");
+        assert_eq!(v["signal"], "SIGINT");
+    }
+
+    #[test]
+    fn build_request_send_signal_needs_one_valid_signal() {
+        let err = build_request("send-signal", &s(&["-t", "2"])).unwrap_err();
+        assert!(err.contains("requires one signal"), "got: {err}");
+        let err = build_request("send-signal", &s(&["INT", "TERM"])).unwrap_err();
+        assert!(err.contains("requires one signal"), "got: {err}");
+        let err = build_request("send-signal", &s(&["USR1"])).unwrap_err();
+        assert!(err.starts_with("invalid signal"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: build_request — move-window, sort-windows, filter-windows
+    // ========================================================================
+
+    #[test]
+    fn build_request_move_window() {
+        let v: Value = serde_json::from_str(&build_request("move-window", &s(&["--up"])).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
+        assert_eq!(v["by"], -1);
+        let v: Value = serde_json::from_str(&build_request("move-window", &s(&["-t", "2", "--by", "-3"])).unwrap()).unwrap();
+        assert_eq!(v["by"], -3);
+        let v: Value = serde_json::from_str(&build_request("move-window", &s(&["--to", "0"])).unwrap()).unwrap();
+        assert_eq!(v["to"], 0);
+        let v: Value = serde_json::from_str(&build_request("move-window", &s(&["--swap", "4"])).unwrap()).unwrap();
+        assert_eq!(v["swap"], 4);
+        let v: Value = serde_json::from_str(&build_request("move-window", &s(&["--swap", "api"])).unwrap()).unwrap();
+        assert_eq!(v["swap"], "api");
+    }
+
+    #[test]
+    fn build_request_move_window_needs_one_valid_move() {
+        for args in [&[][..], &["--up", "--down"], &["--to"], &["--left"]] {
+            let err = build_request("move-window", &s(args)).unwrap_err();
+            assert!(err.contains("requires"), "got: {err}");
+        }
+        let err = build_request("move-window", &s(&["--to", "-1"])).unwrap_err();
+        assert!(err.contains("invalid --to"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_sort_windows() {
+        let v: Value = serde_json::from_str(&build_request("sort-windows", &s(&["activity"])).unwrap()).unwrap();
+        assert_eq!(v["key"], "activity");
+        let err = build_request("sort-windows", &s(&["size"])).unwrap_err();
+        assert!(err.starts_with("invalid sort key"), "got: {err}");
+        let err = build_request("sort-windows", &s(&[])).unwrap_err();
+        assert!(err.contains("requires one key"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_filter_windows() {
+        let json_str = build_request("filter-windows", &s(&["--status", "running", "api", "server"])).unwrap();
+        let v: Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(v["status"], "running");
+        assert_eq!(v["text"], "api server");
+        let v: Value = serde_json::from_str(&build_request("filter-windows", &s(&[])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "filter-windows"}));
+        let err = build_request("filter-windows", &s(&["--status", "idle"])).unwrap_err();
+        assert!(err.starts_with("invalid status filter"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_create_window_with_group() {
+        let v: Value = serde_json::from_str(&build_request("create-window", &s(&["--group", "api"])).unwrap()).unwrap();
+        assert_eq!(v["group"], "api");
+        let err = build_request("create-window", &s(&["--group"])).unwrap_err();
+        assert!(err.contains("--group requires a value"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_set_group() {
+        let v: Value = serde_json::from_str(&build_request("set-group", &s(&["-t", "2", "api"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "set-group", "target": 2, "group": "api"}));
+        let v: Value = serde_json::from_str(&build_request("set-group", &s(&[])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "set-group", "target": "@self"}));
+        let err = build_request("set-group", &s(&["a", "b"])).unwrap_err();
+        assert!(err.contains("one group name"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_toggle_group() {
+        let v: Value = serde_json::from_str(&build_request("toggle-group", &s(&["api"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "toggle-group", "group": "api"}));
+        let v: Value = serde_json::from_str(&build_request("toggle-group", &s(&["--collapse", "api"])).unwrap()).unwrap();
+        assert_eq!(v["collapsed"], true);
+        let v: Value = serde_json::from_str(&build_request("toggle-group", &s(&["api", "--expand"])).unwrap()).unwrap();
+        assert_eq!(v["collapsed"], false);
+        let err = build_request("toggle-group", &s(&[])).unwrap_err();
+        assert!(err.contains("requires a group name"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_mark_window_and_sync_input() {
+        let v: Value = serde_json::from_str(&build_request("mark-window", &s(&["-t", "2"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "mark-window", "target": 2}));
+        let v: Value = serde_json::from_str(&build_request("mark-window", &s(&["--off"])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "mark-window", "target": "@self", "marked": false}));
+        let err = build_request("mark-window", &s(&["yes"])).unwrap_err();
+        assert!(err.contains("--on or --off"), "got: {err}");
+
+        let v: Value = serde_json::from_str(&build_request("sync-input", &s(&[])).unwrap()).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "sync-input"}));
+        let v: Value = serde_json::from_str(&build_request("sync-input", &s(&["on"])).unwrap()).unwrap();
+        assert_eq!(v["enabled"], true);
+        let err = build_request("sync-input", &s(&["maybe"])).unwrap_err();
+        assert!(err.contains("on or off"), "got: {err}");
+    }
+
+    #[test]
+    fn build_request_send_keys_to_several_targets_roundtrips_with_protocol() {
+        let json = build_request("send-keys", &s(&["-t", "1", "-t", "agent-*", "Enter"])).unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json).unwrap();
+        assert!(matches!(
+            cmd,
+            crate::domain::primitive::IpcCommand::Resolve { target: TargetSpec::List(ref items), .. } if items.len() == 2
+        ));
+    }
+
+    #[test]
+    fn build_request_group_target() {
+        let v: Value = serde_json::from_str(&build_request("kill-window", &s(&["-t", "#api"])).unwrap()).unwrap();
+        assert_eq!(v["target"], "#api");
+    }
+
     // ========================================================================
     // Tests: build_request — kill-window
     // ========================================================================
@@ -729,10 +1917,10 @@ mod tests {
     }
 
     #[test]
-    fn build_request_kill_window_missing_target() {
+    fn build_request_kill_window_defaults_to_caller_terminal() {
         let args = s(&[]);
-        let err = build_request("kill-window", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("kill-window", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     // ========================================================================
@@ -749,10 +1937,10 @@ mod tests {
     }
 
     #[test]
-    fn build_request_select_window_missing_target() {
+    fn build_request_select_window_defaults_to_caller_terminal() {
         let args = s(&[]);
-        let err = build_request("select-window", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("select-window", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     // ========================================================================
@@ -770,10 +1958,10 @@ mod tests {
     }
 
     #[test]
-    fn build_request_rename_window_missing_target() {
+    fn build_request_rename_window_defaults_to_caller_terminal() {
         let args = s(&["--name", "foo"]);
-        let err = build_request("rename-window", &args).unwrap_err();
-        assert!(err.contains("requires -t <id>"), "got: {err}");
+        let v: Value = serde_json::from_str(&build_request("rename-window", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
@@ -839,6 +2027,67 @@ mod tests {
         assert_eq!(v["name"], "renamed");
     }
 
+    // ========================================================================
+    // Tests: build_request — set-status
+    // ========================================================================
+
+    #[test]
+    fn build_request_set_status_with_icon_and_color() {
+        let args = s(&["-t", "agent-1", "waiting", "for", "approval", "--icon", "?", "--color", "yellow"]);
+        let v: Value = serde_json::from_str(&build_request("set-status", &args).unwrap()).unwrap();
+        assert_eq!(v["cmd"], "set-status");
+        assert_eq!(v["target"], "agent-1");
+        assert_eq!(v["text"], "waiting for approval");
+        assert_eq!(v["icon"], "?");
+        assert_eq!(v["color"], "yellow");
+    }
+
+    #[test]
+    fn build_request_set_status_defaults_to_caller_terminal() {
+        let v: Value = serde_json::from_str(&build_request("set-status", &s(&["done"])).unwrap()).unwrap();
+        assert_eq!(v["target"], "@self");
+        assert_eq!(v["text"], "done");
+        assert!(v.get("color").is_none());
+    }
+
+    #[test]
+    fn build_request_set_status_clear_sends_no_text() {
+        let v: Value = serde_json::from_str(&build_request("set-status", &s(&["--clear"])).unwrap()).unwrap();
+        assert!(v.get("text").is_none());
+    }
+
+    #[test]
+    fn build_request_set_status_rejects_bad_input() {
+        for (args, expected) in [
+            (s(&[]), "requires a text argument"),
+            (s(&["done", "--clear"]), "either a text or --clear"),
+            (s(&["done", "--color", "purple"]), "invalid color: purple"),
+            (s(&["done", "--icon"]), "--icon requires a value"),
+            (s(&["done", "--bold"]), "unknown option: --bold"),
+        ] {
+            let err = build_request("set-status", &args).unwrap_err();
+            assert!(err.contains(expected), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn build_request_set_status_roundtrip_with_protocol() {
+        let args = s(&["-t", "3", "tests failing", "--color", "#ff0000"]);
+        let json_str = build_request("set-status", &args).unwrap();
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(&json_str).unwrap();
+        assert_eq!(
+            cmd,
+            crate::domain::primitive::IpcCommand::SetStatus {
+                target: 3,
+                status: Some(StatusBadge {
+                    text: "tests failing".to_string(),
+                    icon: None,
+                    color: crate::domain::primitive::Color::Rgb(255, 0, 0),
+                }),
+            }
+        );
+    }
+
     // ========================================================================
     // Tests: build_request — notify
     // ========================================================================
@@ -864,6 +2113,17 @@ mod tests {
         assert!(v.get("title").is_none());
     }
 
+    #[test]
+    fn build_request_notify_target_is_optional() {
+        let v: Value = serde_json::from_str(&build_request("notify", &s(&["--body", "Hi"])).unwrap()).unwrap();
+        assert!(v.get("target").is_none());
+        let args = s(&["-t", "agent", "--body", "Hi"]);
+        let v: Value = serde_json::from_str(&build_request("notify", &args).unwrap()).unwrap();
+        assert_eq!(v["target"], "agent");
+        let err = build_request("notify", &s(&["--body", "Hi", "-t"])).unwrap_err();
+        assert!(err.contains("-t requires a target"), "got: {err}");
+    }
+
     #[test]
     fn build_request_notify_missing_body() {
         let args = s(&["--title", "Test"]);
@@ -927,7 +2187,7 @@ mod tests {
             "/tmp/nonexistent-cm-discovery-test.sock",
         );
 
-        let result = send_request("{}");
+        let result = send_request("{}", None);
 
         // Restore env var
         if let Some(val) = saved_env {
@@ -0,0 +1,327 @@
+//! Session event stream for `subscribe` connections (`cm ctl watch`).
+//!
+//! Events are derived by comparing the session against the state seen on
+//! the previous tick, so the usecase needs no event plumbing of its own:
+//! terminals that appear, disappear, change name, exit or report a new OSC 7
+//! cwd, and changes of the active terminal. Notifications and pty output are
+//! forwarded as they are drained from the usecase.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::primitive::{IpcEvent, IpcResponse, NotificationEvent, TerminalId, TerminalStatus};
+use crate::interface_adapter::port::{ConnectionId, IpcPort, PtyPort, ScreenPort};
+use crate::usecase::terminal_usecase::TerminalUsecase;
+
+/// A connection streaming session events.
+struct Subscriber {
+    /// Terminals whose output is streamed to this subscriber.
+    output: HashSet<u32>,
+}
+
+/// What the previous tick saw of one terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ObservedTerminal {
+    id: u32,
+    name: String,
+    status: TerminalStatus,
+    cwd: Option<String>,
+}
+
+/// What the previous tick saw of the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ObservedSession {
+    terminals: Vec<ObservedTerminal>,
+    active: Option<u32>,
+}
+
+/// Subscribed connections and the state their events are derived from.
+#[derive(Default)]
+pub struct EventStream {
+    subscribers: HashMap<ConnectionId, Subscriber>,
+    /// `None` while nobody is subscribed.
+    last: Option<ObservedSession>,
+    /// Trailing bytes of an incomplete UTF-8 sequence, per terminal.
+    utf8_tails: HashMap<u32, Vec<u8>>,
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turn the connection into an event stream and acknowledge it.
+    ///
+    /// `output` lists the terminals whose pty output the subscriber wants.
+    pub fn subscribe<P: PtyPort, S: ScreenPort>(
+        &mut self,
+        ipc: &mut dyn IpcPort,
+        conn_id: ConnectionId,
+        output: &[u32],
+        usecase: &mut TerminalUsecase<P, S>,
+    ) {
+        ipc.open_stream(conn_id);
+        ipc.send_response(conn_id, IpcResponse::Ok);
+        self.subscribers.insert(
+            conn_id,
+            Subscriber {
+                output: output.iter().copied().collect(),
+            },
+        );
+        if self.last.is_none() {
+            self.last = Some(observe(usecase));
+        }
+        self.update_watched_output(usecase);
+    }
+
+    /// Push the events since the previous call to every subscriber.
+    ///
+    /// `notifications` are the ones drained from the usecase this tick.
+    pub fn publish<P: PtyPort, S: ScreenPort>(
+        &mut self,
+        ipc: &mut dyn IpcPort,
+        usecase: &mut TerminalUsecase<P, S>,
+        notifications: &[(String, NotificationEvent)],
+    ) {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|conn_id, _| ipc.is_connected(*conn_id));
+        if self.subscribers.is_empty() {
+            if self.last.take().is_some() {
+                self.utf8_tails.clear();
+                usecase.set_watched_output(HashSet::new());
+            }
+            return;
+        }
+
+        // Output was read before the changes it may have caused (an exit,
+        // a notification), so it goes out after creations and before the rest
+        let current = observe(usecase);
+        let changes = self
+            .last
+            .as_ref()
+            .map(|last| diff(last, &current))
+            .unwrap_or_default();
+        self.last = Some(current);
+        let (mut events, changes): (Vec<_>, Vec<_>) = changes
+            .into_iter()
+            .partition(|e| matches!(e, IpcEvent::TerminalCreated { .. }));
+        for (id, data) in usecase.take_watched_output() {
+            if let Some(data) = self.decode_output(id.value(), data) {
+                events.push(IpcEvent::Output { id: id.value(), data });
+            }
+        }
+        events.extend(notifications.iter().map(|(terminal, event)| IpcEvent::Notification {
+            terminal: terminal.clone(),
+            event: event.clone(),
+        }));
+        events.extend(changes);
+
+        let mut gone = Vec::new();
+        for (conn_id, subscriber) in &self.subscribers {
+            let wanted = events.iter().filter(|event| match event {
+                IpcEvent::Output { id, .. } => subscriber.output.contains(id),
+                _ => true,
+            });
+            for event in wanted {
+                if !ipc.send_event(*conn_id, event) {
+                    gone.push(*conn_id);
+                    break;
+                }
+            }
+        }
+        for conn_id in gone {
+            self.subscribers.remove(&conn_id);
+        }
+        if self.subscribers.len() != before {
+            self.update_watched_output(usecase);
+        }
+    }
+
+    /// Watch the output of every terminal some subscriber asked for.
+    fn update_watched_output<P: PtyPort, S: ScreenPort>(&mut self, usecase: &mut TerminalUsecase<P, S>) {
+        let ids: HashSet<TerminalId> = self
+            .subscribers
+            .values()
+            .flat_map(|s| s.output.iter().map(|id| TerminalId::new(*id)))
+            .collect();
+        self.utf8_tails.retain(|id, _| ids.contains(&TerminalId::new(*id)));
+        usecase.set_watched_output(ids);
+    }
+
+    /// Decode an output chunk as UTF-8, holding back a sequence split across
+    /// reads until the rest arrives. Invalid bytes become U+FFFD.
+    fn decode_output(&mut self, id: u32, data: Vec<u8>) -> Option<String> {
+        let mut bytes = self.utf8_tails.remove(&id).unwrap_or_default();
+        bytes.extend(data);
+        if let Err(e) = std::str::from_utf8(&bytes)
+            && e.error_len().is_none()
+        {
+            let tail = bytes.split_off(e.valid_up_to());
+            self.utf8_tails.insert(id, tail);
+        }
+        if bytes.is_empty() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+fn observe<P: PtyPort, S: ScreenPort>(usecase: &TerminalUsecase<P, S>) -> ObservedSession {
+    ObservedSession {
+        terminals: usecase
+            .get_terminals()
+            .iter()
+            .map(|t| ObservedTerminal {
+                id: t.id().value(),
+                name: t.name().to_string(),
+                status: t.status().clone(),
+                cwd: usecase.screen_port().get_cwd(t.id()).ok().flatten(),
+            })
+            .collect(),
+        active: usecase.get_active_terminal().map(|t| t.id().value()),
+    }
+}
+
+/// Events that turn `last` into `current`, in a stable order: closes first,
+/// then per-terminal changes in session order, then the focus change.
+fn diff(last: &ObservedSession, current: &ObservedSession) -> Vec<IpcEvent> {
+    let mut events = Vec::new();
+    for old in &last.terminals {
+        if !current.terminals.iter().any(|t| t.id == old.id) {
+            events.push(IpcEvent::TerminalClosed { id: old.id });
+        }
+    }
+    for t in &current.terminals {
+        let old = last.terminals.iter().find(|o| o.id == t.id);
+        match old {
+            None => events.push(IpcEvent::TerminalCreated {
+                id: t.id,
+                name: t.name.clone(),
+            }),
+            Some(old) if old.name != t.name => events.push(IpcEvent::TerminalRenamed {
+                id: t.id,
+                name: t.name.clone(),
+            }),
+            Some(_) => {}
+        }
+        if let TerminalStatus::Exited(code) = t.status
+            && old.is_none_or(|o| o.status.is_running())
+        {
+            events.push(IpcEvent::TerminalExited { id: t.id, exit_code: code });
+        }
+        if let Some(cwd) = &t.cwd
+            && old.is_none_or(|o| o.cwd.as_ref() != Some(cwd))
+        {
+            events.push(IpcEvent::CwdChanged { id: t.id, cwd: cwd.clone() });
+        }
+    }
+    if current.active != last.active {
+        events.push(IpcEvent::FocusChanged { id: current.active });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terminal(id: u32, name: &str) -> ObservedTerminal {
+        ObservedTerminal {
+            id,
+            name: name.to_string(),
+            status: TerminalStatus::Running,
+            cwd: None,
+        }
+    }
+
+    fn session(terminals: Vec<ObservedTerminal>, active: Option<u32>) -> ObservedSession {
+        ObservedSession { terminals, active }
+    }
+
+    // =========================================================================
+    // Tests: diff
+    // =========================================================================
+
+    #[test]
+    fn diff_of_identical_sessions_is_empty() {
+        let s = session(vec![terminal(1, "a")], Some(1));
+        assert!(diff(&s, &s.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_created_and_focus() {
+        let last = session(vec![terminal(1, "a")], Some(1));
+        let current = session(vec![terminal(1, "a"), terminal(2, "b")], Some(2));
+        assert_eq!(
+            diff(&last, &current),
+            vec![
+                IpcEvent::TerminalCreated { id: 2, name: "b".to_string() },
+                IpcEvent::FocusChanged { id: Some(2) },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_closed_before_other_changes() {
+        let last = session(vec![terminal(1, "a"), terminal(2, "b")], Some(2));
+        let current = session(vec![terminal(1, "renamed")], Some(1));
+        assert_eq!(
+            diff(&last, &current),
+            vec![
+                IpcEvent::TerminalClosed { id: 2 },
+                IpcEvent::TerminalRenamed { id: 1, name: "renamed".to_string() },
+                IpcEvent::FocusChanged { id: Some(1) },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_exit_once() {
+        let last = session(vec![terminal(1, "a")], Some(1));
+        let mut exited = terminal(1, "a");
+        exited.status = TerminalStatus::Exited(3);
+        let current = session(vec![exited], Some(1));
+        assert_eq!(
+            diff(&last, &current),
+            vec![IpcEvent::TerminalExited { id: 1, exit_code: 3 }]
+        );
+        assert!(diff(&current, &current.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_cwd_changes_only_when_known() {
+        let last = session(vec![terminal(1, "a")], None);
+        let mut moved = terminal(1, "a");
+        moved.cwd = Some("/tmp".to_string());
+        let current = session(vec![moved], None);
+        assert_eq!(
+            diff(&last, &current),
+            vec![IpcEvent::CwdChanged { id: 1, cwd: "/tmp".to_string() }]
+        );
+        // Losing the OSC 7 cwd is not reported
+        assert!(diff(&current, &last).is_empty());
+    }
+
+    // =========================================================================
+    // Tests: output decoding
+    // =========================================================================
+
+    #[test]
+    fn decode_output_holds_back_split_utf8_sequence() {
+        let mut stream = EventStream::new();
+        let bytes = "あ".as_bytes();
+        assert_eq!(stream.decode_output(1, bytes[..2].to_vec()), None);
+        assert_eq!(
+            stream.decode_output(1, [&bytes[2..], b"!"].concat()),
+            Some("あ!".to_string())
+        );
+    }
+
+    #[test]
+    fn decode_output_replaces_invalid_bytes() {
+        let mut stream = EventStream::new();
+        assert_eq!(
+            stream.decode_output(1, vec![b'a', 0xff, b'b']),
+            Some("a\u{fffd}b".to_string())
+        );
+    }
+}
@@ -1,27 +1,38 @@
-/// Parses a sequence of key strings into a byte vector.
-/// Special key names are converted to their control byte equivalents.
-/// Plain text is converted to UTF-8 bytes.
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::infrastructure::tui::input::{ctrl_byte, key_to_bytes};
+
+/// Parses a sequence of key strings into a byte vector, using tmux's
+/// `send-keys` key names.
+/// Special key names are converted to the bytes the key would send from a
+/// real xterm. Plain text is converted to UTF-8 bytes.
 ///
-/// Key mappings (case-insensitive):
-/// - `Enter`  -> `\r`
-/// - `Tab`    -> `\t`
-/// - `Escape` -> `\x1b`
-/// - `Space`  -> `\x20`
-/// - `BSpace` -> `\x7f`
-/// - `C-a` through `C-z` -> `\x01` through `\x1a`
+/// Key names (case-insensitive):
+/// - `Enter`, `Tab`, `BTab` (Shift+Tab), `Escape`, `Space`, `BSpace`
+/// - `Up`, `Down`, `Left`, `Right`, `Home`, `End`
+/// - `PageUp` / `PgUp` / `PPage`, `PageDown` / `PgDn` / `NPage`
+/// - `Insert` / `IC`, `Delete` / `DC`, `F1` through `F12`
+///
+/// Modifier prefixes, combinable (`C-M-x`):
+/// - `C-` Ctrl: `C-a`..`C-z`, `C-[` (ESC), `C-]`, `C-\\`, `C-Space`, `C-Up`, ...
+/// - `M-` Alt/Meta: ESC prefix for characters, `CSI 1;3 X` for cursor keys
+/// - `S-` Shift: upper-cases characters, `CSI 1;2 X` for cursor keys
 ///
 /// Processing rules:
 /// - Each string in the array is processed independently
-/// - Special keys are matched case-insensitively
-/// - `C-` prefix: the character after `C-` must be a single lowercase letter a-z (case-insensitive)
-/// - If a string doesn't match any special key, treat it as literal text (UTF-8 bytes)
-/// - `C-` followed by a non-letter returns an error
+/// - Arrow, Home and End keys follow the target's DECCKM state
+///   (`application_cursor_keys`): `ESC O A` when set, `ESC [ A` otherwise
+/// - `0xNN` sends the raw byte `NN` (`0x1b` = ESC); larger values such as
+///   `0x3042` send the UTF-8 encoding of that code point
+/// - `C-` with anything that has no control code returns an error
+/// - Any other string, including an unknown `M-`/`S-` combination, is
+///   literal text (UTF-8 bytes)
 /// - Empty input array returns Ok(empty vec)
-pub fn parse_keys(keys: &[String]) -> Result<Vec<u8>, String> {
+pub fn parse_keys(keys: &[String], application_cursor_keys: bool) -> Result<Vec<u8>, String> {
     let mut result = Vec::new();
 
     for key in keys {
-        let bytes = parse_single_key(key)?;
+        let bytes = parse_single_key(key, application_cursor_keys)?;
         result.extend_from_slice(&bytes);
     }
 
@@ -29,38 +40,101 @@ pub fn parse_keys(keys: &[String]) -> Result<Vec<u8>, String> {
 }
 
 /// Parse a single key string into bytes.
-/// Returns the special key byte(s) if it matches a known key name,
-/// otherwise returns the literal UTF-8 bytes of the string.
-fn parse_single_key(key: &str) -> Result<Vec<u8>, String> {
-    let lower = key.to_ascii_lowercase();
-
-    // Check named special keys (case-insensitive)
-    match lower.as_str() {
-        "enter" => return Ok(vec![b'\r']),
-        "tab" => return Ok(vec![b'\t']),
-        "escape" => return Ok(vec![0x1b]),
-        "space" => return Ok(vec![0x20]),
-        "bspace" => return Ok(vec![0x7f]),
-        _ => {}
-    }
-
-    // Check C- prefix (ctrl key combinations)
-    if let Some(rest) = lower.strip_prefix("c-") {
-        let mut chars = rest.chars();
-        match (chars.next(), chars.next()) {
-            (Some(ch), None) if ch.is_ascii_lowercase() => {
-                // Convert a-z to control byte 0x01-0x1a
-                let ctrl_byte = ch as u8 - b'a' + 1;
-                return Ok(vec![ctrl_byte]);
-            }
-            _ => {
-                return Err(format!("invalid ctrl key: {key}"));
-            }
+/// Returns the key's byte sequence if it is a key name (with optional
+/// modifiers) or a hex escape, otherwise the literal UTF-8 bytes of the string.
+fn parse_single_key(key: &str, application_cursor_keys: bool) -> Result<Vec<u8>, String> {
+    if let Some(bytes) = parse_hex(key)? {
+        return Ok(bytes);
+    }
+
+    // Strip modifier prefixes (case-insensitive)
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+    while rest.len() >= 2 && rest.as_bytes()[1] == b'-' {
+        let modifier = match rest.as_bytes()[0].to_ascii_lowercase() {
+            b'c' => KeyModifiers::CONTROL,
+            b'm' => KeyModifiers::ALT,
+            b's' => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        modifiers |= modifier;
+        rest = &rest[2..];
+    }
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+    let Some(code) = key_code(rest) else {
+        if ctrl {
+            return Err(format!("invalid ctrl key: {key}"));
+        }
+        // Not a key name: literal text
+        return Ok(key.as_bytes().to_vec());
+    };
+
+    let code = match code {
+        KeyCode::Char(c) if ctrl && ctrl_byte(c).is_none() => {
+            return Err(format!("invalid ctrl key: {key}"));
+        }
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            modifiers.remove(KeyModifiers::SHIFT);
+            KeyCode::Char(c.to_ascii_uppercase())
         }
+        code => code,
+    };
+
+    Ok(key_to_bytes(KeyEvent::new(code, modifiers), application_cursor_keys, 0))
+}
+
+/// Map a key name or a single character to a `KeyCode`.
+fn key_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
     }
 
-    // Literal text: return UTF-8 bytes
-    Ok(key.as_bytes().to_vec())
+    let lower = name.to_ascii_lowercase();
+    let code = match lower.as_str() {
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "btab" => KeyCode::BackTab,
+        "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "bspace" => KeyCode::Backspace,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" | "ppage" => KeyCode::PageUp,
+        "pagedown" | "pgdn" | "npage" => KeyCode::PageDown,
+        "insert" | "ic" => KeyCode::Insert,
+        "delete" | "dc" => KeyCode::Delete,
+        _ => {
+            let n: u8 = lower.strip_prefix('f')?.parse().ok()?;
+            if !(1..=12).contains(&n) {
+                return None;
+            }
+            KeyCode::F(n)
+        }
+    };
+    Some(code)
+}
+
+/// Parse a `0xNN` hex escape. `Ok(None)` if the string is not one.
+fn parse_hex(key: &str) -> Result<Option<Vec<u8>>, String> {
+    let Some(digits) = key.strip_prefix("0x").or_else(|| key.strip_prefix("0X")) else {
+        return Ok(None);
+    };
+    if digits.is_empty() || digits.len() > 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(None);
+    }
+    let value = u32::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+    if let Ok(byte) = u8::try_from(value) {
+        return Ok(Some(vec![byte]));
+    }
+    char::from_u32(value)
+        .map(|c| Some(c.to_string().into_bytes()))
+        .ok_or_else(|| format!("invalid key code: {key}"))
 }
 
 #[cfg(test)]
@@ -70,7 +144,7 @@ mod tests {
     #[test]
     fn literal_text_with_enter() {
         let keys = vec!["cargo test".to_string(), "Enter".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         let mut expected = b"cargo test".to_vec();
         expected.push(b'\r');
         assert_eq!(result, expected);
@@ -79,105 +153,105 @@ mod tests {
     #[test]
     fn ctrl_c() {
         let keys = vec!["C-c".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x03]);
     }
 
     #[test]
     fn ctrl_d() {
         let keys = vec!["C-d".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x04]);
     }
 
     #[test]
     fn escape_then_q() {
         let keys = vec!["Escape".to_string(), "q".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x1b, b'q']);
     }
 
     #[test]
     fn tab_key() {
         let keys = vec!["Tab".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![b'\t']);
     }
 
     #[test]
     fn bspace_key() {
         let keys = vec!["BSpace".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x7f]);
     }
 
     #[test]
     fn space_key() {
         let keys = vec!["Space".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x20]);
     }
 
     #[test]
     fn ctrl_a() {
         let keys = vec!["C-a".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x01]);
     }
 
     #[test]
     fn ctrl_z() {
         let keys = vec!["C-z".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x1a]);
     }
 
     #[test]
     fn case_insensitive_enter_uppercase() {
         let keys = vec!["ENTER".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![b'\r']);
     }
 
     #[test]
     fn case_insensitive_enter_lowercase() {
         let keys = vec!["enter".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![b'\r']);
     }
 
     #[test]
     fn case_insensitive_ctrl_lowercase_prefix() {
         let keys = vec!["c-c".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x03]);
     }
 
     #[test]
     fn case_insensitive_ctrl_uppercase_letter() {
         let keys = vec!["C-C".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, vec![0x03]);
     }
 
     #[test]
     fn empty_array() {
         let keys: Vec<String> = vec![];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, Vec::<u8>::new());
     }
 
     #[test]
     fn japanese_text() {
         let keys = vec!["テスト".to_string()];
-        let result = parse_keys(&keys).unwrap();
+        let result = parse_keys(&keys, false).unwrap();
         assert_eq!(result, "テスト".as_bytes().to_vec());
     }
 
     #[test]
     fn ctrl_digit_error() {
         let keys = vec!["C-1".to_string()];
-        let result = parse_keys(&keys);
+        let result = parse_keys(&keys, false);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "invalid ctrl key: C-1");
     }
@@ -185,8 +259,106 @@ mod tests {
     #[test]
     fn ctrl_no_letter_error() {
         let keys = vec!["C-".to_string()];
-        let result = parse_keys(&keys);
+        let result = parse_keys(&keys, false);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "invalid ctrl key: C-");
     }
+
+    // ========================================================================
+    // Tests: extended key names
+    // ========================================================================
+
+    fn parse(keys: &[&str]) -> Vec<u8> {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        parse_keys(&keys, false).unwrap()
+    }
+
+    #[test]
+    fn arrow_keys_follow_decckm() {
+        assert_eq!(parse(&["Up", "Down", "Right", "Left"]), b"\x1b[A\x1b[B\x1b[C\x1b[D");
+        let keys = vec!["Up".to_string(), "Home".to_string()];
+        assert_eq!(parse_keys(&keys, true).unwrap(), b"\x1bOA\x1bOH");
+    }
+
+    #[test]
+    fn paging_and_editing_keys() {
+        assert_eq!(parse(&["PageUp"]), b"\x1b[5~");
+        assert_eq!(parse(&["PgDn"]), b"\x1b[6~");
+        assert_eq!(parse(&["NPage"]), b"\x1b[6~");
+        assert_eq!(parse(&["IC"]), b"\x1b[2~");
+        assert_eq!(parse(&["Delete"]), b"\x1b[3~");
+        assert_eq!(parse(&["Home", "End"]), b"\x1b[H\x1b[F");
+    }
+
+    #[test]
+    fn function_keys() {
+        assert_eq!(parse(&["F1"]), b"\x1bOP");
+        assert_eq!(parse(&["f5"]), b"\x1b[15~");
+        assert_eq!(parse(&["F12"]), b"\x1b[24~");
+        // Out of range: literal text
+        assert_eq!(parse(&["F13"]), b"F13");
+    }
+
+    #[test]
+    fn meta_prefix_sends_escape() {
+        assert_eq!(parse(&["M-x"]), b"\x1bx");
+        assert_eq!(parse(&["M-X"]), b"\x1bX");
+        assert_eq!(parse(&["M-BSpace"]), b"\x1b\x7f");
+    }
+
+    #[test]
+    fn combined_modifiers() {
+        assert_eq!(parse(&["C-M-x"]), b"\x1b\x18");
+        assert_eq!(parse(&["C-Right"]), b"\x1b[1;5C");
+        assert_eq!(parse(&["S-Up"]), b"\x1b[1;2A");
+        assert_eq!(parse(&["C-S-F5"]), b"\x1b[15;6~");
+        assert_eq!(parse(&["S-a"]), b"A");
+    }
+
+    #[test]
+    fn modified_cursor_keys_ignore_decckm() {
+        let keys = vec!["C-Up".to_string()];
+        assert_eq!(parse_keys(&keys, true).unwrap(), b"\x1b[1;5A");
+    }
+
+    #[test]
+    fn ctrl_punctuation() {
+        assert_eq!(parse(&["C-["]), vec![0x1b]);
+        assert_eq!(parse(&["C-]"]), vec![0x1d]);
+        assert_eq!(parse(&["C-\\"]), vec![0x1c]);
+        assert_eq!(parse(&["C-Space"]), vec![0x00]);
+    }
+
+    #[test]
+    fn shift_tab() {
+        assert_eq!(parse(&["BTab"]), b"\x1b[Z");
+        assert_eq!(parse(&["S-Tab"]), b"\x1b[Z");
+    }
+
+    #[test]
+    fn hex_escapes_send_raw_bytes() {
+        assert_eq!(parse(&["0x1b", "0x5b", "0x41"]), b"\x1b[A");
+        assert_eq!(parse(&["0xff"]), vec![0xff]);
+        assert_eq!(parse(&["0x3042"]), "あ".as_bytes().to_vec());
+        // Not hex digits: literal text
+        assert_eq!(parse(&["0xzz"]), b"0xzz");
+    }
+
+    #[test]
+    fn hex_escape_invalid_code_point_error() {
+        let result = parse_keys(&["0xd800".to_string()], false);
+        assert_eq!(result.unwrap_err(), "invalid key code: 0xd800");
+    }
+
+    #[test]
+    fn unknown_meta_combination_is_literal() {
+        assert_eq!(parse(&["s-expression"]), b"s-expression");
+        assert_eq!(parse(&["M-"]), b"M-");
+    }
+
+    #[test]
+    fn ctrl_with_unknown_name_error() {
+        let result = parse_keys(&["C-Foo".to_string()], false);
+        assert_eq!(result.unwrap_err(), "invalid ctrl key: C-Foo");
+    }
 }
@@ -1,7 +1,10 @@
+pub mod capture;
 pub mod cli_client;
+pub mod event_stream;
 pub mod key_parser;
 pub mod protocol;
 pub mod socket_discovery;
 pub mod unix_socket_server;
+pub mod wait_for;
 
 pub use unix_socket_server::UnixSocketServer;
@@ -3,9 +3,16 @@
 //! Converts between JSON strings and domain IPC types using serde intermediate
 //! structs. The domain types themselves do not derive serde traits (domain purity).
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::domain::primitive::{IpcCommand, IpcResponse, IpcResponseData, WindowInfo};
+use crate::domain::primitive::{
+    group_name, CaptureFormat, CaptureOptions, Cell, Color, CursorPos, FrameWindow, GroupHeader, IpcCommand, IpcEvent,
+    IpcResponse, IpcResponseData, LogMode, NotificationEvent, RestartPolicy, ScreenFrame, Signal, SortKey, StatusBadge, StatusFilter, TargetSpec,
+    TerminalFilter, TerminalStatus, WaitCondition,
+    WindowInfo, WindowMove,
+};
 
 // ============================================================================
 // Request (inbound) intermediate types
@@ -14,14 +21,81 @@ use crate::domain::primitive::{IpcCommand, IpcResponse, IpcResponseData, WindowI
 #[derive(Deserialize)]
 struct RawRequest {
     cmd: String,
+    #[serde(rename = "target")]
+    target_arg: Option<RawTarget>,
+    /// Numeric target the commands are built from, set from `target_arg`.
+    #[serde(skip)]
     target: Option<u32>,
+    /// Terminal the client runs in, for `@self`.
+    caller: Option<u32>,
     keys: Option<Vec<String>>,
+    literal: Option<bool>,
     scrollback: Option<bool>,
     text: Option<String>,
     name: Option<String>,
     command: Option<String>,
+    cwd: Option<String>,
+    env: Option<BTreeMap<String, String>>,
     title: Option<String>,
     body: Option<String>,
+    data: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    mode: Option<String>,
+    max_size: Option<u64>,
+    path: Option<String>,
+    output: Option<Vec<u32>>,
+    pattern: Option<String>,
+    regex: Option<bool>,
+    idle_ms: Option<u64>,
+    exit: Option<bool>,
+    timeout_ms: Option<u64>,
+    start: Option<i64>,
+    end: Option<i64>,
+    format: Option<String>,
+    limit: Option<usize>,
+    icon: Option<String>,
+    color: Option<RawColor>,
+    restart: Option<String>,
+    kill: Option<bool>,
+    signal: Option<String>,
+    to: Option<usize>,
+    by: Option<isize>,
+    swap: Option<RawTarget>,
+    key: Option<String>,
+    status: Option<String>,
+    group: Option<String>,
+    collapsed: Option<bool>,
+    marked: Option<bool>,
+    enabled: Option<bool>,
+}
+
+/// `target`: an id, a string parsed by `TargetSpec::parse`, or an array
+/// of those selecting several terminals at once.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTarget {
+    Id(u32),
+    Spec(String),
+    List(Vec<RawTarget>),
+}
+
+impl RawTarget {
+    fn into_spec(self) -> Result<TargetSpec, String> {
+        match self {
+            RawTarget::Id(id) => Ok(TargetSpec::Id(id)),
+            RawTarget::Spec(s) => TargetSpec::parse(&s),
+            RawTarget::List(items) if items.is_empty() => Err("invalid target: empty list".to_string()),
+            RawTarget::List(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    RawTarget::List(_) => Err("invalid target: nested list".to_string()),
+                    item => item.into_spec(),
+                })
+                .collect::<Result<_, _>>()
+                .map(TargetSpec::List),
+        }
+    }
 }
 
 // ============================================================================
@@ -45,14 +119,23 @@ struct DataResponse<T: Serialize> {
     data: T,
 }
 
+/// `text` for the text and ANSI formats, `rows` (style runs, as in frames)
+/// for the cells format.
 #[derive(Serialize)]
 struct CapturePaneData {
-    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows: Option<Vec<Vec<RawRun>>>,
     cursor: CursorData,
     size: SizeData,
     name: String,
     cwd: Option<String>,
     scrollback_total: usize,
+    start: i64,
+    end: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_start: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -74,6 +157,22 @@ struct WindowInfoData {
     cwd: Option<String>,
     is_active: bool,
     is_running: bool,
+    /// "running", "idle" or "exited".
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    process: Option<ProcessData>,
+    quiet_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    marked: bool,
+}
+
+#[derive(Serialize)]
+struct ProcessData {
+    pid: u32,
+    name: String,
+    cmdline: String,
 }
 
 #[derive(Serialize)]
@@ -81,6 +180,270 @@ struct BufferData {
     text: Option<String>,
 }
 
+#[derive(Serialize)]
+struct LogData {
+    logging: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes_written: Option<u64>,
+}
+
+// ============================================================================
+// Event (streamed) intermediate types
+// ============================================================================
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "kebab-case")]
+enum RawEvent {
+    Frame(RawFrame),
+    TerminalCreated { id: u32, name: String },
+    TerminalClosed { id: u32 },
+    TerminalRenamed { id: u32, name: String },
+    TerminalExited { id: u32, exit_code: i32 },
+    Notification(RawNotification),
+    CwdChanged { id: u32, cwd: String },
+    FocusChanged { id: Option<u32> },
+    Output { id: u32, data: String },
+}
+
+/// Notification raised by a terminal. `kind` is `bell`, `osc9`, `osc777`
+/// or `external`.
+#[derive(Serialize, Deserialize)]
+struct RawNotification {
+    terminal: String,
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    body: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawFrame {
+    windows: Vec<RawFrameWindow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<RawGroupHeader>,
+    /// Sidebar filter, omitted when it shows every terminal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter: Option<RawFilter>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    sync: bool,
+    active: Option<u32>,
+    cursor: RawCursor,
+    cursor_visible: bool,
+    application_cursor_keys: bool,
+    bracketed_paste: bool,
+    #[serde(default)]
+    keyboard_flags: u16,
+    /// Row width; rows are sent without trailing default cells.
+    cols: usize,
+    rows: Vec<Vec<RawRun>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawFrameWindow {
+    id: u32,
+    name: String,
+    cwd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    idle: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    process: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero_u64")]
+    quiet_mins: u64,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    memo: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    badge: Option<RawBadge>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    marked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notification: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawGroupHeader {
+    name: String,
+    count: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    collapsed: bool,
+    at: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawFilter {
+    status: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawBadge {
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<RawColor>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawCursor {
+    row: u16,
+    col: u16,
+}
+
+/// Consecutive cells sharing one style. `wide` runs hold double-width
+/// characters; their continuation cells are implied.
+#[derive(Serialize, Deserialize)]
+struct RawRun {
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fg: Option<RawColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bg: Option<RawColor>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    attrs: u8,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    wide: bool,
+}
+
+/// Palette index, or `"#rrggbb"` for true color. Absent means default.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Indexed(u8),
+    Rgb(String),
+}
+
+const ATTR_BOLD: u8 = 1;
+const ATTR_UNDERLINE: u8 = 1 << 1;
+const ATTR_ITALIC: u8 = 1 << 2;
+const ATTR_DIM: u8 = 1 << 3;
+const ATTR_REVERSE: u8 = 1 << 4;
+const ATTR_STRIKETHROUGH: u8 = 1 << 5;
+const ATTR_HIDDEN: u8 = 1 << 6;
+
+fn is_zero(v: &u8) -> bool {
+    *v == 0
+}
+
+fn is_zero_u64(v: &u64) -> bool {
+    *v == 0
+}
+
+fn color_to_raw(color: Color) -> Option<RawColor> {
+    match color {
+        Color::Default => None,
+        Color::Indexed(i) => Some(RawColor::Indexed(i)),
+        Color::Rgb(r, g, b) => Some(RawColor::Rgb(format!("#{r:02x}{g:02x}{b:02x}"))),
+    }
+}
+
+fn color_from_raw(raw: Option<&RawColor>) -> Result<Color, String> {
+    match raw {
+        None => Ok(Color::Default),
+        Some(RawColor::Indexed(i)) => Ok(Color::Indexed(*i)),
+        Some(RawColor::Rgb(hex)) => {
+            let digits = hex
+                .strip_prefix('#')
+                .filter(|d| d.len() == 6)
+                .ok_or_else(|| format!("invalid color: {hex}"))?;
+            let channel = |i: usize| {
+                u8::from_str_radix(&digits[i..i + 2], 16)
+                    .map_err(|_| format!("invalid color: {hex}"))
+            };
+            Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+        }
+    }
+}
+
+fn cell_attrs(cell: &Cell) -> u8 {
+    let mut attrs = 0;
+    for (set, bit) in [
+        (cell.bold, ATTR_BOLD),
+        (cell.underline, ATTR_UNDERLINE),
+        (cell.italic, ATTR_ITALIC),
+        (cell.dim, ATTR_DIM),
+        (cell.reverse, ATTR_REVERSE),
+        (cell.strikethrough, ATTR_STRIKETHROUGH),
+        (cell.hidden, ATTR_HIDDEN),
+    ] {
+        if set {
+            attrs |= bit;
+        }
+    }
+    attrs
+}
+
+/// Encode a row as style runs, dropping trailing default cells.
+fn encode_row(row: &[Cell]) -> Vec<RawRun> {
+    let blank = Cell::default();
+    let len = row.len() - row.iter().rev().take_while(|c| **c == blank).count();
+
+    let mut runs: Vec<RawRun> = Vec::new();
+    let mut prev: Option<&Cell> = None;
+    for cell in row[..len].iter().filter(|c| c.width != 0) {
+        let same_style = prev.is_some_and(|p| {
+            p.fg == cell.fg
+                && p.bg == cell.bg
+                && cell_attrs(p) == cell_attrs(cell)
+                && (p.width == 2) == (cell.width == 2)
+        });
+        match runs.last_mut() {
+            Some(run) if same_style => run.text.push(cell.ch),
+            _ => runs.push(RawRun {
+                text: cell.ch.to_string(),
+                fg: color_to_raw(cell.fg),
+                bg: color_to_raw(cell.bg),
+                attrs: cell_attrs(cell),
+                wide: cell.width == 2,
+            }),
+        }
+        prev = Some(cell);
+    }
+    runs
+}
+
+/// Decode style runs back into a row of `cols` cells.
+fn decode_row(runs: &[RawRun], cols: usize) -> Result<Vec<Cell>, String> {
+    let mut row = Vec::with_capacity(cols);
+    for run in runs {
+        let template = Cell {
+            fg: color_from_raw(run.fg.as_ref())?,
+            bg: color_from_raw(run.bg.as_ref())?,
+            bold: run.attrs & ATTR_BOLD != 0,
+            underline: run.attrs & ATTR_UNDERLINE != 0,
+            italic: run.attrs & ATTR_ITALIC != 0,
+            dim: run.attrs & ATTR_DIM != 0,
+            reverse: run.attrs & ATTR_REVERSE != 0,
+            strikethrough: run.attrs & ATTR_STRIKETHROUGH != 0,
+            hidden: run.attrs & ATTR_HIDDEN != 0,
+            ..Cell::default()
+        };
+        for ch in run.text.chars() {
+            if run.wide {
+                row.push(Cell { ch, width: 2, ..template });
+                row.push(Cell { ch: ' ', width: 0, ..template });
+            } else {
+                row.push(Cell { ch, ..template });
+            }
+        }
+    }
+    if row.len() < cols {
+        row.resize(cols, Cell::default());
+    }
+    Ok(row)
+}
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -89,9 +452,35 @@ struct BufferData {
 ///
 /// Returns `Err(String)` with a human-readable message on parse failure.
 pub fn parse_command(json: &str) -> Result<IpcCommand, String> {
-    let raw: RawRequest =
+    let mut raw: RawRequest =
         serde_json::from_str(json).map_err(|e| format!("invalid JSON: {e}"))?;
 
+    // Targets other than ids are resolved by the runner; the command is
+    // built with a placeholder id until then
+    let spec = match raw.target_arg.take().map(RawTarget::into_spec).transpose()? {
+        None => None,
+        Some(TargetSpec::Id(id)) => {
+            raw.target = Some(id);
+            None
+        }
+        Some(spec) => {
+            raw.target = Some(0);
+            Some(spec)
+        }
+    };
+    let caller = raw.caller;
+    let command = parse_raw_command(raw)?;
+    Ok(match spec {
+        Some(target) if command.has_target() => IpcCommand::Resolve {
+            target,
+            caller,
+            command: Box::new(command),
+        },
+        _ => command,
+    })
+}
+
+fn parse_raw_command(raw: RawRequest) -> Result<IpcCommand, String> {
     match raw.cmd.as_str() {
         "send-keys" => {
             let target = raw
@@ -100,16 +489,30 @@ pub fn parse_command(json: &str) -> Result<IpcCommand, String> {
             let keys = raw
                 .keys
                 .ok_or_else(|| "missing field: keys".to_string())?;
-            Ok(IpcCommand::SendKeys { target, keys })
+            let literal = raw.literal.unwrap_or(false);
+            Ok(IpcCommand::SendKeys { target, keys, literal })
         }
         "capture-pane" => {
             let target = raw
                 .target
                 .ok_or_else(|| "missing field: target".to_string())?;
-            let include_scrollback = raw.scrollback.unwrap_or(false);
+            let format = match raw.format.as_deref() {
+                None => CaptureFormat::default(),
+                Some(f) => CaptureFormat::parse(f)
+                    .ok_or_else(|| format!("invalid format: {f} (expected text, ansi or cells)"))?,
+            };
+            if raw.limit == Some(0) {
+                return Err("limit must be greater than 0".to_string());
+            }
             Ok(IpcCommand::CapturePane {
                 target,
-                include_scrollback,
+                options: CaptureOptions {
+                    start: raw.start,
+                    end: raw.end,
+                    include_scrollback: raw.scrollback.unwrap_or(false),
+                    format,
+                    limit: raw.limit,
+                },
             })
         }
         "list-windows" => Ok(IpcCommand::ListWindows),
@@ -129,6 +532,13 @@ pub fn parse_command(json: &str) -> Result<IpcCommand, String> {
         "create-window" => Ok(IpcCommand::CreateWindow {
             name: raw.name,
             command: raw.command,
+            cwd: raw.cwd,
+            env: raw.env.unwrap_or_default().into_iter().collect(),
+            restart: match raw.restart {
+                Some(policy) => RestartPolicy::parse(&policy)?,
+                None => RestartPolicy::Never,
+            },
+            group: raw.group.as_deref().and_then(group_name),
         }),
         "kill-window" => {
             let target = raw
@@ -136,12 +546,77 @@ pub fn parse_command(json: &str) -> Result<IpcCommand, String> {
                 .ok_or_else(|| "missing field: target".to_string())?;
             Ok(IpcCommand::KillWindow { target })
         }
+        "respawn-window" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::RespawnWindow { target, kill: raw.kill.unwrap_or(false) })
+        }
+        "send-signal" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            let signal = raw
+                .signal
+                .ok_or_else(|| "missing field: signal".to_string())?;
+            Ok(IpcCommand::SendSignal { target, signal: Signal::parse(&signal)? })
+        }
         "select-window" => {
             let target = raw
                 .target
                 .ok_or_else(|| "missing field: target".to_string())?;
             Ok(IpcCommand::SelectWindow { target })
         }
+        "move-window" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            let to = match (raw.by, raw.to, raw.swap) {
+                (Some(by), None, None) => WindowMove::By(by),
+                (None, Some(to), None) => WindowMove::To(to),
+                (None, None, Some(with)) => WindowMove::Swap { with: with.into_spec()?, caller: raw.caller },
+                _ => return Err("move-window needs one of: by, to, swap".to_string()),
+            };
+            Ok(IpcCommand::MoveWindow { target, to })
+        }
+        "sort-windows" => {
+            let key = raw
+                .key
+                .ok_or_else(|| "missing field: key".to_string())?;
+            Ok(IpcCommand::SortWindows { key: SortKey::parse(&key)? })
+        }
+        "filter-windows" => {
+            // No fields clear the filter
+            let status = match raw.status {
+                Some(status) => StatusFilter::parse(&status)?,
+                None => StatusFilter::All,
+            };
+            Ok(IpcCommand::FilterWindows {
+                filter: TerminalFilter { status, text: raw.text.unwrap_or_default() },
+            })
+        }
+        "set-group" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            // No group takes the terminal out of its group
+            Ok(IpcCommand::SetGroup { target, group: raw.group.as_deref().and_then(group_name) })
+        }
+        "toggle-group" => {
+            let group = raw
+                .group
+                .as_deref()
+                .and_then(group_name)
+                .ok_or_else(|| "missing field: group".to_string())?;
+            Ok(IpcCommand::ToggleGroup { group, collapsed: raw.collapsed })
+        }
+        "mark-window" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::MarkWindow { target, marked: raw.marked })
+        }
+        "sync-input" => Ok(IpcCommand::SyncInput { enabled: raw.enabled }),
         "rename-window" => {
             let target = raw
                 .target
@@ -154,15 +629,316 @@ pub fn parse_command(json: &str) -> Result<IpcCommand, String> {
         "notify" => {
             let body = raw.body
                 .ok_or_else(|| "missing field: body".to_string())?;
+            // Attributed to the caller's terminal unless told otherwise;
+            // clients only send `caller` to the instance it belongs to
             Ok(IpcCommand::Notify {
+                target: raw.target.or(raw.caller),
                 title: raw.title,
                 body,
             })
         }
+        "set-status" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            // No text clears the badge
+            let status = match raw.text.filter(|t| !t.is_empty()) {
+                None => None,
+                Some(text) => Some(StatusBadge {
+                    text,
+                    icon: raw.icon.filter(|i| !i.is_empty()),
+                    color: match raw.color {
+                        None => Color::Default,
+                        Some(RawColor::Indexed(i)) => Color::Indexed(i),
+                        Some(RawColor::Rgb(s)) => StatusBadge::parse_color(&s)?,
+                    },
+                }),
+            };
+            Ok(IpcCommand::SetStatus { target, status })
+        }
+        "input" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            let data = raw
+                .data
+                .ok_or_else(|| "missing field: data".to_string())?;
+            Ok(IpcCommand::Input { target, data })
+        }
+        "attach" => {
+            let cols = raw
+                .cols
+                .ok_or_else(|| "missing field: cols".to_string())?;
+            let rows = raw
+                .rows
+                .ok_or_else(|| "missing field: rows".to_string())?;
+            Ok(IpcCommand::Attach { cols, rows })
+        }
+        "kill-server" => Ok(IpcCommand::KillServer),
+        "log-start" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            let mode = match raw.mode.as_deref() {
+                None => LogMode::default(),
+                Some(m) => LogMode::parse(m)
+                    .ok_or_else(|| format!("invalid mode: {m} (expected raw or text)"))?,
+            };
+            if raw.max_size == Some(0) {
+                return Err("max_size must be greater than 0".to_string());
+            }
+            Ok(IpcCommand::LogStart {
+                target,
+                mode,
+                max_bytes: raw.max_size,
+            })
+        }
+        "log-stop" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::LogStop { target })
+        }
+        "log-status" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::LogStatus { target })
+        }
+        "record-start" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::RecordStart {
+                target,
+                path: raw.path,
+            })
+        }
+        "record-stop" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            Ok(IpcCommand::RecordStop { target })
+        }
+        "subscribe" => Ok(IpcCommand::Subscribe {
+            output: raw.output.unwrap_or_default(),
+        }),
+        "wait-for" => {
+            let target = raw
+                .target
+                .ok_or_else(|| "missing field: target".to_string())?;
+            let condition = match (raw.pattern, raw.idle_ms, raw.exit.unwrap_or(false)) {
+                (Some(pattern), None, false) => WaitCondition::Match {
+                    pattern,
+                    regex: raw.regex.unwrap_or(false),
+                },
+                (None, Some(ms), false) => WaitCondition::Idle { ms },
+                (None, None, true) => WaitCondition::Exit,
+                _ => return Err("wait-for requires exactly one of pattern, idle_ms or exit".to_string()),
+            };
+            Ok(IpcCommand::WaitFor {
+                target,
+                condition,
+                timeout_ms: raw.timeout_ms,
+            })
+        }
         other => Err(format!("unknown command: {other}")),
     }
 }
 
+fn notification_to_raw(terminal: &str, event: &NotificationEvent) -> RawNotification {
+    let (kind, title, body) = match event {
+        NotificationEvent::Bell => ("bell", None, String::new()),
+        NotificationEvent::Osc9 { message } => ("osc9", None, message.clone()),
+        NotificationEvent::Osc777 { title, body } => ("osc777", Some(title.clone()), body.clone()),
+        NotificationEvent::External { title, body } => ("external", Some(title.clone()), body.clone()),
+    };
+    RawNotification {
+        terminal: terminal.to_string(),
+        kind: kind.to_string(),
+        title,
+        body,
+    }
+}
+
+fn notification_from_raw(raw: RawNotification) -> Result<IpcEvent, String> {
+    let title = raw.title.unwrap_or_default();
+    let event = match raw.kind.as_str() {
+        "bell" => NotificationEvent::Bell,
+        "osc9" => NotificationEvent::Osc9 { message: raw.body },
+        "osc777" => NotificationEvent::Osc777 { title, body: raw.body },
+        "external" => NotificationEvent::External { title, body: raw.body },
+        other => return Err(format!("invalid notification kind: {other}")),
+    };
+    Ok(IpcEvent::Notification {
+        terminal: raw.terminal,
+        event,
+    })
+}
+
+/// Serialize an `IpcEvent` into a JSON string.
+pub fn serialize_event(event: &IpcEvent) -> String {
+    let raw = match event {
+        IpcEvent::Frame(frame) => RawEvent::Frame(RawFrame {
+            windows: frame
+                .windows
+                .iter()
+                .map(|w| RawFrameWindow {
+                    id: w.id,
+                    name: w.name.clone(),
+                    cwd: w.cwd.clone(),
+                    exit_code: match w.status {
+                        TerminalStatus::Running | TerminalStatus::Idle => None,
+                        TerminalStatus::Exited(code) => Some(code),
+                    },
+                    idle: w.status == TerminalStatus::Idle,
+                    process: w.process.clone(),
+                    quiet_mins: w.quiet_mins,
+                    memo: w.memo.clone(),
+                    badge: w.badge.as_ref().map(|b| RawBadge {
+                        text: b.text.clone(),
+                        icon: b.icon.clone(),
+                        color: color_to_raw(b.color),
+                    }),
+                    group: w.group.clone(),
+                    marked: w.marked,
+                    notification: w.notification.clone(),
+                })
+                .collect(),
+            groups: frame
+                .groups
+                .iter()
+                .map(|g| RawGroupHeader {
+                    name: g.name.clone(),
+                    count: g.count,
+                    collapsed: g.collapsed,
+                    at: g.at,
+                })
+                .collect(),
+            filter: frame.filter.is_active().then(|| RawFilter {
+                status: frame.filter.status.as_str().to_string(),
+                text: frame.filter.text.clone(),
+            }),
+            sync: frame.sync,
+            active: frame.active,
+            cursor: RawCursor {
+                row: frame.cursor.row,
+                col: frame.cursor.col,
+            },
+            cursor_visible: frame.cursor_visible,
+            application_cursor_keys: frame.application_cursor_keys,
+            bracketed_paste: frame.bracketed_paste,
+            keyboard_flags: frame.keyboard_flags,
+            cols: frame.cells.first().map_or(0, |r| r.len()),
+            rows: frame.cells.iter().map(|row| encode_row(row)).collect(),
+        }),
+        IpcEvent::TerminalCreated { id, name } => RawEvent::TerminalCreated {
+            id: *id,
+            name: name.clone(),
+        },
+        IpcEvent::TerminalClosed { id } => RawEvent::TerminalClosed { id: *id },
+        IpcEvent::TerminalRenamed { id, name } => RawEvent::TerminalRenamed {
+            id: *id,
+            name: name.clone(),
+        },
+        IpcEvent::TerminalExited { id, exit_code } => RawEvent::TerminalExited {
+            id: *id,
+            exit_code: *exit_code,
+        },
+        IpcEvent::Notification { terminal, event } => {
+            RawEvent::Notification(notification_to_raw(terminal, event))
+        }
+        IpcEvent::CwdChanged { id, cwd } => RawEvent::CwdChanged {
+            id: *id,
+            cwd: cwd.clone(),
+        },
+        IpcEvent::FocusChanged { id } => RawEvent::FocusChanged { id: *id },
+        IpcEvent::Output { id, data } => RawEvent::Output {
+            id: *id,
+            data: data.clone(),
+        },
+    };
+    serde_json::to_string(&raw).expect("serialize event")
+}
+
+/// Parse a JSON line streamed by the server into an `IpcEvent`.
+///
+/// Returns `Err(String)` for malformed lines and for lines that are plain
+/// responses rather than events.
+pub fn parse_event(json: &str) -> Result<IpcEvent, String> {
+    let raw: RawEvent = serde_json::from_str(json).map_err(|e| format!("invalid event: {e}"))?;
+    match raw {
+        RawEvent::Frame(raw) => {
+            let cells = raw
+                .rows
+                .iter()
+                .map(|runs| decode_row(runs, raw.cols))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(IpcEvent::Frame(ScreenFrame {
+                windows: raw
+                    .windows
+                    .into_iter()
+                    .map(|w| {
+                        let badge = match w.badge {
+                            None => None,
+                            Some(b) => Some(StatusBadge {
+                                text: b.text,
+                                icon: b.icon,
+                                color: color_from_raw(b.color.as_ref())?,
+                            }),
+                        };
+                        Ok(FrameWindow {
+                            id: w.id,
+                            name: w.name,
+                            cwd: w.cwd,
+                            status: match (w.exit_code, w.idle) {
+                                (Some(code), _) => TerminalStatus::Exited(code),
+                                (None, true) => TerminalStatus::Idle,
+                                (None, false) => TerminalStatus::Running,
+                            },
+                            memo: w.memo,
+                            badge,
+                            group: w.group,
+                            marked: w.marked,
+                            process: w.process,
+                            quiet_mins: w.quiet_mins,
+                            notification: w.notification,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?,
+                groups: raw
+                    .groups
+                    .into_iter()
+                    .map(|g| GroupHeader { name: g.name, count: g.count, collapsed: g.collapsed, at: g.at })
+                    .collect(),
+                filter: match raw.filter {
+                    None => TerminalFilter::default(),
+                    Some(f) => TerminalFilter { status: StatusFilter::parse(&f.status)?, text: f.text },
+                },
+                sync: raw.sync,
+                active: raw.active,
+                cells,
+                cursor: CursorPos {
+                    row: raw.cursor.row,
+                    col: raw.cursor.col,
+                },
+                cursor_visible: raw.cursor_visible,
+                application_cursor_keys: raw.application_cursor_keys,
+                bracketed_paste: raw.bracketed_paste,
+                keyboard_flags: raw.keyboard_flags,
+            }))
+        }
+        RawEvent::TerminalCreated { id, name } => Ok(IpcEvent::TerminalCreated { id, name }),
+        RawEvent::TerminalClosed { id } => Ok(IpcEvent::TerminalClosed { id }),
+        RawEvent::TerminalRenamed { id, name } => Ok(IpcEvent::TerminalRenamed { id, name }),
+        RawEvent::TerminalExited { id, exit_code } => Ok(IpcEvent::TerminalExited { id, exit_code }),
+        RawEvent::Notification(raw) => notification_from_raw(raw),
+        RawEvent::CwdChanged { id, cwd } => Ok(IpcEvent::CwdChanged { id, cwd }),
+        RawEvent::FocusChanged { id } => Ok(IpcEvent::FocusChanged { id }),
+        RawEvent::Output { id, data } => Ok(IpcEvent::Output { id, data }),
+    }
+}
+
 /// Serialize an `IpcResponse` into a JSON string.
 pub fn serialize_response(response: &IpcResponse) -> String {
     match response {
@@ -177,6 +953,10 @@ pub fn serialize_response(response: &IpcResponse) -> String {
         IpcResponse::OkWithData(data) => match data {
             IpcResponseData::CapturePane {
                 text,
+                cells,
+                start,
+                end,
+                next_start,
                 cursor_row,
                 cursor_col,
                 size_rows,
@@ -188,7 +968,10 @@ pub fn serialize_response(response: &IpcResponse) -> String {
                 let payload = DataResponse {
                     ok: true,
                     data: CapturePaneData {
-                        text: text.clone(),
+                        text: cells.is_none().then(|| text.clone()),
+                        rows: cells
+                            .as_ref()
+                            .map(|rows| rows.iter().map(|row| encode_row(row)).collect()),
                         cursor: CursorData {
                             row: *cursor_row,
                             col: *cursor_col,
@@ -200,6 +983,9 @@ pub fn serialize_response(response: &IpcResponse) -> String {
                         name: name.clone(),
                         cwd: cwd.clone(),
                         scrollback_total: *scrollback_total,
+                        start: *start,
+                        end: *end,
+                        next_start: *next_start,
                     },
                 };
                 serde_json::to_string(&payload).expect("serialize CapturePane")
@@ -213,6 +999,19 @@ pub fn serialize_response(response: &IpcResponse) -> String {
                         cwd: w.cwd.clone(),
                         is_active: w.is_active,
                         is_running: w.is_running,
+                        status: match (w.is_running, w.is_idle) {
+                            (false, _) => "exited",
+                            (true, true) => "idle",
+                            (true, false) => "running",
+                        },
+                        process: w.process.as_ref().map(|p| ProcessData {
+                            pid: p.pid,
+                            name: p.name.clone(),
+                            cmdline: p.cmdline.clone(),
+                        }),
+                        quiet_secs: w.quiet_secs,
+                        group: w.group.clone(),
+                        marked: w.marked,
                     })
                     .collect();
                 let payload = DataResponse {
@@ -241,6 +1040,78 @@ pub fn serialize_response(response: &IpcResponse) -> String {
                 };
                 serde_json::to_string(&payload).expect("serialize CreateWindow")
             }
+            IpcResponseData::Log { status } => {
+                let payload = DataResponse {
+                    ok: true,
+                    data: LogData {
+                        logging: status.is_some(),
+                        path: status.as_ref().map(|s| s.path.display().to_string()),
+                        mode: status.as_ref().map(|s| s.mode.as_str()),
+                        max_size: status.as_ref().map(|s| s.max_bytes),
+                        bytes_written: status.as_ref().map(|s| s.bytes_written),
+                    },
+                };
+                serde_json::to_string(&payload).expect("serialize Log")
+            }
+            IpcResponseData::Recording { path } => {
+                #[derive(Serialize)]
+                struct RecordingData<'a> {
+                    path: &'a str,
+                }
+                let payload = DataResponse {
+                    ok: true,
+                    data: RecordingData { path },
+                };
+                serde_json::to_string(&payload).expect("serialize Recording")
+            }
+            IpcResponseData::WaitMatch { text, line, row, col } => {
+                #[derive(Serialize)]
+                struct WaitMatchData<'a> {
+                    text: &'a str,
+                    line: &'a str,
+                    row: usize,
+                    col: usize,
+                }
+                let payload = DataResponse {
+                    ok: true,
+                    data: WaitMatchData { text, line, row: *row, col: *col },
+                };
+                serde_json::to_string(&payload).expect("serialize WaitMatch")
+            }
+            IpcResponseData::Exited { exit_code } => {
+                #[derive(Serialize)]
+                struct ExitedData {
+                    exit_code: i32,
+                }
+                let payload = DataResponse {
+                    ok: true,
+                    data: ExitedData { exit_code: *exit_code },
+                };
+                serde_json::to_string(&payload).expect("serialize Exited")
+            }
+            IpcResponseData::Targets { results } => {
+                // Each result is a full response of its own, tagged with the
+                // terminal it came from
+                #[derive(Serialize)]
+                struct TargetsData {
+                    results: Vec<serde_json::Value>,
+                }
+                let results = results
+                    .iter()
+                    .map(|(id, response)| {
+                        let mut value: serde_json::Value =
+                            serde_json::from_str(&serialize_response(response))
+                                .expect("serialized response is JSON");
+                        value["id"] = serde_json::json!(id);
+                        value
+                    })
+                    .collect();
+                let payload = DataResponse {
+                    ok: true,
+                    data: TargetsData { results },
+                };
+                serde_json::to_string(&payload).expect("serialize Targets")
+            }
         },
     }
 }
@@ -263,6 +1134,7 @@ mod tests {
             IpcCommand::SendKeys {
                 target: 2,
                 keys: vec!["cargo test".to_string(), "Enter".to_string()],
+                literal: false,
             }
         );
     }
@@ -276,6 +1148,21 @@ mod tests {
             IpcCommand::SendKeys {
                 target: 1,
                 keys: vec![],
+                literal: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_send_keys_literal() {
+        let json = r#"{"cmd": "send-keys", "target": 1, "keys": ["Enter"], "literal": true}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::SendKeys {
+                target: 1,
+                keys: vec!["Enter".to_string()],
+                literal: true,
             }
         );
     }
@@ -288,7 +1175,7 @@ mod tests {
             cmd,
             IpcCommand::CapturePane {
                 target: 3,
-                include_scrollback: true,
+                options: CaptureOptions { include_scrollback: true, ..CaptureOptions::default() },
             }
         );
     }
@@ -301,7 +1188,7 @@ mod tests {
             cmd,
             IpcCommand::CapturePane {
                 target: 2,
-                include_scrollback: false,
+                options: CaptureOptions::default(),
             }
         );
     }
@@ -314,11 +1201,38 @@ mod tests {
             cmd,
             IpcCommand::CapturePane {
                 target: 2,
-                include_scrollback: false,
+                options: CaptureOptions::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_capture_pane_with_range_format_and_limit() {
+        let json = r#"{"cmd": "capture-pane", "target": 2, "start": -100, "end": -1, "format": "ansi", "limit": 50}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::CapturePane {
+                target: 2,
+                options: CaptureOptions {
+                    start: Some(-100),
+                    end: Some(-1),
+                    include_scrollback: false,
+                    format: CaptureFormat::Ansi,
+                    limit: Some(50),
+                },
             }
         );
     }
 
+    #[test]
+    fn parse_capture_pane_rejects_unknown_format_and_zero_limit() {
+        let err = parse_command(r#"{"cmd": "capture-pane", "target": 2, "format": "html"}"#).unwrap_err();
+        assert!(err.contains("invalid format: html"), "got: {err}");
+        let err = parse_command(r#"{"cmd": "capture-pane", "target": 2, "limit": 0}"#).unwrap_err();
+        assert!(err.contains("limit must be greater than 0"), "got: {err}");
+    }
+
     #[test]
     fn parse_list_windows() {
         let json = r#"{"cmd": "list-windows"}"#;
@@ -456,6 +1370,10 @@ mod tests {
     fn serialize_capture_pane() {
         let resp = IpcResponse::OkWithData(IpcResponseData::CapturePane {
             text: "$ ls\nfoo bar\n".to_string(),
+            cells: None,
+            start: 0,
+            end: 23,
+            next_start: None,
             cursor_row: 24,
             cursor_col: 0,
             size_rows: 30,
@@ -475,12 +1393,48 @@ mod tests {
         assert_eq!(v["data"]["name"], "dev server");
         assert_eq!(v["data"]["cwd"], "/home/user/project");
         assert_eq!(v["data"]["scrollback_total"], 1500);
+        assert_eq!(v["data"]["start"], 0);
+        assert_eq!(v["data"]["end"], 23);
+        assert!(v["data"].get("next_start").is_none());
+        assert!(v["data"].get("rows").is_none());
+    }
+
+    #[test]
+    fn serialize_capture_pane_cells_as_rows_with_next_page() {
+        let bold = Cell { ch: 'o', bold: true, fg: Color::Indexed(2), ..Cell::default() };
+        let row = vec![bold, Cell { ch: 'k', ..bold }, Cell::default()];
+        let resp = IpcResponse::OkWithData(IpcResponseData::CapturePane {
+            text: String::new(),
+            cells: Some(vec![row, vec![Cell::default(); 3]]),
+            start: -10,
+            end: -9,
+            next_start: Some(-8),
+            cursor_row: 0,
+            cursor_col: 0,
+            size_rows: 24,
+            size_cols: 3,
+            name: "term".to_string(),
+            cwd: None,
+            scrollback_total: 10,
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&resp)).unwrap();
+        assert!(v["data"].get("text").is_none());
+        assert_eq!(v["data"]["rows"][0][0]["text"], "ok");
+        assert_eq!(v["data"]["rows"][0][0]["fg"], 2);
+        assert_eq!(v["data"]["rows"][1], serde_json::json!([]));
+        assert_eq!(v["data"]["start"], -10);
+        assert_eq!(v["data"]["end"], -9);
+        assert_eq!(v["data"]["next_start"], -8);
     }
 
     #[test]
     fn serialize_capture_pane_without_cwd() {
         let resp = IpcResponse::OkWithData(IpcResponseData::CapturePane {
             text: String::new(),
+            cells: None,
+            start: 0,
+            end: 23,
+            next_start: None,
             cursor_row: 0,
             cursor_col: 0,
             size_rows: 24,
@@ -505,6 +1459,11 @@ mod tests {
                     cwd: Some("/home/user/project".to_string()),
                     is_active: true,
                     is_running: true,
+                    is_idle: false,
+                    process: None,
+                    quiet_secs: 0,
+                    group: None,
+                    marked: false,
                 },
                 WindowInfo {
                     id: 2,
@@ -512,6 +1471,11 @@ mod tests {
                     cwd: None,
                     is_active: false,
                     is_running: false,
+                    is_idle: false,
+                    process: None,
+                    quiet_secs: 0,
+                    group: None,
+                    marked: false,
                 },
             ],
         });
@@ -530,6 +1494,44 @@ mod tests {
         assert!(data[1]["cwd"].is_null());
         assert_eq!(data[1]["is_active"], false);
         assert_eq!(data[1]["is_running"], false);
+        assert_eq!(data[1]["status"], "exited");
+        assert!(data[1].get("process").is_none());
+    }
+
+    #[test]
+    fn serialize_list_windows_with_foreground_process() {
+        let window = |id, is_idle, name: &str, quiet_secs| WindowInfo {
+            id,
+            name: format!("Terminal {id}"),
+            cwd: None,
+            is_active: false,
+            is_running: true,
+            is_idle,
+            process: Some(crate::domain::primitive::ForegroundProcess {
+                pid: 100 + id,
+                name: name.to_string(),
+                cmdline: format!("{name} -x"),
+                is_terminal_process: is_idle,
+            }),
+            quiet_secs,
+            group: (id == 1).then(|| "api".to_string()),
+            marked: id == 2,
+        };
+        let resp = IpcResponse::OkWithData(IpcResponseData::ListWindows {
+            windows: vec![window(1, true, "zsh", 400), window(2, false, "vim", 3)],
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&resp)).unwrap();
+        let data = v["data"].as_array().unwrap();
+        assert_eq!(data[0]["status"], "idle");
+        assert_eq!(data[0]["quiet_secs"], 400);
+        assert_eq!(data[0]["process"]["name"], "zsh");
+        assert_eq!(data[1]["status"], "running");
+        assert_eq!(data[1]["process"]["pid"], 102);
+        assert_eq!(data[1]["process"]["cmdline"], "vim -x");
+        assert_eq!(data[0]["group"], "api");
+        assert!(data[1].get("group").is_none());
+        assert!(data[0].get("marked").is_none());
+        assert_eq!(data[1]["marked"], true);
     }
 
     #[test]
@@ -594,6 +1596,10 @@ mod tests {
     fn japanese_text_roundtrip_capture_pane() {
         let resp = IpcResponse::OkWithData(IpcResponseData::CapturePane {
             text: "$ echo テスト\nテスト\n".to_string(),
+            cells: None,
+            start: 0,
+            end: 23,
+            next_start: None,
             cursor_row: 2,
             cursor_col: 0,
             size_rows: 24,
@@ -618,6 +1624,7 @@ mod tests {
             IpcCommand::SendKeys {
                 target: 1,
                 keys: vec!["日本語入力".to_string(), "Enter".to_string()],
+                literal: false,
             }
         );
     }
@@ -635,6 +1642,10 @@ mod tests {
             IpcCommand::CreateWindow {
                 name: Some("my-term".to_string()),
                 command: Some("/bin/bash".to_string()),
+                cwd: None,
+                env: Vec::new(),
+                restart: RestartPolicy::Never,
+                group: None,
             }
         );
     }
@@ -648,24 +1659,167 @@ mod tests {
             IpcCommand::CreateWindow {
                 name: None,
                 command: None,
+                cwd: None,
+                env: Vec::new(),
+                restart: RestartPolicy::Never,
+                group: None,
             }
         );
     }
 
     #[test]
-    fn parse_kill_window() {
-        let json = r#"{"cmd": "kill-window", "target": 5}"#;
+    fn parse_create_window_with_cwd_and_env() {
+        let json = r#"{"cmd": "create-window", "command": "cargo watch", "cwd": "/work", "env": {"RUST_LOG": "debug", "A": "1"}}"#;
         let cmd = parse_command(json).unwrap();
-        assert_eq!(cmd, IpcCommand::KillWindow { target: 5 });
-    }
-
-    #[test]
-    fn parse_kill_window_missing_target() {
+        assert_eq!(
+            cmd,
+            IpcCommand::CreateWindow {
+                name: None,
+                command: Some("cargo watch".to_string()),
+                cwd: Some("/work".to_string()),
+                env: vec![
+                    ("A".to_string(), "1".to_string()),
+                    ("RUST_LOG".to_string(), "debug".to_string()),
+                ],
+                restart: RestartPolicy::Never,
+                group: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_create_window_with_restart_policy() {
+        let json = r#"{"cmd": "create-window", "command": "npm run dev", "restart": "on-failure"}"#;
+        let cmd = parse_command(json).unwrap();
+        assert!(matches!(cmd, IpcCommand::CreateWindow { restart: RestartPolicy::OnFailure, .. }));
+
+        let err = parse_command(r#"{"cmd": "create-window", "restart": "sometimes"}"#).unwrap_err();
+        assert!(err.contains("invalid restart policy"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_create_window_with_group() {
+        let cmd = parse_command(r##"{"cmd": "create-window", "group": "#backend"}"##).unwrap();
+        assert!(matches!(cmd, IpcCommand::CreateWindow { group: Some(g), .. } if g == "backend"));
+        let cmd = parse_command(r#"{"cmd": "create-window", "group": ""}"#).unwrap();
+        assert!(matches!(cmd, IpcCommand::CreateWindow { group: None, .. }));
+    }
+
+    #[test]
+    fn parse_create_window_env_non_string_value_is_error() {
+        let json = r#"{"cmd": "create-window", "env": {"A": 1}}"#;
+        assert!(parse_command(json).is_err());
+    }
+
+    #[test]
+    fn parse_kill_window() {
+        let json = r#"{"cmd": "kill-window", "target": 5}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(cmd, IpcCommand::KillWindow { target: 5 });
+    }
+
+    #[test]
+    fn parse_kill_window_missing_target() {
         let json = r#"{"cmd": "kill-window"}"#;
         let err = parse_command(json).unwrap_err();
         assert!(err.contains("missing field: target"), "got: {err}");
     }
 
+    #[test]
+    fn parse_move_window() {
+        let cmd = parse_command(r#"{"cmd": "move-window", "target": 2, "by": -1}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MoveWindow { target: 2, to: WindowMove::By(-1) });
+        let cmd = parse_command(r#"{"cmd": "move-window", "target": 2, "to": 0}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MoveWindow { target: 2, to: WindowMove::To(0) });
+        let cmd = parse_command(r#"{"cmd": "move-window", "target": 2, "swap": "api", "caller": 4}"#).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::MoveWindow {
+                target: 2,
+                to: WindowMove::Swap { with: TargetSpec::Name("api".to_string()), caller: Some(4) },
+            }
+        );
+
+        for json in [
+            r#"{"cmd": "move-window", "target": 2}"#,
+            r#"{"cmd": "move-window", "target": 2, "by": 1, "to": 0}"#,
+        ] {
+            let err = parse_command(json).unwrap_err();
+            assert!(err.contains("needs one of"), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_sort_and_filter_windows() {
+        let cmd = parse_command(r#"{"cmd": "sort-windows", "key": "activity"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SortWindows { key: SortKey::Activity });
+        let err = parse_command(r#"{"cmd": "sort-windows", "key": "size"}"#).unwrap_err();
+        assert!(err.starts_with("invalid sort key"), "got: {err}");
+
+        let cmd = parse_command(r#"{"cmd": "filter-windows", "status": "exited", "text": "api"}"#).unwrap();
+        let filter = TerminalFilter { status: StatusFilter::Exited, text: "api".to_string() };
+        assert_eq!(cmd, IpcCommand::FilterWindows { filter });
+        let cmd = parse_command(r#"{"cmd": "filter-windows"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::FilterWindows { filter: TerminalFilter::default() });
+    }
+
+    #[test]
+    fn parse_set_and_toggle_group() {
+        let cmd = parse_command(r#"{"cmd": "set-group", "target": 2, "group": "api"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SetGroup { target: 2, group: Some("api".to_string()) });
+        let cmd = parse_command(r#"{"cmd": "set-group", "target": 2}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SetGroup { target: 2, group: None });
+
+        let cmd = parse_command(r#"{"cmd": "toggle-group", "group": "api"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::ToggleGroup { group: "api".to_string(), collapsed: None });
+        let cmd = parse_command(r#"{"cmd": "toggle-group", "group": "api", "collapsed": true}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::ToggleGroup { group: "api".to_string(), collapsed: Some(true) });
+        let err = parse_command(r#"{"cmd": "toggle-group"}"#).unwrap_err();
+        assert!(err.contains("missing field: group"), "got: {err}");
+
+        // A group target fans out like a glob
+        let cmd = parse_command(r##"{"cmd": "kill-window", "target": "#api"}"##).unwrap();
+        assert!(matches!(cmd, IpcCommand::Resolve { target: TargetSpec::Group(ref g), .. } if g == "api"));
+    }
+
+    #[test]
+    fn parse_mark_window_and_sync_input() {
+        let cmd = parse_command(r#"{"cmd": "mark-window", "target": 2}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MarkWindow { target: 2, marked: None });
+        let cmd = parse_command(r#"{"cmd": "mark-window", "target": 2, "marked": false}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MarkWindow { target: 2, marked: Some(false) });
+        let err = parse_command(r#"{"cmd": "mark-window"}"#).unwrap_err();
+        assert!(err.contains("missing field: target"), "got: {err}");
+
+        let cmd = parse_command(r#"{"cmd": "sync-input"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SyncInput { enabled: None });
+        let cmd = parse_command(r#"{"cmd": "sync-input", "enabled": true}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SyncInput { enabled: Some(true) });
+    }
+
+    #[test]
+    fn parse_send_signal() {
+        let cmd = parse_command(r#"{"cmd": "send-signal", "target": 2, "signal": "int"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::SendSignal { target: 2, signal: Signal::Int });
+
+        let err = parse_command(r#"{"cmd": "send-signal", "target": 2}"#).unwrap_err();
+        assert!(err.contains("missing field: signal"), "got: {err}");
+        let err = parse_command(r#"{"cmd": "send-signal", "target": 2, "signal": "USR1"}"#).unwrap_err();
+        assert!(err.starts_with("invalid signal"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_respawn_window() {
+        let cmd = parse_command(r#"{"cmd": "respawn-window", "target": 5}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::RespawnWindow { target: 5, kill: false });
+
+        let cmd = parse_command(r#"{"cmd": "respawn-window", "target": 5, "kill": true}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::RespawnWindow { target: 5, kill: true });
+
+        let err = parse_command(r#"{"cmd": "respawn-window"}"#).unwrap_err();
+        assert!(err.contains("missing field: target"), "got: {err}");
+    }
+
     #[test]
     fn parse_select_window() {
         let json = r#"{"cmd": "select-window", "target": 2}"#;
@@ -731,6 +1885,7 @@ mod tests {
         assert_eq!(
             cmd,
             IpcCommand::Notify {
+                target: None,
                 title: Some("Claude Code".to_string()),
                 body: "Response complete".to_string(),
             }
@@ -744,6 +1899,7 @@ mod tests {
         assert_eq!(
             cmd,
             IpcCommand::Notify {
+                target: None,
                 title: None,
                 body: "Task done".to_string(),
             }
@@ -763,4 +1919,558 @@ mod tests {
         let err = parse_command(json).unwrap_err();
         assert!(err.contains("missing field: body"), "got: {err}");
     }
+
+    // ========================================================================
+    // Tests: parse_command — set-status command
+    // ========================================================================
+
+    #[test]
+    fn parse_set_status_with_icon_and_color() {
+        let json = r#"{"cmd": "set-status", "target": 2, "text": "thinking", "icon": "⏳", "color": "yellow"}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::SetStatus {
+                target: 2,
+                status: Some(StatusBadge {
+                    text: "thinking".to_string(),
+                    icon: Some("⏳".to_string()),
+                    color: Color::Indexed(3),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_set_status_accepts_palette_and_hex_colors() {
+        let badge_color = |color: &str| match parse_command(&format!(
+            r#"{{"cmd": "set-status", "target": 1, "text": "done", "color": {color}}}"#
+        ))
+        .unwrap()
+        {
+            IpcCommand::SetStatus { status: Some(badge), .. } => badge.color,
+            other => panic!("unexpected command: {other:?}"),
+        };
+        assert_eq!(badge_color("208"), Color::Indexed(208));
+        assert_eq!(badge_color(r##""#00ff80""##), Color::Rgb(0, 255, 128));
+    }
+
+    #[test]
+    fn parse_set_status_without_text_clears() {
+        for json in [
+            r#"{"cmd": "set-status", "target": 2}"#,
+            r#"{"cmd": "set-status", "target": 2, "text": ""}"#,
+        ] {
+            assert_eq!(parse_command(json).unwrap(), IpcCommand::SetStatus { target: 2, status: None });
+        }
+    }
+
+    #[test]
+    fn parse_set_status_rejects_unknown_color() {
+        let json = r#"{"cmd": "set-status", "target": 2, "text": "x", "color": "purple"}"#;
+        let err = parse_command(json).unwrap_err();
+        assert!(err.contains("invalid color: purple"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_set_status_missing_target() {
+        let json = r#"{"cmd": "set-status", "text": "x"}"#;
+        let err = parse_command(json).unwrap_err();
+        assert!(err.contains("missing field: target"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: input / attach / kill-server
+    // ========================================================================
+
+    #[test]
+    fn parse_input() {
+        let json = r#"{"cmd": "input", "target": 2, "data": "ls\r"}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::Input {
+                target: 2,
+                data: "ls\r".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_input_missing_data() {
+        let json = r#"{"cmd": "input", "target": 2}"#;
+        let err = parse_command(json).unwrap_err();
+        assert!(err.contains("missing field: data"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_attach() {
+        let json = r#"{"cmd": "attach", "cols": 120, "rows": 40}"#;
+        let cmd = parse_command(json).unwrap();
+        assert_eq!(cmd, IpcCommand::Attach { cols: 120, rows: 40 });
+    }
+
+    #[test]
+    fn parse_attach_missing_rows() {
+        let json = r#"{"cmd": "attach", "cols": 120}"#;
+        let err = parse_command(json).unwrap_err();
+        assert!(err.contains("missing field: rows"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_kill_server() {
+        let json = r#"{"cmd": "kill-server"}"#;
+        assert_eq!(parse_command(json).unwrap(), IpcCommand::KillServer);
+    }
+
+    // ========================================================================
+    // Tests: frame events
+    // ========================================================================
+
+    fn sample_frame() -> ScreenFrame {
+        let mut cells = vec![vec![Cell::default(); 6]; 2];
+        cells[0][0].ch = 'o';
+        cells[0][1].ch = 'k';
+        cells[0][1].bold = true;
+        cells[0][1].fg = Color::Indexed(2);
+        cells[0][2] = Cell { ch: '日', width: 2, bg: Color::Rgb(1, 2, 3), ..Cell::default() };
+        cells[0][3] = Cell { ch: ' ', width: 0, bg: Color::Rgb(1, 2, 3), ..Cell::default() };
+        ScreenFrame {
+            windows: vec![
+                FrameWindow {
+                    id: 1,
+                    name: "api".to_string(),
+                    cwd: "/srv".to_string(),
+                    status: TerminalStatus::Idle,
+                    memo: "port 8080".to_string(),
+                    badge: Some(StatusBadge {
+                        text: "tests failing".to_string(),
+                        icon: Some("✗".to_string()),
+                        color: Color::Indexed(1),
+                    }),
+                    group: Some("backend".to_string()),
+                    marked: true,
+                    process: Some("zsh".to_string()),
+                    quiet_mins: 7,
+                    notification: None,
+                },
+                FrameWindow {
+                    id: 2,
+                    name: "build".to_string(),
+                    cwd: "/tmp".to_string(),
+                    status: TerminalStatus::Exited(1),
+                    memo: String::new(),
+                    badge: None,
+                    group: None,
+                    marked: false,
+                    process: None,
+                    quiet_mins: 0,
+                    notification: Some("Bell".to_string()),
+                },
+            ],
+            groups: vec![GroupHeader { name: "backend".to_string(), count: 3, collapsed: true, at: 0 }],
+            filter: TerminalFilter { status: StatusFilter::Exited, text: "bu".to_string() },
+            sync: true,
+            active: Some(1),
+            cells,
+            cursor: CursorPos { row: 0, col: 4 },
+            cursor_visible: true,
+            application_cursor_keys: true,
+            bracketed_paste: false,
+            keyboard_flags: 1,
+        }
+    }
+
+    #[test]
+    fn frame_event_roundtrip() {
+        let frame = sample_frame();
+        let json = serialize_event(&IpcEvent::Frame(frame.clone()));
+        assert_eq!(parse_event(&json).unwrap(), IpcEvent::Frame(frame));
+    }
+
+    #[test]
+    fn frame_event_wire_format() {
+        let json = serialize_event(&IpcEvent::Frame(sample_frame()));
+        let v: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["event"], "frame");
+        assert_eq!(v["data"]["cols"], 6);
+        assert_eq!(v["data"]["windows"][1]["exit_code"], 1);
+        assert_eq!(v["data"]["filter"], serde_json::json!({"status": "exited", "text": "bu"}));
+        assert_eq!(
+            v["data"]["groups"],
+            serde_json::json!([{"name": "backend", "count": 3, "collapsed": true, "at": 0}])
+        );
+        assert_eq!(v["data"]["windows"][0]["group"], "backend");
+        assert!(v["data"]["windows"][1].get("group").is_none());
+        assert_eq!(v["data"]["sync"], true);
+        assert_eq!(v["data"]["windows"][0]["marked"], true);
+        assert!(v["data"]["windows"][1].get("marked").is_none());
+        assert_eq!(
+            v["data"]["windows"][0]["badge"],
+            serde_json::json!({"text": "tests failing", "icon": "✗", "color": 1})
+        );
+        assert!(v["data"]["windows"][1].get("badge").is_none());
+        assert_eq!(v["data"]["windows"][0]["idle"], true);
+        assert_eq!(v["data"]["windows"][0]["process"], "zsh");
+        assert_eq!(v["data"]["windows"][0]["quiet_mins"], 7);
+        assert!(v["data"]["windows"][1].get("idle").is_none());
+        assert!(v["data"]["windows"][1].get("quiet_mins").is_none());
+        let runs = v["data"]["rows"][0].as_array().unwrap();
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0]["text"], "o");
+        assert_eq!(runs[1]["fg"], 2);
+        assert_eq!(runs[1]["attrs"], ATTR_BOLD);
+        assert_eq!(runs[2]["text"], "日");
+        assert_eq!(runs[2]["bg"], "#010203");
+        assert_eq!(runs[2]["wide"], true);
+        // Blank rows are sent empty and padded back to `cols`
+        assert!(v["data"]["rows"][1].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_event_rejects_plain_response() {
+        assert!(parse_event(r#"{"ok":true}"#).is_err());
+    }
+
+    // ========================================================================
+    // Tests: subscription events
+    // ========================================================================
+
+    #[test]
+    fn parse_subscribe_defaults_to_no_output() {
+        let cmd = parse_command(r#"{"cmd": "subscribe"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::Subscribe { output: vec![] });
+    }
+
+    #[test]
+    fn parse_subscribe_with_output_targets() {
+        let cmd = parse_command(r#"{"cmd": "subscribe", "output": [1, 3]}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::Subscribe { output: vec![1, 3] });
+    }
+
+    #[test]
+    fn session_events_roundtrip() {
+        let events = vec![
+            IpcEvent::TerminalCreated { id: 1, name: "build".to_string() },
+            IpcEvent::TerminalClosed { id: 1 },
+            IpcEvent::TerminalRenamed { id: 2, name: "test".to_string() },
+            IpcEvent::TerminalExited { id: 2, exit_code: 127 },
+            IpcEvent::Notification {
+                terminal: "agent".to_string(),
+                event: NotificationEvent::Osc777 {
+                    title: "Done".to_string(),
+                    body: "build ok".to_string(),
+                },
+            },
+            IpcEvent::Notification {
+                terminal: "agent".to_string(),
+                event: NotificationEvent::Bell,
+            },
+            IpcEvent::CwdChanged { id: 3, cwd: "/tmp".to_string() },
+            IpcEvent::FocusChanged { id: None },
+            IpcEvent::Output { id: 3, data: "ok\r\n".to_string() },
+        ];
+        for event in events {
+            let json = serialize_event(&event);
+            assert_eq!(parse_event(&json).unwrap(), event, "json: {json}");
+        }
+    }
+
+    #[test]
+    fn session_event_wire_format() {
+        let json = serialize_event(&IpcEvent::TerminalExited { id: 2, exit_code: 1 });
+        let v: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["event"], "terminal-exited");
+        assert_eq!(v["data"]["id"], 2);
+        assert_eq!(v["data"]["exit_code"], 1);
+
+        let json = serialize_event(&IpcEvent::Notification {
+            terminal: "agent".to_string(),
+            event: NotificationEvent::Osc9 { message: "hi".to_string() },
+        });
+        let v: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["event"], "notification");
+        assert_eq!(v["data"]["kind"], "osc9");
+        assert_eq!(v["data"]["body"], "hi");
+        assert!(v["data"].get("title").is_none());
+    }
+
+    #[test]
+    fn parse_event_rejects_unknown_notification_kind() {
+        let json = r#"{"event":"notification","data":{"terminal":"a","kind":"beep"}}"#;
+        let err = parse_event(json).unwrap_err();
+        assert!(err.contains("invalid notification kind"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_event_rejects_invalid_color() {
+        let json = r##"{"event":"frame","data":{"windows":[],"active":null,"cursor":{"row":0,"col":0},"cursor_visible":true,"application_cursor_keys":false,"bracketed_paste":false,"cols":1,"rows":[[{"text":"x","fg":"#12"}]]}}"##;
+        let err = parse_event(json).unwrap_err();
+        assert!(err.contains("invalid color"), "got: {err}");
+    }
+
+    // ========================================================================
+    // Tests: log-start / log-stop / log-status
+    // ========================================================================
+
+    #[test]
+    fn parse_log_start_defaults() {
+        let json = r#"{"cmd": "log-start", "target": 1}"#;
+        assert_eq!(
+            parse_command(json).unwrap(),
+            IpcCommand::LogStart { target: 1, mode: LogMode::Text, max_bytes: None }
+        );
+    }
+
+    #[test]
+    fn parse_log_start_with_mode_and_max_size() {
+        let json = r#"{"cmd": "log-start", "target": 2, "mode": "raw", "max_size": 4096}"#;
+        assert_eq!(
+            parse_command(json).unwrap(),
+            IpcCommand::LogStart { target: 2, mode: LogMode::Raw, max_bytes: Some(4096) }
+        );
+    }
+
+    #[test]
+    fn parse_log_start_invalid_mode() {
+        let json = r#"{"cmd": "log-start", "target": 1, "mode": "html"}"#;
+        let err = parse_command(json).unwrap_err();
+        assert!(err.contains("invalid mode"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_log_start_zero_max_size() {
+        let json = r#"{"cmd": "log-start", "target": 1, "max_size": 0}"#;
+        assert!(parse_command(json).is_err());
+    }
+
+    #[test]
+    fn parse_log_start_missing_target() {
+        let err = parse_command(r#"{"cmd": "log-start"}"#).unwrap_err();
+        assert!(err.contains("missing field: target"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_log_stop_and_status() {
+        assert_eq!(
+            parse_command(r#"{"cmd": "log-stop", "target": 3}"#).unwrap(),
+            IpcCommand::LogStop { target: 3 }
+        );
+        assert_eq!(
+            parse_command(r#"{"cmd": "log-status", "target": 3}"#).unwrap(),
+            IpcCommand::LogStatus { target: 3 }
+        );
+    }
+
+    #[test]
+    fn serialize_log_status_active() {
+        let resp = IpcResponse::OkWithData(IpcResponseData::Log {
+            status: Some(crate::domain::primitive::LogStatus {
+                path: std::path::PathBuf::from("/home/u/.cli-manager/logs/s/1-shell.log"),
+                mode: LogMode::Raw,
+                max_bytes: 1024,
+                bytes_written: 10,
+            }),
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&resp)).unwrap();
+        assert_eq!(v["ok"], true);
+        assert_eq!(v["data"]["logging"], true);
+        assert_eq!(v["data"]["path"], "/home/u/.cli-manager/logs/s/1-shell.log");
+        assert_eq!(v["data"]["mode"], "raw");
+        assert_eq!(v["data"]["max_size"], 1024);
+        assert_eq!(v["data"]["bytes_written"], 10);
+    }
+
+    #[test]
+    fn serialize_log_status_inactive_has_only_flag() {
+        let resp = IpcResponse::OkWithData(IpcResponseData::Log { status: None });
+        let v: Value = serde_json::from_str(&serialize_response(&resp)).unwrap();
+        assert_eq!(v["data"], serde_json::json!({"logging": false}));
+    }
+
+    // ========================================================================
+    // Tests: record-start / record-stop
+    // ========================================================================
+
+    #[test]
+    fn parse_record_start_with_and_without_path() {
+        assert_eq!(
+            parse_command(r#"{"cmd": "record-start", "target": 1}"#).unwrap(),
+            IpcCommand::RecordStart { target: 1, path: None }
+        );
+        assert_eq!(
+            parse_command(r#"{"cmd": "record-start", "target": 1, "path": "/tmp/a.cast"}"#)
+                .unwrap(),
+            IpcCommand::RecordStart { target: 1, path: Some("/tmp/a.cast".to_string()) }
+        );
+    }
+
+    #[test]
+    fn parse_record_stop() {
+        assert_eq!(
+            parse_command(r#"{"cmd": "record-stop", "target": 4}"#).unwrap(),
+            IpcCommand::RecordStop { target: 4 }
+        );
+        let err = parse_command(r#"{"cmd": "record-stop"}"#).unwrap_err();
+        assert!(err.contains("missing field: target"), "got: {err}");
+    }
+
+    #[test]
+    fn serialize_recording_response() {
+        let resp = IpcResponse::OkWithData(IpcResponseData::Recording {
+            path: "/tmp/a.cast".to_string(),
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&resp)).unwrap();
+        assert_eq!(v, serde_json::json!({"ok": true, "data": {"path": "/tmp/a.cast"}}));
+    }
+
+    // ========================================================================
+    // Tests: wait-for
+    // ========================================================================
+
+    #[test]
+    fn parse_wait_for_pattern() {
+        let cmd = parse_command(
+            r#"{"cmd":"wait-for","target":3,"pattern":"PASS|FAIL","regex":true,"timeout_ms":60000}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::WaitFor {
+                target: 3,
+                condition: WaitCondition::Match { pattern: "PASS|FAIL".to_string(), regex: true },
+                timeout_ms: Some(60000),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_wait_for_idle_and_exit() {
+        let idle = parse_command(r#"{"cmd":"wait-for","target":1,"idle_ms":500}"#).unwrap();
+        assert_eq!(
+            idle,
+            IpcCommand::WaitFor { target: 1, condition: WaitCondition::Idle { ms: 500 }, timeout_ms: None }
+        );
+        let exit = parse_command(r#"{"cmd":"wait-for","target":1,"exit":true}"#).unwrap();
+        assert_eq!(
+            exit,
+            IpcCommand::WaitFor { target: 1, condition: WaitCondition::Exit, timeout_ms: None }
+        );
+    }
+
+    #[test]
+    fn parse_wait_for_requires_exactly_one_condition() {
+        for json in [
+            r#"{"cmd":"wait-for","target":1}"#,
+            r#"{"cmd":"wait-for","target":1,"pattern":"x","exit":true}"#,
+            r#"{"cmd":"wait-for","target":1,"pattern":"x","idle_ms":10}"#,
+        ] {
+            assert!(parse_command(json).unwrap_err().contains("exactly one"), "{json}");
+        }
+        assert!(parse_command(r#"{"cmd":"wait-for","exit":true}"#).is_err());
+    }
+
+    #[test]
+    fn serialize_wait_for_responses() {
+        let found = IpcResponse::OkWithData(IpcResponseData::WaitMatch {
+            text: "PASS".to_string(),
+            line: "test: PASS".to_string(),
+            row: 12,
+            col: 6,
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&found)).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({"ok": true, "data": {"text": "PASS", "line": "test: PASS", "row": 12, "col": 6}})
+        );
+        let exited = IpcResponse::OkWithData(IpcResponseData::Exited { exit_code: 2 });
+        let v: Value = serde_json::from_str(&serialize_response(&exited)).unwrap();
+        assert_eq!(v, serde_json::json!({"ok": true, "data": {"exit_code": 2}}));
+    }
+
+    // ========================================================================
+    // Tests: target specifiers
+    // ========================================================================
+
+    #[test]
+    fn parse_string_target_wraps_command_in_resolve() {
+        let cmd = parse_command(r#"{"cmd":"kill-window","target":"agent-*","caller":4}"#).unwrap();
+        assert_eq!(
+            cmd,
+            IpcCommand::Resolve {
+                target: TargetSpec::Pattern("agent-*".to_string()),
+                caller: Some(4),
+                command: Box::new(IpcCommand::KillWindow { target: 0 }),
+            }
+        );
+        let cmd = parse_command(r#"{"cmd":"select-window","target":"@last"}"#).unwrap();
+        assert!(matches!(cmd, IpcCommand::Resolve { target: TargetSpec::Last, caller: None, .. }));
+    }
+
+    #[test]
+    fn parse_list_target_wraps_command_in_resolve() {
+        let cmd = parse_command(r##"{"cmd":"send-keys","target":[2,"build","#api"],"keys":["ls"]}"##).unwrap();
+        let IpcCommand::Resolve { target, command, .. } = cmd else {
+            panic!("expected Resolve, got {cmd:?}");
+        };
+        assert_eq!(
+            target,
+            TargetSpec::List(vec![
+                TargetSpec::Id(2),
+                TargetSpec::Name("build".to_string()),
+                TargetSpec::Group("api".to_string()),
+            ])
+        );
+        assert!(matches!(*command, IpcCommand::SendKeys { target: 0, .. }));
+    }
+
+    #[test]
+    fn parse_list_target_rejects_empty_and_nested_lists() {
+        let err = parse_command(r#"{"cmd":"kill-window","target":[]}"#).unwrap_err();
+        assert!(err.contains("empty list"), "got: {err}");
+        let err = parse_command(r#"{"cmd":"kill-window","target":[1,[2]]}"#).unwrap_err();
+        assert!(err.contains("nested list"), "got: {err}");
+        let err = parse_command(r#"{"cmd":"kill-window","target":[1,"@nope"]}"#).unwrap_err();
+        assert!(err.contains("invalid target: @nope"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_numeric_string_target_is_an_id() {
+        let cmd = parse_command(r#"{"cmd":"kill-window","target":"7"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::KillWindow { target: 7 });
+    }
+
+    #[test]
+    fn parse_notify_defaults_target_to_caller() {
+        let cmd = parse_command(r#"{"cmd":"notify","body":"done","caller":4}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::Notify { target: Some(4), title: None, body: "done".to_string() });
+        let cmd = parse_command(r#"{"cmd":"notify","body":"done","target":2,"caller":4}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::Notify { target: Some(2), title: None, body: "done".to_string() });
+        let cmd = parse_command(r#"{"cmd":"notify","body":"done","target":"build"}"#).unwrap();
+        assert!(matches!(cmd, IpcCommand::Resolve { target: TargetSpec::Name(_), .. }));
+    }
+
+    #[test]
+    fn parse_invalid_target_is_error() {
+        let err = parse_command(r#"{"cmd":"kill-window","target":"@nope"}"#).unwrap_err();
+        assert!(err.contains("invalid target"), "got: {err}");
+    }
+
+    #[test]
+    fn serialize_targets_tags_each_result_with_its_id() {
+        let response = IpcResponse::OkWithData(IpcResponseData::Targets {
+            results: vec![
+                (1, IpcResponse::Ok),
+                (2, IpcResponse::Error("Terminal not found: 2".to_string())),
+            ],
+        });
+        let v: Value = serde_json::from_str(&serialize_response(&response)).unwrap();
+        assert_eq!(
+            v,
+            serde_json::json!({"ok": true, "data": {"results": [
+                {"ok": true, "id": 1},
+                {"ok": false, "error": "Terminal not found: 2", "id": 2},
+            ]}})
+        );
+    }
 }
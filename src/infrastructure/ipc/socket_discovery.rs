@@ -1,6 +1,28 @@
+//! Finding the IPC socket of a running cm.
+//!
+//! Every TUI and `cm server` registers itself as one JSON file per process
+//! in the instance registry, so several instances can run side by side.
+//! `~/.cli-manager/socket` is still written for external tools and points at
+//! the most recently started instance.
+
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// A running cm (TUI or server) as recorded in the instance registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    pub pid: u32,
+    pub socket: String,
+    /// Unix time in seconds.
+    pub started_at: u64,
+    pub cwd: String,
+    /// `--name`, or the stem of the `--session` file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
 
 /// Get the directory for cli-manager runtime files.
 ///
@@ -48,6 +70,138 @@ pub fn read_socket_path() -> io::Result<String> {
     Ok(content.trim().to_string())
 }
 
+/// Directory of the instance registry: `$XDG_RUNTIME_DIR/cli-manager/instances`
+/// when the runtime dir is set, `~/.cli-manager/instances` otherwise.
+pub fn instances_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join("cli-manager").join("instances"),
+        None => discovery_dir().join("instances"),
+    }
+}
+
+/// Add this process to the instance registry and point the discovery file
+/// at it.
+pub fn register_instance(info: &InstanceInfo) -> io::Result<()> {
+    write_instance(&instances_dir(), info)?;
+    write_socket_path(&info.socket)
+}
+
+/// Remove the process from the instance registry (best-effort). If the
+/// discovery file points at it, it is handed to the newest instance left.
+pub fn unregister_instance(pid: u32) {
+    let dir = instances_dir();
+    let file = instance_file(&dir, pid);
+    let socket = read_instance(&file).map(|info| info.socket);
+    let _ = fs::remove_file(&file);
+    if socket.is_some() && read_socket_path().ok() == socket {
+        match list_instances_in(&dir).pop() {
+            Some(newest) => {
+                let _ = write_socket_path(&newest.socket);
+            }
+            None => remove_socket_path(),
+        }
+    }
+}
+
+/// Running instances, oldest first. Entries left behind by processes that
+/// are gone are deleted.
+pub fn list_instances() -> Vec<InstanceInfo> {
+    list_instances_in(&instances_dir())
+}
+
+/// Socket to talk to: the instance picked by `selector` (a pid or session
+/// name), else `CLI_MANAGER_SOCK`, else the newest registered instance, else
+/// the discovery file (instances started by older versions).
+pub fn resolve_socket_path(selector: Option<&str>) -> Result<String, String> {
+    if let Some(selector) = selector {
+        return select_instance(&list_instances(), selector).map(|info| info.socket.clone());
+    }
+    if let Ok(path) = std::env::var("CLI_MANAGER_SOCK") {
+        return Ok(path);
+    }
+    if let Some(newest) = list_instances().pop() {
+        return Ok(newest.socket);
+    }
+    read_socket_path()
+        .map_err(|_| "No running cli-manager instance found. Is cli-manager running?".to_string())
+}
+
+/// The instance whose pid or session name is `selector`.
+fn select_instance<'a>(
+    instances: &'a [InstanceInfo],
+    selector: &str,
+) -> Result<&'a InstanceInfo, String> {
+    if let Ok(pid) = selector.parse::<u32>()
+        && let Some(info) = instances.iter().find(|i| i.pid == pid)
+    {
+        return Ok(info);
+    }
+    let mut named = instances.iter().filter(|i| i.session.as_deref() == Some(selector));
+    match (named.next(), named.next()) {
+        (Some(info), None) => Ok(info),
+        (Some(_), Some(_)) => Err(format!(
+            "several cli-manager instances are named {selector}; select one by pid"
+        )),
+        (None, _) => Err(format!("no running cli-manager instance matches: {selector}")),
+    }
+}
+
+fn instance_file(dir: &Path, pid: u32) -> PathBuf {
+    dir.join(format!("{pid}.json"))
+}
+
+/// Write an entry through a temporary file, so readers never see half of it.
+fn write_instance(dir: &Path, info: &InstanceInfo) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let tmp = dir.join(format!(".{}.json.tmp", info.pid));
+    fs::write(&tmp, serde_json::to_string(info)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(&tmp, instance_file(dir, info.pid))
+}
+
+fn read_instance(path: &Path) -> Option<InstanceInfo> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn list_instances_in(dir: &Path) -> Vec<InstanceInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut instances = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match read_instance(&path) {
+            Some(info) if is_alive(&info) => instances.push(info),
+            _ => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+    instances.sort_by_key(|i| (i.started_at, i.pid));
+    instances
+}
+
+/// Whether the process still runs and its socket is still there.
+fn is_alive(info: &InstanceInfo) -> bool {
+    // kill(0 or negative pid) would address process groups
+    let Ok(pid) = i32::try_from(info.pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // SAFETY: signal 0 only checks that the process exists.
+    let exists = unsafe { libc::kill(pid, 0) } == 0
+        || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+    exists && Path::new(&info.socket).exists()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,4 +426,86 @@ mod tests {
         remove_socket_path();
         // If we reach here, the test passes -- no panic occurred
     }
+
+    // ========================================================================
+    // Tests: instance registry
+    // ========================================================================
+
+    fn instance(pid: u32, socket: &Path, started_at: u64, session: Option<&str>) -> InstanceInfo {
+        InstanceInfo {
+            pid,
+            socket: socket.to_string_lossy().to_string(),
+            started_at,
+            cwd: "/work".to_string(),
+            session: session.map(String::from),
+        }
+    }
+
+    #[test]
+    fn registry_lists_live_instances_oldest_first() {
+        let temp_dir = create_temp_dir("registry");
+        let _cleanup = TempDirCleanup(temp_dir.clone());
+        let dir = temp_dir.join("instances");
+        let socket = temp_dir.join("cm.sock");
+        fs::write(&socket, "").unwrap();
+
+        // Two live pids: this process and its parent
+        let me = std::process::id();
+        let parent = std::os::unix::process::parent_id();
+        write_instance(&dir, &instance(me, &socket, 200, Some("work"))).unwrap();
+        write_instance(&dir, &instance(parent, &socket, 100, None)).unwrap();
+
+        let pids: Vec<u32> = list_instances_in(&dir).iter().map(|i| i.pid).collect();
+        assert_eq!(pids, vec![parent, me]);
+        assert_eq!(list_instances_in(&dir)[1].session.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn registry_drops_stale_entries() {
+        let temp_dir = create_temp_dir("stale");
+        let _cleanup = TempDirCleanup(temp_dir.clone());
+        let dir = temp_dir.join("instances");
+        let socket = temp_dir.join("cm.sock");
+        fs::write(&socket, "").unwrap();
+
+        // A pid above pid_max, and a live pid whose socket is gone
+        write_instance(&dir, &instance(0x7fff_fff0, &socket, 1, None)).unwrap();
+        write_instance(&dir, &instance(std::process::id(), &temp_dir.join("gone.sock"), 2, None))
+            .unwrap();
+        fs::write(dir.join("999.json"), "not json").unwrap();
+
+        assert!(list_instances_in(&dir).is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0, "stale files should be removed");
+    }
+
+    #[test]
+    fn registry_entry_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = create_temp_dir("entry-perms");
+        let _cleanup = TempDirCleanup(temp_dir.clone());
+        let dir = temp_dir.join("instances");
+        write_instance(&dir, &instance(42, &temp_dir, 1, None)).unwrap();
+
+        let mode = fs::metadata(instance_file(&dir, 42)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+        assert_eq!(read_instance(&instance_file(&dir, 42)).unwrap().pid, 42);
+    }
+
+    #[test]
+    fn select_instance_by_pid_or_session_name() {
+        let sock = Path::new("/tmp/x.sock");
+        let instances = vec![
+            instance(10, sock, 1, Some("work")),
+            instance(20, sock, 2, Some("play")),
+            instance(30, sock, 3, Some("play")),
+        ];
+        assert_eq!(select_instance(&instances, "20").unwrap().pid, 20);
+        assert_eq!(select_instance(&instances, "work").unwrap().pid, 10);
+
+        let err = select_instance(&instances, "play").unwrap_err();
+        assert!(err.contains("select one by pid"), "got: {err}");
+        let err = select_instance(&instances, "nope").unwrap_err();
+        assert!(err.contains("no running cli-manager instance matches: nope"), "got: {err}");
+    }
 }
@@ -3,12 +3,16 @@
 //! Provides a non-blocking Unix socket server for IPC. Each client connects,
 //! sends a single JSON command (newline-delimited), receives a JSON response,
 //! and the connection is closed (1-request-per-connection model).
+//!
+//! Connections turned into streams (`open_stream`, used by `attach`) stay
+//! open: they may send further commands and receive pushed events. Their
+//! output is buffered and flushed without blocking.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 
-use crate::domain::primitive::{IpcCommand, IpcResponse};
+use crate::domain::primitive::{IpcCommand, IpcEvent, IpcResponse};
 use crate::interface_adapter::port::ipc_port::{ConnectionId, IpcPort};
 use crate::shared::error::AppError;
 
@@ -21,10 +25,16 @@ pub struct UnixSocketServer {
     socket_path: String,
     connections: HashMap<u64, UnixStream>,
     read_buffers: HashMap<u64, Vec<u8>>,
+    streams: HashSet<u64>,
+    write_buffers: HashMap<u64, Vec<u8>>,
     next_conn_id: u64,
     pending_commands: Vec<(ConnectionId, IpcCommand)>,
 }
 
+/// Unsent output allowed per stream before the client is considered stuck
+/// and disconnected.
+const MAX_STREAM_BACKLOG: usize = 8 * 1024 * 1024;
+
 /// Set a file descriptor to non-blocking mode using libc fcntl.
 ///
 /// # Safety
@@ -107,6 +117,8 @@ impl UnixSocketServer {
             socket_path: socket_path.to_string(),
             connections: HashMap::new(),
             read_buffers: HashMap::new(),
+            streams: HashSet::new(),
+            write_buffers: HashMap::new(),
             next_conn_id: 1,
             pending_commands: Vec::new(),
         })
@@ -189,21 +201,68 @@ impl UnixSocketServer {
             }
         }
 
-        // Send error responses and remove those connections
+        // Send error responses and remove those connections (streams stay open)
         for (conn_id, err_msg) in error_responses {
-            let response = IpcResponse::Error(err_msg);
-            let json = protocol::serialize_response(&response);
-            if let Some(mut stream) = self.connections.remove(&conn_id) {
-                let _ = write!(stream, "{json}\n");
-            }
-            self.read_buffers.remove(&conn_id);
+            self.send_response(ConnectionId(conn_id), IpcResponse::Error(err_msg));
         }
 
         // Remove disconnected connections
         for conn_id in to_remove {
-            self.connections.remove(&conn_id);
-            self.read_buffers.remove(&conn_id);
+            self.remove_connection(conn_id);
+        }
+    }
+
+    fn remove_connection(&mut self, conn_id: u64) {
+        self.connections.remove(&conn_id);
+        self.read_buffers.remove(&conn_id);
+        self.streams.remove(&conn_id);
+        self.write_buffers.remove(&conn_id);
+    }
+
+    /// Queue a line for a stream connection and try to flush it.
+    ///
+    /// Returns `false` (and drops the connection) when the client has gone
+    /// away or its backlog exceeds `MAX_STREAM_BACKLOG`.
+    fn queue_line(&mut self, conn_id: u64, line: &str) -> bool {
+        if !self.connections.contains_key(&conn_id) {
+            return false;
+        }
+        let buf = self.write_buffers.entry(conn_id).or_default();
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(b'\n');
+        if buf.len() > MAX_STREAM_BACKLOG {
+            self.remove_connection(conn_id);
+            return false;
         }
+        self.flush_stream(conn_id)
+    }
+
+    /// Write as much buffered output as the socket accepts without blocking.
+    fn flush_stream(&mut self, conn_id: u64) -> bool {
+        let (Some(stream), Some(buf)) = (
+            self.connections.get_mut(&conn_id),
+            self.write_buffers.get_mut(&conn_id),
+        ) else {
+            return self.connections.contains_key(&conn_id);
+        };
+        while !buf.is_empty() {
+            match stream.write(buf) {
+                Ok(0) => {
+                    self.remove_connection(conn_id);
+                    return false;
+                }
+                Ok(n) => {
+                    buf.drain(..n);
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => {
+                    self.remove_connection(conn_id);
+                    return false;
+                }
+            }
+        }
+        true
     }
 }
 
@@ -211,17 +270,42 @@ impl IpcPort for UnixSocketServer {
     fn poll_commands(&mut self) -> Vec<(ConnectionId, IpcCommand)> {
         self.accept_connections();
         self.read_commands();
+        let streams: Vec<u64> = self.streams.iter().copied().collect();
+        for conn_id in streams {
+            self.flush_stream(conn_id);
+        }
         std::mem::take(&mut self.pending_commands)
     }
 
     fn send_response(&mut self, conn_id: ConnectionId, response: IpcResponse) {
         let json = protocol::serialize_response(&response);
+        if self.streams.contains(&conn_id.0) {
+            self.queue_line(conn_id.0, &json);
+            return;
+        }
         if let Some(mut stream) = self.connections.remove(&conn_id.0) {
             let _ = write!(stream, "{json}\n");
         }
         self.read_buffers.remove(&conn_id.0);
     }
 
+    fn open_stream(&mut self, conn_id: ConnectionId) {
+        if self.connections.contains_key(&conn_id.0) {
+            self.streams.insert(conn_id.0);
+        }
+    }
+
+    fn send_event(&mut self, conn_id: ConnectionId, event: &IpcEvent) -> bool {
+        if !self.streams.contains(&conn_id.0) {
+            return false;
+        }
+        self.queue_line(conn_id.0, &protocol::serialize_event(event))
+    }
+
+    fn is_connected(&self, conn_id: ConnectionId) -> bool {
+        self.connections.contains_key(&conn_id.0)
+    }
+
     fn socket_path(&self) -> &str {
         &self.socket_path
     }
@@ -230,6 +314,8 @@ impl IpcPort for UnixSocketServer {
         // Close all connections
         self.connections.clear();
         self.read_buffers.clear();
+        self.streams.clear();
+        self.write_buffers.clear();
         // Remove socket file
         let _ = std::fs::remove_file(&self.socket_path);
     }
@@ -357,6 +443,7 @@ mod tests {
             IpcCommand::SendKeys {
                 target: 2,
                 keys: vec!["cargo test".to_string(), "Enter".to_string()],
+                literal: false,
             }
         );
     }
@@ -580,4 +667,82 @@ mod tests {
         assert_eq!(v["ok"], true);
         assert_eq!(v["data"]["text"], "yanked text");
     }
+
+    // ========================================================================
+    // Stream connection tests
+    // ========================================================================
+
+    #[test]
+    fn stream_stays_open_and_receives_events() {
+        let path = temp_socket_path("stream");
+        let _cleanup = SocketCleanup(path.clone());
+        let mut server = UnixSocketServer::new(&path).unwrap();
+
+        let mut client = UnixStream::connect(&path).unwrap();
+        writeln!(client, r#"{{"cmd": "attach", "cols": 80, "rows": 24}}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let commands = server.poll_commands();
+        assert_eq!(commands[0].1, IpcCommand::Attach { cols: 80, rows: 24 });
+        let conn_id = commands[0].0;
+        server.open_stream(conn_id);
+        server.send_response(conn_id, IpcResponse::Ok);
+        assert!(server.is_connected(conn_id));
+        let frame = IpcEvent::Frame(crate::domain::primitive::ScreenFrame {
+            active: Some(3),
+            ..Default::default()
+        });
+        assert!(server.send_event(conn_id, &frame));
+
+        let mut reader = BufReader::new(client.try_clone().unwrap());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let v: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(v["ok"], true);
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(protocol::parse_event(line.trim()).unwrap(), frame);
+
+        // Further commands arrive on the same connection
+        writeln!(client, r#"{{"cmd": "input", "target": 3, "data": "x"}}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let commands = server.poll_commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].0, conn_id);
+    }
+
+    #[test]
+    fn send_event_to_non_stream_connection_returns_false() {
+        let path = temp_socket_path("nostream");
+        let _cleanup = SocketCleanup(path.clone());
+        let mut server = UnixSocketServer::new(&path).unwrap();
+
+        let mut client = UnixStream::connect(&path).unwrap();
+        writeln!(client, r#"{{"cmd": "list-windows"}}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        let conn_id = server.poll_commands()[0].0;
+        let event = IpcEvent::Frame(Default::default());
+        assert!(!server.send_event(conn_id, &event));
+    }
+
+    #[test]
+    fn closed_stream_is_detected() {
+        let path = temp_socket_path("closedstream");
+        let _cleanup = SocketCleanup(path.clone());
+        let mut server = UnixSocketServer::new(&path).unwrap();
+
+        let mut client = UnixStream::connect(&path).unwrap();
+        writeln!(client, r#"{{"cmd": "attach", "cols": 80, "rows": 24}}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let conn_id = server.poll_commands()[0].0;
+        server.open_stream(conn_id);
+
+        drop(client);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        server.poll_commands();
+
+        assert!(!server.is_connected(conn_id));
+        assert!(!server.send_event(conn_id, &IpcEvent::Frame(Default::default())));
+    }
 }
@@ -0,0 +1,410 @@
+//! Pending `wait-for` requests.
+//!
+//! A wait holds its connection open without a reply until its condition
+//! holds, its terminal goes away or its timeout passes. The runner re-checks
+//! the waits every tick; screen text is only searched again after the
+//! terminal has produced more output, and only text printed after the wait
+//! started counts.
+
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+
+use crate::domain::primitive::{
+    Cell, IpcResponse, IpcResponseData, TerminalId, TerminalStatus, WaitCondition,
+};
+use crate::interface_adapter::port::{ConnectionId, IpcPort, PtyPort, ScreenPort};
+use crate::usecase::terminal_usecase::TerminalUsecase;
+
+/// A condition ready to be checked.
+enum Check {
+    /// Text printed from `from` on.
+    Match { re: Regex, from: Start },
+    Idle(Duration),
+    Exit,
+}
+
+/// Where the output a wait may match begins.
+struct Start {
+    /// Row the cursor was on, counted from the top of the scrollback.
+    row: usize,
+    /// The row's text back then: a match on the row only counts once the
+    /// text has changed. `None` once output has rewritten the row.
+    line: Option<String>,
+    /// Rows dropped off the full scrollback when `row` was last updated.
+    scrolled_off: usize,
+}
+
+impl Start {
+    /// The cursor's row and its current text.
+    fn at_cursor<S: ScreenPort>(screen: &mut S, id: TerminalId) -> Self {
+        let row = cursor_row(screen, id).unwrap_or(0);
+        let line = screen.get_row_cells(id, row).map(|cells| row_text(&cells).0).ok();
+        let scrolled_off = screen.get_scrolled_off(id).unwrap_or(0);
+        Self { row, line, scrolled_off }
+    }
+
+    /// Keep `row` on the same output while rows drop off the top of a full
+    /// scrollback, and move it up to the cursor when output went back above
+    /// it (a clear screen, a redraw).
+    fn follow<S: ScreenPort>(&mut self, screen: &S, id: TerminalId) {
+        let scrolled_off = screen.get_scrolled_off(id).unwrap_or(self.scrolled_off);
+        let dropped = scrolled_off.saturating_sub(self.scrolled_off);
+        self.scrolled_off = scrolled_off;
+        if dropped > self.row {
+            // The start itself is gone: everything left is newer
+            *self = Self { row: 0, line: None, scrolled_off };
+        } else {
+            self.row -= dropped;
+        }
+        if let Some(cursor) = cursor_row(screen, id)
+            && cursor < self.row
+        {
+            self.row = cursor;
+            self.line = None;
+        }
+    }
+}
+
+/// A request waiting for its reply.
+struct Wait {
+    conn_id: ConnectionId,
+    target: TerminalId,
+    check: Check,
+    started: Instant,
+    timeout: Option<Duration>,
+    /// Output time of the terminal when its text was last searched, `None`
+    /// before the first search.
+    searched: Option<Option<Instant>>,
+}
+
+/// What a `wait-for` request waits for, taken from its `IpcCommand::WaitFor`.
+pub struct WaitRequest<'a> {
+    pub target: u32,
+    pub condition: &'a WaitCondition,
+    pub timeout_ms: Option<u64>,
+}
+
+/// `wait-for` requests that have not been answered yet.
+#[derive(Default)]
+pub struct WaitQueue {
+    waits: Vec<Wait>,
+}
+
+impl WaitQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start waiting for the request's condition. Replies right away when
+    /// the condition already holds or the request is invalid.
+    pub fn add<P: PtyPort, S: ScreenPort>(
+        &mut self,
+        ipc: &mut dyn IpcPort,
+        conn_id: ConnectionId,
+        request: WaitRequest,
+        usecase: &mut TerminalUsecase<P, S>,
+        now: Instant,
+    ) {
+        let WaitRequest { target, condition, timeout_ms } = request;
+        let check = match condition {
+            WaitCondition::Match { pattern, regex } => {
+                let source = if *regex { pattern.clone() } else { regex::escape(pattern) };
+                // Only output printed from here on counts, not what is
+                // already on the screen or in the scrollback
+                let from = Start::at_cursor(usecase.screen_port_mut(), TerminalId::new(target));
+                match Regex::new(&source) {
+                    Ok(re) => Check::Match { re, from },
+                    Err(e) => {
+                        ipc.send_response(conn_id, IpcResponse::Error(format!("invalid regex: {e}")));
+                        return;
+                    }
+                }
+            }
+            WaitCondition::Idle { ms } => Check::Idle(Duration::from_millis(*ms)),
+            WaitCondition::Exit => Check::Exit,
+        };
+        let mut wait = Wait {
+            conn_id,
+            target: TerminalId::new(target),
+            check,
+            started: now,
+            timeout: timeout_ms.map(Duration::from_millis),
+            searched: None,
+        };
+        match wait.poll(usecase, now) {
+            Some(response) => ipc.send_response(conn_id, response),
+            None => self.waits.push(wait),
+        }
+    }
+
+    /// Reply to the waits that are done and forget the ones whose client
+    /// has gone away.
+    pub fn poll<P: PtyPort, S: ScreenPort>(
+        &mut self,
+        ipc: &mut dyn IpcPort,
+        usecase: &mut TerminalUsecase<P, S>,
+        now: Instant,
+    ) {
+        self.waits.retain_mut(|wait| {
+            if !ipc.is_connected(wait.conn_id) {
+                return false;
+            }
+            match wait.poll(usecase, now) {
+                Some(response) => {
+                    ipc.send_response(wait.conn_id, response);
+                    false
+                }
+                None => true,
+            }
+        });
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.waits.is_empty()
+    }
+}
+
+impl Wait {
+    /// The reply, once the wait is over.
+    fn poll<P: PtyPort, S: ScreenPort>(
+        &mut self,
+        usecase: &mut TerminalUsecase<P, S>,
+        now: Instant,
+    ) -> Option<IpcResponse> {
+        let Some(terminal) = usecase.get_terminal_by_id(self.target) else {
+            return Some(IpcResponse::Error(format!(
+                "terminal not found: {}",
+                self.target.value()
+            )));
+        };
+        let status = terminal.status().clone();
+        let last_output = terminal.last_output();
+
+        let done = match &mut self.check {
+            Check::Match { re, from } => {
+                if self.searched != Some(last_output) {
+                    self.searched = Some(last_output);
+                    from.follow(usecase.screen_port(), self.target);
+                    if let Some(found) = search(usecase.screen_port_mut(), self.target, re, from) {
+                        return Some(IpcResponse::OkWithData(found));
+                    }
+                }
+                // An exited terminal will not print the text any more
+                (!status.is_running()).then(|| {
+                    IpcResponse::Error(format!(
+                        "terminal {} exited without a match",
+                        self.target.value()
+                    ))
+                })
+            }
+            Check::Idle(idle) => {
+                let quiet_since = last_output.map_or(self.started, |t| t.max(self.started));
+                (!status.is_running() || now.duration_since(quiet_since) >= *idle)
+                    .then_some(IpcResponse::Ok)
+            }
+            Check::Exit => match status {
+                TerminalStatus::Exited(exit_code) => {
+                    Some(IpcResponse::OkWithData(IpcResponseData::Exited { exit_code }))
+                }
+                TerminalStatus::Running | TerminalStatus::Idle => None,
+            },
+        };
+        done.or_else(|| {
+            let timeout = self.timeout?;
+            (now.duration_since(self.started) >= timeout)
+                .then(|| IpcResponse::Error(format!("timed out after {timeout:?}")))
+        })
+    }
+}
+
+/// Search the scrollback and screen from the bottom up to `from`, so the
+/// most recent match wins.
+fn search<S: ScreenPort>(
+    screen: &mut S,
+    id: TerminalId,
+    re: &Regex,
+    from: &Start,
+) -> Option<IpcResponseData> {
+    let visible_rows = screen.get_cells(id).map(|c| c.len()).ok()?;
+    let total_rows = screen.get_max_scrollback(id).unwrap_or(0) + visible_rows;
+    (from.row..total_rows).rev().find_map(|row| {
+        let cells = screen.get_row_cells(id, row).ok()?;
+        let (text, line, col) = match_row(re, &cells)?;
+        // The cursor's row as it was when the wait started
+        if row == from.row && from.line.as_ref() == Some(&line) {
+            return None;
+        }
+        Some(IpcResponseData::WaitMatch { text, line, row, col })
+    })
+}
+
+/// The cursor's row, counted from the top of the scrollback.
+fn cursor_row<S: ScreenPort>(screen: &S, id: TerminalId) -> Option<usize> {
+    let cursor = screen.get_cursor(id).ok()?;
+    Some(screen.get_max_scrollback(id).unwrap_or(0) + cursor.row as usize)
+}
+
+/// A row's text without trailing blanks, and the cell column of each of its
+/// characters.
+fn row_text(row: &[Cell]) -> (String, Vec<usize>) {
+    let mut line = String::new();
+    let mut cols = Vec::new();
+    for (col, cell) in row.iter().enumerate() {
+        // Continuation half of a wide character
+        if cell.width == 0 {
+            continue;
+        }
+        line.push(cell.ch);
+        cols.push(col);
+    }
+    line.truncate(line.trim_end().len());
+    (line, cols)
+}
+
+/// The last match in a row: matched text, the row's text without trailing
+/// blanks, and the cell column the match starts at.
+fn match_row(re: &Regex, row: &[Cell]) -> Option<(String, String, usize)> {
+    let (line, cols) = row_text(row);
+    let found = re.find_iter(&line).last()?;
+    let start = line[..found.start()].chars().count();
+    let col = cols.get(start).copied().unwrap_or(row.len());
+    Some((found.as_str().to_string(), line, col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::primitive::TerminalSize;
+    use crate::infrastructure::screen::vt100_screen::Vt100ScreenAdapter;
+
+    fn row(text: &str) -> Vec<Cell> {
+        let mut cells = Vec::new();
+        for ch in text.chars() {
+            if unicode_width::UnicodeWidthChar::width(ch) == Some(2) {
+                cells.push(Cell { ch, width: 2, ..Cell::default() });
+                cells.push(Cell { ch: ' ', width: 0, ..Cell::default() });
+            } else {
+                cells.push(Cell { ch, ..Cell::default() });
+            }
+        }
+        cells.resize(20, Cell::default());
+        cells
+    }
+
+    // =========================================================================
+    // Tests: match_row
+    // =========================================================================
+
+    #[test]
+    fn match_row_reports_text_line_and_column() {
+        let re = Regex::new("PASS|FAIL").unwrap();
+        assert_eq!(
+            match_row(&re, &row("test: FAIL")),
+            Some(("FAIL".to_string(), "test: FAIL".to_string(), 6))
+        );
+    }
+
+    #[test]
+    fn match_row_takes_last_match_in_line() {
+        let re = Regex::new(r"\d+").unwrap();
+        assert_eq!(match_row(&re, &row("1 2 30")).map(|m| (m.0, m.2)), Some(("30".to_string(), 4)));
+    }
+
+    #[test]
+    fn match_row_counts_wide_characters_as_two_columns() {
+        let re = Regex::new("ok").unwrap();
+        assert_eq!(match_row(&re, &row("完了 ok")).map(|m| m.2), Some(5));
+    }
+
+    #[test]
+    fn match_row_ignores_trailing_blanks() {
+        let re = Regex::new(r"\$$").unwrap();
+        assert_eq!(match_row(&re, &row("~ $")).map(|m| m.2), Some(2));
+        assert_eq!(match_row(&re, &row("")), None);
+    }
+
+    // =========================================================================
+    // Tests: search
+    // =========================================================================
+
+    #[test]
+    fn search_skips_text_printed_before_the_wait() {
+        let id = TerminalId::new(1);
+        let mut screen = Vt100ScreenAdapter::new();
+        screen.create(id, TerminalSize::new(20, 3)).unwrap();
+        // An old result that has scrolled into the scrollback
+        screen.process(id, b"old: PASS\r\n1\r\n2\r\n3\r\n$ ").unwrap();
+        assert!(screen.get_max_scrollback(id).unwrap() > 0);
+        let re = Regex::new("PASS").unwrap();
+        let mut from = Start::at_cursor(&mut screen, id);
+
+        assert_eq!(search(&mut screen, id, &re, &from), None);
+        // ...though it is there to be found
+        let everything = Start { row: 0, line: None, scrolled_off: 0 };
+        assert!(search(&mut screen, id, &re, &everything).is_some());
+
+        screen.process(id, b"make\r\nnew: PASS\r\n$ ").unwrap();
+        from.follow(&screen, id);
+        let Some(IpcResponseData::WaitMatch { line, .. }) = search(&mut screen, id, &re, &from) else {
+            panic!("no match after the start");
+        };
+        assert_eq!(line, "new: PASS");
+    }
+
+    #[test]
+    fn search_on_the_start_row_counts_once_the_row_changes() {
+        let id = TerminalId::new(1);
+        let mut screen = Vt100ScreenAdapter::new();
+        screen.create(id, TerminalSize::new(20, 3)).unwrap();
+        screen.process(id, b"PASS ").unwrap();
+        let re = Regex::new("PASS").unwrap();
+        let from = Start::at_cursor(&mut screen, id);
+        assert_eq!(search(&mut screen, id, &re, &from), None);
+
+        screen.process(id, b"PASS").unwrap();
+        assert!(matches!(
+            search(&mut screen, id, &re, &from),
+            Some(IpcResponseData::WaitMatch { col: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn search_keeps_its_start_while_a_full_scrollback_drops_rows() {
+        let id = TerminalId::new(1);
+        let mut screen = Vt100ScreenAdapter::new();
+        screen.create(id, TerminalSize::new(20, 3)).unwrap();
+        let old: String = (0..10_100).map(|i| format!("old {i}\r\n")).collect();
+        screen.process(id, old.as_bytes()).unwrap();
+        screen.process(id, b"$ ").unwrap();
+        let re = Regex::new("PASS").unwrap();
+        let mut from = Start::at_cursor(&mut screen, id);
+
+        // More than a screen of output, with the match well above the
+        // bottom, all between two checks
+        screen.process(id, b"make\r\nnew: PASS\r\n1\r\n2\r\n3\r\n4\r\n$ ").unwrap();
+        from.follow(&screen, id);
+        let Some(IpcResponseData::WaitMatch { line, .. }) = search(&mut screen, id, &re, &from) else {
+            panic!("no match after the start");
+        };
+        assert_eq!(line, "new: PASS");
+    }
+
+    #[test]
+    fn search_after_a_clear_screen_counts_the_whole_screen() {
+        let id = TerminalId::new(1);
+        let mut screen = Vt100ScreenAdapter::new();
+        screen.create(id, TerminalSize::new(20, 3)).unwrap();
+        screen.process(id, b"1\r\n2\r\n$ ").unwrap();
+        let re = Regex::new("PASS").unwrap();
+        let mut from = Start::at_cursor(&mut screen, id);
+
+        screen.process(id, b"\x1b[H\x1b[2JPASS").unwrap();
+        from.follow(&screen, id);
+        assert!(matches!(
+            search(&mut screen, id, &re, &from),
+            Some(IpcResponseData::WaitMatch { col: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Removes terminal control sequences from pty output, leaving plain text.
+//!
+//! Stateful so that escape sequences split across pty reads are still
+//! recognized.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum State {
+    #[default]
+    Ground,
+    /// After ESC.
+    Escape,
+    /// Inside `ESC [ ...` until the final byte.
+    Csi,
+    /// Inside OSC / DCS / APC / PM / SOS until BEL or ST.
+    String,
+    /// ESC seen inside a string (possible ST).
+    StringEscape,
+    /// After `ESC (` and friends; one designator byte follows.
+    Charset,
+}
+
+#[derive(Debug, Default)]
+pub struct AnsiStripper {
+    state: State,
+}
+
+impl AnsiStripper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Strip escape sequences and control characters other than `\n` and
+    /// `\t`. Carriage returns are dropped so CRLF becomes LF.
+    pub fn strip(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(input.len());
+        for &b in input {
+            self.state = match self.state {
+                State::Ground => match b {
+                    0x1b => State::Escape,
+                    b'\n' | b'\t' => {
+                        out.push(b);
+                        State::Ground
+                    }
+                    0x00..=0x1f | 0x7f => State::Ground,
+                    _ => {
+                        out.push(b);
+                        State::Ground
+                    }
+                },
+                State::Escape => match b {
+                    b'[' => State::Csi,
+                    b']' | b'P' | b'X' | b'^' | b'_' => State::String,
+                    b'(' | b')' | b'*' | b'+' | b'#' | b'%' => State::Charset,
+                    _ => State::Ground,
+                },
+                State::Csi => match b {
+                    0x40..=0x7e => State::Ground,
+                    0x1b => State::Escape,
+                    _ => State::Csi,
+                },
+                State::String => match b {
+                    0x07 => State::Ground,
+                    0x1b => State::StringEscape,
+                    _ => State::String,
+                },
+                State::StringEscape => match b {
+                    b'\\' => State::Ground,
+                    _ => State::String,
+                },
+                State::Charset => State::Ground,
+            };
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(input: &[u8]) -> String {
+        String::from_utf8(AnsiStripper::new().strip(input)).unwrap()
+    }
+
+    #[test]
+    fn plain_text_passes_through() {
+        assert_eq!(strip(b"hello world\n"), "hello world\n");
+    }
+
+    #[test]
+    fn utf8_passes_through() {
+        assert_eq!(strip("ビルド完了\n".as_bytes()), "ビルド完了\n");
+    }
+
+    #[test]
+    fn sgr_sequences_are_removed() {
+        assert_eq!(strip(b"\x1b[1;31merror\x1b[0m: failed"), "error: failed");
+    }
+
+    #[test]
+    fn crlf_becomes_lf() {
+        assert_eq!(strip(b"a\r\nb\r\n"), "a\nb\n");
+    }
+
+    #[test]
+    fn osc_title_is_removed_with_bel_and_st() {
+        assert_eq!(strip(b"\x1b]0;title\x07x"), "x");
+        assert_eq!(strip(b"\x1b]7;file:///tmp\x1b\\y"), "y");
+    }
+
+    #[test]
+    fn charset_and_two_byte_escapes_are_removed() {
+        assert_eq!(strip(b"\x1b(Ba\x1b=b\x1b>c"), "abc");
+    }
+
+    #[test]
+    fn control_characters_are_removed_except_tab() {
+        assert_eq!(strip(b"a\x07b\x08c\td"), "abc\td");
+    }
+
+    #[test]
+    fn sequence_split_across_chunks() {
+        let mut stripper = AnsiStripper::new();
+        let mut out = stripper.strip(b"ok\x1b[3");
+        out.extend(stripper.strip(b"2mgreen\x1b"));
+        out.extend(stripper.strip(b"[0m done"));
+        assert_eq!(String::from_utf8(out).unwrap(), "okgreen done");
+    }
+}
@@ -0,0 +1,267 @@
+//! asciicast v2 file format (<https://docs.asciinema.org/manual/asciicast/v2/>).
+//!
+//! A header line (JSON object) followed by one JSON array per event:
+//! `[seconds, "o", "output"]` or `[seconds, "r", "COLSxROWS"]`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::primitive::TerminalSize;
+
+#[derive(Serialize, Deserialize)]
+struct RawHeader {
+    version: u32,
+    width: u16,
+    height: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+}
+
+/// Recording metadata from the header line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastHeader {
+    pub size: TerminalSize,
+    /// Unix time the recording started.
+    pub timestamp: Option<u64>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CastEvent {
+    Output(String),
+    Resize(TerminalSize),
+}
+
+/// A parsed recording. Events are in file order with times in seconds
+/// since the start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cast {
+    pub header: CastHeader,
+    pub events: Vec<(f64, CastEvent)>,
+}
+
+impl Cast {
+    /// Time of the last event.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |(t, _)| *t)
+    }
+}
+
+/// Round to microseconds, the precision asciinema itself writes.
+fn round_time(seconds: f64) -> f64 {
+    (seconds * 1_000_000.0).round() / 1_000_000.0
+}
+
+pub fn header_line(header: &CastHeader) -> String {
+    let mut env = BTreeMap::new();
+    env.insert("TERM".to_string(), "xterm-256color".to_string());
+    if let Ok(shell) = std::env::var("SHELL") {
+        env.insert("SHELL".to_string(), shell);
+    }
+    let raw = RawHeader {
+        version: 2,
+        width: header.size.cols,
+        height: header.size.rows,
+        timestamp: header.timestamp,
+        title: header.title.clone(),
+        env,
+    };
+    serde_json::to_string(&raw).expect("serialize cast header")
+}
+
+pub fn event_line(seconds: f64, event: &CastEvent) -> String {
+    let (code, data) = match event {
+        CastEvent::Output(text) => ("o", text.clone()),
+        CastEvent::Resize(size) => ("r", format!("{}x{}", size.cols, size.rows)),
+    };
+    serde_json::to_string(&(round_time(seconds), code, data)).expect("serialize cast event")
+}
+
+fn parse_size(value: &str) -> Option<TerminalSize> {
+    let (cols, rows) = value.split_once('x')?;
+    Some(TerminalSize::new(cols.parse().ok()?, rows.parse().ok()?))
+}
+
+/// Parse a recording. Input (`"i"`) and marker (`"m"`) events are skipped.
+pub fn parse(content: &str) -> Result<Cast, String> {
+    let mut lines = content.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let (_, first) = lines.next().ok_or_else(|| "empty recording".to_string())?;
+    let raw: RawHeader =
+        serde_json::from_str(first).map_err(|e| format!("invalid header: {e}"))?;
+    if raw.version != 2 {
+        return Err(format!("unsupported asciicast version: {}", raw.version));
+    }
+
+    let mut events = Vec::new();
+    for (i, line) in lines {
+        let (time, code, data): (f64, String, String) =
+            serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+        let event = match code.as_str() {
+            "o" => CastEvent::Output(data),
+            "r" => CastEvent::Resize(
+                parse_size(&data).ok_or_else(|| format!("line {}: invalid size: {data}", i + 1))?,
+            ),
+            _ => continue,
+        };
+        events.push((time, event));
+    }
+
+    Ok(Cast {
+        header: CastHeader {
+            size: TerminalSize::new(raw.width, raw.height),
+            timestamp: raw.timestamp,
+            title: raw.title,
+        },
+        events,
+    })
+}
+
+/// Decode pty output as UTF-8 for an `"o"` event, carrying an incomplete
+/// trailing sequence over to the next chunk in `pending`. Invalid bytes
+/// become U+FFFD.
+pub fn decode_output(pending: &mut Vec<u8>, data: &[u8]) -> String {
+    pending.extend_from_slice(data);
+    let mut out = String::new();
+    let mut rest: &[u8] = pending;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(text) => {
+                out.push_str(text);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).expect("valid prefix"));
+                match e.error_len() {
+                    Some(len) => {
+                        out.push('\u{FFFD}');
+                        rest = &after[len..];
+                    }
+                    // Incomplete sequence at the end: wait for more bytes
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    *pending = rest.to_vec();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> CastHeader {
+        CastHeader {
+            size: TerminalSize::new(80, 24),
+            timestamp: Some(1_700_000_000),
+            title: Some("build".to_string()),
+        }
+    }
+
+    // ========================================================================
+    // Tests: writing
+    // ========================================================================
+
+    #[test]
+    fn header_line_is_v2_object() {
+        let v: serde_json::Value = serde_json::from_str(&header_line(&header())).unwrap();
+        assert_eq!(v["version"], 2);
+        assert_eq!(v["width"], 80);
+        assert_eq!(v["height"], 24);
+        assert_eq!(v["timestamp"], 1_700_000_000);
+        assert_eq!(v["title"], "build");
+        assert_eq!(v["env"]["TERM"], "xterm-256color");
+    }
+
+    #[test]
+    fn event_lines() {
+        assert_eq!(
+            event_line(1.5, &CastEvent::Output("hi\r\n".to_string())),
+            r#"[1.5,"o","hi\r\n"]"#
+        );
+        assert_eq!(
+            event_line(0.1234567, &CastEvent::Resize(TerminalSize::new(100, 30))),
+            r#"[0.123457,"r","100x30"]"#
+        );
+    }
+
+    #[test]
+    fn written_recording_parses_back() {
+        let content = [
+            header_line(&header()),
+            event_line(0.25, &CastEvent::Output("\x1b[1mhi\x1b[0m".to_string())),
+            event_line(1.0, &CastEvent::Resize(TerminalSize::new(120, 40))),
+        ]
+        .join("\n");
+
+        let cast = parse(&content).unwrap();
+
+        assert_eq!(cast.header, header());
+        assert_eq!(
+            cast.events,
+            vec![
+                (0.25, CastEvent::Output("\x1b[1mhi\x1b[0m".to_string())),
+                (1.0, CastEvent::Resize(TerminalSize::new(120, 40))),
+            ]
+        );
+        assert_eq!(cast.duration(), 1.0);
+    }
+
+    // ========================================================================
+    // Tests: parsing
+    // ========================================================================
+
+    #[test]
+    fn parse_skips_input_and_marker_events() {
+        let content = "{\"version\":2,\"width\":10,\"height\":5}\n[0.1,\"i\",\"x\"]\n[0.2,\"m\",\"\"]\n[0.3,\"o\",\"y\"]\n";
+        let cast = parse(content).unwrap();
+        assert_eq!(cast.events, vec![(0.3, CastEvent::Output("y".to_string()))]);
+        assert_eq!(cast.header.title, None);
+    }
+
+    #[test]
+    fn parse_rejects_other_versions() {
+        let err = parse("{\"version\":1,\"width\":10,\"height\":5}").unwrap_err();
+        assert!(err.contains("version"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_rejects_empty_and_malformed() {
+        assert!(parse("").is_err());
+        assert!(parse("not json").is_err());
+        let err = parse("{\"version\":2,\"width\":1,\"height\":1}\n[0.1,\"o\"]").unwrap_err();
+        assert!(err.contains("line 2"), "got: {err}");
+        assert!(parse("{\"version\":2,\"width\":1,\"height\":1}\n[0.1,\"r\",\"big\"]").is_err());
+    }
+
+    // ========================================================================
+    // Tests: decode_output
+    // ========================================================================
+
+    #[test]
+    fn decode_output_carries_split_utf8() {
+        let bytes = "日本".as_bytes();
+        let mut pending = Vec::new();
+        assert_eq!(decode_output(&mut pending, &bytes[..4]), "日");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(decode_output(&mut pending, &bytes[4..]), "本");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn decode_output_replaces_invalid_bytes() {
+        let mut pending = Vec::new();
+        assert_eq!(decode_output(&mut pending, b"a\xffb"), "a\u{FFFD}b");
+        assert!(pending.is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+//! File-backed `RecorderPort` writing asciicast v2 recordings, by default
+//! to `~/.cli-manager/recordings/`.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::domain::primitive::{TerminalId, TerminalSize};
+use crate::infrastructure::log::asciicast::{self, CastEvent, CastHeader};
+use crate::infrastructure::log::file_logger::file_stem;
+use crate::interface_adapter::port::RecorderPort;
+use crate::shared::error::AppError;
+
+/// Default directory for recordings.
+pub fn recordings_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home).join(".cli-manager").join("recordings")
+}
+
+struct Recording {
+    file: File,
+    path: PathBuf,
+    started: Instant,
+    /// Incomplete UTF-8 sequence from the previous output chunk.
+    pending: Vec<u8>,
+}
+
+impl Recording {
+    fn write_event(&mut self, event: &CastEvent) -> io::Result<()> {
+        let mut line = asciicast::event_line(self.started.elapsed().as_secs_f64(), event);
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+    }
+}
+
+pub struct CastRecorder {
+    dir: PathBuf,
+    recordings: HashMap<TerminalId, Recording>,
+}
+
+impl CastRecorder {
+    /// Recorder writing to `dir` unless a path is given to `start`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            recordings: HashMap::new(),
+        }
+    }
+}
+
+impl RecorderPort for CastRecorder {
+    fn start(
+        &mut self,
+        id: TerminalId,
+        name: &str,
+        size: TerminalSize,
+        path: Option<&Path>,
+    ) -> Result<PathBuf, AppError> {
+        let to_err = |source| AppError::Record { id, source };
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = match path {
+            Some(p) => p.to_path_buf(),
+            None => {
+                fs::create_dir_all(&self.dir).map_err(to_err)?;
+                self.dir
+                    .join(format!("{}-{}.cast", started_at, file_stem(id, name)))
+            }
+        };
+
+        let mut options = OpenOptions::new();
+        options.create(true).write(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&path).map_err(to_err)?;
+        let header = CastHeader {
+            size,
+            timestamp: Some(started_at),
+            title: Some(name.to_string()),
+        };
+        writeln!(file, "{}", asciicast::header_line(&header)).map_err(to_err)?;
+
+        self.recordings.insert(
+            id,
+            Recording {
+                file,
+                path: path.clone(),
+                started: Instant::now(),
+                pending: Vec::new(),
+            },
+        );
+        Ok(path)
+    }
+
+    fn stop(&mut self, id: TerminalId) -> Option<PathBuf> {
+        self.recordings.remove(&id).map(|r| r.path)
+    }
+
+    fn output(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+        let Some(recording) = self.recordings.get_mut(&id) else {
+            return Ok(());
+        };
+        let text = asciicast::decode_output(&mut recording.pending, data);
+        if text.is_empty() {
+            return Ok(());
+        }
+        recording
+            .write_event(&CastEvent::Output(text))
+            .map_err(|source| AppError::Record { id, source })
+    }
+
+    fn resize(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+        match self.recordings.get_mut(&id) {
+            Some(recording) => recording
+                .write_event(&CastEvent::Resize(size))
+                .map_err(|source| AppError::Record { id, source }),
+            None => Ok(()),
+        }
+    }
+
+    fn recording_path(&self, id: TerminalId) -> Option<PathBuf> {
+        self.recordings.get(&id).map(|r| r.path.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        PathBuf::from(format!("/tmp/cm-cast-test-{ts}-{name}"))
+    }
+
+    #[test]
+    fn recordings_dir_is_under_cli_manager() {
+        assert!(recordings_dir().ends_with(".cli-manager/recordings"));
+    }
+
+    #[test]
+    fn start_writes_header_to_default_location() {
+        let dir = temp_dir("default");
+        let mut recorder = CastRecorder::new(dir.clone());
+        let id = TerminalId::new(2);
+
+        let path = recorder
+            .start(id, "agent run", TerminalSize::new(100, 30), None)
+            .unwrap();
+
+        assert_eq!(path.parent().unwrap(), dir);
+        assert!(path.to_string_lossy().ends_with("-2-agent_run.cast"));
+        assert_eq!(recorder.recording_path(id), Some(path.clone()));
+        let cast = asciicast::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(cast.header.size, TerminalSize::new(100, 30));
+        assert_eq!(cast.header.title.as_deref(), Some("agent run"));
+        assert!(cast.events.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn records_output_and_resize_in_order() {
+        let dir = temp_dir("events");
+        fs::create_dir_all(&dir).unwrap();
+        let explicit = dir.join("session.cast");
+        let mut recorder = CastRecorder::new(temp_dir("unused"));
+        let id = TerminalId::new(1);
+        recorder.start(id, "t", TerminalSize::new(80, 24), Some(&explicit)).unwrap();
+
+        recorder.output(id, b"$ ls\r\n").unwrap();
+        recorder.resize(id, TerminalSize::new(90, 20)).unwrap();
+        recorder.output(id, "日".as_bytes()).unwrap();
+        assert_eq!(recorder.stop(id), Some(explicit.clone()));
+        recorder.output(id, b"after stop").unwrap();
+
+        let cast = asciicast::parse(&fs::read_to_string(&explicit).unwrap()).unwrap();
+        let events: Vec<CastEvent> = cast.events.into_iter().map(|(_, e)| e).collect();
+        assert_eq!(
+            events,
+            vec![
+                CastEvent::Output("$ ls\r\n".to_string()),
+                CastEvent::Resize(TerminalSize::new(90, 20)),
+                CastEvent::Output("日".to_string()),
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn split_utf8_is_written_as_one_event() {
+        let dir = temp_dir("split");
+        let mut recorder = CastRecorder::new(dir.clone());
+        let id = TerminalId::new(1);
+        let path = recorder.start(id, "t", TerminalSize::new(80, 24), None).unwrap();
+        let bytes = "あ".as_bytes();
+
+        recorder.output(id, &bytes[..1]).unwrap();
+        recorder.output(id, &bytes[1..]).unwrap();
+
+        let cast = asciicast::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(cast.events.len(), 1);
+        assert_eq!(cast.events[0].1, CastEvent::Output("あ".to_string()));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn start_with_unwritable_path_fails() {
+        let mut recorder = CastRecorder::new(temp_dir("unused"));
+        let result = recorder.start(
+            TerminalId::new(1),
+            "t",
+            TerminalSize::new(80, 24),
+            Some(Path::new("/nonexistent-dir/x.cast")),
+        );
+        assert!(matches!(result, Err(AppError::Record { .. })));
+    }
+}
@@ -0,0 +1,389 @@
+//! File-backed `LogPort`: one log file per terminal under
+//! `~/.cli-manager/logs/<session>/`, rotated by size.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::domain::primitive::{LogMode, LogOptions, LogStatus, TerminalId};
+use crate::infrastructure::log::ansi_stripper::AnsiStripper;
+use crate::interface_adapter::port::LogPort;
+use crate::shared::error::AppError;
+
+/// Rotated files kept next to the current one (`.1` is the newest).
+const ROTATED_FILES_KEPT: u32 = 5;
+
+/// Longest terminal name used in a log file name.
+const MAX_NAME_LEN: usize = 48;
+
+/// Log directory of this cli-manager instance:
+/// `~/.cli-manager/logs/<unix-time>-<pid>`.
+///
+/// Each run gets its own directory so terminal ids, which restart at 1,
+/// never mix output from different sessions.
+pub fn session_log_dir() -> PathBuf {
+    let started = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home)
+        .join(".cli-manager")
+        .join("logs")
+        .join(format!("{}-{}", started, std::process::id()))
+}
+
+/// `<id>` or `<id>-<name>`, with the name reduced to filename-safe
+/// characters. Shared with the session recorder.
+pub(crate) fn file_stem(id: TerminalId, name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_NAME_LEN)
+        .collect();
+    if safe.is_empty() {
+        id.value().to_string()
+    } else {
+        format!("{}-{}", id.value(), safe)
+    }
+}
+
+/// `<id>-<name>.log`
+fn log_file_name(id: TerminalId, name: &str) -> String {
+    format!("{}.log", file_stem(id, name))
+}
+
+/// `path` with `.n` appended (`1-shell.log.2`).
+fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+struct TerminalLog {
+    file: File,
+    status: LogStatus,
+    stripper: AnsiStripper,
+}
+
+impl TerminalLog {
+    /// Shift `path` to `path.1` (and older files up by one), then start an
+    /// empty file.
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = &self.status.path;
+        let _ = fs::remove_file(rotated_path(path, ROTATED_FILES_KEPT));
+        for n in (1..ROTATED_FILES_KEPT).rev() {
+            let from = rotated_path(path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, n + 1))?;
+            }
+        }
+        fs::rename(path, rotated_path(path, 1))?;
+        self.file = open_append(path)?;
+        self.status.bytes_written = 0;
+        Ok(())
+    }
+
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        let data = match self.status.mode {
+            LogMode::Raw => data.to_vec(),
+            LogMode::Text => self.stripper.strip(data),
+        };
+        if data.is_empty() {
+            return Ok(());
+        }
+        if self.status.bytes_written > 0
+            && self.status.bytes_written + data.len() as u64 > self.status.max_bytes
+        {
+            self.rotate()?;
+        }
+        self.file.write_all(&data)?;
+        self.status.bytes_written += data.len() as u64;
+        Ok(())
+    }
+}
+
+pub struct FileLogger {
+    dir: PathBuf,
+    logs: HashMap<TerminalId, TerminalLog>,
+}
+
+impl FileLogger {
+    /// Logger writing into `dir`, which is created on the first `start`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            logs: HashMap::new(),
+        }
+    }
+}
+
+impl LogPort for FileLogger {
+    fn start(
+        &mut self,
+        id: TerminalId,
+        name: &str,
+        options: &LogOptions,
+    ) -> Result<LogStatus, AppError> {
+        let to_err = |source| AppError::Log { id, source };
+        fs::create_dir_all(&self.dir).map_err(to_err)?;
+
+        // Starting again while still logging keeps appending to the same
+        // file, even if the terminal has been renamed since. After a stop the
+        // file is named after the terminal's current name.
+        let path = match self.logs.remove(&id) {
+            Some(previous) => previous.status.path,
+            None => self.dir.join(log_file_name(id, name)),
+        };
+        let file = open_append(&path).map_err(to_err)?;
+        let bytes_written = file.metadata().map_err(to_err)?.len();
+
+        let status = LogStatus {
+            path,
+            mode: options.mode,
+            max_bytes: options.max_bytes.max(1),
+            bytes_written,
+        };
+        self.logs.insert(
+            id,
+            TerminalLog {
+                file,
+                status: status.clone(),
+                stripper: AnsiStripper::new(),
+            },
+        );
+        Ok(status)
+    }
+
+    fn stop(&mut self, id: TerminalId) -> bool {
+        self.logs.remove(&id).is_some()
+    }
+
+    fn write(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+        match self.logs.get_mut(&id) {
+            Some(log) => log.append(data).map_err(|source| AppError::Log { id, source }),
+            None => Ok(()),
+        }
+    }
+
+    fn status(&self, id: TerminalId) -> Option<LogStatus> {
+        self.logs.get(&id).map(|log| log.status.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        PathBuf::from(format!("/tmp/cm-log-test-{ts}-{name}"))
+    }
+
+    fn options(mode: LogMode, max_bytes: u64) -> LogOptions {
+        LogOptions { mode, max_bytes }
+    }
+
+    // ========================================================================
+    // Tests: file naming
+    // ========================================================================
+
+    #[test]
+    fn log_file_name_sanitizes_name() {
+        assert_eq!(log_file_name(TerminalId::new(3), "api server"), "3-api_server.log");
+        assert_eq!(log_file_name(TerminalId::new(1), "../etc"), "1-.._etc.log");
+        assert_eq!(log_file_name(TerminalId::new(2), ""), "2.log");
+    }
+
+    #[test]
+    fn log_file_name_truncates_long_names() {
+        let name = "x".repeat(200);
+        let file = log_file_name(TerminalId::new(1), &name);
+        assert_eq!(file.len(), "1-".len() + MAX_NAME_LEN + ".log".len());
+    }
+
+    #[test]
+    fn session_log_dir_is_under_cli_manager_logs() {
+        let dir = session_log_dir();
+        let session = dir.file_name().unwrap().to_string_lossy().to_string();
+        assert!(dir.parent().unwrap().ends_with(".cli-manager/logs"));
+        assert!(session.ends_with(&format!("-{}", std::process::id())));
+    }
+
+    // ========================================================================
+    // Tests: writing
+    // ========================================================================
+
+    #[test]
+    fn start_creates_dir_and_file() {
+        let dir = temp_dir("start");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+
+        let status = logger.start(id, "shell", &LogOptions::default()).unwrap();
+
+        assert_eq!(status.path, dir.join("1-shell.log"));
+        assert_eq!(status.bytes_written, 0);
+        assert!(status.path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn raw_mode_keeps_escape_sequences() {
+        let dir = temp_dir("raw");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Raw, 1024)).unwrap();
+
+        logger.write(id, b"\x1b[31mred\x1b[0m\r\n").unwrap();
+
+        assert_eq!(fs::read(&status.path).unwrap(), b"\x1b[31mred\x1b[0m\r\n");
+        assert_eq!(logger.status(id).unwrap().bytes_written, 14);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn text_mode_strips_escape_sequences() {
+        let dir = temp_dir("text");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Text, 1024)).unwrap();
+
+        logger.write(id, b"\x1b[31mred\x1b[0m\r\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&status.path).unwrap(), "red\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_without_start_is_ignored() {
+        let dir = temp_dir("ignored");
+        let mut logger = FileLogger::new(dir.clone());
+
+        logger.write(TerminalId::new(1), b"data").unwrap();
+
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn stop_closes_log() {
+        let dir = temp_dir("stop");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Raw, 1024)).unwrap();
+
+        assert!(logger.stop(id));
+        logger.write(id, b"after").unwrap();
+
+        assert!(logger.status(id).is_none());
+        assert!(fs::read(&status.path).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restart_appends_to_same_file() {
+        let dir = temp_dir("restart");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        logger.start(id, "t", &options(LogMode::Raw, 1024)).unwrap();
+        logger.write(id, b"one ").unwrap();
+        logger.stop(id);
+
+        let status = logger.start(id, "t", &options(LogMode::Raw, 1024)).unwrap();
+        logger.write(id, b"two").unwrap();
+
+        assert_eq!(status.bytes_written, 4);
+        assert_eq!(fs::read_to_string(&status.path).unwrap(), "one two");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn start_again_while_logging_keeps_path_after_rename() {
+        let dir = temp_dir("rename");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let first = logger.start(id, "old", &options(LogMode::Raw, 1024)).unwrap();
+
+        let second = logger.start(id, "new", &options(LogMode::Text, 1024)).unwrap();
+
+        assert_eq!(first.path, second.path);
+        assert_eq!(second.mode, LogMode::Text);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // ========================================================================
+    // Tests: rotation
+    // ========================================================================
+
+    #[test]
+    fn rotates_when_limit_exceeded() {
+        let dir = temp_dir("rotate");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Raw, 10)).unwrap();
+
+        logger.write(id, b"12345678").unwrap();
+        logger.write(id, b"abcd").unwrap();
+
+        assert_eq!(fs::read_to_string(rotated_path(&status.path, 1)).unwrap(), "12345678");
+        assert_eq!(fs::read_to_string(&status.path).unwrap(), "abcd");
+        assert_eq!(logger.status(id).unwrap().bytes_written, 4);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn oversized_chunk_is_written_whole_to_fresh_file() {
+        let dir = temp_dir("oversized");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Raw, 4)).unwrap();
+
+        logger.write(id, b"0123456789").unwrap();
+
+        assert_eq!(fs::read_to_string(&status.path).unwrap(), "0123456789");
+        assert!(!rotated_path(&status.path, 1).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keeps_limited_number_of_rotated_files() {
+        let dir = temp_dir("keep");
+        let mut logger = FileLogger::new(dir.clone());
+        let id = TerminalId::new(1);
+        let status = logger.start(id, "t", &options(LogMode::Raw, 1)).unwrap();
+
+        for i in 0..(ROTATED_FILES_KEPT + 3) {
+            logger.write(id, i.to_string().as_bytes()).unwrap();
+        }
+
+        let last = ROTATED_FILES_KEPT + 2;
+        assert_eq!(fs::read_to_string(&status.path).unwrap(), last.to_string());
+        assert_eq!(
+            fs::read_to_string(rotated_path(&status.path, 1)).unwrap(),
+            (last - 1).to_string()
+        );
+        assert!(rotated_path(&status.path, ROTATED_FILES_KEPT).exists());
+        assert!(!rotated_path(&status.path, ROTATED_FILES_KEPT + 1).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod ansi_stripper;
+pub mod asciicast;
+pub mod cast_recorder;
+pub mod file_logger;
@@ -7,7 +7,8 @@ use super::tool_handlers::handle_tool_call;
 /// Build a JSON-RPC response for a given request.
 ///
 /// Returns `None` for notification messages that should not generate a response.
-fn build_response(request: &Value) -> Option<Value> {
+/// Tool calls go to the instance picked by `instance` (see `cm mcp-server --instance`).
+fn build_response(request: &Value, instance: Option<&str>) -> Option<Value> {
     let id = request.get("id").cloned().unwrap_or(Value::Null);
     let method = request
         .get("method")
@@ -48,7 +49,7 @@ fn build_response(request: &Value) -> Option<Value> {
                 .unwrap_or("");
             let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
 
-            let (content, is_error) = handle_tool_call(tool_name, &arguments);
+            let (content, is_error) = handle_tool_call(tool_name, &arguments, instance);
 
             Some(json!({
                 "jsonrpc": "2.0",
@@ -87,8 +88,9 @@ fn build_parse_error(error_message: &str) -> Value {
     })
 }
 
-/// Run the MCP server using stdio transport.
-pub fn run() -> ! {
+/// Run the MCP server using stdio transport, talking to the instance
+/// `instance` selects (`--instance <name|pid>`).
+pub fn run(instance: Option<String>) -> ! {
     eprintln!("cli-manager MCP server starting...");
 
     let stdin = io::stdin();
@@ -115,7 +117,7 @@ pub fn run() -> ! {
             }
         };
 
-        if let Some(response) = build_response(&request) {
+        if let Some(response) = build_response(&request, instance.as_deref()) {
             let _ = writeln!(stdout, "{}", response);
             let _ = stdout.flush();
         }
@@ -140,7 +142,7 @@ mod tests {
             "method": "initialize",
             "params": {}
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 1);
         let result = &response["result"];
@@ -157,7 +159,7 @@ mod tests {
             "id": 42,
             "method": "initialize"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["id"], 42);
     }
 
@@ -168,7 +170,7 @@ mod tests {
             "id": "abc-123",
             "method": "initialize"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["id"], "abc-123");
     }
 
@@ -182,7 +184,7 @@ mod tests {
             "jsonrpc": "2.0",
             "method": "notifications/initialized"
         });
-        let response = build_response(&request);
+        let response = build_response(&request, None);
         assert!(response.is_none());
     }
 
@@ -197,11 +199,11 @@ mod tests {
             "id": 2,
             "method": "tools/list"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 2);
         let tools = response["result"]["tools"].as_array().unwrap();
-        assert_eq!(tools.len(), 11);
+        assert_eq!(tools.len(), 15);
     }
 
     #[test]
@@ -211,7 +213,7 @@ mod tests {
             "id": 3,
             "method": "tools/list"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         let tools = response["result"]["tools"].as_array().unwrap();
         let names: Vec<&str> = tools
             .iter()
@@ -238,7 +240,7 @@ mod tests {
                 "arguments": {}
             }
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 4);
         assert_eq!(response["result"]["isError"], true);
@@ -260,7 +262,7 @@ mod tests {
             "method": "tools/call",
             "params": {}
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         // When name is missing, it defaults to "" which is an unknown tool
         assert_eq!(response["result"]["isError"], true);
     }
@@ -272,11 +274,11 @@ mod tests {
             "id": 6,
             "method": "tools/call",
             "params": {
-                "name": "terminal_kill",
-                "arguments": {}
+                "name": "terminal_rename",
+                "arguments": {"target": 1}
             }
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["result"]["isError"], true);
         let text = response["result"]["content"][0]["text"].as_str().unwrap();
         assert!(text.contains("Missing required parameter"), "got: {text}");
@@ -293,7 +295,7 @@ mod tests {
                 "arguments": {}
             }
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 7);
         // result has content and isError
@@ -312,7 +314,7 @@ mod tests {
             "id": 10,
             "method": "ping"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 10);
         assert!(response["result"].is_object());
@@ -331,7 +333,7 @@ mod tests {
             "id": 11,
             "method": "some/unknown/method"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["jsonrpc"], "2.0");
         assert_eq!(response["id"], 11);
         assert_eq!(response["error"]["code"], -32601);
@@ -348,7 +350,7 @@ mod tests {
             "jsonrpc": "2.0",
             "id": 12
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert_eq!(response["error"]["code"], -32601);
     }
 
@@ -363,7 +365,7 @@ mod tests {
             "id": null,
             "method": "ping"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert!(response["id"].is_null());
     }
 
@@ -373,7 +375,7 @@ mod tests {
             "jsonrpc": "2.0",
             "method": "ping"
         });
-        let response = build_response(&request).unwrap();
+        let response = build_response(&request, None).unwrap();
         assert!(response["id"].is_null());
     }
 
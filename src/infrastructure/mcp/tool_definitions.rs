@@ -23,7 +23,25 @@ pub fn get_tool_definitions() -> Vec<Value> {
                     },
                     "command": {
                         "type": "string",
-                        "description": "Command to run in the new terminal (default: $SHELL)"
+                        "description": "Command line to run in the new terminal via `$SHELL -c` (default: interactive $SHELL)"
+                    },
+                    "cwd": {
+                        "type": "string",
+                        "description": "Working directory for the new terminal (default: CLI Manager's cwd)"
+                    },
+                    "env": {
+                        "type": "object",
+                        "additionalProperties": {"type": "string"},
+                        "description": "Extra environment variables as KEY: VALUE pairs"
+                    },
+                    "restart": {
+                        "type": "string",
+                        "enum": ["never", "on-failure", "always"],
+                        "description": "Start the process again when it exits: never (default), on-failure (non-zero exit) or always. Restarts back off from 1s up to 60s"
+                    },
+                    "group": {
+                        "type": "string",
+                        "description": "Sidebar group to put the terminal in, e.g. \"api\". Target the whole group with \"#api\""
                     }
                 },
                 "required": []
@@ -36,11 +54,48 @@ pub fn get_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to kill"
+                        "type": ["integer", "string"],
+                        "description": "Terminal to kill: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
+                    }
+                },
+                "required": []
+            }
+        }),
+        json!({
+            "name": "terminal_respawn",
+            "description": "Start a terminal's process again in the same slot, keeping its ID, name, memo and scrollback (a separator line marks the restart). Use it to restart an exited dev server or watcher",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": ["integer", "string"],
+                        "description": "Terminal to respawn: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
+                    },
+                    "kill": {
+                        "type": "boolean",
+                        "description": "Kill the process first if it is still running (default: false, which fails for a running terminal)"
+                    }
+                },
+                "required": []
+            }
+        }),
+        json!({
+            "name": "terminal_signal",
+            "description": "Send a signal to the foreground process group of a terminal, e.g. INT to interrupt a stuck command the way Ctrl+C would, or STOP/CONT to pause and resume it",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": ["integer", "string"],
+                        "description": "Terminal to signal: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
+                    },
+                    "signal": {
+                        "type": "string",
+                        "enum": ["INT", "TERM", "HUP", "KILL", "STOP", "CONT"],
+                        "description": "Signal to send"
                     }
                 },
-                "required": ["target"]
+                "required": ["signal"]
             }
         }),
         json!({
@@ -50,11 +105,11 @@ pub fn get_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to select"
+                        "type": ["integer", "string"],
+                        "description": "Terminal to select: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
                     }
                 },
-                "required": ["target"]
+                "required": []
             }
         }),
         json!({
@@ -64,52 +119,134 @@ pub fn get_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to rename"
+                        "type": ["integer", "string"],
+                        "description": "Terminal to rename: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
                     },
                     "name": {
                         "type": "string",
                         "description": "New name for the terminal"
                     }
                 },
-                "required": ["target", "name"]
+                "required": ["name"]
+            }
+        }),
+        json!({
+            "name": "terminal_set_status",
+            "description": "Publish the agent's state for a terminal, e.g. \"thinking\", \"waiting for approval\" or \"tests failing\". Shown in the sidebar next to the terminal name and searchable in the quick switcher. Call without text to clear it",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": ["integer", "string"],
+                        "description": "Terminal the status is for: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "Status text; omit or leave empty to clear the status"
+                    },
+                    "icon": {
+                        "type": "string",
+                        "description": "Short symbol shown after the terminal name, e.g. \"⏳\""
+                    },
+                    "color": {
+                        "type": ["string", "integer"],
+                        "description": "Color of the status: black, red, green, yellow, blue, magenta, cyan, white, gray, a palette index 0-255 or \"#rrggbb\""
+                    }
+                },
+                "required": []
             }
         }),
         json!({
             "name": "terminal_send_keys",
-            "description": "Send keystrokes to a terminal. Supports special keys: Enter, Tab, Escape, Space, BSpace, C-a through C-z",
+            "description": "Send keystrokes to a terminal. Supports tmux key names: Enter, Tab, BTab, Escape, Space, BSpace, Up, Down, Left, Right, Home, End, PageUp, PageDown, Insert, Delete, F1-F12, with C- (Ctrl), M- (Alt) and S- (Shift) prefixes (e.g. C-c, M-x, C-Right), and 0xNN for raw bytes",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to send keys to"
+                        "type": ["integer", "string", "array"],
+                        "items": {"type": ["integer", "string"]},
+                        "description": "Terminal to send keys to: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\", or an array of those to send to several terminals, e.g. [2, \"web\"]. Defaults to \"@self\", the terminal the agent runs in"
                     },
                     "keys": {
                         "type": "array",
                         "items": {"type": "string"},
                         "description": "Array of keys/text to send"
+                    },
+                    "literal": {
+                        "type": "boolean",
+                        "description": "Send keys as literal text without interpreting key names (default: false)"
                     }
                 },
-                "required": ["target", "keys"]
+                "required": ["keys"]
             }
         }),
         json!({
             "name": "terminal_capture",
-            "description": "Capture the current visible output of a terminal",
+            "description": "Capture the output of a terminal as text, text with ANSI colors, or styled cells. Lines are numbered like tmux: 0 is the top of the screen, negative lines are scrollback. Long captures are paged; pass the returned next_start as start to read on",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to capture"
+                        "type": ["integer", "string"],
+                        "description": "Terminal to capture: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
                     },
                     "include_scrollback": {
                         "type": "boolean",
-                        "description": "Include scrollback buffer content (default: false)"
+                        "description": "Start at the oldest scrollback line instead of the top of the screen (default: false)"
+                    },
+                    "start": {
+                        "type": "integer",
+                        "description": "First line (default: 0, or the oldest scrollback line with include_scrollback)"
+                    },
+                    "end": {
+                        "type": "integer",
+                        "description": "Last line, inclusive (default: bottom of the screen)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["text", "ansi", "cells"],
+                        "description": "text (default), ansi for SGR color escapes, or cells for per-row styled runs"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum lines to return (default: 500)"
                     }
                 },
-                "required": ["target"]
+                "required": []
+            }
+        }),
+        json!({
+            "name": "terminal_wait_for",
+            "description": "Wait until a terminal prints text matching a pattern (output from before the call does not count), its output has been quiet for a while, or its process exits. Give exactly one of pattern, idle_ms or exit",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": ["integer", "string"],
+                        "description": "Terminal to watch: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "Text to wait for in output printed after the call (a regular expression when regex is true)"
+                    },
+                    "regex": {
+                        "type": "boolean",
+                        "description": "Treat pattern as a regular expression (default: false)"
+                    },
+                    "idle_ms": {
+                        "type": "integer",
+                        "description": "Wait until there has been no output for this many milliseconds"
+                    },
+                    "exit": {
+                        "type": "boolean",
+                        "description": "Wait until the terminal's process exits and return its exit code"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Give up with an error after this many milliseconds (default: no limit)"
+                    }
+                },
+                "required": []
             }
         }),
         json!({
@@ -142,19 +279,23 @@ pub fn get_tool_definitions() -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "target": {
-                        "type": "integer",
-                        "description": "Terminal ID to paste into"
+                        "type": ["integer", "string"],
+                        "description": "Terminal to paste into: an ID, a name, a glob such as \"agent-*\" or a group such as \"#api\" (runs on every match), \"@active\", \"@self\" or \"@last\". Defaults to \"@self\", the terminal the agent runs in"
                     }
                 },
-                "required": ["target"]
+                "required": []
             }
         }),
         json!({
             "name": "notify",
-            "description": "Send a desktop notification through CLI Manager",
+            "description": "Notify the user: marks the terminal in the sidebar and sends a desktop notification titled with its name",
             "inputSchema": {
                 "type": "object",
                 "properties": {
+                    "target": {
+                        "type": ["integer", "string"],
+                        "description": "Terminal the notification is about: an ID, a name, \"@active\", \"@self\" or \"@last\". Defaults to the terminal the agent runs in"
+                    },
                     "title": {
                         "type": "string",
                         "description": "Notification title (default: 'CLI Manager')"
@@ -179,9 +320,9 @@ mod tests {
     // ========================================================================
 
     #[test]
-    fn tool_definitions_returns_11_tools() {
+    fn tool_definitions_returns_15_tools() {
         let tools = get_tool_definitions();
-        assert_eq!(tools.len(), 11);
+        assert_eq!(tools.len(), 15);
     }
 
     // ========================================================================
@@ -268,10 +409,14 @@ mod tests {
         assert!(names.contains(&"terminal_list"));
         assert!(names.contains(&"terminal_create"));
         assert!(names.contains(&"terminal_kill"));
+        assert!(names.contains(&"terminal_respawn"));
+        assert!(names.contains(&"terminal_signal"));
         assert!(names.contains(&"terminal_select"));
         assert!(names.contains(&"terminal_rename"));
+        assert!(names.contains(&"terminal_set_status"));
         assert!(names.contains(&"terminal_send_keys"));
         assert!(names.contains(&"terminal_capture"));
+        assert!(names.contains(&"terminal_wait_for"));
         assert!(names.contains(&"buffer_get"));
         assert!(names.contains(&"buffer_set"));
         assert!(names.contains(&"buffer_paste"));
@@ -317,47 +462,56 @@ mod tests {
     }
 
     #[test]
-    fn terminal_kill_requires_target() {
+    fn terminal_kill_target_is_optional() {
         let tool = find_tool("terminal_kill");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 1);
-        assert_eq!(required[0], "target");
+        assert!(required.is_empty());
     }
 
     #[test]
-    fn terminal_select_requires_target() {
+    fn terminal_select_target_is_optional() {
         let tool = find_tool("terminal_select");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 1);
-        assert_eq!(required[0], "target");
+        assert!(required.is_empty());
     }
 
     #[test]
-    fn terminal_rename_requires_target_and_name() {
+    fn terminal_rename_requires_name() {
         let tool = find_tool("terminal_rename");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 2);
-        let req_strs: Vec<&str> = required.iter().map(|v| v.as_str().unwrap()).collect();
-        assert!(req_strs.contains(&"target"));
-        assert!(req_strs.contains(&"name"));
+        assert_eq!(required, &vec![json!("name")]);
     }
 
     #[test]
-    fn terminal_send_keys_requires_target_and_keys() {
+    fn terminal_set_status_has_no_required_fields() {
+        let tool = find_tool("terminal_set_status");
+        let required = tool["inputSchema"]["required"].as_array().unwrap();
+        assert!(required.is_empty());
+        let props = &tool["inputSchema"]["properties"];
+        assert_eq!(props["text"]["type"], "string");
+        assert_eq!(props["icon"]["type"], "string");
+        assert_eq!(props["color"]["type"], json!(["string", "integer"]));
+    }
+
+    #[test]
+    fn terminal_send_keys_requires_keys() {
         let tool = find_tool("terminal_send_keys");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 2);
-        let req_strs: Vec<&str> = required.iter().map(|v| v.as_str().unwrap()).collect();
-        assert!(req_strs.contains(&"target"));
-        assert!(req_strs.contains(&"keys"));
+        assert_eq!(required, &vec![json!("keys")]);
     }
 
     #[test]
-    fn terminal_capture_requires_target() {
+    fn terminal_capture_target_is_optional() {
         let tool = find_tool("terminal_capture");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 1);
-        assert_eq!(required[0], "target");
+        assert!(required.is_empty());
+    }
+
+    #[test]
+    fn terminal_wait_for_target_is_optional() {
+        let tool = find_tool("terminal_wait_for");
+        let required = tool["inputSchema"]["required"].as_array().unwrap();
+        assert!(required.is_empty());
     }
 
     #[test]
@@ -376,11 +530,10 @@ mod tests {
     }
 
     #[test]
-    fn buffer_paste_requires_target() {
+    fn buffer_paste_target_is_optional() {
         let tool = find_tool("buffer_paste");
         let required = tool["inputSchema"]["required"].as_array().unwrap();
-        assert_eq!(required.len(), 1);
-        assert_eq!(required[0], "target");
+        assert!(required.is_empty());
     }
 
     #[test]
@@ -411,6 +564,34 @@ mod tests {
         assert_eq!(props["command"]["type"], "string");
     }
 
+    #[test]
+    fn terminal_create_has_cwd_and_env_properties() {
+        let tool = find_tool("terminal_create");
+        let props = &tool["inputSchema"]["properties"];
+        assert_eq!(props["cwd"]["type"], "string");
+        assert_eq!(props["env"]["type"], "object");
+        assert_eq!(props["env"]["additionalProperties"]["type"], "string");
+        assert_eq!(props["restart"]["enum"], json!(["never", "on-failure", "always"]));
+        assert_eq!(props["group"]["type"], "string");
+    }
+
+    #[test]
+    fn terminal_respawn_has_optional_target_and_kill() {
+        let tool = find_tool("terminal_respawn");
+        assert!(tool["inputSchema"]["required"].as_array().unwrap().is_empty());
+        assert_eq!(tool["inputSchema"]["properties"]["kill"]["type"], "boolean");
+    }
+
+    #[test]
+    fn terminal_signal_requires_signal_name() {
+        let tool = find_tool("terminal_signal");
+        assert_eq!(tool["inputSchema"]["required"], json!(["signal"]));
+        assert_eq!(
+            tool["inputSchema"]["properties"]["signal"]["enum"],
+            json!(["INT", "TERM", "HUP", "KILL", "STOP", "CONT"])
+        );
+    }
+
     #[test]
     fn terminal_send_keys_has_keys_array_property() {
         let tool = find_tool("terminal_send_keys");
@@ -427,21 +608,42 @@ mod tests {
     }
 
     #[test]
-    fn target_properties_are_integer_type() {
+    fn terminal_capture_has_range_format_and_limit_properties() {
+        let tool = find_tool("terminal_capture");
+        let props = &tool["inputSchema"]["properties"];
+        assert_eq!(props["start"]["type"], "integer");
+        assert_eq!(props["end"]["type"], "integer");
+        assert_eq!(props["format"]["enum"], json!(["text", "ansi", "cells"]));
+        assert_eq!(props["limit"]["type"], "integer");
+    }
+
+    #[test]
+    fn target_properties_accept_id_or_name() {
         for name in &[
             "terminal_kill",
+            "terminal_respawn",
+            "terminal_signal",
             "terminal_select",
             "terminal_rename",
-            "terminal_send_keys",
+            "terminal_set_status",
             "terminal_capture",
             "buffer_paste",
         ] {
             let tool = find_tool(name);
             let props = &tool["inputSchema"]["properties"];
             assert_eq!(
-                props["target"]["type"], "integer",
-                "target should be integer for tool {name}"
+                props["target"]["type"],
+                json!(["integer", "string"]),
+                "target should be an id or a name for tool {name}"
             );
         }
     }
+
+    #[test]
+    fn terminal_send_keys_target_accepts_a_list() {
+        let tool = find_tool("terminal_send_keys");
+        let target = &tool["inputSchema"]["properties"]["target"];
+        assert_eq!(target["type"], json!(["integer", "string", "array"]));
+        assert_eq!(target["items"]["type"], json!(["integer", "string"]));
+    }
 }
@@ -2,18 +2,21 @@ use serde_json::{json, Value};
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
 
-use crate::infrastructure::ipc::socket_discovery;
+use crate::infrastructure::ipc::{cli_client, socket_discovery};
+
+/// Lines per `terminal_capture` page unless the caller asks otherwise, so a
+/// long scrollback does not overflow the tool response.
+const DEFAULT_CAPTURE_LIMIT: u64 = 500;
 
 /// Send an IPC command to the running CLI Manager instance and return the response.
-fn send_ipc_command(ipc_json: &str) -> Result<Value, String> {
-    let socket_path = socket_discovery::read_socket_path()
-        .map_err(|_| "No running cli-manager instance found. Is cli-manager running?".to_string())?;
+fn send_ipc_command(ipc_json: &str, instance: Option<&str>) -> Result<Value, String> {
+    let socket_path = socket_discovery::resolve_socket_path(instance)?;
 
     let mut stream = UnixStream::connect(&socket_path)
         .map_err(|e| format!("Failed to connect to cli-manager: {}", e))?;
 
     // Send command (newline-delimited)
-    let mut msg = ipc_json.to_string();
+    let mut msg = cli_client::with_caller(ipc_json, cli_client::caller_for_socket(&socket_path));
     msg.push('\n');
     stream
         .write_all(msg.as_bytes())
@@ -54,6 +57,34 @@ fn format_ipc_response(ipc_response: &Value) -> (Value, bool) {
     }
 }
 
+/// The `target` argument: a terminal id, or a string such as a name, a
+/// glob or `@self`, which the instance resolves. Defaults to `@self`, the
+/// terminal the agent runs in.
+fn target_param(arguments: &Value) -> Result<Value, (Value, bool)> {
+    match arguments.get("target") {
+        None | Some(Value::Null) => Ok(json!("@self")),
+        Some(v) if v.is_u64() || v.is_string() => Ok(v.clone()),
+        Some(_) => Err((
+            json!([{"type": "text", "text": "Invalid parameter: target must be a terminal ID or a string"}]),
+            true,
+        )),
+    }
+}
+
+/// The `target` argument of a tool that also takes a list of targets.
+fn targets_param(arguments: &Value) -> Result<Value, (Value, bool)> {
+    match arguments.get("target") {
+        Some(Value::Array(items)) if !items.is_empty() && items.iter().all(|v| v.is_u64() || v.is_string()) => {
+            Ok(Value::Array(items.clone()))
+        }
+        Some(Value::Array(_)) => Err((
+            json!([{"type": "text", "text": "Invalid parameter: target list must hold terminal IDs or strings"}]),
+            true,
+        )),
+        _ => target_param(arguments),
+    }
+}
+
 /// Build the IPC JSON command string for a given MCP tool call.
 ///
 /// Returns Ok(ipc_json_string) on success, or Err((content, is_error)) for
@@ -69,38 +100,66 @@ fn build_ipc_command(tool_name: &str, arguments: &Value) -> Result<String, (Valu
             if let Some(command) = arguments.get("command").and_then(|v| v.as_str()) {
                 cmd["command"] = json!(command);
             }
+            if let Some(cwd) = arguments.get("cwd").and_then(|v| v.as_str()) {
+                cmd["cwd"] = json!(cwd);
+            }
+            if let Some(env) = arguments.get("env").and_then(|v| v.as_object()) {
+                cmd["env"] = json!(env);
+            }
+            if let Some(restart) = arguments.get("restart").and_then(|v| v.as_str()) {
+                cmd["restart"] = json!(restart);
+            }
+            if let Some(group) = arguments.get("group").and_then(|v| v.as_str()) {
+                cmd["group"] = json!(group);
+            }
             Ok(cmd.to_string())
         }
         "terminal_kill" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = target_param(arguments)?;
             Ok(json!({"cmd": "kill-window", "target": target}).to_string())
         }
+        "terminal_respawn" => {
+            let target = target_param(arguments)?;
+            let kill = arguments.get("kill").and_then(|v| v.as_bool()).unwrap_or(false);
+            Ok(json!({"cmd": "respawn-window", "target": target, "kill": kill}).to_string())
+        }
+        "terminal_signal" => {
+            let target = target_param(arguments)?;
+            let signal = arguments
+                .get("signal")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| missing_param_error("signal"))?;
+            Ok(json!({"cmd": "send-signal", "target": target, "signal": signal}).to_string())
+        }
         "terminal_select" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = target_param(arguments)?;
             Ok(json!({"cmd": "select-window", "target": target}).to_string())
         }
         "terminal_rename" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = target_param(arguments)?;
             let name = arguments
                 .get("name")
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| missing_param_error("name"))?;
             Ok(json!({"cmd": "rename-window", "target": target, "name": name}).to_string())
         }
+        "terminal_set_status" => {
+            let target = target_param(arguments)?;
+            // Without text the status is cleared
+            let mut cmd = json!({"cmd": "set-status", "target": target});
+            if let Some(text) = arguments.get("text").and_then(|v| v.as_str()) {
+                cmd["text"] = json!(text);
+            }
+            if let Some(icon) = arguments.get("icon").and_then(|v| v.as_str()) {
+                cmd["icon"] = json!(icon);
+            }
+            if let Some(color) = arguments.get("color").filter(|v| v.is_string() || v.is_u64()) {
+                cmd["color"] = color.clone();
+            }
+            Ok(cmd.to_string())
+        }
         "terminal_send_keys" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = targets_param(arguments)?;
             let keys = arguments
                 .get("keys")
                 .and_then(|v| v.as_array())
@@ -108,18 +167,52 @@ fn build_ipc_command(tool_name: &str, arguments: &Value) -> Result<String, (Valu
                 .iter()
                 .filter_map(|v| v.as_str().map(String::from))
                 .collect::<Vec<_>>();
-            Ok(json!({"cmd": "send-keys", "target": target, "keys": keys}).to_string())
+            let literal = arguments.get("literal").and_then(|v| v.as_bool()).unwrap_or(false);
+            Ok(json!({"cmd": "send-keys", "target": target, "keys": keys, "literal": literal}).to_string())
         }
         "terminal_capture" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = target_param(arguments)?;
             let scrollback = arguments
                 .get("include_scrollback")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
-            Ok(json!({"cmd": "capture-pane", "target": target, "scrollback": scrollback}).to_string())
+            let limit = arguments
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(DEFAULT_CAPTURE_LIMIT);
+            let mut cmd = json!({
+                "cmd": "capture-pane",
+                "target": target,
+                "scrollback": scrollback,
+                "limit": limit,
+            });
+            for key in ["start", "end"] {
+                if let Some(line) = arguments.get(key).and_then(|v| v.as_i64()) {
+                    cmd[key] = json!(line);
+                }
+            }
+            if let Some(format) = arguments.get("format").and_then(|v| v.as_str()) {
+                cmd["format"] = json!(format);
+            }
+            Ok(cmd.to_string())
+        }
+        "terminal_wait_for" => {
+            let target = target_param(arguments)?;
+            let mut cmd = json!({"cmd": "wait-for", "target": target});
+            if let Some(pattern) = arguments.get("pattern").and_then(|v| v.as_str()) {
+                cmd["pattern"] = json!(pattern);
+                cmd["regex"] = json!(arguments.get("regex").and_then(|v| v.as_bool()).unwrap_or(false));
+            }
+            if let Some(idle_ms) = arguments.get("idle_ms").and_then(|v| v.as_u64()) {
+                cmd["idle_ms"] = json!(idle_ms);
+            }
+            if arguments.get("exit").and_then(|v| v.as_bool()) == Some(true) {
+                cmd["exit"] = json!(true);
+            }
+            if let Some(timeout_ms) = arguments.get("timeout_ms").and_then(|v| v.as_u64()) {
+                cmd["timeout_ms"] = json!(timeout_ms);
+            }
+            Ok(cmd.to_string())
         }
         "buffer_get" => Ok(r#"{"cmd":"show-buffer"}"#.to_string()),
         "buffer_set" => {
@@ -130,10 +223,7 @@ fn build_ipc_command(tool_name: &str, arguments: &Value) -> Result<String, (Valu
             Ok(json!({"cmd": "set-buffer", "text": text}).to_string())
         }
         "buffer_paste" => {
-            let target = arguments
-                .get("target")
-                .and_then(|v| v.as_u64())
-                .ok_or_else(|| missing_param_error("target"))?;
+            let target = target_param(arguments)?;
             Ok(json!({"cmd": "paste-buffer", "target": target}).to_string())
         }
         "notify" => {
@@ -142,6 +232,10 @@ fn build_ipc_command(tool_name: &str, arguments: &Value) -> Result<String, (Valu
                 .and_then(|v| v.as_str())
                 .ok_or_else(|| missing_param_error("body"))?;
             let mut cmd = json!({"cmd": "notify", "body": body});
+            // Left out, the instance uses the caller's terminal
+            if arguments.get("target").is_some_and(|v| !v.is_null()) {
+                cmd["target"] = target_param(arguments)?;
+            }
             if let Some(title) = arguments.get("title").and_then(|v| v.as_str()) {
                 cmd["title"] = json!(title);
             }
@@ -164,14 +258,15 @@ fn missing_param_error(param_name: &str) -> (Value, bool) {
 
 /// Handle an MCP tool call by converting to IPC and returning the result.
 ///
+/// The command goes to the instance `instance` selects, or the default one.
 /// Returns (content_array, is_error).
-pub fn handle_tool_call(tool_name: &str, arguments: &Value) -> (Value, bool) {
+pub fn handle_tool_call(tool_name: &str, arguments: &Value, instance: Option<&str>) -> (Value, bool) {
     let ipc_json = match build_ipc_command(tool_name, arguments) {
         Ok(json) => json,
         Err(err_tuple) => return err_tuple,
     };
 
-    match send_ipc_command(&ipc_json) {
+    match send_ipc_command(&ipc_json, instance) {
         Ok(ipc_response) => format_ipc_response(&ipc_response),
         Err(e) => (json!([{"type": "text", "text": e}]), true),
     }
@@ -196,34 +291,28 @@ mod tests {
     }
 
     // ========================================================================
-    // Tests: build_ipc_command — parameter validation for tools requiring target
+    // Tests: build_ipc_command — target default and required parameters
     // ========================================================================
 
     #[test]
-    fn build_terminal_kill_missing_target() {
-        let result = build_ipc_command("terminal_kill", &json!({}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_terminal_kill_defaults_to_caller_terminal() {
+        let result = build_ipc_command("terminal_kill", &json!({})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
-    fn build_terminal_select_missing_target() {
-        let result = build_ipc_command("terminal_select", &json!({}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_terminal_select_defaults_to_caller_terminal() {
+        let result = build_ipc_command("terminal_select", &json!({})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
-    fn build_terminal_rename_missing_target() {
-        let result = build_ipc_command("terminal_rename", &json!({"name": "foo"}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_terminal_rename_defaults_to_caller_terminal() {
+        let result = build_ipc_command("terminal_rename", &json!({"name": "foo"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
@@ -236,12 +325,26 @@ mod tests {
     }
 
     #[test]
-    fn build_terminal_send_keys_missing_target() {
-        let result = build_ipc_command("terminal_send_keys", &json!({"keys": ["a"]}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_terminal_send_keys_defaults_to_caller_terminal() {
+        let result = build_ipc_command("terminal_send_keys", &json!({"keys": ["a"]})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
+    }
+
+    #[test]
+    fn build_terminal_send_keys_to_several_targets() {
+        let result = build_ipc_command("terminal_send_keys", &json!({"target": [2, "web"], "keys": ["a"]})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], json!([2, "web"]));
+
+        for target in [json!([]), json!([1, [2]])] {
+            let (content, is_error) =
+                build_ipc_command("terminal_send_keys", &json!({"target": target, "keys": ["a"]})).unwrap_err();
+            assert!(is_error);
+            assert!(content[0]["text"].as_str().unwrap().contains("target list"));
+        }
+        // Other tools take one target
+        assert!(build_ipc_command("terminal_kill", &json!({"target": [1, 2]})).is_err());
     }
 
     #[test]
@@ -254,12 +357,10 @@ mod tests {
     }
 
     #[test]
-    fn build_terminal_capture_missing_target() {
-        let result = build_ipc_command("terminal_capture", &json!({}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_terminal_capture_defaults_to_caller_terminal() {
+        let result = build_ipc_command("terminal_capture", &json!({})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     #[test]
@@ -272,12 +373,10 @@ mod tests {
     }
 
     #[test]
-    fn build_buffer_paste_missing_target() {
-        let result = build_ipc_command("buffer_paste", &json!({}));
-        let (content, is_error) = result.unwrap_err();
-        assert!(is_error);
-        let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+    fn build_buffer_paste_defaults_to_caller_terminal() {
+        let result = build_ipc_command("buffer_paste", &json!({})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
     }
 
     // ========================================================================
@@ -324,6 +423,27 @@ mod tests {
         assert_eq!(v["command"], "/bin/zsh");
     }
 
+    #[test]
+    fn build_terminal_create_with_cwd_and_env() {
+        let result = build_ipc_command(
+            "terminal_create",
+            &json!({"command": "cargo watch", "cwd": "/work", "env": {"RUST_LOG": "debug"}}),
+        )
+        .unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["command"], "cargo watch");
+        assert_eq!(v["cwd"], "/work");
+        assert_eq!(v["env"]["RUST_LOG"], "debug");
+    }
+
+    #[test]
+    fn build_terminal_create_ignores_non_object_env() {
+        let result =
+            build_ipc_command("terminal_create", &json!({"env": "RUST_LOG=debug"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert!(v.get("env").is_none());
+    }
+
     #[test]
     fn build_terminal_kill_command() {
         let result = build_ipc_command("terminal_kill", &json!({"target": 3})).unwrap();
@@ -332,6 +452,49 @@ mod tests {
         assert_eq!(v["target"], 3);
     }
 
+    #[test]
+    fn build_terminal_create_with_restart_policy() {
+        let result = build_ipc_command(
+            "terminal_create",
+            &json!({"command": "npm run dev", "restart": "always"}),
+        )
+        .unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["restart"], "always");
+    }
+
+    #[test]
+    fn build_terminal_create_with_group() {
+        let result = build_ipc_command("terminal_create", &json!({"name": "db", "group": "backend"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["group"], "backend");
+    }
+
+    #[test]
+    fn build_terminal_respawn_command() {
+        let result = build_ipc_command("terminal_respawn", &json!({"target": 3, "kill": true})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["cmd"], "respawn-window");
+        assert_eq!(v["target"], 3);
+        assert_eq!(v["kill"], true);
+
+        let result = build_ipc_command("terminal_respawn", &json!({})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "@self");
+        assert_eq!(v["kill"], false);
+    }
+
+    #[test]
+    fn build_terminal_signal_command() {
+        let result = build_ipc_command("terminal_signal", &json!({"target": "dev", "signal": "INT"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["cmd"], "send-signal");
+        assert_eq!(v["target"], "dev");
+        assert_eq!(v["signal"], "INT");
+
+        assert!(build_ipc_command("terminal_signal", &json!({"target": 2})).is_err());
+    }
+
     #[test]
     fn build_terminal_select_command() {
         let result = build_ipc_command("terminal_select", &json!({"target": 2})).unwrap();
@@ -353,6 +516,50 @@ mod tests {
         assert_eq!(v["name"], "my-server");
     }
 
+    #[test]
+    fn build_terminal_set_status_command() {
+        let result = build_ipc_command(
+            "terminal_set_status",
+            &json!({"text": "thinking", "icon": "⏳", "color": 208}),
+        )
+        .unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["cmd"], "set-status");
+        assert_eq!(v["target"], "@self");
+        assert_eq!(v["text"], "thinking");
+        assert_eq!(v["icon"], "⏳");
+        assert_eq!(v["color"], 208);
+    }
+
+    #[test]
+    fn build_terminal_set_status_without_text_clears() {
+        let result = build_ipc_command("terminal_set_status", &json!({"target": 2})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["cmd"], "set-status");
+        assert!(v.get("text").is_none());
+        assert_eq!(
+            crate::infrastructure::ipc::protocol::parse_command(&result).unwrap(),
+            crate::domain::primitive::IpcCommand::SetStatus { target: 2, status: None }
+        );
+    }
+
+    #[test]
+    fn build_command_passes_string_targets_through() {
+        for target in ["api-server", "agent-*", "@self"] {
+            let result = build_ipc_command("terminal_kill", &json!({"target": target})).unwrap();
+            let v: Value = serde_json::from_str(&result).unwrap();
+            assert_eq!(v["target"], target);
+        }
+    }
+
+    #[test]
+    fn build_command_rejects_non_id_target() {
+        for target in [json!(-1), json!(true), json!([1])] {
+            let result = build_ipc_command("terminal_select", &json!({"target": target}));
+            assert!(result.is_err(), "accepted target {target}");
+        }
+    }
+
     #[test]
     fn build_terminal_send_keys_command() {
         let result = build_ipc_command(
@@ -369,6 +576,17 @@ mod tests {
         assert_eq!(keys[1], "Enter");
     }
 
+    #[test]
+    fn build_terminal_send_keys_literal() {
+        let result = build_ipc_command(
+            "terminal_send_keys",
+            &json!({"target": 1, "keys": ["Enter"], "literal": true}),
+        )
+        .unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["literal"], true);
+    }
+
     #[test]
     fn build_terminal_capture_without_scrollback() {
         let result =
@@ -392,6 +610,45 @@ mod tests {
         assert_eq!(v["scrollback"], true);
     }
 
+    #[test]
+    fn build_terminal_capture_pages_by_default() {
+        let result = build_ipc_command("terminal_capture", &json!({"target": 1})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["limit"], DEFAULT_CAPTURE_LIMIT);
+        assert!(v.get("start").is_none());
+        assert!(v.get("format").is_none());
+    }
+
+    #[test]
+    fn build_terminal_capture_with_range_and_format() {
+        let args = json!({"target": 1, "start": -200, "end": -1, "format": "ansi", "limit": 50});
+        let result = build_ipc_command("terminal_capture", &args).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["start"], -200);
+        assert_eq!(v["end"], -1);
+        assert_eq!(v["format"], "ansi");
+        assert_eq!(v["limit"], 50);
+    }
+
+    #[test]
+    fn build_terminal_wait_for_pattern_command() {
+        let args = json!({"target": 3, "pattern": "PASS|FAIL", "regex": true, "timeout_ms": 60000});
+        let result = build_ipc_command("terminal_wait_for", &args).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(
+            v,
+            json!({"cmd": "wait-for", "target": 3, "pattern": "PASS|FAIL", "regex": true, "timeout_ms": 60000})
+        );
+    }
+
+    #[test]
+    fn build_terminal_wait_for_exit_command() {
+        let args = json!({"target": 1, "exit": true});
+        let result = build_ipc_command("terminal_wait_for", &args).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v, json!({"cmd": "wait-for", "target": 1, "exit": true}));
+    }
+
     #[test]
     fn build_buffer_get_command() {
         let result = build_ipc_command("buffer_get", &json!({})).unwrap();
@@ -480,24 +737,24 @@ mod tests {
 
     #[test]
     fn handle_unknown_tool() {
-        let (content, is_error) = handle_tool_call("does_not_exist", &json!({}));
+        let (content, is_error) = handle_tool_call("does_not_exist", &json!({}), None);
         assert!(is_error);
         let text = content[0]["text"].as_str().unwrap();
         assert!(text.contains("Unknown tool: does_not_exist"), "got: {text}");
     }
 
     #[test]
-    fn handle_terminal_kill_no_target() {
-        let (content, is_error) = handle_tool_call("terminal_kill", &json!({}));
+    fn handle_terminal_kill_invalid_target() {
+        let (content, is_error) = handle_tool_call("terminal_kill", &json!({"target": true}), None);
         assert!(is_error);
         let text = content[0]["text"].as_str().unwrap();
-        assert!(text.contains("Missing required parameter: target"), "got: {text}");
+        assert!(text.contains("Invalid parameter: target"), "got: {text}");
     }
 
     #[test]
     fn handle_terminal_rename_no_name() {
         let (content, is_error) =
-            handle_tool_call("terminal_rename", &json!({"target": 1}));
+            handle_tool_call("terminal_rename", &json!({"target": 1}), None);
         assert!(is_error);
         let text = content[0]["text"].as_str().unwrap();
         assert!(text.contains("Missing required parameter: name"), "got: {text}");
@@ -505,7 +762,7 @@ mod tests {
 
     #[test]
     fn handle_buffer_set_no_text() {
-        let (content, is_error) = handle_tool_call("buffer_set", &json!({}));
+        let (content, is_error) = handle_tool_call("buffer_set", &json!({}), None);
         assert!(is_error);
         let text = content[0]["text"].as_str().unwrap();
         assert!(text.contains("Missing required parameter: text"), "got: {text}");
@@ -537,6 +794,16 @@ mod tests {
         assert!(v.get("title").is_none());
     }
 
+    #[test]
+    fn build_notify_target_is_optional() {
+        let result = build_ipc_command("notify", &json!({"body": "Done"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert!(v.get("target").is_none());
+        let result = build_ipc_command("notify", &json!({"body": "Done", "target": "agent"})).unwrap();
+        let v: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(v["target"], "agent");
+    }
+
     #[test]
     fn build_notify_missing_body() {
         let result = build_ipc_command("notify", &json!({"title": "Test"}));
@@ -561,7 +828,7 @@ mod tests {
 
     #[test]
     fn handle_notify_no_body() {
-        let (content, is_error) = handle_tool_call("notify", &json!({}));
+        let (content, is_error) = handle_tool_call("notify", &json!({}), None);
         assert!(is_error);
         let text = content[0]["text"].as_str().unwrap();
         assert!(text.contains("Missing required parameter: body"), "got: {text}");
@@ -576,7 +843,7 @@ mod tests {
         // This test verifies that when no cli-manager is running,
         // handle_tool_call returns a connection error (not a crash).
         // The exact error depends on whether a discovery file exists.
-        let (content, is_error) = handle_tool_call("terminal_list", &json!({}));
+        let (content, is_error) = handle_tool_call("terminal_list", &json!({}), None);
         // Should be an error since no cli-manager instance is running
         // (unless one happens to be running, in which case it would succeed)
         // We just verify the response structure is valid
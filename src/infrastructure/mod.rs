@@ -1,7 +1,10 @@
 pub mod clipboard;
 pub mod ipc;
+pub mod log;
 pub mod mcp;
 pub mod notification;
 pub mod pty;
 pub mod screen;
+pub mod server;
+pub mod session;
 pub mod tui;
@@ -2,10 +2,11 @@ use std::collections::HashMap;
 use std::io::{self, Read, Write};
 use std::path::Path;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 
-use crate::domain::primitive::{TerminalId, TerminalSize};
+use crate::domain::primitive::{ForegroundProcess, Signal, TerminalId, TerminalSize};
 use crate::interface_adapter::port::pty_port::PtyPort;
 use crate::shared::error::AppError;
 
@@ -67,21 +68,102 @@ fn set_nonblocking(fd: std::os::fd::RawFd) -> io::Result<()> {
     Ok(())
 }
 
+/// Program name and command line of a process, from `/proc/<pid>/comm`
+/// and `/proc/<pid>/cmdline`.
+#[cfg(target_os = "linux")]
+fn process_info(pid: u32) -> Option<(String, String)> {
+    let name = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+    let cmdline = std::fs::read(format!("/proc/{pid}/cmdline")).unwrap_or_default();
+    // Arguments are NUL-terminated
+    let args: Vec<String> = cmdline
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    let name = name.trim_end().to_string();
+    let cmdline = if args.is_empty() { name.clone() } else { args.join(" ") };
+    Some((name, cmdline))
+}
+
+/// Program name and path of a process. macOS has no `/proc`; the
+/// executable path stands in for the command line.
+#[cfg(target_os = "macos")]
+fn process_info(pid: u32) -> Option<(String, String)> {
+    let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+    // SAFETY: `buf` is a writable buffer of the length passed in.
+    let len = unsafe { libc::proc_pidpath(pid as libc::c_int, buf.as_mut_ptr().cast(), buf.len() as u32) };
+    if len <= 0 {
+        return None;
+    }
+    let path = String::from_utf8_lossy(&buf[..len as usize]).into_owned();
+    let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+    Some((name, path))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_info(_pid: u32) -> Option<(String, String)> {
+    None
+}
+
+fn signal_number(signal: Signal) -> libc::c_int {
+    match signal {
+        Signal::Int => libc::SIGINT,
+        Signal::Term => libc::SIGTERM,
+        Signal::Hup => libc::SIGHUP,
+        Signal::Kill => libc::SIGKILL,
+        Signal::Stop => libc::SIGSTOP,
+        Signal::Cont => libc::SIGCONT,
+    }
+}
+
+/// Send SIGKILL to each of `groups`.
+fn kill_process_groups(groups: &[libc::pid_t]) {
+    for &pgid in groups {
+        // SAFETY: the child is not reaped yet, so its group ids are still ours.
+        unsafe {
+            libc::killpg(pgid, libc::SIGKILL);
+        }
+    }
+}
+
+/// Process groups that make up a terminal: the foreground job and the
+/// child itself (a session leader, so its pid is also its group id).
+fn process_groups(instance: &PtyInstance) -> Vec<libc::pid_t> {
+    let mut groups: Vec<libc::pid_t> = instance.master.process_group_leader().into_iter().collect();
+    if let Some(pid) = instance.child.process_id() {
+        groups.push(pid as libc::pid_t);
+    }
+    groups.retain(|pgid| *pgid > 0);
+    groups.dedup();
+    groups
+}
+
+/// How often a closing terminal's child is checked for exit.
+const CLOSE_POLL: Duration = Duration::from_millis(50);
+
 impl PtyPort for PortablePtyAdapter {
     fn spawn(
         &mut self,
         id: TerminalId,
-        shell: &str,
+        argv: &[String],
         cwd: &Path,
+        env: &[(String, String)],
         size: TerminalSize,
     ) -> Result<(), AppError> {
+        if argv.is_empty() {
+            return Err(AppError::PtySpawn(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "empty argv",
+            )));
+        }
+
         let pty_system = native_pty_system();
 
         let pair = pty_system
             .openpty(to_pty_size(size))
             .map_err(|e| AppError::PtySpawn(io::Error::other(e.to_string())))?;
 
-        let mut cmd = CommandBuilder::new(shell);
+        let mut cmd = CommandBuilder::from_argv(argv.iter().map(Into::into).collect());
         cmd.cwd(cwd);
         // Set TERM to xterm-256color — our VTE adapter now supports enough
         // escape sequences (alternate screen, scroll regions, SGR attributes,
@@ -92,6 +174,9 @@ impl PtyPort for PortablePtyAdapter {
         // which registers a precmd hook to emit OSC 7 (current working directory)
         // on every directory change. This enables dynamic cwd tracking.
         cmd.env("TERM_PROGRAM", "Apple_Terminal");
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
 
         let child = pair
             .slave
@@ -182,6 +267,94 @@ impl PtyPort for PortablePtyAdapter {
         }
     }
 
+    fn foreground_process(&mut self, id: TerminalId) -> Result<Option<ForegroundProcess>, AppError> {
+        let instances = self.instances.lock().expect("pty instances lock poisoned");
+        let instance = instances.get(&id).ok_or(AppError::TerminalNotFound(id))?;
+
+        // tcgetpgrp on the master
+        let Some(pid) = instance.master.process_group_leader().filter(|pid| *pid > 0) else {
+            return Ok(None);
+        };
+        let pid = pid as u32;
+        Ok(process_info(pid).map(|(name, cmdline)| ForegroundProcess {
+            pid,
+            name,
+            cmdline,
+            is_terminal_process: instance.child.process_id() == Some(pid),
+        }))
+    }
+
+    fn signal(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError> {
+        let instances = self.instances.lock().expect("pty instances lock poisoned");
+        let instance = instances.get(&id).ok_or(AppError::TerminalNotFound(id))?;
+
+        // The foreground job comes first so e.g. SIGINT reaches `make` and
+        // its children rather than the shell that started them
+        let Some(&pgid) = process_groups(instance).first() else {
+            return Err(AppError::PtyIo { id, source: io::Error::other("no process to signal") });
+        };
+        // SAFETY: killpg only sends a signal; pgid is a positive process group id.
+        if unsafe { libc::killpg(pgid, signal_number(signal)) } < 0 {
+            return Err(AppError::PtyIo { id, source: io::Error::last_os_error() });
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, id: TerminalId, grace: Duration) -> Result<(), AppError> {
+        let mut instances = self.instances.lock().expect("pty instances lock poisoned");
+        let instance = instances.remove(&id).ok_or(AppError::TerminalNotFound(id))?;
+        drop(instances);
+
+        let groups = process_groups(&instance);
+        let PtyInstance { master, mut child, reader, writer } = instance;
+        // Closing our side of the pty hangs up the session as well
+        drop(writer);
+        drop(reader);
+        drop(master);
+        if let Ok(Some(_)) = child.try_wait() {
+            return Ok(());
+        }
+
+        // Hang up like a closed terminal window would; SIGCONT lets stopped
+        // jobs handle it.
+        for &pgid in &groups {
+            // SAFETY: killpg only sends a signal; pgid is a positive process group id.
+            unsafe {
+                libc::killpg(pgid, libc::SIGHUP);
+                libc::killpg(pgid, libc::SIGCONT);
+            }
+        }
+
+        // Wait for the exit off the main loop and kill whatever ignored the hangup
+        let pid = child.process_id();
+        let kill_groups = groups.clone();
+        let closer = std::thread::Builder::new()
+            .name(format!("pty-closer-{}", id.value()))
+            .spawn(move || {
+                let deadline = Instant::now() + grace;
+                while Instant::now() < deadline {
+                    if let Ok(Some(_)) = child.try_wait() {
+                        return;
+                    }
+                    std::thread::sleep(CLOSE_POLL);
+                }
+                kill_process_groups(&kill_groups);
+                let _ = child.wait();
+            });
+        // Without a thread there is no grace period: kill and reap right away
+        if closer.is_err() {
+            kill_process_groups(&groups);
+            if let Some(pid) = pid {
+                // SAFETY: waitpid only reaps our own child; a null status pointer is allowed.
+                unsafe {
+                    libc::waitpid(pid as libc::pid_t, std::ptr::null_mut(), 0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn kill(&mut self, id: TerminalId) -> Result<(), AppError> {
         let mut instances = self.instances.lock().expect("pty instances lock poisoned");
         let mut instance = instances
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use super::osc7::parse_osc7_uri;
-use crate::domain::primitive::{Cell, Color, CursorPos, CursorStyle, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
+use crate::domain::primitive::{Cell, Color, CursorPos, CursorStyle, MouseMode, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
 use crate::interface_adapter::port::screen_port::ScreenPort;
 use crate::shared::error::AppError;
 
@@ -16,6 +16,55 @@ struct Vt100Callbacks {
     /// Flag set by unhandled_csi when CSI 6 n (DSR cursor position query) is received.
     /// Checked after process() to synthesize the response with actual cursor position.
     pending_dsr: bool,
+    /// Kitty keyboard protocol flag stacks for the main and alternate screens
+    /// (`CSI > flags u` pushes, `CSI < n u` pops, `CSI = flags ; mode u` edits the top).
+    keyboard_flags: [Vec<u16>; 2],
+    /// Flag set by unhandled_csi when CSI ? u (keyboard flags query) is received.
+    pending_keyboard_query: bool,
+}
+
+/// Maximum depth of a kitty keyboard flag stack; the oldest entry is dropped
+/// when an application pushes beyond it.
+const KEYBOARD_FLAG_STACK_LIMIT: usize = 16;
+
+impl Vt100Callbacks {
+    /// Current kitty keyboard flags for the given screen (0 = legacy encoding).
+    fn keyboard_flags(&self, alternate_screen: bool) -> u16 {
+        self.keyboard_flags[usize::from(alternate_screen)].last().copied().unwrap_or(0)
+    }
+
+    /// Apply a kitty keyboard protocol `CSI ... u` sequence.
+    fn handle_keyboard_protocol(&mut self, alternate_screen: bool, marker: u8, params: &[&[u16]]) {
+        let param = |i: usize| params.get(i).and_then(|p| p.first()).copied();
+        let stack = &mut self.keyboard_flags[usize::from(alternate_screen)];
+        match marker {
+            b'>' => {
+                if stack.len() == KEYBOARD_FLAG_STACK_LIMIT {
+                    stack.remove(0);
+                }
+                stack.push(param(0).unwrap_or(0));
+            }
+            b'<' => {
+                let n = usize::from(param(0).unwrap_or(1).max(1));
+                stack.truncate(stack.len().saturating_sub(n));
+            }
+            b'=' => {
+                let flags = param(0).unwrap_or(0);
+                let current = stack.last().copied().unwrap_or(0);
+                let updated = match param(1).unwrap_or(1) {
+                    2 => current | flags,
+                    3 => current & !flags,
+                    _ => flags,
+                };
+                match stack.last_mut() {
+                    Some(top) => *top = updated,
+                    None => stack.push(updated),
+                }
+            }
+            b'?' => self.pending_keyboard_query = true,
+            _ => {}
+        }
+    }
 }
 
 impl vt100::Callbacks for Vt100Callbacks {
@@ -33,12 +82,19 @@ impl vt100::Callbacks for Vt100Callbacks {
 
     fn unhandled_csi(
         &mut self,
-        _: &mut vt100::Screen,
+        screen: &mut vt100::Screen,
         i1: Option<u8>,
         _i2: Option<u8>,
         params: &[&[u16]],
         c: char,
     ) {
+        // Kitty keyboard protocol: CSI > / < / = / ? ... u
+        if c == 'u'
+            && let Some(marker) = i1
+        {
+            self.handle_keyboard_protocol(screen.alternate_screen(), marker, params);
+        }
+
         // DECSCUSR: CSI Ps SP q — Set Cursor Style
         if c == 'q' && i1 == Some(b' ') {
             let ps = params.first()
@@ -111,6 +167,8 @@ struct Vt100Instance {
     new_output_while_scrolled: bool,
     /// Cached max scrollback value (updated in `&mut self` methods).
     cached_max_scrollback: usize,
+    /// Rows dropped off the top of the full scrollback so far.
+    scrolled_off: usize,
     /// Pending responses to be written back to the PTY (e.g., DSR cursor position replies).
     pending_responses: Vec<Vec<u8>>,
 }
@@ -177,6 +235,30 @@ fn update_max_scrollback(inst: &mut Vt100Instance) {
     inst.parser.screen_mut().set_scrollback(current);
 }
 
+/// Process `data` and count the rows it pushed into the scrollback.
+///
+/// The grid raises a non-zero scrollback offset by one for every pushed row,
+/// even once the scrollback is full, so the output is processed with the
+/// offset at 1 and the user's offset is moved the same way afterwards. From
+/// an empty scrollback the offset stays at 0 and the growth is the count.
+fn process_counting_pushes(parser: &mut vt100::Parser<Vt100Callbacks>, data: &[u8]) -> usize {
+    let offset = parser.screen().scrollback();
+    parser.screen_mut().set_scrollback(usize::MAX);
+    let len_before = parser.screen().scrollback();
+    parser.screen_mut().set_scrollback(1);
+    let probe = parser.screen().scrollback();
+
+    parser.process(data);
+
+    let counted = parser.screen().scrollback().saturating_sub(probe);
+    parser.screen_mut().set_scrollback(usize::MAX);
+    let len_after = parser.screen().scrollback();
+    let pushed = if probe == 0 { len_after.saturating_sub(len_before) } else { counted };
+    let offset = if offset > 0 { offset + pushed } else { 0 };
+    parser.screen_mut().set_scrollback(offset);
+    pushed
+}
+
 fn rebuild_cell_cache(parser: &vt100::Parser<Vt100Callbacks>, cache: &mut Vec<Vec<Cell>>) {
     let screen = parser.screen();
     let rows = screen.size().0 as usize;
@@ -211,6 +293,7 @@ impl ScreenPort for Vt100ScreenAdapter {
             cached_cells,
             new_output_while_scrolled: false,
             cached_max_scrollback: 0,
+            scrolled_off: 0,
             pending_responses: Vec::new(),
         });
         Ok(())
@@ -222,10 +305,23 @@ impl ScreenPort for Vt100ScreenAdapter {
             .get_mut(&id)
             .ok_or(AppError::ScreenNotFound(id))?;
         let was_scrolled = inst.parser.screen().scrollback() > 0;
-        inst.parser.process(data);
+        let was_alternate = inst.parser.screen().alternate_screen();
+        let scrolled_before = inst.cached_max_scrollback;
+        let pushed = process_counting_pushes(&mut inst.parser, data);
         if was_scrolled {
             inst.new_output_while_scrolled = true;
         }
+        // Leaving the alternate screen discards its keyboard flags, so the
+        // next visit starts fresh even if the app never popped them
+        if was_alternate && !inst.parser.screen().alternate_screen() {
+            inst.parser.callbacks_mut().keyboard_flags[1].clear();
+        }
+        // Answer a kitty keyboard flags query with the current flags
+        if inst.parser.callbacks().pending_keyboard_query {
+            let flags = inst.parser.callbacks().keyboard_flags(inst.parser.screen().alternate_screen());
+            inst.pending_responses.push(format!("\x1b[?{}u", flags).into_bytes());
+            inst.parser.callbacks_mut().pending_keyboard_query = false;
+        }
         // Synthesize DSR cursor position response if requested by child process
         if inst.parser.callbacks().pending_dsr {
             let pos = inst.parser.screen().cursor_position();
@@ -234,6 +330,11 @@ impl ScreenPort for Vt100ScreenAdapter {
             inst.parser.callbacks_mut().pending_dsr = false;
         }
         update_max_scrollback(inst);
+        // Pushes that did not grow the scrollback each dropped its oldest row
+        if !was_alternate && !inst.parser.screen().alternate_screen() {
+            let grown = inst.cached_max_scrollback.saturating_sub(scrolled_before);
+            inst.scrolled_off += pushed.saturating_sub(grown);
+        }
         rebuild_cell_cache(&inst.parser, &mut inst.cached_cells);
         Ok(())
     }
@@ -339,6 +440,13 @@ impl ScreenPort for Vt100ScreenAdapter {
             .ok_or(AppError::ScreenNotFound(id))
     }
 
+    fn get_scrolled_off(&self, id: TerminalId) -> Result<usize, AppError> {
+        self.instances
+            .get(&id)
+            .map(|inst| inst.scrolled_off)
+            .ok_or(AppError::ScreenNotFound(id))
+    }
+
     fn is_alternate_screen(&self, id: TerminalId) -> Result<bool, AppError> {
         self.instances
             .get(&id)
@@ -353,6 +461,26 @@ impl ScreenPort for Vt100ScreenAdapter {
             .ok_or(AppError::ScreenNotFound(id))
     }
 
+    fn get_keyboard_flags(&self, id: TerminalId) -> Result<u16, AppError> {
+        self.instances
+            .get(&id)
+            .map(|inst| inst.parser.callbacks().keyboard_flags(inst.parser.screen().alternate_screen()))
+            .ok_or(AppError::ScreenNotFound(id))
+    }
+
+    fn get_mouse_mode(&self, id: TerminalId) -> Result<MouseMode, AppError> {
+        self.instances
+            .get(&id)
+            .map(|inst| match inst.parser.screen().mouse_protocol_mode() {
+                vt100::MouseProtocolMode::None => MouseMode::Off,
+                vt100::MouseProtocolMode::Press => MouseMode::Press,
+                vt100::MouseProtocolMode::PressRelease => MouseMode::PressRelease,
+                vt100::MouseProtocolMode::ButtonMotion => MouseMode::ButtonMotion,
+                vt100::MouseProtocolMode::AnyMotion => MouseMode::AnyMotion,
+            })
+            .ok_or(AppError::ScreenNotFound(id))
+    }
+
     fn drain_pending_responses(&mut self, id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> {
         let inst = self
             .instances
@@ -1526,6 +1654,59 @@ mod tests {
         assert_ne!(live_row0, scrolled_row0);
     }
 
+    #[test]
+    fn keyboard_flags_push_pop_and_set() {
+        let mut adapter = Vt100ScreenAdapter::new();
+        adapter.create(id(1), default_size()).unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 0);
+
+        adapter.process(id(1), b"\x1b[>1u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 1);
+        adapter.process(id(1), b"\x1b[>3u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 3);
+        adapter.process(id(1), b"\x1b[=4;2u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 7);
+        adapter.process(id(1), b"\x1b[<u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 1);
+        adapter.process(id(1), b"\x1b[<5u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 0);
+    }
+
+    #[test]
+    fn keyboard_flags_query_queues_response() {
+        let mut adapter = Vt100ScreenAdapter::new();
+        adapter.create(id(1), default_size()).unwrap();
+        adapter.process(id(1), b"\x1b[>1u\x1b[?u").unwrap();
+        assert_eq!(adapter.drain_pending_responses(id(1)).unwrap(), vec![b"\x1b[?1u".to_vec()]);
+    }
+
+    #[test]
+    fn keyboard_flags_are_separate_for_alternate_screen() {
+        let mut adapter = Vt100ScreenAdapter::new();
+        adapter.create(id(1), default_size()).unwrap();
+        adapter.process(id(1), b"\x1b[?1049h\x1b[>1u").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 1);
+
+        // Leaving without popping (e.g. a crashed editor) restores legacy keys
+        adapter.process(id(1), b"\x1b[?1049l").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 0);
+        adapter.process(id(1), b"\x1b[?1049h").unwrap();
+        assert_eq!(adapter.get_keyboard_flags(id(1)).unwrap(), 0);
+    }
+
+    #[test]
+    fn mouse_mode_follows_decset_and_decrst() {
+        let mut adapter = Vt100ScreenAdapter::new();
+        adapter.create(id(1), default_size()).unwrap();
+        assert_eq!(adapter.get_mouse_mode(id(1)).unwrap(), MouseMode::Off);
+
+        adapter.process(id(1), b"\x1b[?1002h\x1b[?1006h").unwrap();
+        assert_eq!(adapter.get_mouse_mode(id(1)).unwrap(), MouseMode::ButtonMotion);
+
+        adapter.process(id(1), b"\x1b[?1002l").unwrap();
+        assert_eq!(adapter.get_mouse_mode(id(1)).unwrap(), MouseMode::Off);
+    }
+
     #[test]
     fn alternate_screen_default_false() {
         let mut adapter = Vt100ScreenAdapter::new();
@@ -1610,6 +1791,29 @@ mod tests {
         assert_eq!(adapter.get_scrollback_offset(id(2)).unwrap(), 0);
     }
 
+    #[test]
+    fn scrolled_off_counts_rows_dropped_from_a_full_scrollback() {
+        let mut adapter = Vt100ScreenAdapter::new();
+        let size = TerminalSize::new(80, 5);
+        adapter.create(id(1), size).unwrap();
+
+        // Filling the scrollback drops nothing
+        let fill: String = (0..10_004).map(|i| format!("line {}\r\n", i)).collect();
+        adapter.process(id(1), fill.as_bytes()).unwrap();
+        assert_eq!(adapter.get_max_scrollback(id(1)).unwrap(), 10_000);
+        assert_eq!(adapter.get_scrolled_off(id(1)).unwrap(), 0);
+
+        adapter.process(id(1), b"a\r\nb\r\nc\r\n").unwrap();
+        assert_eq!(adapter.get_scrolled_off(id(1)).unwrap(), 3);
+
+        // Counted the same while the user is scrolled back, and the view
+        // stays on the same rows
+        adapter.set_scrollback_offset(id(1), 10).unwrap();
+        adapter.process(id(1), b"d\r\ne\r\n").unwrap();
+        assert_eq!(adapter.get_scrolled_off(id(1)).unwrap(), 5);
+        assert_eq!(adapter.get_scrollback_offset(id(1)).unwrap(), 12);
+    }
+
     // ─── Cursor style (DECSCUSR) tests ───
 
     #[test]
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::domain::primitive::{Cell, Color, CursorPos, CursorStyle, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
+use crate::domain::primitive::{Cell, Color, CursorPos, CursorStyle, MouseMode, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
 use crate::interface_adapter::port::screen_port::ScreenPort;
 use crate::shared::error::AppError;
 
@@ -1074,6 +1074,10 @@ impl ScreenPort for VteScreenAdapter {
         Ok(0)
     }
 
+    fn get_scrolled_off(&self, _id: TerminalId) -> Result<usize, AppError> {
+        Ok(0)
+    }
+
     fn is_alternate_screen(&self, id: TerminalId) -> Result<bool, AppError> {
         self.screens
             .get(&id)
@@ -1086,6 +1090,16 @@ impl ScreenPort for VteScreenAdapter {
         Ok(CursorStyle::DefaultUserShape)
     }
 
+    fn get_mouse_mode(&self, _id: TerminalId) -> Result<MouseMode, AppError> {
+        // VteScreenAdapter does not track mouse reporting
+        Ok(MouseMode::Off)
+    }
+
+    fn get_keyboard_flags(&self, _id: TerminalId) -> Result<u16, AppError> {
+        // VteScreenAdapter does not support the kitty keyboard protocol
+        Ok(0)
+    }
+
     fn drain_pending_responses(&mut self, _id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> {
         // VteScreenAdapter does not handle DSR queries
         Ok(vec![])
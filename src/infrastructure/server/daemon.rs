@@ -0,0 +1,107 @@
+//! Starting `cm server` in the background.
+//!
+//! `cm server` re-executes itself as `cm server --foreground` in a new
+//! session (detached from the controlling terminal), so closing the
+//! terminal window it was started from does not send it SIGHUP.
+
+use std::fs::{self, OpenOptions};
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the daemon's socket to accept connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A server started by `spawn`.
+#[derive(Debug)]
+pub struct SpawnedServer {
+    pub pid: u32,
+    pub socket_path: String,
+    pub log_path: PathBuf,
+}
+
+/// Path of the log file receiving the daemon's stderr (~/.cli-manager/server.log).
+pub fn log_file_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home).join(".cli-manager").join("server.log")
+}
+
+/// Socket path used by the process with the given pid.
+pub fn socket_path_for(pid: u32) -> String {
+    format!("/tmp/cli-manager-{pid}.sock")
+}
+
+/// Start `cm server --foreground <extra_args>` in the background and wait
+/// until it accepts IPC connections.
+pub fn spawn(extra_args: &[String]) -> anyhow::Result<SpawnedServer> {
+    let log_path = log_file_path();
+    if let Some(dir) = log_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let log = OpenOptions::new().create(true).append(true).open(&log_path)?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg("server")
+        .arg("--foreground")
+        .args(extra_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(log);
+    // SAFETY: `setsid` is async-signal-safe and is the only call made
+    // between fork and exec.
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn()?;
+
+    let pid = child.id();
+    let socket_path = socket_path_for(pid);
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        if UnixStream::connect(&socket_path).is_ok() {
+            return Ok(SpawnedServer {
+                pid,
+                socket_path,
+                log_path,
+            });
+        }
+        if let Some(status) = child.try_wait()? {
+            anyhow::bail!(
+                "cm server exited during startup ({status}); see {}",
+                log_path.display()
+            );
+        }
+        if Instant::now() >= deadline {
+            anyhow::bail!(
+                "cm server did not start within {}s; see {}",
+                STARTUP_TIMEOUT.as_secs(),
+                log_path.display()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_path_matches_pid() {
+        assert_eq!(socket_path_for(4242), "/tmp/cli-manager-4242.sock");
+    }
+
+    #[test]
+    fn log_file_lives_in_cli_manager_dir() {
+        let path = log_file_path();
+        assert!(path.ends_with(".cli-manager/server.log"), "got: {}", path.display());
+    }
+}
@@ -0,0 +1,782 @@
+//! Headless session host for `cm server`.
+//!
+//! Owns the terminals (through the same `TuiController` the TUI uses) and
+//! serves them over IPC. Every regular command is handled exactly like in
+//! the TUI; `attach` turns the connection into a stream that receives a
+//! `ScreenFrame` whenever the session's screen changes, and `subscribe` into
+//! one that receives session events. `wait-for` is answered once its
+//! condition holds.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::domain::primitive::{
+    IpcCommand, IpcEvent, IpcResponse, ScreenFrame, TerminalSize,
+};
+use crate::infrastructure::ipc::event_stream::EventStream;
+use crate::infrastructure::ipc::wait_for::{WaitQueue, WaitRequest};
+use crate::infrastructure::notification::MacOsNotifier;
+use crate::infrastructure::session::session_file;
+use crate::infrastructure::tui::app_runner::{handle_ipc_command, notify_event, resolve_runner_target};
+use crate::interface_adapter::controller::tui_controller::{AppAction, TuiController};
+use crate::interface_adapter::port::{ConnectionId, IpcPort, PtyPort, ScreenPort};
+use crate::shared::error::AppError;
+
+/// Terminal size used until the first client attaches.
+pub const DEFAULT_SIZE: TerminalSize = TerminalSize { cols: 80, rows: 24 };
+
+/// Delay between iterations of the server loop.
+const TICK: Duration = Duration::from_millis(16);
+
+/// Set by the signal handler to request a clean shutdown.
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_shutdown(_signal: libc::c_int) {
+    SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Startup and shutdown behaviour of `run`.
+#[derive(Debug, Default)]
+pub struct ServerOptions {
+    /// Write a session snapshot here when the server stops. `None` disables it.
+    pub snapshot_path: Option<PathBuf>,
+}
+
+/// A client streaming frames over an `attach` connection.
+struct AttachedClient {
+    /// Main pane size the client can display.
+    size: TerminalSize,
+    /// Last frame pushed to the client; `None` forces a full send.
+    last_frame: Option<ScreenFrame>,
+}
+
+/// State of a running server, independent of the socket and the loop.
+struct ServerState<P: PtyPort, S: ScreenPort> {
+    controller: TuiController<P, S>,
+    clients: HashMap<ConnectionId, AttachedClient>,
+    events: EventStream,
+    waits: WaitQueue,
+    /// Size every terminal is kept at.
+    size: TerminalSize,
+    yank_buffer: Option<String>,
+    should_quit: bool,
+}
+
+impl<P: PtyPort, S: ScreenPort> ServerState<P, S> {
+    fn new(controller: TuiController<P, S>) -> Self {
+        Self {
+            controller,
+            clients: HashMap::new(),
+            events: EventStream::new(),
+            waits: WaitQueue::new(),
+            size: DEFAULT_SIZE,
+            yank_buffer: None,
+            should_quit: false,
+        }
+    }
+
+    /// Handle one IPC command and reply on its connection.
+    fn handle_command(&mut self, ipc: &mut dyn IpcPort, conn_id: ConnectionId, command: IpcCommand) {
+        let command = match resolve_runner_target(command, self.controller.usecase()) {
+            Ok(command) => command,
+            Err(e) => {
+                ipc.send_response(conn_id, IpcResponse::Error(e));
+                return;
+            }
+        };
+        match command {
+            IpcCommand::Attach { cols, rows } => {
+                ipc.open_stream(conn_id);
+                self.clients.insert(
+                    conn_id,
+                    AttachedClient {
+                        size: TerminalSize::new(cols, rows),
+                        last_frame: None,
+                    },
+                );
+                ipc.send_response(conn_id, IpcResponse::Ok);
+                self.apply_shared_size();
+            }
+            IpcCommand::Subscribe { output } => {
+                self.events.subscribe(ipc, conn_id, &output, self.controller.usecase_mut());
+            }
+            IpcCommand::WaitFor { target, condition, timeout_ms } => {
+                let request = WaitRequest { target, condition: &condition, timeout_ms };
+                self.waits.add(ipc, conn_id, request, self.controller.usecase_mut(), Instant::now());
+            }
+            IpcCommand::KillServer => {
+                ipc.send_response(conn_id, IpcResponse::Ok);
+                self.should_quit = true;
+            }
+            command => {
+                let response =
+                    handle_ipc_command(&command, &mut self.controller, &mut self.yank_buffer, self.size);
+                ipc.send_response(conn_id, response);
+            }
+        }
+    }
+
+    /// Forget clients whose connection has closed.
+    fn drop_detached(&mut self, ipc: &dyn IpcPort) {
+        let before = self.clients.len();
+        self.clients.retain(|conn_id, _| ipc.is_connected(*conn_id));
+        if self.clients.len() != before {
+            self.apply_shared_size();
+        }
+    }
+
+    /// Resize every terminal to the smallest attached client, like tmux, so
+    /// that each client can show the whole screen. Keeps the current size
+    /// when nobody is attached.
+    fn apply_shared_size(&mut self) {
+        let Some(size) = shared_size(self.clients.values().map(|c| c.size)) else {
+            return;
+        };
+        if size != self.size {
+            self.size = size;
+            let _ = self.controller.dispatch(AppAction::ResizeAll(size), size);
+        }
+    }
+
+    /// Push the current frame to every client that has not seen it yet.
+    fn push_frames(&mut self, ipc: &mut dyn IpcPort) {
+        if self.clients.is_empty() {
+            return;
+        }
+        let frame = self.controller.usecase().screen_frame();
+        let mut gone = Vec::new();
+        for (conn_id, client) in self.clients.iter_mut() {
+            if client.last_frame.as_ref() == Some(&frame) {
+                continue;
+            }
+            if ipc.send_event(*conn_id, &IpcEvent::Frame(frame.clone())) {
+                client.last_frame = Some(frame.clone());
+            } else {
+                gone.push(*conn_id);
+            }
+        }
+        if !gone.is_empty() {
+            for conn_id in gone {
+                self.clients.remove(&conn_id);
+            }
+            self.apply_shared_size();
+        }
+    }
+}
+
+/// Smallest width and height among the given client sizes.
+fn shared_size(sizes: impl Iterator<Item = TerminalSize>) -> Option<TerminalSize> {
+    sizes.reduce(|a, b| TerminalSize::new(a.cols.min(b.cols), a.rows.min(b.rows)))
+}
+
+/// Run the server until `kill-server` or SIGTERM/SIGINT/SIGHUP.
+pub fn run<P: PtyPort, S: ScreenPort>(
+    controller: TuiController<P, S>,
+    mut ipc_port: Box<dyn IpcPort>,
+    options: ServerOptions,
+) -> anyhow::Result<()> {
+    // SAFETY: `request_shutdown` only stores to an atomic, which is
+    // async-signal-safe.
+    unsafe {
+        for signal in [libc::SIGTERM, libc::SIGINT, libc::SIGHUP] {
+            libc::signal(signal, request_shutdown as *const () as libc::sighandler_t);
+        }
+    }
+
+    let mut state = ServerState::new(controller);
+    let mut notifier = MacOsNotifier::new();
+
+    let result = loop {
+        if state.should_quit || SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+            break Ok(());
+        }
+
+        // 1. Poll all ptys
+        if let Err(e) = state.controller.dispatch(AppAction::PollAll, state.size)
+            && !matches!(
+                e,
+                AppError::NoActiveTerminal | AppError::TerminalNotFound(_) | AppError::ScreenNotFound(_)
+            )
+        {
+            break Err(e.into());
+        }
+
+        // 2. Desktop notifications
+        let notifications = state.controller.usecase_mut().take_pending_notifications();
+        for (terminal_name, event) in &notifications {
+            notifier.notify(terminal_name, event);
+        }
+
+        // 3. IPC commands
+        for (conn_id, command) in ipc_port.poll_commands() {
+            if let IpcCommand::Notify { target: None, title, body } = &command {
+                notifier.notify("external", &notify_event(title, body));
+            }
+            state.handle_command(ipc_port.as_mut(), conn_id, command);
+        }
+
+        // 4. Stream screen updates to attached clients and session events
+        //    to subscribers, and answer finished waits
+        state.drop_detached(ipc_port.as_ref());
+        state.push_frames(ipc_port.as_mut());
+        state
+            .events
+            .publish(ipc_port.as_mut(), state.controller.usecase_mut(), &notifications);
+        state
+            .waits
+            .poll(ipc_port.as_mut(), state.controller.usecase_mut(), Instant::now());
+
+        std::thread::sleep(TICK);
+    };
+
+    // Snapshot the session for `cm --restore` (best-effort)
+    if let Some(path) = &options.snapshot_path {
+        let spec = state
+            .controller
+            .usecase_mut()
+            .snapshot(session_file::SNAPSHOT_SCROLLBACK_LINES);
+        let _ = session_file::save_session(path, &spec);
+    }
+
+    ipc_port.shutdown();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::domain::primitive::{
+        Cell, CursorPos, CursorStyle, ForegroundProcess, IpcResponseData, MouseMode, NotificationEvent, SearchMatch, Signal,
+        TargetSpec, TerminalId, WaitCondition,
+    };
+    use crate::interface_adapter::port::ipc_port::tests::MockIpcPort;
+    use crate::usecase::terminal_usecase::TerminalUsecase;
+
+    // =========================================================================
+    // Test doubles
+    // =========================================================================
+
+    #[derive(Default)]
+    struct FakePty {
+        written: Vec<(TerminalId, Vec<u8>)>,
+        resized: Vec<(TerminalId, TerminalSize)>,
+        /// Output returned by the next `read` of each terminal.
+        output: HashMap<TerminalId, Vec<u8>>,
+        exit_codes: HashMap<TerminalId, i32>,
+    }
+
+    impl PtyPort for FakePty {
+        fn spawn(&mut self, _id: TerminalId, _argv: &[String], _cwd: &Path, _env: &[(String, String)], _size: TerminalSize) -> Result<(), AppError> { Ok(()) }
+        fn read(&mut self, id: TerminalId) -> Result<Vec<u8>, AppError> {
+            Ok(self.output.remove(&id).unwrap_or_default())
+        }
+        fn write(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+            self.written.push((id, data.to_vec()));
+            Ok(())
+        }
+        fn resize(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+            self.resized.push((id, size));
+            Ok(())
+        }
+        fn try_wait(&mut self, id: TerminalId) -> Result<Option<i32>, AppError> {
+            Ok(self.exit_codes.get(&id).copied())
+        }
+        fn foreground_process(&mut self, _id: TerminalId) -> Result<Option<ForegroundProcess>, AppError> { Ok(None) }
+        fn signal(&mut self, _id: TerminalId, _signal: Signal) -> Result<(), AppError> { Ok(()) }
+        fn close(&mut self, _id: TerminalId, _grace: Duration) -> Result<(), AppError> { Ok(()) }
+        fn kill(&mut self, _id: TerminalId) -> Result<(), AppError> { Ok(()) }
+    }
+
+    #[derive(Default)]
+    struct FakeScreen {
+        cells: HashMap<u32, Vec<Vec<Cell>>>,
+        cursors: HashMap<u32, CursorPos>,
+    }
+
+    impl ScreenPort for FakeScreen {
+        fn create(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+            self.cells.insert(id.value(), vec![vec![Cell::default(); size.cols as usize]; size.rows as usize]);
+            Ok(())
+        }
+        fn process(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+            let grid = self.cells.get_mut(&id.value()).ok_or(AppError::ScreenNotFound(id))?;
+            let cursor = self.cursors.entry(id.value()).or_default();
+            for ch in String::from_utf8_lossy(data).chars() {
+                if ch == '\n' {
+                    *cursor = CursorPos { row: cursor.row + 1, col: 0 };
+                } else {
+                    grid[cursor.row as usize][cursor.col as usize].ch = ch;
+                    cursor.col += 1;
+                }
+            }
+            Ok(())
+        }
+        fn get_cells(&self, id: TerminalId) -> Result<&Vec<Vec<Cell>>, AppError> {
+            self.cells.get(&id.value()).ok_or(AppError::ScreenNotFound(id))
+        }
+        fn get_cursor(&self, id: TerminalId) -> Result<CursorPos, AppError> {
+            Ok(self.cursors.get(&id.value()).copied().unwrap_or_default())
+        }
+        fn resize(&mut self, _id: TerminalId, _size: TerminalSize) -> Result<(), AppError> { Ok(()) }
+        fn remove(&mut self, id: TerminalId) -> Result<(), AppError> {
+            self.cells.remove(&id.value());
+            Ok(())
+        }
+        fn get_cursor_visible(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(true) }
+        fn get_application_cursor_keys(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(false) }
+        fn get_bracketed_paste(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(false) }
+        fn get_cwd(&self, _id: TerminalId) -> Result<Option<String>, AppError> { Ok(None) }
+        fn drain_notifications(&mut self, _id: TerminalId) -> Result<Vec<NotificationEvent>, AppError> { Ok(vec![]) }
+        fn set_scrollback_offset(&mut self, _id: TerminalId, _offset: usize) -> Result<(), AppError> { Ok(()) }
+        fn get_scrollback_offset(&self, _id: TerminalId) -> Result<usize, AppError> { Ok(0) }
+        fn get_max_scrollback(&self, _id: TerminalId) -> Result<usize, AppError> { Ok(0) }
+        fn get_scrolled_off(&self, _id: TerminalId) -> Result<usize, AppError> { Ok(0) }
+        fn is_alternate_screen(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(false) }
+        fn get_cursor_style(&self, _id: TerminalId) -> Result<CursorStyle, AppError> { Ok(CursorStyle::DefaultUserShape) }
+        fn get_mouse_mode(&self, _id: TerminalId) -> Result<MouseMode, AppError> { Ok(MouseMode::Off) }
+        fn get_keyboard_flags(&self, _id: TerminalId) -> Result<u16, AppError> { Ok(0) }
+        fn drain_pending_responses(&mut self, _id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> { Ok(vec![]) }
+        fn search_scrollback(&mut self, _id: TerminalId, _query: &str) -> Result<Vec<SearchMatch>, AppError> { Ok(vec![]) }
+        fn get_row_cells(&mut self, id: TerminalId, abs_row: usize) -> Result<Vec<Cell>, AppError> {
+            let grid = self.cells.get(&id.value()).ok_or(AppError::ScreenNotFound(id))?;
+            Ok(grid.get(abs_row).cloned().unwrap_or_default())
+        }
+    }
+
+    fn make_state() -> ServerState<FakePty, FakeScreen> {
+        let usecase = TerminalUsecase::new(PathBuf::from("/tmp"), FakePty::default(), FakeScreen::default());
+        ServerState::new(TuiController::new(usecase))
+    }
+
+    fn attach(state: &mut ServerState<FakePty, FakeScreen>, ipc: &mut MockIpcPort, conn: u64, cols: u16, rows: u16) {
+        state.handle_command(ipc, ConnectionId(conn), IpcCommand::Attach { cols, rows });
+    }
+
+    // =========================================================================
+    // Tests: shared_size
+    // =========================================================================
+
+    #[test]
+    fn shared_size_without_clients_is_none() {
+        assert_eq!(shared_size(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn shared_size_takes_smallest_width_and_height() {
+        let sizes = [TerminalSize::new(120, 30), TerminalSize::new(100, 40)];
+        assert_eq!(shared_size(sizes.into_iter()), Some(TerminalSize::new(100, 30)));
+    }
+
+    // =========================================================================
+    // Tests: command handling
+    // =========================================================================
+
+    #[test]
+    fn attach_opens_stream_and_replies_ok() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+
+        attach(&mut state, &mut ipc, 1, 100, 30);
+
+        assert_eq!(ipc.streams, vec![ConnectionId(1)]);
+        assert_eq!(ipc.sent_responses, vec![(ConnectionId(1), IpcResponse::Ok)]);
+        assert_eq!(state.size, TerminalSize::new(100, 30));
+    }
+
+    #[test]
+    fn attach_resizes_terminals_to_smallest_client() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+
+        attach(&mut state, &mut ipc, 1, 120, 40);
+        attach(&mut state, &mut ipc, 2, 90, 50);
+
+        assert_eq!(state.size, TerminalSize::new(90, 40));
+        let resized = &state.controller.usecase().pty_port().resized;
+        assert_eq!(resized.last(), Some(&(id, TerminalSize::new(90, 40))));
+    }
+
+    #[test]
+    fn detached_client_no_longer_limits_size() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        attach(&mut state, &mut ipc, 1, 120, 40);
+        attach(&mut state, &mut ipc, 2, 80, 20);
+
+        ipc.disconnected.push(ConnectionId(2));
+        state.drop_detached(&ipc);
+
+        assert_eq!(state.clients.len(), 1);
+        assert_eq!(state.size, TerminalSize::new(120, 40));
+    }
+
+    #[test]
+    fn kill_server_replies_and_quits() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+
+        state.handle_command(&mut ipc, ConnectionId(1), IpcCommand::KillServer);
+
+        assert!(state.should_quit);
+        assert_eq!(ipc.sent_responses, vec![(ConnectionId(1), IpcResponse::Ok)]);
+    }
+
+    #[test]
+    fn regular_commands_use_shared_handler() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+
+        let command = IpcCommand::Input { target: id.value(), data: "ls\r".to_string() };
+        state.handle_command(&mut ipc, ConnectionId(1), command);
+
+        assert_eq!(ipc.sent_responses, vec![(ConnectionId(1), IpcResponse::Ok)]);
+        assert_eq!(state.controller.usecase().pty_port().written, vec![(id, b"ls\r".to_vec())]);
+    }
+
+    // =========================================================================
+    // Tests: frame streaming
+    // =========================================================================
+
+    #[test]
+    fn frames_are_pushed_only_when_changed() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        attach(&mut state, &mut ipc, 1, 80, 24);
+
+        state.push_frames(&mut ipc);
+        state.push_frames(&mut ipc);
+        assert_eq!(ipc.sent_events.len(), 1);
+
+        state.controller.usecase_mut().screen_port_mut().process(id, b"hi").unwrap();
+        state.push_frames(&mut ipc);
+        assert_eq!(ipc.sent_events.len(), 2);
+        let IpcEvent::Frame(frame) = &ipc.sent_events[1].1 else {
+            panic!("expected a frame");
+        };
+        assert_eq!(frame.cells[0][0].ch, 'h');
+    }
+
+    #[test]
+    fn every_attached_client_receives_frames() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        attach(&mut state, &mut ipc, 1, 80, 24);
+        attach(&mut state, &mut ipc, 2, 80, 24);
+
+        state.push_frames(&mut ipc);
+
+        let mut conns: Vec<u64> = ipc.sent_events.iter().map(|(c, _)| c.0).collect();
+        conns.sort();
+        assert_eq!(conns, vec![1, 2]);
+    }
+
+    #[test]
+    fn client_that_fails_to_receive_is_dropped() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        attach(&mut state, &mut ipc, 1, 80, 24);
+        ipc.disconnected.push(ConnectionId(1));
+
+        state.push_frames(&mut ipc);
+
+        assert!(state.clients.is_empty());
+        assert!(ipc.sent_events.is_empty());
+    }
+
+    // =========================================================================
+    // Tests: event subscriptions
+    // =========================================================================
+
+    fn subscribe(state: &mut ServerState<FakePty, FakeScreen>, ipc: &mut MockIpcPort, conn: u64, output: Vec<u32>) {
+        state.handle_command(ipc, ConnectionId(conn), IpcCommand::Subscribe { output });
+    }
+
+    fn events_for(ipc: &MockIpcPort, conn: u64) -> Vec<IpcEvent> {
+        ipc.sent_events
+            .iter()
+            .filter(|(c, _)| c.0 == conn)
+            .map(|(_, e)| e.clone())
+            .collect()
+    }
+
+    #[test]
+    fn subscribe_opens_stream_and_replies_ok() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        subscribe(&mut state, &mut ipc, 1, vec![]);
+
+        assert_eq!(ipc.streams, vec![ConnectionId(1)]);
+        assert_eq!(ipc.sent_responses, vec![(ConnectionId(1), IpcResponse::Ok)]);
+        // Nothing has changed since subscribing
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+        assert!(ipc.sent_events.is_empty());
+    }
+
+    #[test]
+    fn subscriber_receives_lifecycle_and_focus_events() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        subscribe(&mut state, &mut ipc, 1, vec![]);
+
+        let id = state.controller.usecase_mut().create_terminal(Some("build".to_string()), DEFAULT_SIZE).unwrap();
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+        state.controller.usecase_mut().rename_by_id(id, "test".to_string()).unwrap();
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+        state.controller.usecase_mut().close_by_id(id).unwrap();
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+
+        let id = id.value();
+        assert_eq!(
+            events_for(&ipc, 1),
+            vec![
+                IpcEvent::TerminalCreated { id, name: "build".to_string() },
+                IpcEvent::FocusChanged { id: Some(id) },
+                IpcEvent::TerminalRenamed { id, name: "test".to_string() },
+                IpcEvent::TerminalClosed { id },
+                IpcEvent::FocusChanged { id: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn subscriber_receives_notifications() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        subscribe(&mut state, &mut ipc, 1, vec![]);
+
+        let notifications = vec![("agent".to_string(), NotificationEvent::Bell)];
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &notifications);
+
+        assert_eq!(
+            events_for(&ipc, 1),
+            vec![IpcEvent::Notification { terminal: "agent".to_string(), event: NotificationEvent::Bell }]
+        );
+    }
+
+    #[test]
+    fn output_is_streamed_only_to_subscribers_that_asked_for_it() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        subscribe(&mut state, &mut ipc, 1, vec![id.value()]);
+        subscribe(&mut state, &mut ipc, 2, vec![]);
+
+        state.controller.usecase_mut().pty_port_mut().output.insert(id, b"hello".to_vec());
+        state.controller.dispatch(AppAction::PollAll, DEFAULT_SIZE).unwrap();
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+
+        assert_eq!(
+            events_for(&ipc, 1),
+            vec![IpcEvent::Output { id: id.value(), data: "hello".to_string() }]
+        );
+        assert!(events_for(&ipc, 2).is_empty());
+    }
+
+    #[test]
+    fn closed_subscriber_stops_output_capture() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        subscribe(&mut state, &mut ipc, 1, vec![id.value()]);
+        ipc.disconnected.push(ConnectionId(1));
+
+        state.events.publish(&mut ipc, state.controller.usecase_mut(), &[]);
+        state.controller.usecase_mut().pty_port_mut().output.insert(id, b"hello".to_vec());
+        state.controller.dispatch(AppAction::PollAll, DEFAULT_SIZE).unwrap();
+
+        assert!(state.controller.usecase_mut().take_watched_output().is_empty());
+    }
+
+    // =========================================================================
+    // Tests: wait-for
+    // =========================================================================
+
+    fn wait_for(
+        state: &mut ServerState<FakePty, FakeScreen>,
+        ipc: &mut MockIpcPort,
+        id: TerminalId,
+        condition: WaitCondition,
+        timeout_ms: Option<u64>,
+    ) {
+        let command = IpcCommand::WaitFor { target: id.value(), condition, timeout_ms };
+        state.handle_command(ipc, ConnectionId(1), command);
+    }
+
+    fn matching(pattern: &str) -> WaitCondition {
+        WaitCondition::Match { pattern: pattern.to_string(), regex: true }
+    }
+
+    fn print(state: &mut ServerState<FakePty, FakeScreen>, id: TerminalId, text: &str) {
+        state.controller.usecase_mut().pty_port_mut().output.insert(id, text.as_bytes().to_vec());
+        state.controller.dispatch(AppAction::PollAll, DEFAULT_SIZE).unwrap();
+    }
+
+    #[test]
+    fn wait_for_text_ignores_output_from_before_the_wait() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        print(&mut state, id, "old: PASS\n");
+
+        wait_for(&mut state, &mut ipc, id, matching("PASS|FAIL"), None);
+        assert!(ipc.sent_responses.is_empty());
+
+        print(&mut state, id, "ok: FAIL");
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+        assert_eq!(
+            ipc.sent_responses,
+            vec![(
+                ConnectionId(1),
+                IpcResponse::OkWithData(IpcResponseData::WaitMatch {
+                    text: "FAIL".to_string(),
+                    line: "ok: FAIL".to_string(),
+                    row: 1,
+                    col: 4,
+                })
+            )]
+        );
+    }
+
+    #[test]
+    fn wait_for_text_replies_once_it_is_printed() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, matching("done"), None);
+        assert!(ipc.sent_responses.is_empty());
+
+        print(&mut state, id, "done");
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+
+        assert_eq!(ipc.sent_responses.len(), 1);
+        assert!(matches!(
+            &ipc.sent_responses[0].1,
+            IpcResponse::OkWithData(IpcResponseData::WaitMatch { text, .. }) if text == "done"
+        ));
+        assert!(state.waits.is_empty());
+    }
+
+    #[test]
+    fn wait_for_times_out() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, matching("never"), Some(1000));
+
+        let later = Instant::now() + Duration::from_secs(2);
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), later);
+
+        assert_eq!(
+            ipc.sent_responses,
+            vec![(ConnectionId(1), IpcResponse::Error("timed out after 1s".to_string()))]
+        );
+    }
+
+    #[test]
+    fn wait_for_idle_replies_after_quiet_period() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, WaitCondition::Idle { ms: 500 }, None);
+
+        print(&mut state, id, "busy");
+        let soon = Instant::now() + Duration::from_millis(100);
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), soon);
+        assert!(ipc.sent_responses.is_empty());
+
+        let later = Instant::now() + Duration::from_secs(1);
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), later);
+        assert_eq!(ipc.sent_responses, vec![(ConnectionId(1), IpcResponse::Ok)]);
+    }
+
+    #[test]
+    fn wait_for_exit_reports_exit_code() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, WaitCondition::Exit, None);
+        assert!(ipc.sent_responses.is_empty());
+
+        state.controller.usecase_mut().pty_port_mut().exit_codes.insert(id, 3);
+        state.controller.dispatch(AppAction::PollAll, DEFAULT_SIZE).unwrap();
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+
+        assert_eq!(
+            ipc.sent_responses,
+            vec![(ConnectionId(1), IpcResponse::OkWithData(IpcResponseData::Exited { exit_code: 3 }))]
+        );
+    }
+
+    #[test]
+    fn wait_for_text_fails_when_terminal_exits_without_match() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, matching("never"), None);
+
+        state.controller.usecase_mut().pty_port_mut().exit_codes.insert(id, 0);
+        state.controller.dispatch(AppAction::PollAll, DEFAULT_SIZE).unwrap();
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+
+        assert_eq!(
+            ipc.sent_responses,
+            vec![(ConnectionId(1), IpcResponse::Error(format!("terminal {} exited without a match", id.value())))]
+        );
+    }
+
+    #[test]
+    fn wait_for_rejects_unknown_terminal_and_invalid_regex() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        wait_for(&mut state, &mut ipc, TerminalId::new(9), WaitCondition::Exit, None);
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, matching("("), None);
+
+        assert_eq!(ipc.sent_responses[0].1, IpcResponse::Error("terminal not found: 9".to_string()));
+        assert!(matches!(&ipc.sent_responses[1].1, IpcResponse::Error(e) if e.starts_with("invalid regex")));
+        assert!(state.waits.is_empty());
+    }
+
+    #[test]
+    fn wait_for_resolves_a_name_target() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(Some("build".to_string()), DEFAULT_SIZE).unwrap();
+
+        let command = IpcCommand::Resolve {
+            target: TargetSpec::Name("build".to_string()),
+            caller: None,
+            command: Box::new(IpcCommand::WaitFor { target: 0, condition: matching("PASS"), timeout_ms: None }),
+        };
+        state.handle_command(&mut ipc, ConnectionId(1), command);
+        print(&mut state, id, "ok: PASS");
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+
+        assert!(matches!(
+            &ipc.sent_responses[0].1,
+            IpcResponse::OkWithData(IpcResponseData::WaitMatch { text, .. }) if text == "PASS"
+        ));
+    }
+
+    #[test]
+    fn wait_for_is_dropped_when_client_disconnects() {
+        let mut state = make_state();
+        let mut ipc = MockIpcPort::new();
+        let id = state.controller.usecase_mut().create_terminal(None, DEFAULT_SIZE).unwrap();
+        wait_for(&mut state, &mut ipc, id, WaitCondition::Exit, None);
+
+        ipc.disconnected.push(ConnectionId(1));
+        state.waits.poll(&mut ipc, state.controller.usecase_mut(), Instant::now());
+
+        assert!(state.waits.is_empty());
+        assert!(ipc.sent_responses.is_empty());
+    }
+}
@@ -0,0 +1,518 @@
+//! Declarative workspace file for `cm --session <file>`.
+//!
+//! Converts between the JSON file format and the domain `SessionSpec` using
+//! serde intermediate structs. The domain types themselves do not derive serde
+//! traits (domain purity). The quit-time snapshot restored by `cm --restore`
+//! is written in the same format.
+//!
+//! ```json
+//! {
+//!   "active": "agent",
+//!   "terminals": [
+//!     {"name": "api", "cwd": "~/src/api", "command": "cargo watch -x run",
+//!      "env": {"RUST_LOG": "debug"}, "memo": "port 8080", "restart": "on-failure",
+//!      "group": "api"},
+//!     {"name": "agent", "cwd": "~/src/api", "command": "claude", "group": "api"}
+//!   ]
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::primitive::{group_name, RestartPolicy, SessionSpec, SpawnOptions, TerminalSpec};
+use crate::shared::error::AppError;
+
+/// Lines of output kept per terminal in the quit-time snapshot.
+pub const SNAPSHOT_SCROLLBACK_LINES: usize = 100;
+
+// ============================================================================
+// File format intermediate types
+// ============================================================================
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RawSession {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<RawActive>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    mini_terminal: bool,
+    terminals: Vec<RawTerminal>,
+}
+
+/// `active` may name a terminal or give its zero-based position.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawActive {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct RawTerminal {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    scrollback: Vec<String>,
+    /// `never` (default), `on-failure` or `always`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restart: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Parse a session definition from JSON.
+///
+/// Relative `cwd` values are resolved against `base_dir` (the directory that
+/// contains the session file) and a leading `~` expands to `$HOME`.
+pub fn parse_session(json: &str, base_dir: &Path) -> Result<SessionSpec, String> {
+    let raw: RawSession = serde_json::from_str(json).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    let active = match &raw.active {
+        Some(RawActive::Name(name)) => Some(
+            raw.terminals
+                .iter()
+                .position(|t| t.name.as_deref() == Some(name.as_str()))
+                .ok_or_else(|| format!("active terminal not found: {}", name))?,
+        ),
+        Some(RawActive::Index(index)) if *index >= raw.terminals.len() => {
+            return Err(format!("active index out of range: {}", index));
+        }
+        Some(RawActive::Index(index)) => Some(*index),
+        None => None,
+    };
+
+    let terminals = raw
+        .terminals
+        .into_iter()
+        .map(|t| {
+            let restart = match &t.restart {
+                Some(policy) => RestartPolicy::parse(policy)?,
+                None => RestartPolicy::Never,
+            };
+            Ok(TerminalSpec {
+                name: t.name,
+                spawn: SpawnOptions {
+                    command: t.command,
+                    cwd: t.cwd.map(|c| resolve_cwd(&c, base_dir)),
+                    env: t.env.into_iter().collect(),
+                    restart,
+                },
+                memo: t.memo,
+                scrollback: t.scrollback,
+                group: t.group.as_deref().and_then(group_name),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(SessionSpec {
+        terminals,
+        active,
+        mini_terminal_visible: raw.mini_terminal,
+    })
+}
+
+/// Serialize a session to pretty-printed JSON readable by `parse_session`.
+/// The active terminal is written as an index since names may repeat.
+pub fn serialize_session(spec: &SessionSpec) -> String {
+    let raw = RawSession {
+        active: spec.active.map(RawActive::Index),
+        mini_terminal: spec.mini_terminal_visible,
+        terminals: spec
+            .terminals
+            .iter()
+            .map(|t| RawTerminal {
+                name: t.name.clone(),
+                cwd: t.spawn.cwd.as_ref().map(|c| c.to_string_lossy().into_owned()),
+                command: t.spawn.command.clone(),
+                env: t.spawn.env.iter().cloned().collect(),
+                memo: t.memo.clone(),
+                scrollback: t.scrollback.clone(),
+                restart: Some(t.spawn.restart)
+                    .filter(|r| *r != RestartPolicy::Never)
+                    .map(|r| r.as_str().to_string()),
+                group: t.group.clone(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&raw).expect("session serialization cannot fail")
+}
+
+/// Read and parse a session file, checking that every cwd exists.
+pub fn load_session(path: &Path) -> Result<SessionSpec, AppError> {
+    let spec = read_session(path)?;
+
+    for entry in &spec.terminals {
+        if let Some(cwd) = &entry.spawn.cwd
+            && !cwd.is_dir()
+        {
+            return Err(AppError::SessionFile(format!(
+                "{}: cwd is not a directory: {}",
+                path.display(),
+                cwd.display()
+            )));
+        }
+    }
+
+    Ok(spec)
+}
+
+/// Write a session file (mode 0600, it may contain terminal output).
+///
+/// Writes to a temporary file first so a crash never leaves a truncated file.
+pub fn save_session(path: &Path, spec: &SessionSpec) -> Result<(), AppError> {
+    let to_err = |e: std::io::Error| AppError::SessionFile(format!("{}: {}", path.display(), e));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(to_err)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serialize_session(spec)).map_err(to_err)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600)).map_err(to_err)?;
+    }
+    fs::rename(&tmp, path).map_err(to_err)
+}
+
+/// Path of the snapshot written on quit (`~/.cli-manager/snapshot.json`).
+pub fn snapshot_file_path() -> PathBuf {
+    home_dir().join(".cli-manager").join("snapshot.json")
+}
+
+/// Read the quit-time snapshot for `cm --restore`.
+///
+/// Unlike `load_session`, a cwd that no longer exists is dropped (the
+/// terminal starts in the default cwd) instead of failing the restore.
+pub fn load_snapshot(path: &Path) -> Result<SessionSpec, AppError> {
+    let mut spec = read_session(path)?;
+    for entry in &mut spec.terminals {
+        if entry.spawn.cwd.as_ref().is_some_and(|c| !c.is_dir()) {
+            entry.spawn.cwd = None;
+        }
+    }
+    Ok(spec)
+}
+
+fn read_session(path: &Path) -> Result<SessionSpec, AppError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AppError::SessionFile(format!("{}: {}", path.display(), e)))?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    parse_session(&content, base_dir)
+        .map_err(|e| AppError::SessionFile(format!("{}: {}", path.display(), e)))
+}
+
+/// Expand `~` and resolve relative paths against `base_dir`.
+fn resolve_cwd(raw: &str, base_dir: &Path) -> PathBuf {
+    let expanded = if raw == "~" {
+        home_dir()
+    } else if let Some(rest) = raw.strip_prefix("~/") {
+        home_dir().join(rest)
+    } else {
+        PathBuf::from(raw)
+    };
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        base_dir.join(expanded)
+    }
+}
+
+fn home_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| "/".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn base() -> PathBuf {
+        PathBuf::from("/work")
+    }
+
+    // ========================================================================
+    // Tests: parse_session
+    // ========================================================================
+
+    #[test]
+    fn parse_minimal_session() {
+        let spec = parse_session(r#"{"terminals": [{}]}"#, &base()).unwrap();
+        assert_eq!(spec.terminals.len(), 1);
+        assert_eq!(spec.terminals[0], TerminalSpec::default());
+        assert_eq!(spec.active, None);
+    }
+
+    #[test]
+    fn parse_empty_terminal_list() {
+        let spec = parse_session(r#"{"terminals": []}"#, &base()).unwrap();
+        assert!(spec.terminals.is_empty());
+    }
+
+    #[test]
+    fn parse_full_entry() {
+        let json = r##"{"terminals": [{
+            "name": "api",
+            "cwd": "/srv/api",
+            "command": "cargo watch -x run",
+            "env": {"RUST_LOG": "debug", "PORT": "8080"},
+            "memo": "backend",
+            "restart": "on-failure",
+            "group": "#backend"
+        }]}"##;
+        let spec = parse_session(json, &base()).unwrap();
+        let t = &spec.terminals[0];
+        assert_eq!(t.name.as_deref(), Some("api"));
+        assert_eq!(t.spawn.command.as_deref(), Some("cargo watch -x run"));
+        assert_eq!(t.spawn.cwd, Some(PathBuf::from("/srv/api")));
+        assert_eq!(
+            t.spawn.env,
+            vec![
+                ("PORT".to_string(), "8080".to_string()),
+                ("RUST_LOG".to_string(), "debug".to_string()),
+            ]
+        );
+        assert_eq!(t.memo.as_deref(), Some("backend"));
+        assert_eq!(t.spawn.restart, RestartPolicy::OnFailure);
+        assert_eq!(t.group.as_deref(), Some("backend"));
+    }
+
+    #[test]
+    fn parse_invalid_restart_policy_is_error() {
+        let json = r#"{"terminals": [{"command": "npm start", "restart": "forever"}]}"#;
+        let err = parse_session(json, &base()).unwrap_err();
+        assert!(err.contains("invalid restart policy: forever"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_preserves_terminal_order() {
+        let json = r#"{"terminals": [{"name": "c"}, {"name": "a"}, {"name": "b"}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        let names: Vec<&str> = spec.terminals.iter().map(|t| t.name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn parse_active_by_name() {
+        let json = r#"{"active": "web", "terminals": [{"name": "api"}, {"name": "web"}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        assert_eq!(spec.active, Some(1));
+    }
+
+    #[test]
+    fn parse_active_by_index() {
+        let json = r#"{"active": 0, "terminals": [{"name": "api"}, {"name": "web"}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        assert_eq!(spec.active, Some(0));
+    }
+
+    #[test]
+    fn parse_active_index_out_of_range_is_error() {
+        let json = r#"{"active": 2, "terminals": [{"name": "api"}]}"#;
+        let err = parse_session(json, &base()).unwrap_err();
+        assert!(err.contains("out of range"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_mini_terminal_and_scrollback() {
+        let json = r#"{"mini_terminal": true, "terminals": [{"scrollback": ["a", "b"]}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        assert!(spec.mini_terminal_visible);
+        assert_eq!(spec.terminals[0].scrollback, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn parse_unknown_active_is_error() {
+        let json = r#"{"active": "nope", "terminals": [{"name": "api"}]}"#;
+        let err = parse_session(json, &base()).unwrap_err();
+        assert!(err.contains("active terminal not found: nope"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_relative_cwd_uses_base_dir() {
+        let json = r#"{"terminals": [{"cwd": "frontend"}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        assert_eq!(spec.terminals[0].spawn.cwd, Some(PathBuf::from("/work/frontend")));
+    }
+
+    #[test]
+    fn parse_tilde_cwd_expands_home() {
+        let json = r#"{"terminals": [{"cwd": "~/src"}]}"#;
+        let spec = parse_session(json, &base()).unwrap();
+        let cwd = spec.terminals[0].spawn.cwd.clone().unwrap();
+        assert!(cwd.is_absolute());
+        assert!(cwd.ends_with("src"));
+        assert!(!cwd.starts_with("/work"));
+    }
+
+    #[test]
+    fn parse_missing_terminals_is_error() {
+        assert!(parse_session(r#"{}"#, &base()).is_err());
+    }
+
+    #[test]
+    fn parse_unknown_field_is_error() {
+        let err = parse_session(r#"{"terminals": [{"cmd": "ls"}]}"#, &base()).unwrap_err();
+        assert!(err.contains("invalid JSON"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_invalid_json_is_error() {
+        assert!(parse_session("not json", &base()).is_err());
+    }
+
+    // ========================================================================
+    // Tests: serialize_session
+    // ========================================================================
+
+    fn sample_spec() -> SessionSpec {
+        SessionSpec {
+            terminals: vec![
+                TerminalSpec {
+                    name: Some("api".to_string()),
+                    spawn: SpawnOptions {
+                        command: Some("cargo run".to_string()),
+                        cwd: Some(PathBuf::from("/srv/api")),
+                        env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+                        restart: RestartPolicy::Always,
+                    },
+                    memo: Some("line1\nline2".to_string()),
+                    scrollback: vec!["$ ls".to_string(), "Cargo.toml".to_string()],
+                    group: Some("backend".to_string()),
+                },
+                TerminalSpec {
+                    name: Some("api".to_string()),
+                    ..Default::default()
+                },
+            ],
+            active: Some(1),
+            mini_terminal_visible: true,
+        }
+    }
+
+    #[test]
+    fn serialize_roundtrips_through_parse() {
+        let spec = sample_spec();
+        let json = serialize_session(&spec);
+        assert_eq!(parse_session(&json, &base()).unwrap(), spec);
+    }
+
+    #[test]
+    fn serialize_omits_empty_fields() {
+        let spec = SessionSpec {
+            terminals: vec![TerminalSpec::default()],
+            ..Default::default()
+        };
+        let v: serde_json::Value = serde_json::from_str(&serialize_session(&spec)).unwrap();
+        assert!(v.get("active").is_none());
+        assert!(v.get("mini_terminal").is_none());
+        assert_eq!(v["terminals"][0], serde_json::json!({}));
+    }
+
+    // ========================================================================
+    // Tests: load_session
+    // ========================================================================
+
+    fn temp_file(name: &str, content: &str) -> PathBuf {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = PathBuf::from(format!("/tmp/cm-session-test-{ts}-{name}"));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn load_session_resolves_cwd_against_file_dir() {
+        let path = temp_file("rel", r#"{"terminals": [{"cwd": "."}]}"#);
+        let spec = load_session(&path).unwrap();
+        assert_eq!(spec.terminals[0].spawn.cwd, Some(path.parent().unwrap().join(".")));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn load_session_missing_cwd_is_error() {
+        let path = temp_file("missing", r#"{"terminals": [{"cwd": "does-not-exist"}]}"#);
+        let err = load_session(&path).unwrap_err();
+        assert!(matches!(err, AppError::SessionFile(_)));
+        assert!(err.to_string().contains("cwd is not a directory"), "got: {err}");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn load_session_missing_file_is_error() {
+        let err = load_session(Path::new("/nonexistent/cm-session.json")).unwrap_err();
+        assert!(matches!(err, AppError::SessionFile(_)));
+    }
+
+    // ========================================================================
+    // Tests: save_session / load_snapshot
+    // ========================================================================
+
+    #[test]
+    fn save_then_load_snapshot_roundtrip() {
+        let path = temp_file("save", "");
+        let mut spec = sample_spec();
+        spec.terminals[0].spawn.cwd = Some(path.parent().unwrap().to_path_buf());
+
+        save_session(&path, &spec).unwrap();
+        let loaded = load_snapshot(&path).unwrap();
+
+        assert_eq!(loaded, spec);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_session_sets_owner_only_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_file("perm", "");
+        save_session(&path, &sample_spec()).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn save_session_creates_parent_dir() {
+        let path = temp_file("mkdir", "");
+        let nested = path.parent().unwrap().join("a/b/snapshot.json");
+        save_session(&nested, &SessionSpec::default()).unwrap();
+        assert!(nested.exists());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn load_snapshot_drops_missing_cwd() {
+        let path = temp_file("snapcwd", r#"{"terminals": [{"name": "a", "cwd": "/nonexistent/cm-dir"}]}"#);
+        let spec = load_snapshot(&path).unwrap();
+        assert_eq!(spec.terminals[0].spawn.cwd, None);
+        assert_eq!(spec.terminals[0].name.as_deref(), Some("a"));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn snapshot_file_path_is_under_cli_manager_dir() {
+        let path = snapshot_file_path();
+        assert!(path.ends_with(".cli-manager/snapshot.json"));
+    }
+}
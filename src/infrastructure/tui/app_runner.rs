@@ -1,15 +1,17 @@
+use std::collections::HashMap;
 use std::io;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use crossterm::cursor::SetCursorStyle as CrosstermCursorStyle;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
 use crossterm::execute;
-use crossterm::event::{EnableBracketedPaste, DisableBracketedPaste};
+use crossterm::event::{EnableBracketedPaste, DisableBracketedPaste, EnableMouseCapture, DisableMouseCapture};
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
-use ratatui::layout::Rect;
-use ratatui::Terminal;
+use ratatui::layout::{Position, Rect};
+use ratatui::{Frame, Terminal};
 use ratatui::backend::CrosstermBackend;
 
 /// Convert a character-based position to a byte index in a string.
@@ -21,14 +23,22 @@ fn char_to_byte_index(s: &str, char_pos: usize) -> usize {
         .unwrap_or(s.len())
 }
 
-use crate::domain::primitive::{Cell, CursorPos, CursorStyle, IpcCommand, IpcResponse, IpcResponseData, NotificationEvent, SearchMatch, TerminalId, TerminalSize, WindowInfo};
+use crate::domain::model::ManagedTerminal;
+use crate::domain::primitive::{group_name, Cell, CursorPos, CursorStyle, DEFAULT_LOG_MAX_BYTES, IpcCommand, IpcResponse, IpcResponseData, LogOptions, MouseMode, NotificationEvent, SearchMatch, SpawnOptions, TerminalId, TerminalSize, TerminalStatus, WindowInfo, WindowMove};
+use crate::infrastructure::ipc::capture;
+use crate::infrastructure::ipc::event_stream::EventStream;
+use crate::infrastructure::ipc::wait_for::{WaitQueue, WaitRequest};
 use crate::infrastructure::notification::MacOsNotifier;
-use crate::infrastructure::tui::input::{InputHandler, InputMode};
+use crate::infrastructure::session::session_file;
+use crate::infrastructure::tui::input::{self, InputHandler, InputMode};
 use crate::infrastructure::tui::fuzzy_matcher;
+use crate::infrastructure::tui::pane_layout::{PaneLayout, PaneRects};
 use crate::infrastructure::tui::widgets::{dialog, help_overlay, layout, memo_overlay, mini_terminal_view, quick_switcher, search_bar, sidebar, terminal_view};
+use crate::infrastructure::tui::widgets::layout::{LayoutAreas, LayoutSettings};
 use crate::infrastructure::tui::widgets::quick_switcher::QuickSwitchItem;
 use crate::interface_adapter::controller::tui_controller::{AppAction, TuiController};
 use crate::interface_adapter::port::{IpcPort, PtyPort, ScreenPort};
+use crate::usecase::terminal_usecase::TerminalUsecase;
 
 /// Height in rows for the mini terminal pane.
 pub(crate) const MINI_TERMINAL_HEIGHT: u16 = 10;
@@ -86,6 +96,8 @@ struct MiniTerminalState {
     visible: bool,
     spawned: bool,
     terminal_id: TerminalId,
+    /// Size last applied to the pty/screen.
+    size: Option<TerminalSize>,
 }
 
 impl MiniTerminalState {
@@ -94,6 +106,7 @@ impl MiniTerminalState {
             visible: false,
             spawned: false,
             terminal_id: TerminalId::new(u32::MAX),
+            size: None,
         }
     }
 
@@ -102,12 +115,65 @@ impl MiniTerminalState {
     }
 }
 
+/// Keep the mini terminal's pty and screen at the size of its pane (minus
+/// borders) while it is open.
+fn resize_mini_terminal<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    mini_terminal: &mut MiniTerminalState,
+    area: Option<Rect>,
+) {
+    let Some(area) = area.filter(|_| mini_terminal.spawned) else {
+        return;
+    };
+    let size = TerminalSize::new(area.width.saturating_sub(2), area.height.saturating_sub(2));
+    if mini_terminal.size != Some(size) {
+        let mid = mini_terminal.terminal_id;
+        let _ = controller.usecase_mut().pty_port_mut().resize(mid, size);
+        let _ = controller.usecase_mut().screen_port_mut().resize(mid, size);
+        mini_terminal.size = Some(size);
+    }
+}
+
+/// Spawn the mini terminal's shell in the active terminal's cwd.
+fn spawn_mini_terminal<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    mini_terminal: &mut MiniTerminalState,
+    cols: u16,
+) -> Result<(), crate::shared::error::AppError> {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let argv = SpawnOptions::default().argv(&shell);
+    let cwd = controller.usecase().get_active_terminal()
+        .and_then(|t| {
+            controller.usecase().screen_port()
+                .get_cwd(t.id()).ok().flatten()
+                .map(|s| std::path::PathBuf::from(s))
+        })
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("/")));
+    let mini_size = TerminalSize {
+        rows: MINI_TERMINAL_HEIGHT - 2, // minus borders
+        cols,
+    };
+    let mid = mini_terminal.terminal_id;
+    controller.usecase_mut().pty_port_mut().spawn(mid, &argv, &cwd, &[], mini_size)?;
+    controller.usecase_mut().screen_port_mut().create(mid, mini_size)?;
+    mini_terminal.spawned = true;
+    mini_terminal.size = Some(mini_size);
+    Ok(())
+}
+
 /// Dialog state for overlay dialogs.
 enum DialogState {
     None,
-    CreateTerminal { input: String, cursor_pos: usize },
-    ConfirmClose { terminal_name: String, is_running: bool },
+    /// `cursor_pos` is in the group field while `on_group`.
+    CreateTerminal { input: String, cursor_pos: usize, group: String, on_group: bool },
+    /// `process` is the foreground process that closing hangs up.
+    ConfirmClose { terminal_name: String, process: Option<String>, is_running: bool },
+    SignalMenu { terminal_name: String, process: Option<String> },
+    SortMenu,
+    /// Editing the sidebar filter, which is applied as it is typed.
+    FilterEdit,
     Rename { input: String, cursor_pos: usize },
+    GroupEdit { input: String, cursor_pos: usize },
     MemoEdit { text: String, cursor_row: usize, cursor_col: usize },
     Help,
     QuickSwitch { query: String, cursor_pos: usize, selected_index: usize },
@@ -193,6 +259,19 @@ impl SelectionState {
     }
 }
 
+/// Mouse gesture in progress between a button press and its release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseDrag {
+    /// Dragging the sidebar's right border to change its width.
+    SidebarBorder,
+    /// Left button held over terminal content; becomes a selection once the
+    /// pointer moves.
+    Select { target: ScrollbackTarget, anchor: SelectionPos },
+    /// Press forwarded to an application with mouse reporting, so the
+    /// matching drag and release go to the same terminal.
+    Forward { id: TerminalId, content: Rect },
+}
+
 /// Extract text from a cell grid.
 ///
 /// - `start_row` / `end_row`: row range (end_row is exclusive)
@@ -273,15 +352,154 @@ struct ScrollbackCursor {
     col: usize,
 }
 
+/// State the main loop owns for the key and mouse handlers: the mini
+/// terminal, yank and selection, split panes and layout, and the mouse
+/// gesture in progress.
+struct UiState {
+    mini_terminal: MiniTerminalState,
+    yank_buffer: Option<String>,
+    yank_flash_until: Option<std::time::Instant>,
+    selection_state: Option<SelectionState>,
+    scrollback_cursor: ScrollbackCursor,
+    panes: PaneLayout,
+    view: LayoutSettings,
+    mouse_drag: Option<MouseDrag>,
+}
+
+impl UiState {
+    fn new() -> Self {
+        Self {
+            mini_terminal: MiniTerminalState::new(),
+            yank_buffer: None,
+            yank_flash_until: None,
+            selection_state: None,
+            scrollback_cursor: ScrollbackCursor::default(),
+            panes: PaneLayout::new(),
+            view: LayoutSettings::default(),
+            mouse_drag: None,
+        }
+    }
+}
+
+/// Size available to a terminal in the main pane (minus the CWD bar).
+pub(crate) fn pane_size(area: Rect, mini_visible: bool) -> TerminalSize {
+    let areas = layout::compute_layout(area, mini_visible);
+    pane_content_size(areas.main_pane)
+}
+
+/// Terminal size for a pane drawn in `area` (minus its CWD bar).
+fn pane_content_size(area: Rect) -> TerminalSize {
+    TerminalSize::new(area.width, area.height.saturating_sub(1))
+}
+
+/// Pane areas for this frame. While zoomed, the `zoom` terminal is drawn over
+/// the whole screen and the other panes keep their (hidden) split areas so
+/// that zooming does not resize them.
+fn frame_pane_rects(panes: &PaneLayout, split_area: Rect, zoom: Option<(TerminalId, Rect)>) -> PaneRects {
+    let mut rects = panes.layout(split_area);
+    if let Some((id, area)) = zoom {
+        rects.dividers.clear();
+        match rects.panes.iter_mut().find(|(pid, _)| *pid == id) {
+            Some(pane) => pane.1 = area,
+            None => rects.panes.push((id, area)),
+        }
+    }
+    rects
+}
+
+/// Size every terminal to the pane it is shown in. Terminals without a pane
+/// (all of them when nothing is split) get the whole main area. Only sizes
+/// that changed since the last call are sent to the pty and screen.
+fn resize_panes<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    rects: &PaneRects,
+    main_area: Rect,
+    applied: &mut HashMap<TerminalId, TerminalSize>,
+) {
+    let full = pane_content_size(main_area);
+    let ids: Vec<TerminalId> = controller.usecase().get_terminals().iter().map(|t| t.id()).collect();
+    applied.retain(|id, _| ids.contains(id));
+    for id in ids {
+        let size = rects.rect_of(id).map_or(full, pane_content_size);
+        if applied.get(&id) != Some(&size) {
+            let _ = controller.usecase_mut().resize_terminal(id, size);
+            applied.insert(id, size);
+        }
+    }
+}
+
+/// Draw the split panes that do not hold the active terminal, plus the
+/// dividers between them. They show the live screen only; scrollback,
+/// search and selection belong to the focused pane.
+fn render_unfocused_panes<P: PtyPort, S: ScreenPort>(
+    frame: &mut Frame,
+    controller: &TuiController<P, S>,
+    pane_rects: &PaneRects,
+) {
+    let active = controller.usecase().get_active_terminal().map(|t| t.id());
+    for &(id, rect) in &pane_rects.panes {
+        if Some(id) == active {
+            continue;
+        }
+        let Some(t) = controller.usecase().get_terminal_by_id(id) else {
+            continue;
+        };
+        let screen = controller.usecase().screen_port();
+        let cwd = screen.get_cwd(id)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| t.cwd().display().to_string());
+        let cells = screen.get_cells(id).ok();
+        let recording = controller.usecase().recording_path(id).is_some();
+        terminal_view::render(
+            frame,
+            rect,
+            cells,
+            None,
+            false,
+            Some(&cwd),
+            false,
+            None,
+            false,
+            None,
+            recording.then_some("REC"),
+            None,
+            None,
+            None,
+        );
+    }
+    for divider in &pane_rects.dividers {
+        terminal_view::render_divider(frame, *divider);
+    }
+}
+
+/// Main pane size for the current host terminal, before the TUI starts.
+///
+/// Used to spawn terminals (e.g. from a session file) at the right size
+/// ahead of the first frame.
+pub fn initial_pane_size() -> io::Result<TerminalSize> {
+    let (cols, rows) = crossterm::terminal::size()?;
+    Ok(pane_size(Rect::new(0, 0, cols, rows), false))
+}
+
+/// Startup and shutdown behaviour of `run`.
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    /// Open the mini terminal (unfocused) on startup, e.g. when restoring.
+    pub mini_terminal_visible: bool,
+    /// Write a session snapshot here when the user quits. `None` disables it.
+    pub snapshot_path: Option<PathBuf>,
+}
+
 /// Main TUI event loop.
 ///
 /// Initializes crossterm raw mode + alternate screen, creates the ratatui Terminal,
 /// runs the draw -> poll -> input loop, and cleans up on exit.
-pub fn run<P: PtyPort, S: ScreenPort>(mut controller: TuiController<P, S>, mut ipc_port: Option<Box<dyn IpcPort>>) -> anyhow::Result<()> {
+pub fn run<P: PtyPort, S: ScreenPort>(mut controller: TuiController<P, S>, mut ipc_port: Option<Box<dyn IpcPort>>, options: RunOptions) -> anyhow::Result<()> {
     // === Initialization ===
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -309,11 +527,12 @@ pub fn run<P: PtyPort, S: ScreenPort>(mut controller: TuiController<P, S>, mut i
         &mut last_cursor_style,
         &mut search_state,
         &mut ipc_port,
+        &options,
     );
 
     // === Cleanup (always runs) ===
     let _ = disable_raw_mode();
-    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableBracketedPaste, CrosstermCursorStyle::DefaultUserShape);
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableBracketedPaste, DisableMouseCapture, CrosstermCursorStyle::DefaultUserShape);
     let _ = terminal.show_cursor();
 
     result
@@ -332,16 +551,45 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
     last_cursor_style: &mut CursorStyle,
     search_state: &mut Option<SearchState>,
     ipc_port: &mut Option<Box<dyn IpcPort>>,
+    options: &RunOptions,
 ) -> anyhow::Result<()> {
-    let mut mini_terminal = MiniTerminalState::new();
-    let mut yank_buffer: Option<String> = None;
-    let mut yank_flash_until: Option<std::time::Instant> = None;
-    let mut selection_state: Option<SelectionState> = None;
-    let mut scrollback_cursor = ScrollbackCursor::default();
+    let mut ui = UiState::new();
+    let mut applied_sizes: HashMap<TerminalId, TerminalSize> = HashMap::new();
+    let mut event_stream = EventStream::new();
+    let mut waits = WaitQueue::new();
+
+    if options.mini_terminal_visible {
+        let size = pane_size(terminal.size()?.into(), true);
+        spawn_mini_terminal(controller, &mut ui.mini_terminal, size.cols)?;
+        ui.mini_terminal.visible = true;
+    }
 
     while !*should_quit {
+        // 0. Keep split panes in step with the terminal list, then size each
+        //    terminal to its pane (sidebar, zoom and mini terminal included)
+        let alive: Vec<TerminalId> = controller.usecase().get_terminals().iter().map(|t| t.id()).collect();
+        let active_id = controller.usecase().get_active_terminal().map(|t| t.id());
+        if let Some(id) = ui.panes.sync(active_id, &alive) {
+            let _ = controller.usecase_mut().select_by_id(id);
+        }
+        let frame_area: Rect = terminal.size()?.into();
+        let areas = layout::compute_layout_with(frame_area, ui.mini_terminal.is_visible(), &ui.view);
+        let split_area = if ui.view.zoomed {
+            layout::compute_layout_with(frame_area, ui.mini_terminal.is_visible(), &ui.view.unzoomed()).main_pane
+        } else {
+            areas.main_pane
+        };
+        let active_id = controller.usecase().get_active_terminal().map(|t| t.id());
+        let pane_rects = frame_pane_rects(
+            &ui.panes,
+            split_area,
+            active_id.filter(|_| ui.view.zoomed).map(|id| (id, areas.main_pane)),
+        );
+        resize_panes(controller, &pane_rects, split_area, &mut applied_sizes);
+        resize_mini_terminal(controller, &mut ui.mini_terminal, areas.mini_terminal);
+
         // 1. Compute status message before draw (flash expires after 2 seconds)
-        let status_msg = if yank_flash_until.map(|t| t > std::time::Instant::now()).unwrap_or(false) {
+        let status_msg = if ui.yank_flash_until.map(|t| t > std::time::Instant::now()).unwrap_or(false) {
             Some("Yanked!")
         } else {
             None
@@ -349,14 +597,15 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
 
         // 1. Draw
         terminal.draw(|frame| {
-            let areas = layout::compute_layout(frame.area(), mini_terminal.is_visible());
-
             // Compute sidebar scroll offset before rendering
             let sidebar_inner_height = areas.sidebar.height.saturating_sub(2); // minus top/bottom border
             let content_height = sidebar_inner_height.saturating_sub(1); // minus help area (1 line)
+            let shown_terminals = controller.usecase().visible_terminals();
+            let groups = controller.usecase().group_headers();
             *sidebar_scroll_offset = sidebar::compute_scroll_offset(
-                controller.usecase().get_terminals().len(),
-                controller.usecase().get_active_index(),
+                shown_terminals.len(),
+                &groups,
+                controller.usecase().visible_active_index(),
                 content_height,
                 *sidebar_scroll_offset,
             );
@@ -367,19 +616,45 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 .map(|t| controller.usecase().screen_port().get_cwd(t.id()).ok().flatten())
                 .collect();
 
-            // Sidebar
-            sidebar::render(
-                frame,
-                areas.sidebar,
-                controller.usecase().get_terminals(),
-                controller.usecase().get_active_index(),
-                *focus == FocusPane::Sidebar,
-                *sidebar_scroll_offset,
-                &dynamic_cwds,
-            );
+            // Sidebar, with the filter over the help line while one is set
+            // or being edited
+            let mut filter_cursor = None;
+            if ui.view.shows_sidebar() {
+                let shown_cwds: Vec<Option<String>> = shown_terminals
+                    .iter()
+                    .map(|t| controller.usecase().screen_port().get_cwd(t.id()).ok().flatten())
+                    .collect();
+                sidebar::render(
+                    frame,
+                    areas.sidebar,
+                    &shown_terminals,
+                    &groups,
+                    controller.usecase().visible_active_index(),
+                    *focus == FocusPane::Sidebar,
+                    controller.usecase().sync_input(),
+                    *sidebar_scroll_offset,
+                    &shown_cwds,
+                );
+                let filter = controller.usecase().filter();
+                let editing = matches!(dialog, DialogState::FilterEdit);
+                if editing || filter.is_active() {
+                    filter_cursor = sidebar::render_filter_line(frame, areas.sidebar, &filter.label(), editing);
+                }
+            }
+
+            // Split panes: the active terminal is drawn in the focused pane,
+            // every other pane shows its terminal without scrollback state
+            let main_area = controller.usecase().get_active_terminal()
+                .and_then(|t| pane_rects.rect_of(t.id()))
+                .unwrap_or(areas.main_pane);
+            if !ui.view.zoomed {
+                render_unfocused_panes(frame, controller, &pane_rects);
+            }
 
             // Terminal view - get active terminal info
             let main_in_scrollback = *scrollback_target == Some(ScrollbackTarget::MainTerminal);
+            let main_recording = controller.usecase().get_active_terminal()
+                .is_some_and(|t| controller.usecase().recording_path(t.id()).is_some());
             let (cells_opt, cursor_opt, cursor_visible, cwd_opt, scrollback_info) =
                 match controller.usecase().get_active_terminal() {
                     Some(t) => {
@@ -420,11 +695,11 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                     let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
                     let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
                     let visible_start = max_sb.saturating_sub(offset);
-                    let screen_rows = areas.main_pane.height.saturating_sub(1) as usize; // minus CWD bar in normal, but in scrollback mode with border it's different
+                    let screen_rows = main_area.height.saturating_sub(1) as usize; // minus CWD bar in normal, but in scrollback mode with border it's different
                     // In scrollback mode the content area is inner area minus CWD bar and status bar
-                    // Approximate: use areas.main_pane height minus borders (2) minus cwd (1) minus status (1) = -4
-                    let content_rows = if areas.main_pane.height >= 5 {
-                        (areas.main_pane.height - 4) as usize
+                    // Approximate: use main_area height minus borders (2) minus cwd (1) minus status (1) = -4
+                    let content_rows = if main_area.height >= 5 {
+                        (main_area.height - 4) as usize
                     } else {
                         screen_rows
                     };
@@ -462,7 +737,7 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
 
             // If search is active during scrollback, reserve 1 row at bottom for search bar
             let (terminal_area, search_bar_area) = if main_in_scrollback && search_state.is_some() {
-                let pane = areas.main_pane;
+                let pane = main_area;
                 if pane.height > 2 {
                     (
                         Rect::new(pane.x, pane.y, pane.width, pane.height - 1),
@@ -472,21 +747,21 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                     (pane, None)
                 }
             } else {
-                (areas.main_pane, None)
+                (main_area, None)
             };
 
             // Compute selection highlights for the main terminal
-            let (main_sel_hl, main_visual_label) = if main_in_scrollback && selection_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MainTerminal) {
-                if let Some(sel) = selection_state.as_ref() {
+            let (main_sel_hl, main_visual_label) = if main_in_scrollback && ui.selection_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MainTerminal) {
+                if let Some(sel) = ui.selection_state.as_ref() {
                     if let Some(t) = controller.usecase().get_active_terminal() {
                         let id = t.id();
                         let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
                         let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
                         let visible_start = max_sb.saturating_sub(offset);
-                        let content_rows = if areas.main_pane.height >= 5 {
-                            (areas.main_pane.height - 4) as usize
+                        let content_rows = if main_area.height >= 5 {
+                            (main_area.height - 4) as usize
                         } else {
-                            areas.main_pane.height.saturating_sub(1) as usize
+                            main_area.height.saturating_sub(1) as usize
                         };
                         let visible_end = visible_start + content_rows;
                         let num_cols = controller.usecase().screen_port().get_cells(id)
@@ -543,16 +818,16 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                     let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
                     let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
                     let visible_start = max_sb.saturating_sub(offset);
-                    let content_rows = if areas.main_pane.height >= 5 {
-                        (areas.main_pane.height - 4) as usize
+                    let content_rows = if main_area.height >= 5 {
+                        (main_area.height - 4) as usize
                     } else {
-                        areas.main_pane.height.saturating_sub(1) as usize
+                        main_area.height.saturating_sub(1) as usize
                     };
                     let visible_end = visible_start + content_rows;
-                    if scrollback_cursor.row >= visible_start && scrollback_cursor.row < visible_end {
+                    if ui.scrollback_cursor.row >= visible_start && ui.scrollback_cursor.row < visible_end {
                         Some(terminal_view::ScrollbackCursorHighlight {
-                            display_row: scrollback_cursor.row - visible_start,
-                            col: scrollback_cursor.col,
+                            display_row: ui.scrollback_cursor.row - visible_start,
+                            col: ui.scrollback_cursor.col,
                         })
                     } else {
                         None
@@ -575,7 +850,7 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 scrollback_info,
                 main_in_scrollback,
                 search_hl.as_ref(),
-                if main_in_scrollback { status_msg } else { None },
+                if main_in_scrollback { status_msg } else if main_recording { Some("REC") } else { None },
                 main_sel_hl.as_ref(),
                 main_visual_label,
                 main_scrollback_cursor_hl.as_ref(),
@@ -600,9 +875,9 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
             let mini_in_scrollback = *scrollback_target == Some(ScrollbackTarget::MiniTerminal);
             if let Some(mini_area) = areas.mini_terminal {
                 // Compute selection highlights for the mini terminal
-                let (mini_sel_hl, mini_visual_label) = if mini_in_scrollback && selection_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
-                    if let Some(sel) = selection_state.as_ref() {
-                        let mid = mini_terminal.terminal_id;
+                let (mini_sel_hl, mini_visual_label) = if mini_in_scrollback && ui.selection_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
+                    if let Some(sel) = ui.selection_state.as_ref() {
+                        let mid = ui.mini_terminal.terminal_id;
                         let max_sb = controller.usecase().screen_port().get_max_scrollback(mid).unwrap_or(0);
                         let offset = controller.usecase().screen_port().get_scrollback_offset(mid).unwrap_or(0);
                         let visible_start = max_sb.saturating_sub(offset);
@@ -651,8 +926,8 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                     (None, None)
                 };
 
-                if mini_terminal.spawned {
-                    let mid = mini_terminal.terminal_id;
+                if ui.mini_terminal.spawned {
+                    let mid = ui.mini_terminal.terminal_id;
                     let mini_cells = controller.usecase().screen_port().get_cells(mid).ok();
                     let mini_cursor = controller.usecase().screen_port().get_cursor(mid).ok();
                     let mini_cursor_visible = if mini_in_scrollback {
@@ -676,10 +951,10 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                         let visible_start = max_sb.saturating_sub(offset);
                         let content_rows = (MINI_TERMINAL_HEIGHT as usize).saturating_sub(4);
                         let visible_end = visible_start + content_rows;
-                        if scrollback_cursor.row >= visible_start && scrollback_cursor.row < visible_end {
+                        if ui.scrollback_cursor.row >= visible_start && ui.scrollback_cursor.row < visible_end {
                             Some(terminal_view::ScrollbackCursorHighlight {
-                                display_row: scrollback_cursor.row - visible_start,
-                                col: scrollback_cursor.col,
+                                display_row: ui.scrollback_cursor.row - visible_start,
+                                col: ui.scrollback_cursor.col,
                             })
                         } else {
                             None
@@ -722,18 +997,27 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
 
             // Dialog overlay
             match dialog {
-                DialogState::CreateTerminal { input, cursor_pos } => {
-                    dialog::render_create_dialog(frame, input, *cursor_pos);
+                DialogState::CreateTerminal { input, cursor_pos, group, on_group } => {
+                    dialog::render_create_dialog(frame, input, group, *cursor_pos, *on_group);
                 }
                 DialogState::ConfirmClose {
                     terminal_name,
+                    process,
                     is_running,
                 } => {
-                    dialog::render_confirm_close_dialog(frame, terminal_name, *is_running);
+                    dialog::render_confirm_close_dialog(frame, terminal_name, process.as_deref(), *is_running);
+                }
+                DialogState::SignalMenu { terminal_name, process } => {
+                    dialog::render_signal_dialog(frame, terminal_name, process.as_deref());
                 }
+                DialogState::SortMenu => dialog::render_sort_dialog(frame),
+                DialogState::FilterEdit => {} // Drawn in the sidebar
                 DialogState::Rename { input, cursor_pos } => {
                     dialog::render_rename_dialog(frame, input, *cursor_pos);
                 }
+                DialogState::GroupEdit { input, cursor_pos } => {
+                    dialog::render_group_dialog(frame, input, *cursor_pos);
+                }
                 DialogState::MemoEdit { text, cursor_row, cursor_col } => {
                     memo_overlay::render_memo_overlay(
                         frame,
@@ -756,30 +1040,26 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                             .and_then(|c| c.as_deref())
                             .unwrap_or(&t.cwd().display().to_string())
                             .to_string();
-                        let display = format!("{}: {}  {}", t.id().value(), t.name(), cwd);
-                        let memo = t.memo();
-                        let search = if memo.is_empty() {
-                            display.clone()
-                        } else {
-                            format!("{} {}", display, memo)
-                        };
+                        let (display, search) = quick_switch_texts(t, &cwd);
                         (idx, display, search)
                     }).collect();
 
                     // Filter using search text (includes memo), sort by score
+                    let (group, rest) = split_group_query(query);
                     let search_items: Vec<(usize, String)> = items.iter()
+                        .filter(|(idx, _, _)| group.is_none_or(|g| in_group_prefix(&terminals[*idx], g)))
                         .map(|(idx, _, search)| (*idx, search.clone()))
                         .collect();
-                    let filtered = fuzzy_matcher::filter_and_sort(query, &search_items);
+                    let filtered = fuzzy_matcher::filter_and_sort(&rest, &search_items);
 
                     // Build QuickSwitchItems with positions re-matched against display text
                     let display_items: Vec<QuickSwitchItem> = filtered.iter().map(|(idx, _fm)| {
                         let (_, display, _) = &items[*idx];
                         // Re-match against display text so positions align with what's rendered
-                        let positions = if query.is_empty() {
+                        let positions = if rest.is_empty() {
                             Vec::new()
                         } else {
-                            fuzzy_matcher::fuzzy_match(query, display)
+                            fuzzy_matcher::fuzzy_match(&rest, display)
                                 .map(|m| m.positions)
                                 .unwrap_or_default()
                         };
@@ -795,21 +1075,24 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 }
                 DialogState::None => {}
             }
+            if let Some(position) = filter_cursor {
+                frame.set_cursor_position(position);
+            }
         })?;
 
         // 1.1. Clear expired yank flash
         if status_msg.is_none() {
-            yank_flash_until = None;
+            ui.yank_flash_until = None;
         }
 
         // 1.5. Apply cursor style from the focused terminal
         if !matches!(dialog, DialogState::None) {
             // Dialogs use their own cursor; no style change needed
         } else {
-            let style_opt = if *focus == FocusPane::MiniTerminal && mini_terminal.spawned {
+            let style_opt = if *focus == FocusPane::MiniTerminal && ui.mini_terminal.spawned {
                 Some(
                     controller.usecase().screen_port()
-                        .get_cursor_style(mini_terminal.terminal_id)
+                        .get_cursor_style(ui.mini_terminal.terminal_id)
                         .unwrap_or(CursorStyle::DefaultUserShape)
                 )
             } else {
@@ -836,11 +1119,10 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
             }
         }
 
-        // 2. Calculate terminal size from right pane
-        let frame_size = terminal.size()?;
-        let areas = layout::compute_layout(frame_size.into(), mini_terminal.is_visible());
-        let content_height = areas.main_pane.height.saturating_sub(1); // minus CWD bar
-        let size = TerminalSize::new(areas.main_pane.width, content_height);
+        // 2. Calculate terminal size from the focused pane
+        let size = active_id
+            .and_then(|id| pane_rects.rect_of(id))
+            .map_or_else(|| pane_content_size(split_area), pane_content_size);
 
         // 3. Poll all ptys
         if let Err(e) = controller.dispatch(AppAction::PollAll, size)
@@ -855,8 +1137,8 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
         }
 
         // 3.1. Poll mini terminal (outside TerminalUsecase management)
-        if mini_terminal.spawned {
-            let mid = mini_terminal.terminal_id;
+        if ui.mini_terminal.spawned {
+            let mid = ui.mini_terminal.terminal_id;
             match controller.usecase_mut().pty_port_mut().read(mid) {
                 Ok(data) if !data.is_empty() => {
                     let _ = controller.usecase_mut().screen_port_mut().process(mid, &data);
@@ -870,8 +1152,8 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 Ok(_) => {}
                 Err(_) => {
                     // Mini terminal process exited - hide it
-                    mini_terminal.visible = false;
-                    mini_terminal.spawned = false;
+                    ui.mini_terminal.visible = false;
+                    ui.mini_terminal.spawned = false;
                     let _ = controller.usecase_mut().screen_port_mut().remove(mid);
                     // Clear search state if searching mini terminal
                     if search_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
@@ -879,7 +1161,7 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                     }
                     // Clear selection state if selecting in mini terminal
                     if *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
-                        selection_state = None;
+                        ui.selection_state = None;
                     }
                     // If we were scrolling the mini terminal, exit scrollback
                     if *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
@@ -892,11 +1174,11 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 }
             }
             // Check for exit
-            if mini_terminal.spawned
+            if ui.mini_terminal.spawned
                 && let Ok(Some(_code)) = controller.usecase_mut().pty_port_mut().try_wait(mid)
             {
-                mini_terminal.visible = false;
-                mini_terminal.spawned = false;
+                ui.mini_terminal.visible = false;
+                ui.mini_terminal.spawned = false;
                 let _ = controller.usecase_mut().screen_port_mut().remove(mid);
                 // Clear search state if searching mini terminal
                 if search_state.is_some() && *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
@@ -904,7 +1186,7 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
                 }
                 // Clear selection state if selecting in mini terminal
                 if *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
-                    selection_state = None;
+                    ui.selection_state = None;
                 }
                 // If we were scrolling the mini terminal, exit scrollback
                 if *scrollback_target == Some(ScrollbackTarget::MiniTerminal) {
@@ -927,22 +1209,37 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
         if let Some(ipc) = ipc_port.as_mut() {
             let commands = ipc.poll_commands();
             for (conn_id, command) in commands {
-                // Handle notify command: send desktop notification via notifier
-                if let IpcCommand::Notify { title, body } = &command {
-                    let event = NotificationEvent::External {
-                        title: title.clone().unwrap_or_else(|| "CLI Manager".to_string()),
-                        body: body.clone(),
-                    };
-                    notifier.notify("external", &event);
+                let command = match resolve_runner_target(command, controller.usecase()) {
+                    Ok(command) => command,
+                    Err(e) => {
+                        ipc.send_response(conn_id, IpcResponse::Error(e));
+                        continue;
+                    }
+                };
+                // A notify without a terminal goes straight to the desktop;
+                // one for a terminal is queued by handle_ipc_command
+                if let IpcCommand::Notify { target: None, title, body } = &command {
+                    notifier.notify("external", &notify_event(title, body));
+                }
+                if let IpcCommand::Subscribe { output } = &command {
+                    event_stream.subscribe(ipc.as_mut(), conn_id, output, controller.usecase_mut());
+                    continue;
+                }
+                if let IpcCommand::WaitFor { target, condition, timeout_ms } = &command {
+                    let request = WaitRequest { target: *target, condition, timeout_ms: *timeout_ms };
+                    waits.add(ipc.as_mut(), conn_id, request, controller.usecase_mut(), Instant::now());
+                    continue;
                 }
                 let response = handle_ipc_command(
                     &command,
                     controller,
-                    &mut yank_buffer,
+                    &mut ui.yank_buffer,
                     size,
                 );
                 ipc.send_response(conn_id, response);
             }
+            event_stream.publish(ipc.as_mut(), controller.usecase_mut(), &pending);
+            waits.poll(ipc.as_mut(), controller.usecase_mut(), Instant::now());
         }
 
         // 4. Check prefix timeout
@@ -962,38 +1259,33 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
             let ev = event::read()?;
             match ev {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    handle_key_event(key, controller, input_handler, should_quit, dialog, focus, size, scrollback_target, &mut mini_terminal, search_state, &mut yank_buffer, &mut yank_flash_until, &mut selection_state, &mut scrollback_cursor)?;
+                    handle_key_event(key, controller, input_handler, should_quit, dialog, focus, size, scrollback_target, search_state, &mut ui, split_area)?;
+                }
+                Event::Mouse(mouse) => {
+                    handle_mouse_event(mouse, controller, input_handler, dialog, focus, size, scrollback_target, search_state, &mut ui, &areas, &pane_rects, *sidebar_scroll_offset)?;
                 }
                 Event::Resize(cols, rows) => {
                     let new_full = Rect::new(0, 0, cols, rows);
-                    let new_areas = layout::compute_layout(new_full, mini_terminal.is_visible());
-                    let new_content_height = new_areas.main_pane.height.saturating_sub(1);
-                    let pane_size =
-                        TerminalSize::new(new_areas.main_pane.width, new_content_height);
-                    controller.dispatch(AppAction::ResizeAll(pane_size), pane_size)?;
-                    // Resize mini terminal if spawned
-                    if mini_terminal.spawned
-                        && let Some(mini_area) = new_areas.mini_terminal
-                    {
-                        let mini_size = TerminalSize::new(
-                            mini_area.width.saturating_sub(2),
-                            mini_area.height.saturating_sub(2),
-                        );
-                        let mid = mini_terminal.terminal_id;
-                        let _ = controller.usecase_mut().pty_port_mut().resize(mid, mini_size);
-                        let _ = controller.usecase_mut().screen_port_mut().resize(mid, mini_size);
-                    }
+                    let new_areas = layout::compute_layout_with(new_full, ui.mini_terminal.is_visible(), &ui.view.unzoomed());
+                    let zoom_area = Rect::new(0, 0, cols, rows);
+                    let new_rects = frame_pane_rects(
+                        &ui.panes,
+                        new_areas.main_pane,
+                        active_id.filter(|_| ui.view.zoomed).map(|id| (id, zoom_area)),
+                    );
+                    resize_panes(controller, &new_rects, new_areas.main_pane, &mut applied_sizes);
+                    resize_mini_terminal(controller, &mut ui.mini_terminal, new_areas.mini_terminal.filter(|_| !ui.view.zoomed));
                     // Re-execute search after resize (row positions may have changed)
                     if let Some(state) = search_state.as_mut() {
                         if !state.query.is_empty() {
-                            execute_search(controller, scrollback_target, &mini_terminal, state);
+                            execute_search(controller, scrollback_target, &ui.mini_terminal, state);
                         }
                     }
                 }
                 Event::Paste(text) => {
-                    if *focus == FocusPane::MiniTerminal && mini_terminal.spawned {
+                    if *focus == FocusPane::MiniTerminal && ui.mini_terminal.spawned {
                         // Send paste to mini terminal
-                        let mid = mini_terminal.terminal_id;
+                        let mid = ui.mini_terminal.terminal_id;
                         let bracketed = controller.usecase().screen_port()
                             .get_bracketed_paste(mid)
                             .unwrap_or(false);
@@ -1034,9 +1326,16 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
         }
     }
 
+    // Snapshot the session for `cm --restore` (best-effort)
+    if let Some(path) = &options.snapshot_path {
+        let mut spec = controller.usecase_mut().snapshot(session_file::SNAPSHOT_SCROLLBACK_LINES);
+        spec.mini_terminal_visible = ui.mini_terminal.is_visible();
+        let _ = session_file::save_session(path, &spec);
+    }
+
     // Cleanup mini terminal PTY/Screen on exit
-    if mini_terminal.spawned {
-        let mid = mini_terminal.terminal_id;
+    if ui.mini_terminal.spawned {
+        let mid = ui.mini_terminal.terminal_id;
         let _ = controller.usecase_mut().pty_port_mut().kill(mid);
         let _ = controller.usecase_mut().screen_port_mut().remove(mid);
     }
@@ -1049,25 +1348,83 @@ fn main_loop<P: PtyPort, S: ScreenPort>(
     Ok(())
 }
 
+/// Resolve the target of a command the runner handles itself (`wait-for`),
+/// which must select a single terminal. Other commands are returned as they
+/// are and resolved by `handle_ipc_command`.
+pub(crate) fn resolve_runner_target<P: PtyPort, S: ScreenPort>(
+    command: IpcCommand,
+    usecase: &TerminalUsecase<P, S>,
+) -> Result<IpcCommand, String> {
+    match command {
+        IpcCommand::Resolve { target, caller, command } if matches!(*command, IpcCommand::WaitFor { .. }) => {
+            let ids = usecase
+                .resolve_target(&target, caller.map(TerminalId::new))
+                .map_err(|e| e.to_string())?;
+            match ids.as_slice() {
+                [id] => Ok(command.with_target(id.value())),
+                _ => Err(format!("{target} matches {} terminals; wait-for needs one", ids.len())),
+            }
+        }
+        command => Ok(command),
+    }
+}
+
+/// The notification an IPC `notify` raises.
+pub(crate) fn notify_event(title: &Option<String>, body: &str) -> NotificationEvent {
+    NotificationEvent::External {
+        title: title.clone().unwrap_or_else(|| "CLI Manager".to_string()),
+        body: body.to_string(),
+    }
+}
+
 /// Handle a single IPC command and return the response.
-fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
+///
+/// Shared with `cm server`, which handles the session-level commands
+/// (`attach`, `kill-server`) itself before delegating here.
+pub(crate) fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
     command: &IpcCommand,
     controller: &mut TuiController<P, S>,
     yank_buffer: &mut Option<String>,
     content_size: TerminalSize,
 ) -> IpcResponse {
     match command {
-        IpcCommand::SendKeys { target, keys } => {
-            // Parse keys to bytes
-            let data = match crate::infrastructure::ipc::key_parser::parse_keys(keys) {
-                Ok(bytes) => bytes,
-                Err(e) => return IpcResponse::Error(format!("key parse error: {}", e)),
+        IpcCommand::Resolve { target, caller, command } => {
+            let ids = match controller.usecase().resolve_target(target, caller.map(TerminalId::new)) {
+                Ok(ids) => ids,
+                Err(e) => return IpcResponse::Error(e.to_string()),
             };
+            let mut run = |id: TerminalId| {
+                let command = command.as_ref().clone().with_target(id.value());
+                handle_ipc_command(&command, controller, yank_buffer, content_size)
+            };
+            // A glob or group answers for every terminal it matched, even just one
+            if target.fans_out() {
+                let results = ids.into_iter().map(|id| (id.value(), run(id))).collect();
+                IpcResponse::OkWithData(IpcResponseData::Targets { results })
+            } else {
+                run(ids[0])
+            }
+        }
+        IpcCommand::SendKeys { target, keys, literal } => {
             let tid = TerminalId::new(*target);
             // Check terminal exists
             if controller.usecase().get_terminal_by_id(tid).is_none() {
                 return IpcResponse::Error(format!("terminal not found: {}", target));
             }
+            // Parse keys to bytes; cursor keys follow the target's DECCKM state
+            let data = if *literal {
+                keys.concat().into_bytes()
+            } else {
+                let application_cursor_keys = controller
+                    .usecase()
+                    .screen_port()
+                    .get_application_cursor_keys(tid)
+                    .unwrap_or(false);
+                match crate::infrastructure::ipc::key_parser::parse_keys(keys, application_cursor_keys) {
+                    Ok(bytes) => bytes,
+                    Err(e) => return IpcResponse::Error(format!("key parse error: {}", e)),
+                }
+            };
             // Write to PTY (empty data is OK, just return Ok)
             if data.is_empty() {
                 return IpcResponse::Ok;
@@ -1077,7 +1434,7 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
                 Err(e) => IpcResponse::Error(format!("write error: {}", e)),
             }
         }
-        IpcCommand::CapturePane { target, include_scrollback } => {
+        IpcCommand::CapturePane { target, options } => {
             let tid = TerminalId::new(*target);
             // Check terminal exists
             let (name, cwd_fallback) = match controller.usecase().get_terminal_by_id(tid) {
@@ -1085,40 +1442,9 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
                 None => return IpcResponse::Error(format!("terminal not found: {}", target)),
             };
 
-            // Get text content
-            let text = if *include_scrollback {
-                // Get scrollback + visible content using get_row_cells
-                let max_sb = controller.usecase().screen_port().get_max_scrollback(tid).unwrap_or(0);
-                let visible_rows = controller.usecase().screen_port().get_cells(tid)
-                    .map(|c| c.len()).unwrap_or(0);
-                let total_rows = max_sb + visible_rows;
-
-                let mut lines: Vec<String> = Vec::new();
-                for abs_row in 0..total_rows {
-                    let row_cells = controller.usecase_mut().screen_port_mut().get_row_cells(tid, abs_row);
-                    match row_cells {
-                        Ok(row) => {
-                            let mut line = String::new();
-                            for cell in &row {
-                                if cell.width == 0 { continue; }
-                                line.push(cell.ch);
-                            }
-                            lines.push(line.trim_end().to_string());
-                        }
-                        Err(_) => break,
-                    }
-                }
-                // Remove trailing empty lines
-                while lines.last().is_some_and(|l| l.is_empty()) {
-                    lines.pop();
-                }
-                lines.join("\n")
-            } else {
-                // Get only visible content
-                match controller.usecase().screen_port().get_cells(tid) {
-                    Ok(cells) => extract_text_from_cells(cells, 0, cells.len(), None, None),
-                    Err(_) => String::new(),
-                }
+            let captured = match capture::capture(controller.usecase_mut().screen_port_mut(), tid, options) {
+                Ok(captured) => captured,
+                Err(e) => return IpcResponse::Error(format!("capture error: {}", e)),
             };
 
             // Get metadata
@@ -1135,7 +1461,11 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
             let scrollback_total = controller.usecase().screen_port().get_max_scrollback(tid).unwrap_or(0);
 
             IpcResponse::OkWithData(IpcResponseData::CapturePane {
-                text,
+                text: captured.text,
+                cells: captured.cells,
+                start: captured.start,
+                end: captured.end,
+                next_start: captured.next_start,
                 cursor_row: cursor.row as usize,
                 cursor_col: cursor.col as usize,
                 size_rows,
@@ -1146,6 +1476,7 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
             })
         }
         IpcCommand::ListWindows => {
+            let now = Instant::now();
             let terminals = controller.usecase().get_terminals();
             let active_id = controller.usecase().get_active_terminal().map(|t| t.id());
             let windows: Vec<WindowInfo> = terminals.iter().map(|t| {
@@ -1159,6 +1490,11 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
                     cwd: Some(cwd),
                     is_active: active_id == Some(t.id()),
                     is_running: t.status().is_running(),
+                    is_idle: *t.status() == TerminalStatus::Idle,
+                    process: t.foreground().cloned(),
+                    quiet_secs: t.quiet_for(now).as_secs(),
+                    group: t.group().map(str::to_string),
+                    marked: t.is_marked(),
                 }
             }).collect();
             IpcResponse::OkWithData(IpcResponseData::ListWindows { windows })
@@ -1198,8 +1534,18 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
                 text: yank_buffer.clone(),
             })
         }
-        IpcCommand::CreateWindow { name, command: _ } => {
-            match controller.usecase_mut().create_terminal(name.clone(), content_size) {
+        IpcCommand::CreateWindow { name, command, cwd, env, restart, group } => {
+            let options = SpawnOptions {
+                command: command.clone(),
+                cwd: cwd.as_ref().map(std::path::PathBuf::from),
+                env: env.clone(),
+                restart: *restart,
+            };
+            let usecase = controller.usecase_mut();
+            let created = usecase
+                .create_terminal_with_options(name.clone(), &options, content_size)
+                .and_then(|id| usecase.set_group(id, group.clone()).map(|()| id));
+            match created {
                 Ok(id) => IpcResponse::OkWithData(IpcResponseData::CreateWindow { id: id.value() }),
                 Err(e) => IpcResponse::Error(format!("{}", e)),
             }
@@ -1225,10 +1571,173 @@ fn handle_ipc_command<P: PtyPort, S: ScreenPort>(
                 Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
             }
         }
-        IpcCommand::Notify { .. } => {
+        IpcCommand::MoveWindow { target, to } => {
+            let tid = TerminalId::new(*target);
+            let result = match to {
+                WindowMove::By(delta) => controller.usecase_mut().move_by(tid, *delta),
+                WindowMove::To(index) => controller.usecase_mut().move_to(tid, *index),
+                WindowMove::Swap { with, caller } => {
+                    let other = match controller.usecase().resolve_target(with, caller.map(TerminalId::new)) {
+                        Ok(ids) if ids.len() == 1 => ids[0],
+                        Ok(ids) => {
+                            return IpcResponse::Error(format!("{with} matches {} terminals; swap needs one", ids.len()));
+                        }
+                        Err(e) => return IpcResponse::Error(e.to_string()),
+                    };
+                    controller.usecase_mut().swap(tid, other)
+                }
+            };
+            match result {
+                Ok(()) => IpcResponse::Ok,
+                Err(crate::shared::error::AppError::TerminalNotFound(id)) => {
+                    IpcResponse::Error(format!("terminal not found: {}", id.value()))
+                }
+                Err(crate::shared::error::AppError::DifferentGroups(a, b)) => {
+                    IpcResponse::Error(format!("terminals {} and {} are in different groups", a.value(), b.value()))
+                }
+                Err(e) => IpcResponse::Error(e.to_string()),
+            }
+        }
+        IpcCommand::SetGroup { target, group } => {
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().set_group(tid, group.clone()) {
+                Ok(()) => IpcResponse::Ok,
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::ToggleGroup { group, collapsed } => {
+            match controller.usecase_mut().set_group_collapsed(group, *collapsed) {
+                Ok(_) => IpcResponse::Ok,
+                Err(_e) => IpcResponse::Error(format!("group not found: {}", group)),
+            }
+        }
+        IpcCommand::MarkWindow { target, marked } => {
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().set_marked(tid, *marked) {
+                Ok(_) => IpcResponse::Ok,
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::SyncInput { enabled } => {
+            controller.usecase_mut().set_sync_input(*enabled);
+            IpcResponse::Ok
+        }
+        IpcCommand::SortWindows { key } => {
+            controller.usecase_mut().sort_terminals(*key);
+            IpcResponse::Ok
+        }
+        IpcCommand::FilterWindows { filter } => {
+            controller.usecase_mut().set_filter(filter.clone());
+            IpcResponse::Ok
+        }
+        IpcCommand::RespawnWindow { target, kill } => {
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().respawn_by_id(tid, *kill) {
+                Ok(()) => IpcResponse::Ok,
+                Err(crate::shared::error::AppError::TerminalNotFound(_)) => IpcResponse::Error(format!("terminal not found: {}", target)),
+                Err(crate::shared::error::AppError::StillRunning(_)) => IpcResponse::Error(format!(
+                    "terminal {} is still running; pass kill to restart it",
+                    target
+                )),
+                Err(e) => IpcResponse::Error(format!("{}", e)),
+            }
+        }
+        IpcCommand::SendSignal { target, signal } => {
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().signal_by_id(tid, *signal) {
+                Ok(()) => IpcResponse::Ok,
+                Err(crate::shared::error::AppError::TerminalNotFound(_)) => IpcResponse::Error(format!("terminal not found: {}", target)),
+                Err(crate::shared::error::AppError::NotRunning(_)) => IpcResponse::Error(format!("terminal {} has exited", target)),
+                Err(e) => IpcResponse::Error(format!("{}", e)),
+            }
+        }
+        IpcCommand::SetStatus { target, status } => {
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().set_badge_by_id(tid, status.clone()) {
+                Ok(()) => IpcResponse::Ok,
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::Notify { target: Some(target), title, body } => {
+            // Delivered with the terminal's own notifications on the next poll
+            let tid = TerminalId::new(*target);
+            match controller.usecase_mut().notify_terminal(tid, notify_event(title, body)) {
+                Ok(()) => IpcResponse::Ok,
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::Notify { target: None, .. } => {
             // Notification sending is handled at the call site in main_loop
             IpcResponse::Ok
         }
+        IpcCommand::Input { target, data } => {
+            let tid = TerminalId::new(*target);
+            if controller.usecase().get_terminal_by_id(tid).is_none() {
+                return IpcResponse::Error(format!("terminal not found: {}", target));
+            }
+            match controller.usecase_mut().write_input(tid, data.as_bytes()) {
+                Ok(()) => IpcResponse::Ok,
+                Err(e) => IpcResponse::Error(format!("write error: {}", e)),
+            }
+        }
+        IpcCommand::Attach { .. } | IpcCommand::KillServer => {
+            IpcResponse::Error("only supported by `cm server`".to_string())
+        }
+        IpcCommand::Subscribe { .. } => {
+            // Needs the connection; handled by the runner's `EventStream`
+            IpcResponse::Error("subscribe is not supported here".to_string())
+        }
+        IpcCommand::WaitFor { .. } => {
+            // Replied to later; handled by the runner's `WaitQueue`
+            IpcResponse::Error("wait-for is not supported here".to_string())
+        }
+        IpcCommand::LogStart { target, mode, max_bytes } => {
+            let options = LogOptions {
+                mode: *mode,
+                max_bytes: max_bytes.unwrap_or(DEFAULT_LOG_MAX_BYTES),
+            };
+            match controller.usecase_mut().start_logging(TerminalId::new(*target), &options) {
+                Ok(status) => IpcResponse::OkWithData(IpcResponseData::Log { status: Some(status) }),
+                Err(crate::shared::error::AppError::TerminalNotFound(_)) => {
+                    IpcResponse::Error(format!("terminal not found: {}", target))
+                }
+                Err(e) => IpcResponse::Error(format!("{}", e)),
+            }
+        }
+        IpcCommand::LogStop { target } => {
+            match controller.usecase_mut().stop_logging(TerminalId::new(*target)) {
+                Ok(true) => IpcResponse::Ok,
+                Ok(false) => IpcResponse::Error(format!("terminal {} is not being logged", target)),
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::LogStatus { target } => {
+            match controller.usecase().logging_status(TerminalId::new(*target)) {
+                Ok(status) => IpcResponse::OkWithData(IpcResponseData::Log { status }),
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
+        IpcCommand::RecordStart { target, path } => {
+            let path = path.as_deref().map(std::path::Path::new);
+            match controller.usecase_mut().start_recording(TerminalId::new(*target), path) {
+                Ok(path) => IpcResponse::OkWithData(IpcResponseData::Recording {
+                    path: path.display().to_string(),
+                }),
+                Err(crate::shared::error::AppError::TerminalNotFound(_)) => {
+                    IpcResponse::Error(format!("terminal not found: {}", target))
+                }
+                Err(e) => IpcResponse::Error(format!("{}", e)),
+            }
+        }
+        IpcCommand::RecordStop { target } => {
+            match controller.usecase_mut().stop_recording(TerminalId::new(*target)) {
+                Ok(Some(path)) => IpcResponse::OkWithData(IpcResponseData::Recording {
+                    path: path.display().to_string(),
+                }),
+                Ok(None) => IpcResponse::Error(format!("terminal {} is not being recorded", target)),
+                Err(_e) => IpcResponse::Error(format!("terminal not found: {}", target)),
+            }
+        }
     }
 }
 
@@ -1241,13 +1750,11 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
     focus: &mut FocusPane,
     size: TerminalSize,
     scrollback_target: &mut Option<ScrollbackTarget>,
-    mini_terminal: &mut MiniTerminalState,
     search_state: &mut Option<SearchState>,
-    yank_buffer: &mut Option<String>,
-    yank_flash_until: &mut Option<std::time::Instant>,
-    selection_state: &mut Option<SelectionState>,
-    scrollback_cursor: &mut ScrollbackCursor,
+    ui: &mut UiState,
+    main_area: Rect,
 ) -> anyhow::Result<()> {
+    let UiState { mini_terminal, yank_buffer, yank_flash_until, selection_state, scrollback_cursor, panes, view, .. } = ui;
     // If in ScrollbackSearch mode, handle search bar input directly
     if matches!(input_handler.mode(), InputMode::ScrollbackSearch) {
         handle_search_key(key, controller, input_handler, scrollback_target, mini_terminal, search_state)?;
@@ -1296,6 +1803,16 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
             .unwrap_or(false)
     };
     input_handler.set_application_cursor_keys(app_cursor);
+    let keyboard_flags = if *focus == FocusPane::MiniTerminal && mini_terminal.spawned {
+        controller.usecase().screen_port()
+            .get_keyboard_flags(mini_terminal.terminal_id)
+            .unwrap_or(0)
+    } else {
+        controller.usecase().get_active_terminal()
+            .and_then(|t| controller.usecase().screen_port().get_keyboard_flags(t.id()).ok())
+            .unwrap_or(0)
+    };
+    input_handler.set_keyboard_flags(keyboard_flags);
 
     // Normal/PrefixWait/ScrollbackMode
     let Some(action) = input_handler.handle_key(key) else {
@@ -1305,9 +1822,16 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
     match action {
         AppAction::CreateTerminal { .. } => {
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            // New terminals join the active terminal's group by default
+            let group = controller.usecase().get_active_terminal()
+                .and_then(|t| t.group())
+                .unwrap_or_default()
+                .to_string();
             *dialog = DialogState::CreateTerminal {
                 input: String::new(),
                 cursor_pos: 0,
+                group,
+                on_group: false,
             };
             input_handler.set_mode(InputMode::DialogInput);
         }
@@ -1318,6 +1842,7 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
                 if terminal.status().is_running() {
                     *dialog = DialogState::ConfirmClose {
                         terminal_name: terminal.name().to_string(),
+                        process: process_label(terminal),
                         is_running: true,
                     };
                     input_handler.set_mode(InputMode::DialogInput);
@@ -1334,10 +1859,36 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
             *focus = match *focus {
                 FocusPane::Sidebar => FocusPane::Terminal,
-                FocusPane::Terminal => FocusPane::Sidebar,
+                FocusPane::Terminal if view.shows_sidebar() => FocusPane::Sidebar,
+                FocusPane::Terminal => FocusPane::Terminal,
                 FocusPane::MiniTerminal => FocusPane::MiniTerminal,
             };
         }
+        AppAction::ToggleSidebar => {
+            if view.zoomed {
+                view.zoomed = false;
+                view.sidebar_visible = true;
+            } else {
+                view.sidebar_visible = !view.sidebar_visible;
+            }
+            if !view.shows_sidebar() && *focus == FocusPane::Sidebar {
+                *focus = FocusPane::Terminal;
+            }
+        }
+        AppAction::WidenSidebar | AppAction::NarrowSidebar => {
+            let step = i32::from(layout::SIDEBAR_WIDTH_STEP);
+            view.resize_sidebar(if matches!(action, AppAction::WidenSidebar) { step } else { -step });
+            view.sidebar_visible = true;
+            view.zoomed = false;
+        }
+        AppAction::ToggleZoom => {
+            view.zoomed = !view.zoomed;
+            if view.zoomed && *focus != FocusPane::Terminal {
+                exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+                *focus = FocusPane::Terminal;
+                input_handler.set_mode(InputMode::Normal);
+            }
+        }
         AppAction::SelectNext | AppAction::SelectPrev | AppAction::SelectByIndex(_) => {
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
             if *focus == FocusPane::MiniTerminal {
@@ -1354,37 +1905,13 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
             }
         }
         AppAction::EnterScrollback => {
-            if *focus == FocusPane::MiniTerminal && mini_terminal.spawned {
-                // Enter scrollback for mini terminal
-                let mid = mini_terminal.terminal_id;
-                let is_alt = controller.usecase().screen_port().is_alternate_screen(mid).unwrap_or(false);
-                if !is_alt {
-                    *scrollback_target = Some(ScrollbackTarget::MiniTerminal);
-                    input_handler.set_mode(InputMode::ScrollbackMode);
-                    // Initialize cursor at top of visible area
-                    let offset = controller.usecase().screen_port().get_scrollback_offset(mid).unwrap_or(0);
-                    let max_sb = controller.usecase().screen_port().get_max_scrollback(mid).unwrap_or(0);
-                    let visible_start = max_sb.saturating_sub(offset);
-                    scrollback_cursor.row = visible_start;
-                    scrollback_cursor.col = 0;
-                }
+            // Scrollback is unavailable in alternate screen (e.g. vim, full-screen TUI)
+            let target = if *focus == FocusPane::MiniTerminal && mini_terminal.spawned {
+                ScrollbackTarget::MiniTerminal
             } else {
-                // Enter scrollback unless in alternate screen (e.g. vim, full-screen TUI)
-                if let Some(t) = controller.usecase().get_active_terminal() {
-                    let id = t.id();
-                    let is_alt = controller.usecase().screen_port().is_alternate_screen(id).unwrap_or(false);
-                    if !is_alt {
-                        *scrollback_target = Some(ScrollbackTarget::MainTerminal);
-                        input_handler.set_mode(InputMode::ScrollbackMode);
-                        // Initialize cursor at top of visible area
-                        let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
-                        let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
-                        let visible_start = max_sb.saturating_sub(offset);
-                        scrollback_cursor.row = visible_start;
-                        scrollback_cursor.col = 0;
-                    }
-                }
-            }
+                ScrollbackTarget::MainTerminal
+            };
+            enter_scrollback(controller, input_handler, scrollback_target, mini_terminal, scrollback_cursor, target);
         }
         AppAction::ExitScrollback => {
             if search_state.is_some() {
@@ -1657,6 +2184,18 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
                 input_handler.set_mode(InputMode::DialogInput);
             }
         }
+        AppAction::SetGroup { .. } => {
+            exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            if let Some(terminal) = controller.usecase().get_active_terminal() {
+                let current_group = terminal.group().unwrap_or_default().to_string();
+                let cursor_pos = current_group.chars().count();
+                *dialog = DialogState::GroupEdit {
+                    input: current_group,
+                    cursor_pos,
+                };
+                input_handler.set_mode(InputMode::DialogInput);
+            }
+        }
         AppAction::OpenMemo => {
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
             if let Ok(memo) = controller.usecase().get_active_memo() {
@@ -1679,24 +2218,11 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
         }
         AppAction::ToggleMiniTerminal => {
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            // The mini terminal is not shown while zoomed
+            view.zoomed = false;
             if !mini_terminal.spawned {
                 // First time: spawn PTY + Screen
-                let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
-                let cwd = controller.usecase().get_active_terminal()
-                    .and_then(|t| {
-                        controller.usecase().screen_port()
-                            .get_cwd(t.id()).ok().flatten()
-                            .map(|s| std::path::PathBuf::from(s))
-                    })
-                    .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("/")));
-                let mini_size = TerminalSize {
-                    rows: MINI_TERMINAL_HEIGHT - 2, // minus borders
-                    cols: size.cols,
-                };
-                let mid = mini_terminal.terminal_id;
-                controller.usecase_mut().pty_port_mut().spawn(mid, &shell, &cwd, mini_size)?;
-                controller.usecase_mut().screen_port_mut().create(mid, mini_size)?;
-                mini_terminal.spawned = true;
+                spawn_mini_terminal(controller, mini_terminal, main_area.width.saturating_sub(2))?;
             }
             if !mini_terminal.is_visible() {
                 // Not visible: open + focus
@@ -1781,17 +2307,74 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
             let visible_cols = (size.cols as usize).saturating_sub(2);
             scrollback_cursor.col = visible_cols.saturating_sub(1);
         }
-        AppAction::OpenQuickSwitcher => {
+        AppAction::SplitPane(direction) => {
             exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
-            *dialog = DialogState::QuickSwitch {
-                query: String::new(),
-                cursor_pos: 0,
-                selected_index: 0,
-            };
-            input_handler.set_mode(InputMode::DialogInput);
+            view.zoomed = false;
+            if *focus == FocusPane::MiniTerminal {
+                *focus = FocusPane::Terminal;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            // The new terminal starts at the current pane size and is shrunk
+            // to its half by `resize_panes` on the next frame
+            let current = controller.usecase().get_active_terminal().map(|t| t.id());
+            let new_id = controller.usecase_mut().create_terminal(None, size)?;
+            if let Some(current) = current {
+                panes.split(current, new_id, direction);
+            }
         }
-        other => {
-            match controller.dispatch(other, size) {
+        AppAction::SelectPane(direction) => {
+            if let Some(id) = panes.neighbor(main_area, direction) {
+                exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+                view.zoomed = false;
+                if *focus != FocusPane::Terminal {
+                    *focus = FocusPane::Terminal;
+                    input_handler.set_mode(InputMode::Normal);
+                }
+                controller.usecase_mut().select_by_id(id)?;
+            }
+        }
+        AppAction::ToggleRecording => {
+            // Best-effort: an unwritable recording directory must not end the session
+            let _ = controller.dispatch(AppAction::ToggleRecording, size);
+        }
+        AppAction::RespawnTerminal => {
+            // Does nothing while the process is still running
+            let _ = controller.dispatch(AppAction::RespawnTerminal, size);
+        }
+        AppAction::OpenSignalMenu => {
+            exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            if let Some(terminal) = controller.usecase().get_active_terminal()
+                && terminal.status().is_running()
+            {
+                *dialog = DialogState::SignalMenu {
+                    terminal_name: terminal.name().to_string(),
+                    process: process_label(terminal),
+                };
+                input_handler.set_mode(InputMode::DialogInput);
+            }
+        }
+        AppAction::OpenSortMenu => {
+            *dialog = DialogState::SortMenu;
+            input_handler.set_mode(InputMode::DialogInput);
+        }
+        AppAction::EditFilter => {
+            // The filter line lives in the sidebar, so make sure it is shown
+            view.sidebar_visible = true;
+            view.zoomed = false;
+            *dialog = DialogState::FilterEdit;
+            input_handler.set_mode(InputMode::DialogInput);
+        }
+        AppAction::OpenQuickSwitcher => {
+            exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            *dialog = DialogState::QuickSwitch {
+                query: String::new(),
+                cursor_pos: 0,
+                selected_index: 0,
+            };
+            input_handler.set_mode(InputMode::DialogInput);
+        }
+        other => {
+            match controller.dispatch(other, size) {
                 Ok(()) => {}
                 Err(e) => {
                     // Silently ignore NoActiveTerminal for input forwarding
@@ -1807,6 +2390,383 @@ fn handle_key_event<P: PtyPort, S: ScreenPort>(
 }
 
 /// Exit scrollback mode and reset offset to 0 if currently in scrollback.
+/// Handle a mouse event.
+///
+/// - Sidebar: clicking an entry selects that terminal; dragging the right
+///   border resizes the sidebar.
+/// - Panes and the mini terminal: clicking focuses the pane. When the
+///   application enabled mouse reporting, events are forwarded to it as SGR
+///   1006 reports. Otherwise the wheel enters and scrolls scrollback, and
+///   dragging selects text (Visual mode) which is yanked on release.
+#[allow(clippy::too_many_arguments)]
+fn handle_mouse_event<P: PtyPort, S: ScreenPort>(
+    mouse: MouseEvent,
+    controller: &mut TuiController<P, S>,
+    input_handler: &mut InputHandler,
+    dialog: &DialogState,
+    focus: &mut FocusPane,
+    size: TerminalSize,
+    scrollback_target: &mut Option<ScrollbackTarget>,
+    search_state: &mut Option<SearchState>,
+    ui: &mut UiState,
+    areas: &LayoutAreas,
+    pane_rects: &PaneRects,
+    sidebar_scroll_offset: usize,
+) -> anyhow::Result<()> {
+    let UiState { mini_terminal, yank_buffer, yank_flash_until, selection_state, scrollback_cursor, view, mouse_drag: drag, .. } = ui;
+    if !matches!(dialog, DialogState::None) {
+        return Ok(());
+    }
+    let pos = Position::new(mouse.column, mouse.row);
+
+    // Continue a gesture started by an earlier press
+    match (*drag, mouse.kind) {
+        (Some(MouseDrag::SidebarBorder), MouseEventKind::Drag(MouseButton::Left)) => {
+            let width = mouse.column.saturating_sub(areas.sidebar.x) + 1;
+            view.resize_sidebar(i32::from(width) - i32::from(view.sidebar_width));
+            return Ok(());
+        }
+        (Some(MouseDrag::Forward { id, content }), MouseEventKind::Drag(_) | MouseEventKind::Up(_)) => {
+            forward_mouse(controller, id, content, mouse);
+            if matches!(mouse.kind, MouseEventKind::Up(_)) {
+                *drag = None;
+            }
+            return Ok(());
+        }
+        (Some(MouseDrag::Select { target, anchor }), MouseEventKind::Drag(MouseButton::Left)) => {
+            if *scrollback_target != Some(target)
+                && !enter_scrollback(controller, input_handler, scrollback_target, mini_terminal, scrollback_cursor, target)
+            {
+                *drag = None;
+                return Ok(());
+            }
+            let Some(id) = active_scrollback_id(scrollback_target, controller, mini_terminal) else {
+                return Ok(());
+            };
+            let area = match target {
+                ScrollbackTarget::MainTerminal => pane_rects.rect_of(id).or(Some(areas.main_pane)),
+                ScrollbackTarget::MiniTerminal => areas.mini_terminal,
+            };
+            let Some(area) = area else {
+                return Ok(());
+            };
+            let content = content_rect(area, target, true);
+            // Dragging past the top or bottom edge scrolls the view
+            if mouse.row < content.y {
+                scroll_viewport(controller, id, 1, content.height as usize, scrollback_cursor);
+            } else if mouse.row >= content.bottom() {
+                scroll_viewport(controller, id, -1, content.height as usize, scrollback_cursor);
+            }
+            let cursor = selection_pos_at(controller, id, content, pos);
+            match selection_state.as_mut() {
+                Some(sel) => sel.cursor = cursor,
+                None => {
+                    let mut sel = SelectionState::new(SelectionMode::Character, anchor);
+                    sel.cursor = cursor;
+                    *selection_state = Some(sel);
+                    input_handler.set_mode(InputMode::VisualSelection);
+                }
+            }
+            return Ok(());
+        }
+        (Some(MouseDrag::Select { .. }), MouseEventKind::Up(MouseButton::Left)) => {
+            *drag = None;
+            if selection_state.is_some()
+                && let Some(id) = active_scrollback_id(scrollback_target, controller, mini_terminal)
+            {
+                yank_selection(controller, input_handler, id, selection_state, yank_buffer, yank_flash_until);
+            }
+            return Ok(());
+        }
+        (Some(_), MouseEventKind::Up(_)) => {
+            *drag = None;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Sidebar: select an entry, or start resizing from the right border
+    if view.shows_sidebar() && areas.sidebar.contains(pos) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return Ok(());
+        }
+        if mouse.column + 1 == areas.sidebar.right() {
+            *drag = Some(MouseDrag::SidebarBorder);
+            return Ok(());
+        }
+        let count = controller.usecase().visible_terminals().len();
+        let groups = controller.usecase().group_headers();
+        if let Some(group) = sidebar::group_at(areas.sidebar, mouse.row, count, &groups, sidebar_scroll_offset) {
+            controller.usecase_mut().set_group_collapsed(&groups[group].name, None)?;
+            return Ok(());
+        }
+        if let Some(index) = sidebar::index_at(areas.sidebar, mouse.row, count, &groups, sidebar_scroll_offset) {
+            exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            *focus = FocusPane::Terminal;
+            input_handler.set_mode(InputMode::Normal);
+            match controller.dispatch(AppAction::SelectByIndex(index), size) {
+                Ok(()) => {}
+                Err(e) => {
+                    if !matches!(e, crate::shared::error::AppError::NoActiveTerminal) {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Which terminal is under the pointer: the mini terminal or a pane
+    // (only the zoomed pane is visible while zoomed)
+    let active_id = controller.usecase().get_active_terminal().map(|t| t.id());
+    let hit = if let Some(area) = areas.mini_terminal.filter(|a| mini_terminal.spawned && a.contains(pos)) {
+        Some((ScrollbackTarget::MiniTerminal, mini_terminal.terminal_id, area))
+    } else {
+        pane_rects
+            .panes
+            .iter()
+            .filter(|(id, _)| !view.zoomed || Some(*id) == active_id)
+            .find(|(_, rect)| rect.contains(pos))
+            .map(|&(id, rect)| (ScrollbackTarget::MainTerminal, id, rect))
+            // Without splits the active terminal fills the main pane
+            .or_else(|| {
+                active_id
+                    .filter(|_| areas.main_pane.contains(pos))
+                    .map(|id| (ScrollbackTarget::MainTerminal, id, areas.main_pane))
+            })
+    };
+    let Some((target, id, area)) = hit else {
+        return Ok(());
+    };
+
+    // Focus the pane first; plain motion never changes focus
+    let focused = match target {
+        ScrollbackTarget::MainTerminal => *focus == FocusPane::Terminal && active_id == Some(id),
+        ScrollbackTarget::MiniTerminal => *focus == FocusPane::MiniTerminal,
+    };
+    if !focused {
+        if mouse.kind == MouseEventKind::Moved {
+            return Ok(());
+        }
+        exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+        match target {
+            ScrollbackTarget::MainTerminal => {
+                let _ = controller.usecase_mut().select_by_id(id);
+                *focus = FocusPane::Terminal;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            ScrollbackTarget::MiniTerminal => {
+                *focus = FocusPane::MiniTerminal;
+                input_handler.set_mode(InputMode::MiniTerminalInput);
+            }
+        }
+    }
+
+    let in_scrollback = *scrollback_target == Some(target);
+    let mouse_mode = controller.usecase().screen_port().get_mouse_mode(id).unwrap_or(MouseMode::Off);
+    if mouse_mode.is_enabled() && !in_scrollback {
+        let content = content_rect(area, target, false);
+        if content.contains(pos) {
+            forward_mouse(controller, id, content, mouse);
+            if matches!(mouse.kind, MouseEventKind::Down(_)) {
+                *drag = Some(MouseDrag::Forward { id, content });
+            }
+        }
+        return Ok(());
+    }
+
+    let content_rows = scrollback_content_rows(&Some(target), size);
+    match mouse.kind {
+        MouseEventKind::ScrollUp => {
+            let scrolling = in_scrollback
+                || enter_scrollback(controller, input_handler, scrollback_target, mini_terminal, scrollback_cursor, target);
+            if scrolling {
+                scroll_viewport(controller, id, MOUSE_WHEEL_LINES, content_rows, scrollback_cursor);
+            }
+        }
+        MouseEventKind::ScrollDown if in_scrollback => {
+            let offset = scroll_viewport(controller, id, -MOUSE_WHEEL_LINES, content_rows, scrollback_cursor);
+            // Scrolling back to the live view leaves scrollback, unless a
+            // selection or search is still in use
+            if offset == 0 && selection_state.is_none() && search_state.is_none() {
+                exit_scrollback_if_active(controller, input_handler, scrollback_target, mini_terminal, search_state, selection_state, scrollback_cursor);
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            let content = content_rect(area, target, in_scrollback);
+            if content.contains(pos) {
+                // A new press replaces any previous selection
+                if selection_state.take().is_some() {
+                    input_handler.set_mode(InputMode::ScrollbackMode);
+                }
+                *drag = Some(MouseDrag::Select { target, anchor: selection_pos_at(controller, id, content, pos) });
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Lines scrolled per mouse wheel notch.
+const MOUSE_WHEEL_LINES: isize = 3;
+
+/// The cells of a pane that show terminal content.
+///
+/// Normal mode draws a CWD bar above the content; scrollback mode adds a
+/// border around the pane as well. The mini terminal always has a border.
+fn content_rect(area: Rect, target: ScrollbackTarget, in_scrollback: bool) -> Rect {
+    match target {
+        ScrollbackTarget::MainTerminal if in_scrollback && area.width >= 4 && area.height >= 5 => {
+            Rect::new(area.x + 1, area.y + 2, area.width - 2, area.height - 4)
+        }
+        ScrollbackTarget::MainTerminal => {
+            Rect::new(area.x, area.y + 1, area.width, area.height.saturating_sub(1))
+        }
+        ScrollbackTarget::MiniTerminal => Rect::new(
+            area.x + 1,
+            area.y + 1,
+            area.width.saturating_sub(2),
+            area.height.saturating_sub(2),
+        ),
+    }
+}
+
+/// Absolute position (row 0 = top of scrollback) of the cell under `pos`,
+/// clamped to the content area.
+fn selection_pos_at<P: PtyPort, S: ScreenPort>(
+    controller: &TuiController<P, S>,
+    id: TerminalId,
+    content: Rect,
+    pos: Position,
+) -> SelectionPos {
+    let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
+    let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
+    let visible_start = max_sb.saturating_sub(offset);
+    let row = pos.y.clamp(content.y, content.bottom().saturating_sub(1).max(content.y)) - content.y;
+    let col = pos.x.clamp(content.x, content.right().saturating_sub(1).max(content.x)) - content.x;
+    SelectionPos { row: visible_start + row as usize, col: col as usize }
+}
+
+/// Move the scrollback view by `delta` lines (positive = further into
+/// history), keeping the scrollback cursor on screen. Returns the new offset.
+fn scroll_viewport<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    id: TerminalId,
+    delta: isize,
+    content_rows: usize,
+    scrollback_cursor: &mut ScrollbackCursor,
+) -> usize {
+    let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
+    let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
+    let new_offset = offset.saturating_add_signed(delta).min(max_sb);
+    let _ = controller.usecase_mut().screen_port_mut().set_scrollback_offset(id, new_offset);
+    let visible_start = max_sb - new_offset;
+    scrollback_cursor.row = scrollback_cursor
+        .row
+        .clamp(visible_start, visible_start + content_rows.saturating_sub(1));
+    new_offset
+}
+
+/// Forward a mouse event to an application that enabled mouse reporting,
+/// as an SGR report relative to its content area.
+fn forward_mouse<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    id: TerminalId,
+    content: Rect,
+    mouse: MouseEvent,
+) {
+    let mode = controller.usecase().screen_port().get_mouse_mode(id).unwrap_or(MouseMode::Off);
+    let col = mouse.column.saturating_sub(content.x).min(content.width.saturating_sub(1));
+    let row = mouse.row.saturating_sub(content.y).min(content.height.saturating_sub(1));
+    if let Some(bytes) = input::mouse_to_sgr(mouse, col, row, mode) {
+        let _ = controller.usecase_mut().pty_port_mut().write(id, &bytes);
+    }
+}
+
+/// Put `target` into scrollback mode with the cursor at the top of the
+/// visible area.
+///
+/// Returns false without changing anything when the terminal is in alternate
+/// screen (e.g. vim, full-screen TUI) or there is no active terminal.
+fn enter_scrollback<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    input_handler: &mut InputHandler,
+    scrollback_target: &mut Option<ScrollbackTarget>,
+    mini_terminal: &MiniTerminalState,
+    scrollback_cursor: &mut ScrollbackCursor,
+    target: ScrollbackTarget,
+) -> bool {
+    let id = match target {
+        ScrollbackTarget::MainTerminal => match controller.usecase().get_active_terminal() {
+            Some(t) => t.id(),
+            None => return false,
+        },
+        ScrollbackTarget::MiniTerminal => mini_terminal.terminal_id,
+    };
+    if controller.usecase().screen_port().is_alternate_screen(id).unwrap_or(false) {
+        return false;
+    }
+    *scrollback_target = Some(target);
+    input_handler.set_mode(InputMode::ScrollbackMode);
+    // Initialize cursor at top of visible area
+    let offset = controller.usecase().screen_port().get_scrollback_offset(id).unwrap_or(0);
+    let max_sb = controller.usecase().screen_port().get_max_scrollback(id).unwrap_or(0);
+    scrollback_cursor.row = max_sb.saturating_sub(offset);
+    scrollback_cursor.col = 0;
+    true
+}
+
+/// Copy the current selection to the clipboard and yank buffer, then return
+/// to plain scrollback mode.
+fn yank_selection<P: PtyPort, S: ScreenPort>(
+    controller: &mut TuiController<P, S>,
+    input_handler: &mut InputHandler,
+    id: TerminalId,
+    selection_state: &mut Option<SelectionState>,
+    yank_buffer: &mut Option<String>,
+    yank_flash_until: &mut Option<std::time::Instant>,
+) {
+    let Some(sel) = selection_state.as_ref() else {
+        return;
+    };
+    let (start, end) = sel.ordered();
+    let text = match sel.mode {
+        SelectionMode::Line => {
+            let mut all_cells: Vec<Vec<Cell>> = Vec::new();
+            for r in start.row..=end.row {
+                if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, r) {
+                    all_cells.push(row_cells);
+                }
+            }
+            extract_text_from_cells(&all_cells, 0, all_cells.len(), None, None)
+        }
+        SelectionMode::Character => {
+            if start.row == end.row {
+                if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, start.row) {
+                    extract_text_from_cells(&[row_cells], 0, 1, Some(start.col), Some(end.col + 1))
+                } else {
+                    String::new()
+                }
+            } else {
+                let mut all_cells: Vec<Vec<Cell>> = Vec::new();
+                for r in start.row..=end.row {
+                    if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, r) {
+                        all_cells.push(row_cells);
+                    }
+                }
+                extract_text_from_cells(&all_cells, 0, all_cells.len(), Some(start.col), Some(end.col + 1))
+            }
+        }
+    };
+    if !text.is_empty() {
+        crate::infrastructure::clipboard::copy_to_clipboard(&text);
+        *yank_buffer = Some(text);
+        *yank_flash_until = Some(std::time::Instant::now() + std::time::Duration::from_secs(2));
+    }
+    *selection_state = None;
+    input_handler.set_mode(InputMode::ScrollbackMode);
+}
+
 fn exit_scrollback_if_active<P: PtyPort, S: ScreenPort>(
     controller: &mut TuiController<P, S>,
     input_handler: &mut InputHandler,
@@ -1903,43 +2863,7 @@ fn handle_visual_key<P: PtyPort, S: ScreenPort>(
             sel.cursor.row = (sel.cursor.row + page).min(total_rows.saturating_sub(1));
         }
         KeyCode::Char('y') => {
-            // Yank selection
-            let (start, end) = sel.ordered();
-            let text = match sel.mode {
-                SelectionMode::Line => {
-                    let mut all_cells: Vec<Vec<Cell>> = Vec::new();
-                    for r in start.row..=end.row {
-                        if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, r) {
-                            all_cells.push(row_cells);
-                        }
-                    }
-                    extract_text_from_cells(&all_cells, 0, all_cells.len(), None, None)
-                }
-                SelectionMode::Character => {
-                    if start.row == end.row {
-                        if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, start.row) {
-                            extract_text_from_cells(&[row_cells], 0, 1, Some(start.col), Some(end.col + 1))
-                        } else {
-                            String::new()
-                        }
-                    } else {
-                        let mut all_cells: Vec<Vec<Cell>> = Vec::new();
-                        for r in start.row..=end.row {
-                            if let Ok(row_cells) = controller.usecase_mut().screen_port_mut().get_row_cells(id, r) {
-                                all_cells.push(row_cells);
-                            }
-                        }
-                        extract_text_from_cells(&all_cells, 0, all_cells.len(), Some(start.col), Some(end.col + 1))
-                    }
-                }
-            };
-            if !text.is_empty() {
-                crate::infrastructure::clipboard::copy_to_clipboard(&text);
-                *yank_buffer = Some(text);
-                *yank_flash_until = Some(std::time::Instant::now() + std::time::Duration::from_secs(2));
-            }
-            *selection_state = None;
-            input_handler.set_mode(InputMode::ScrollbackMode);
+            yank_selection(controller, input_handler, id, selection_state, yank_buffer, yank_flash_until);
             return Ok(());
         }
         KeyCode::Esc => {
@@ -2074,6 +2998,52 @@ fn scroll_to_match<P: PtyPort, S: ScreenPort>(
     }
 }
 
+/// The foreground process as shown in dialogs, e.g. "vim (pid 4242)".
+fn process_label(terminal: &ManagedTerminal) -> Option<String> {
+    terminal.foreground().map(|p| format!("{} (pid {})", p.name, p.pid))
+}
+
+/// Quick switcher texts for a terminal: the line shown (id, name, group,
+/// status badge and cwd) and the text matched against the query, which
+/// adds the memo.
+fn quick_switch_texts(terminal: &ManagedTerminal, cwd: &str) -> (String, String) {
+    let mut display = format!("{}: {}", terminal.id().value(), terminal.name());
+    if let Some(group) = terminal.group() {
+        display.push_str(&format!("  #{}", group));
+    }
+    if let Some(badge) = terminal.badge() {
+        display.push_str(&format!("  [{}]", badge.label()));
+    }
+    // Plain "running" says nothing, so only idle, foreground and quiet
+    // states are shown (and searchable)
+    let activity = terminal.activity_text(Instant::now());
+    if activity != "running" {
+        display.push_str(&format!("  ({})", activity));
+    }
+    display.push_str(&format!("  {}", cwd));
+    let search = if terminal.has_memo() {
+        format!("{} {}", display, terminal.memo())
+    } else {
+        display.clone()
+    };
+    (display, search)
+}
+
+/// Split a `#group` word off a quick switcher query, e.g. "#api serv"
+/// gives `(Some("api"), "serv")`. Without one the query is kept as is.
+fn split_group_query(query: &str) -> (Option<&str>, String) {
+    let Some(group) = query.split_whitespace().find_map(|w| w.strip_prefix('#')) else {
+        return (None, query.to_string());
+    };
+    let rest: Vec<&str> = query.split_whitespace().filter(|w| !w.starts_with('#')).collect();
+    (Some(group), rest.join(" "))
+}
+
+/// Whether a terminal's group starts with `prefix`, ignoring case.
+fn in_group_prefix(terminal: &ManagedTerminal, prefix: &str) -> bool {
+    terminal.group().is_some_and(|g| g.to_lowercase().starts_with(&prefix.to_lowercase()))
+}
+
 fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
     key: KeyEvent,
     controller: &mut TuiController<P, S>,
@@ -2082,18 +3052,10 @@ fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
     size: TerminalSize,
 ) -> anyhow::Result<()> {
     match dialog {
-        DialogState::CreateTerminal { input, cursor_pos } => match key.code {
-            KeyCode::Char(c) => {
-                let byte_idx = char_to_byte_index(input, *cursor_pos);
-                input.insert(byte_idx, c);
-                *cursor_pos += 1;
-            }
-            KeyCode::Backspace => {
-                if *cursor_pos > 0 {
-                    let byte_idx = char_to_byte_index(input, *cursor_pos - 1);
-                    input.remove(byte_idx);
-                    *cursor_pos -= 1;
-                }
+        DialogState::CreateTerminal { input, cursor_pos, group, on_group } => match key.code {
+            KeyCode::Tab | KeyCode::BackTab => {
+                *on_group = !*on_group;
+                *cursor_pos = if *on_group { group.chars().count() } else { input.chars().count() };
             }
             KeyCode::Enter => {
                 let name = if input.is_empty() {
@@ -2102,15 +3064,70 @@ fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
                     Some(input.clone())
                 };
                 controller.dispatch(AppAction::CreateTerminal { name }, size)?;
+                if let Some(group) = group_name(group) {
+                    controller.usecase_mut().set_active_group(Some(group))?;
+                }
+                *dialog = DialogState::None;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            KeyCode::Esc => {
+                *dialog = DialogState::None;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            code => {
+                let field = if *on_group { group } else { input };
+                match code {
+                    KeyCode::Char(c) => {
+                        let byte_idx = char_to_byte_index(field, *cursor_pos);
+                        field.insert(byte_idx, c);
+                        *cursor_pos += 1;
+                    }
+                    KeyCode::Backspace if *cursor_pos > 0 => {
+                        let byte_idx = char_to_byte_index(field, *cursor_pos - 1);
+                        field.remove(byte_idx);
+                        *cursor_pos -= 1;
+                    }
+                    _ => {}
+                }
+            }
+        },
+        DialogState::SignalMenu { .. } => match key.code {
+            KeyCode::Char(c) => {
+                if let Some(signal) = dialog::signal_for_key(c) {
+                    // The process may have exited while the menu was open
+                    let _ = controller.dispatch(AppAction::SignalTerminal(signal), size);
+                    *dialog = DialogState::None;
+                    input_handler.set_mode(InputMode::Normal);
+                }
+            }
+            KeyCode::Esc => {
                 *dialog = DialogState::None;
                 input_handler.set_mode(InputMode::Normal);
             }
+            _ => {}
+        },
+        DialogState::SortMenu => match key.code {
+            KeyCode::Char(c) => {
+                if let Some(key) = dialog::sort_for_key(c) {
+                    controller.dispatch(AppAction::SortTerminals(key), size)?;
+                    *dialog = DialogState::None;
+                    input_handler.set_mode(InputMode::Normal);
+                }
+            }
             KeyCode::Esc => {
                 *dialog = DialogState::None;
                 input_handler.set_mode(InputMode::Normal);
             }
             _ => {}
         },
+        DialogState::FilterEdit => {
+            let mut filter = controller.usecase().filter().clone();
+            if !input::edit_filter(&mut filter, key.code) {
+                *dialog = DialogState::None;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            controller.usecase_mut().set_filter(filter);
+        }
         DialogState::ConfirmClose { .. } => match key.code {
             KeyCode::Char('y') => {
                 let _ = controller.dispatch(AppAction::CloseTerminal, size);
@@ -2163,9 +3180,34 @@ fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
             }
             _ => {}
         },
-        DialogState::MemoEdit { text, cursor_row, cursor_col } => match key.code {
-            // Ctrl+J: insert newline
-            KeyCode::Char('j') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
+        DialogState::GroupEdit { input, cursor_pos } => match key.code {
+            KeyCode::Char(c) => {
+                let byte_idx = char_to_byte_index(input, *cursor_pos);
+                input.insert(byte_idx, c);
+                *cursor_pos += 1;
+            }
+            KeyCode::Backspace if *cursor_pos > 0 => {
+                let byte_idx = char_to_byte_index(input, *cursor_pos - 1);
+                input.remove(byte_idx);
+                *cursor_pos -= 1;
+            }
+            KeyCode::Left if *cursor_pos > 0 => *cursor_pos -= 1,
+            KeyCode::Right if *cursor_pos < input.chars().count() => *cursor_pos += 1,
+            KeyCode::Enter => {
+                // An empty name takes the terminal out of its group
+                let _ = controller.dispatch(AppAction::SetGroup { group: input.clone() }, size);
+                *dialog = DialogState::None;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            KeyCode::Esc => {
+                *dialog = DialogState::None;
+                input_handler.set_mode(InputMode::Normal);
+            }
+            _ => {}
+        },
+        DialogState::MemoEdit { text, cursor_row, cursor_col } => match key.code {
+            // Ctrl+J: insert newline
+            KeyCode::Char('j') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
                 let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
                 if *cursor_row < lines.len() {
                     let current_line = &lines[*cursor_row];
@@ -2287,25 +3329,24 @@ fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
                 KeyCode::Enter => {
                     // Determine which terminal is selected by re-running the filter
                     let terminals = controller.usecase().get_terminals();
-                    let search_items: Vec<(usize, String)> = terminals.iter().enumerate().map(|(idx, t)| {
-                        let cwd = controller.usecase().screen_port()
-                            .get_cwd(t.id()).ok().flatten()
-                            .unwrap_or_else(|| t.cwd().display().to_string());
-                        let display = format!("{}: {}  {}", t.id().value(), t.name(), cwd);
-                        let memo = t.memo();
-                        let search_text = if memo.is_empty() {
-                            display
-                        } else {
-                            format!("{} {}", display, memo)
-                        };
-                        (idx, search_text)
-                    }).collect();
+                    let (group, rest) = split_group_query(query);
+                    let search_items: Vec<(usize, String)> = terminals.iter().enumerate()
+                        .filter(|(_, t)| group.is_none_or(|g| in_group_prefix(t, g)))
+                        .map(|(idx, t)| {
+                            let cwd = controller.usecase().screen_port()
+                                .get_cwd(t.id()).ok().flatten()
+                                .unwrap_or_else(|| t.cwd().display().to_string());
+                            (idx, quick_switch_texts(t, &cwd).1)
+                        })
+                        .collect();
 
-                    let filtered = fuzzy_matcher::filter_and_sort(query, &search_items);
+                    let filtered = fuzzy_matcher::filter_and_sort(&rest, &search_items);
                     let sel = (*selected_index).min(filtered.len().saturating_sub(1));
 
+                    // By id: the switcher also finds terminals the sidebar filter hides
                     if let Some((terminal_idx, _)) = filtered.get(sel) {
-                        controller.dispatch(AppAction::SelectByIndex(*terminal_idx), size)?;
+                        let id = terminals[*terminal_idx].id();
+                        controller.usecase_mut().select_by_id(id)?;
                     }
                     *dialog = DialogState::None;
                     input_handler.set_mode(InputMode::Normal);
@@ -2325,7 +3366,7 @@ fn handle_dialog_key<P: PtyPort, S: ScreenPort>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::primitive::TerminalId;
+    use crate::domain::primitive::{RestartPolicy, TerminalId};
 
     // === MiniTerminalState tests ===
 
@@ -3643,6 +4684,62 @@ mod tests {
         assert!(!items[0].1.ends_with(' '));
     }
 
+    #[test]
+    fn quick_switch_texts_show_badge_and_search_memo() {
+        let mut t = ManagedTerminal::new(TerminalId::new(3), "agent".to_string(), PathBuf::from("/tmp"));
+        assert_eq!(quick_switch_texts(&t, "/srv").0, "3: agent  /srv");
+
+        t.set_badge(Some(crate::domain::primitive::StatusBadge {
+            text: "waiting for approval".to_string(),
+            icon: Some("?".to_string()),
+            color: crate::domain::primitive::Color::Default,
+        }));
+        t.set_memo("review PR".to_string());
+        let (display, search) = quick_switch_texts(&t, "/srv");
+        assert_eq!(display, "3: agent  [? waiting for approval]  /srv");
+        assert_eq!(search, "3: agent  [? waiting for approval]  /srv review PR");
+        assert!(fuzzy_matcher::fuzzy_match("approval", &search).is_some());
+    }
+
+    #[test]
+    fn quick_switch_texts_show_group() {
+        let mut t = ManagedTerminal::new(TerminalId::new(3), "db".to_string(), PathBuf::from("/tmp"));
+        t.set_group(Some("backend".to_string()));
+        assert_eq!(quick_switch_texts(&t, "/srv").0, "3: db  #backend  /srv");
+        assert!(in_group_prefix(&t, "Back"));
+        assert!(!in_group_prefix(&t, "front"));
+    }
+
+    #[test]
+    fn split_group_query_takes_the_hash_word() {
+        assert_eq!(split_group_query("api serv"), (None, "api serv".to_string()));
+        assert_eq!(split_group_query("#back db"), (Some("back"), "db".to_string()));
+        assert_eq!(split_group_query("db #back"), (Some("back"), "db".to_string()));
+        assert_eq!(split_group_query("#"), (Some(""), String::new()));
+    }
+
+    #[test]
+    fn quick_switch_texts_show_foreground_activity() {
+        let mut t = ManagedTerminal::new(TerminalId::new(3), "edit".to_string(), PathBuf::from("/tmp"));
+        t.set_foreground(Some(crate::domain::primitive::ForegroundProcess {
+            pid: 42,
+            name: "vim".to_string(),
+            cmdline: "vim main.rs".to_string(),
+            is_terminal_process: false,
+        }));
+        assert_eq!(quick_switch_texts(&t, "/srv").0, "3: edit  (vim running)  /srv");
+
+        t.set_foreground(Some(crate::domain::primitive::ForegroundProcess {
+            pid: 41,
+            name: "zsh".to_string(),
+            cmdline: "-zsh".to_string(),
+            is_terminal_process: true,
+        }));
+        let (_, search) = quick_switch_texts(&t, "/srv");
+        assert_eq!(search, "3: edit  (idle)  /srv");
+        assert!(fuzzy_matcher::fuzzy_match("idle", &search).is_some());
+    }
+
     #[test]
     fn build_search_items_includes_memo_when_present() {
         let terminals = vec![
@@ -4719,33 +5816,51 @@ mod tests {
 
     use std::collections::HashMap;
     use std::path::{Path, PathBuf};
-    use crate::domain::primitive::{NotificationEvent, IpcResponseData};
+    use crate::domain::primitive::{CaptureFormat, CaptureOptions, Color, ForegroundProcess, NotificationEvent, IpcResponseData, LogMode, Signal, SortKey, StatusBadge, StatusFilter, TargetSpec, TerminalFilter};
     use crate::interface_adapter::port::pty_port::PtyPort;
     use crate::interface_adapter::port::screen_port::ScreenPort;
-    use crate::usecase::terminal_usecase::TerminalUsecase;
+    use crate::usecase::terminal_usecase::{TerminalUsecase, TERMINAL_ID_ENV, TERMINAL_NAME_ENV};
     use crate::interface_adapter::controller::tui_controller::TuiController;
     use crate::shared::error::AppError;
 
+    /// Arguments recorded for a single `spawn` call: (id, argv, cwd, env).
+    type SpawnCall = (TerminalId, Vec<String>, PathBuf, Vec<(String, String)>);
+
     /// Enhanced PtyPort mock that tracks writes.
     struct TestPtyPort {
         written: Vec<(TerminalId, Vec<u8>)>,
+        spawned: Vec<SpawnCall>,
+        resized: Vec<(TerminalId, TerminalSize)>,
+        signals: Vec<(TerminalId, Signal)>,
     }
 
     impl TestPtyPort {
         fn new() -> Self {
-            Self { written: Vec::new() }
+            Self { written: Vec::new(), spawned: Vec::new(), resized: Vec::new(), signals: Vec::new() }
         }
     }
 
     impl PtyPort for TestPtyPort {
-        fn spawn(&mut self, _id: TerminalId, _shell: &str, _cwd: &Path, _size: TerminalSize) -> Result<(), AppError> { Ok(()) }
+        fn spawn(&mut self, id: TerminalId, argv: &[String], cwd: &Path, env: &[(String, String)], _size: TerminalSize) -> Result<(), AppError> {
+            self.spawned.push((id, argv.to_vec(), cwd.to_path_buf(), env.to_vec()));
+            Ok(())
+        }
         fn read(&mut self, id: TerminalId) -> Result<Vec<u8>, AppError> { Err(AppError::TerminalNotFound(id)) }
         fn write(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
             self.written.push((id, data.to_vec()));
             Ok(())
         }
-        fn resize(&mut self, _id: TerminalId, _size: TerminalSize) -> Result<(), AppError> { Ok(()) }
+        fn resize(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+            self.resized.push((id, size));
+            Ok(())
+        }
         fn try_wait(&mut self, _id: TerminalId) -> Result<Option<i32>, AppError> { Ok(None) }
+        fn foreground_process(&mut self, _id: TerminalId) -> Result<Option<ForegroundProcess>, AppError> { Ok(None) }
+        fn signal(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError> {
+            self.signals.push((id, signal));
+            Ok(())
+        }
+        fn close(&mut self, _id: TerminalId, _grace: std::time::Duration) -> Result<(), AppError> { Ok(()) }
         fn kill(&mut self, _id: TerminalId) -> Result<(), AppError> { Ok(()) }
     }
 
@@ -4756,6 +5871,9 @@ mod tests {
         cwds: HashMap<u32, Option<String>>,
         bracketed_paste: HashMap<u32, bool>,
         max_scrollback: HashMap<u32, usize>,
+        scrollback_offsets: HashMap<u32, usize>,
+        mouse_modes: HashMap<u32, MouseMode>,
+        application_cursor_keys: HashMap<u32, bool>,
     }
 
     impl TestScreenPort {
@@ -4766,6 +5884,9 @@ mod tests {
                 cwds: HashMap::new(),
                 bracketed_paste: HashMap::new(),
                 max_scrollback: HashMap::new(),
+                scrollback_offsets: HashMap::new(),
+                mouse_modes: HashMap::new(),
+                application_cursor_keys: HashMap::new(),
             }
         }
     }
@@ -4791,7 +5912,9 @@ mod tests {
             Ok(())
         }
         fn get_cursor_visible(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(true) }
-        fn get_application_cursor_keys(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(false) }
+        fn get_application_cursor_keys(&self, id: TerminalId) -> Result<bool, AppError> {
+            Ok(self.application_cursor_keys.get(&id.value()).copied().unwrap_or(false))
+        }
         fn get_bracketed_paste(&self, id: TerminalId) -> Result<bool, AppError> {
             Ok(self.bracketed_paste.get(&id.value()).copied().unwrap_or(false))
         }
@@ -4799,13 +5922,23 @@ mod tests {
             Ok(self.cwds.get(&id.value()).cloned().unwrap_or(None))
         }
         fn drain_notifications(&mut self, _id: TerminalId) -> Result<Vec<NotificationEvent>, AppError> { Ok(vec![]) }
-        fn set_scrollback_offset(&mut self, _id: TerminalId, _offset: usize) -> Result<(), AppError> { Ok(()) }
-        fn get_scrollback_offset(&self, _id: TerminalId) -> Result<usize, AppError> { Ok(0) }
+        fn set_scrollback_offset(&mut self, id: TerminalId, offset: usize) -> Result<(), AppError> {
+            self.scrollback_offsets.insert(id.value(), offset);
+            Ok(())
+        }
+        fn get_scrollback_offset(&self, id: TerminalId) -> Result<usize, AppError> {
+            Ok(self.scrollback_offsets.get(&id.value()).copied().unwrap_or(0))
+        }
         fn get_max_scrollback(&self, id: TerminalId) -> Result<usize, AppError> {
             Ok(self.max_scrollback.get(&id.value()).copied().unwrap_or(0))
         }
+        fn get_scrolled_off(&self, _id: TerminalId) -> Result<usize, AppError> { Ok(0) }
         fn is_alternate_screen(&self, _id: TerminalId) -> Result<bool, AppError> { Ok(false) }
         fn get_cursor_style(&self, _id: TerminalId) -> Result<CursorStyle, AppError> { Ok(CursorStyle::DefaultUserShape) }
+        fn get_mouse_mode(&self, id: TerminalId) -> Result<MouseMode, AppError> {
+            Ok(self.mouse_modes.get(&id.value()).copied().unwrap_or_default())
+        }
+        fn get_keyboard_flags(&self, _id: TerminalId) -> Result<u16, AppError> { Ok(0) }
         fn drain_pending_responses(&mut self, _id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> { Ok(vec![]) }
         fn search_scrollback(&mut self, _id: TerminalId, _query: &str) -> Result<Vec<SearchMatch>, AppError> { Ok(vec![]) }
         fn get_row_cells(&mut self, id: TerminalId, abs_row: usize) -> Result<Vec<Cell>, AppError> {
@@ -4861,6 +5994,7 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: id.value(),
             keys: vec!["hello".to_string(), "Enter".to_string()],
+            literal: false,
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         assert_eq!(response, IpcResponse::Ok);
@@ -4880,6 +6014,7 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: 999,
             keys: vec!["a".to_string()],
+            literal: false,
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         assert!(matches!(response, IpcResponse::Error(ref msg) if msg.contains("terminal not found")));
@@ -4892,11 +6027,41 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: id.value(),
             keys: vec!["C-".to_string()],  // Invalid ctrl key
+            literal: false,
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         assert!(matches!(response, IpcResponse::Error(ref msg) if msg.contains("key parse error")));
     }
 
+    #[test]
+    fn ipc_send_keys_cursor_keys_follow_decckm() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        controller.usecase_mut().screen_port_mut().application_cursor_keys.insert(id.value(), true);
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::SendKeys {
+            target: id.value(),
+            keys: vec!["Up".to_string(), "C-Up".to_string()],
+            literal: false,
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written[0].1, b"\x1bOA\x1b[1;5A".to_vec());
+    }
+
+    #[test]
+    fn ipc_send_keys_literal_skips_key_names() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::SendKeys {
+            target: id.value(),
+            keys: vec!["Enter".to_string(), " C-".to_string()],
+            literal: true,
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written[0].1, b"Enter C-".to_vec());
+    }
+
     #[test]
     fn ipc_send_keys_empty_keys_returns_ok_without_write() {
         let (mut controller, id) = make_ipc_controller_with_terminal();
@@ -4904,6 +6069,7 @@ mod tests {
         let cmd = IpcCommand::SendKeys {
             target: id.value(),
             keys: vec![],
+            literal: false,
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         assert_eq!(response, IpcResponse::Ok);
@@ -4925,7 +6091,7 @@ mod tests {
 
         let cmd = IpcCommand::CapturePane {
             target: id.value(),
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
 
@@ -4938,6 +6104,7 @@ mod tests {
             name,
             cwd,
             scrollback_total,
+            ..
         }) = &response {
             assert!(text.starts_with("hello world"));
             assert_eq!(*cursor_row, 0);
@@ -4959,7 +6126,7 @@ mod tests {
         let mut yank_buffer: Option<String> = None;
         let cmd = IpcCommand::CapturePane {
             target: 999,
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         assert!(matches!(response, IpcResponse::Error(ref msg) if msg.contains("terminal not found")));
@@ -4971,7 +6138,7 @@ mod tests {
         let mut yank_buffer: Option<String> = None;
         let cmd = IpcCommand::CapturePane {
             target: id.value(),
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
 
@@ -4992,7 +6159,7 @@ mod tests {
 
         let cmd = IpcCommand::CapturePane {
             target: id.value(),
-            include_scrollback: true,
+            options: CaptureOptions { include_scrollback: true, ..CaptureOptions::default() },
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
 
@@ -5003,6 +6170,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ipc_capture_pane_ansi_keeps_colors() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+
+        set_screen_text(&mut controller, id, "ok");
+        controller.usecase_mut().screen_port_mut().cells.get_mut(&id.value()).unwrap()[0][0].fg =
+            Color::Indexed(1);
+
+        let cmd = IpcCommand::CapturePane {
+            target: id.value(),
+            options: CaptureOptions { format: CaptureFormat::Ansi, ..CaptureOptions::default() },
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+
+        if let IpcResponse::OkWithData(IpcResponseData::CapturePane { text, cells, .. }) = &response {
+            assert_eq!(text, "\x1b[0;31mo\x1b[0mk");
+            assert!(cells.is_none());
+        } else {
+            panic!("Expected OkWithData(CapturePane), got {:?}", response);
+        }
+    }
+
+    #[test]
+    fn ipc_capture_pane_cells_page_by_limit() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+
+        set_screen_text(&mut controller, id, "first row");
+
+        let cmd = IpcCommand::CapturePane {
+            target: id.value(),
+            options: CaptureOptions {
+                format: CaptureFormat::Cells,
+                limit: Some(10),
+                ..CaptureOptions::default()
+            },
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+
+        if let IpcResponse::OkWithData(IpcResponseData::CapturePane { cells, start, end, next_start, .. }) = &response {
+            let cells = cells.as_ref().expect("cells format returns cells");
+            assert_eq!(cells.len(), 10);
+            assert_eq!(cells[0][0].ch, 'f');
+            assert_eq!((*start, *end, *next_start), (0, 9, Some(10)));
+        } else {
+            panic!("Expected OkWithData(CapturePane), got {:?}", response);
+        }
+    }
+
     #[test]
     fn ipc_capture_pane_with_cwd_from_screen() {
         let (mut controller, id) = make_ipc_controller_with_terminal();
@@ -5014,7 +6231,7 @@ mod tests {
 
         let cmd = IpcCommand::CapturePane {
             target: id.value(),
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
 
@@ -5036,7 +6253,7 @@ mod tests {
 
         let cmd = IpcCommand::CapturePane {
             target: id.value(),
-            include_scrollback: false,
+            options: CaptureOptions::default(),
         };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
 
@@ -5405,7 +6622,7 @@ mod tests {
     fn ipc_create_window_returns_id() {
         let mut controller = make_ipc_controller();
         let mut yank_buffer: Option<String> = None;
-        let cmd = IpcCommand::CreateWindow { name: None, command: None };
+        let cmd = IpcCommand::CreateWindow { name: None, command: None, cwd: None, env: Vec::new(), restart: RestartPolicy::Never, group: None };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         match response {
             IpcResponse::OkWithData(IpcResponseData::CreateWindow { id }) => {
@@ -5419,7 +6636,7 @@ mod tests {
     fn ipc_create_window_with_name() {
         let mut controller = make_ipc_controller();
         let mut yank_buffer: Option<String> = None;
-        let cmd = IpcCommand::CreateWindow { name: Some("my-term".to_string()), command: None };
+        let cmd = IpcCommand::CreateWindow { name: Some("my-term".to_string()), command: None, cwd: None, env: Vec::new(), restart: RestartPolicy::Never, group: None };
         let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
         match response {
             IpcResponse::OkWithData(IpcResponseData::CreateWindow { id }) => {
@@ -5431,6 +6648,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ipc_create_window_without_command_spawns_interactive_shell() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::CreateWindow { name: None, command: None, cwd: None, env: Vec::new(), restart: RestartPolicy::Never, group: None };
+        handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        let spawned = &controller.usecase().pty_port().spawned;
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].1.len(), 1, "argv should be just the shell");
+        assert_eq!(spawned[0].2, PathBuf::from("/tmp"));
+    }
+
+    #[test]
+    fn ipc_create_window_honors_command_cwd_and_env() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::CreateWindow {
+            name: Some("watch".to_string()),
+            command: Some("cargo watch -x test".to_string()),
+            cwd: Some("/work/project".to_string()),
+            env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+            restart: RestartPolicy::Never,
+            group: None,
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        let id = match response {
+            IpcResponse::OkWithData(IpcResponseData::CreateWindow { id }) => id,
+            other => panic!("Expected OkWithData(CreateWindow), got: {:?}", other),
+        };
+        let spawned = &controller.usecase().pty_port().spawned;
+        assert_eq!(spawned[0].1[1..], ["-c".to_string(), "cargo watch -x test".to_string()]);
+        assert_eq!(spawned[0].2, PathBuf::from("/work/project"));
+        assert_eq!(spawned[0].3[0], ("RUST_LOG".to_string(), "debug".to_string()));
+        let t = controller.usecase().get_terminal_by_id(TerminalId::new(id)).unwrap();
+        assert_eq!(t.cwd(), Path::new("/work/project"));
+    }
+
     #[test]
     fn ipc_kill_window_success() {
         let (mut controller, id) = make_ipc_controller_with_terminal();
@@ -5453,6 +6707,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ipc_send_signal_reaches_pty() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::SendSignal { target: id.value(), signal: Signal::Int };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().signals, vec![(id, Signal::Int)]);
+
+        let cmd = IpcCommand::SendSignal { target: 999, signal: Signal::Int };
+        match handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24)) {
+            IpcResponse::Error(msg) => assert!(msg.contains("terminal not found"), "got: {msg}"),
+            other => panic!("Expected Error, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ipc_move_sort_and_filter_windows() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+        let id2 = controller.usecase_mut().create_terminal(Some("api".to_string()), size).unwrap();
+        let ids = |controller: &TuiController<_, _>| {
+            controller.usecase().get_terminals().iter().map(|t| t.id()).collect::<Vec<_>>()
+        };
+        let mut run = |controller: &mut TuiController<_, _>, cmd: IpcCommand| {
+            handle_ipc_command(&cmd, controller, &mut yank_buffer, size)
+        };
+
+        let cmd = IpcCommand::MoveWindow { target: id2.value(), to: WindowMove::To(0) };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert_eq!(ids(&controller), vec![id2, id1]);
+
+        let with = TargetSpec::Id(id2.value());
+        let cmd = IpcCommand::MoveWindow { target: id1.value(), to: WindowMove::Swap { with, caller: None } };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert_eq!(ids(&controller), vec![id1, id2]);
+        assert_eq!(controller.usecase().get_active_terminal().unwrap().id(), id2);
+
+        let cmd = IpcCommand::MoveWindow { target: 999, to: WindowMove::By(1) };
+        match run(&mut controller, cmd) {
+            IpcResponse::Error(msg) => assert_eq!(msg, "terminal not found: 999"),
+            other => panic!("Expected Error, got: {:?}", other),
+        }
+
+        let cmd = IpcCommand::SortWindows { key: SortKey::Name };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert_eq!(ids(&controller), vec![id2, id1]);
+
+        let filter = TerminalFilter { status: StatusFilter::All, text: "api".to_string() };
+        assert_eq!(run(&mut controller, IpcCommand::FilterWindows { filter: filter.clone() }), IpcResponse::Ok);
+        assert_eq!(controller.usecase().filter(), &filter);
+    }
+
+    #[test]
+    fn ipc_create_set_and_toggle_groups() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+        let mut run = |controller: &mut TuiController<_, _>, cmd: IpcCommand| {
+            handle_ipc_command(&cmd, controller, &mut yank_buffer, size)
+        };
+
+        let cmd = IpcCommand::CreateWindow {
+            name: Some("db".to_string()),
+            command: None,
+            cwd: None,
+            env: Vec::new(),
+            restart: RestartPolicy::Never,
+            group: Some("backend".to_string()),
+        };
+        let IpcResponse::OkWithData(IpcResponseData::CreateWindow { id: id2 }) = run(&mut controller, cmd) else {
+            panic!("Expected OkWithData(CreateWindow)");
+        };
+        let group_of = |controller: &TuiController<_, _>, id: u32| {
+            controller.usecase().get_terminal_by_id(TerminalId::new(id)).unwrap().group().map(str::to_string)
+        };
+        assert_eq!(group_of(&controller, id2), Some("backend".to_string()));
+
+        let cmd = IpcCommand::SetGroup { target: id1.value(), group: Some("backend".to_string()) };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        let IpcResponse::OkWithData(IpcResponseData::ListWindows { windows }) = run(&mut controller, IpcCommand::ListWindows) else {
+            panic!("Expected OkWithData(ListWindows)");
+        };
+        assert!(windows.iter().all(|w| w.group.as_deref() == Some("backend")));
+
+        let cmd = IpcCommand::ToggleGroup { group: "backend".to_string(), collapsed: Some(true) };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert!(controller.usecase().group_headers()[0].collapsed);
+        let cmd = IpcCommand::ToggleGroup { group: "web".to_string(), collapsed: None };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Error("group not found: web".to_string()));
+
+        let cmd = IpcCommand::SetGroup { target: id1.value(), group: None };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        let with = TargetSpec::Id(id2);
+        let cmd = IpcCommand::MoveWindow { target: id1.value(), to: WindowMove::Swap { with, caller: None } };
+        let expected = format!("terminals {} and {} are in different groups", id1.value(), id2);
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Error(expected));
+        let cmd = IpcCommand::SetGroup { target: 999, group: None };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Error("terminal not found: 999".to_string()));
+    }
+
     #[test]
     fn ipc_select_window_success() {
         let (mut controller, id1) = make_ipc_controller_with_terminal();
@@ -5492,6 +6848,31 @@ mod tests {
         assert_eq!(controller.usecase().get_terminal_by_id(id).unwrap().name(), "new-name");
     }
 
+    #[test]
+    fn ipc_set_status_sets_and_clears_badge() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let badge = StatusBadge { text: "waiting for approval".to_string(), icon: None, color: Color::Indexed(3) };
+
+        let cmd = IpcCommand::SetStatus { target: id.value(), status: Some(badge.clone()) };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().get_terminal_by_id(id).unwrap().badge(), Some(&badge));
+
+        let cmd = IpcCommand::SetStatus { target: id.value(), status: None };
+        handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(controller.usecase().get_terminal_by_id(id).unwrap().badge().is_none());
+    }
+
+    #[test]
+    fn ipc_set_status_not_found() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::SetStatus { target: 999, status: None };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Error("terminal not found: 999".to_string()));
+    }
+
     #[test]
     fn ipc_rename_window_not_found() {
         let mut controller = make_ipc_controller();
@@ -5513,6 +6894,7 @@ mod tests {
         let mut controller = make_ipc_controller();
         let mut yank_buffer: Option<String> = None;
         let cmd = IpcCommand::Notify {
+            target: None,
             title: Some("Claude Code".to_string()),
             body: "Response complete".to_string(),
         };
@@ -5525,6 +6907,7 @@ mod tests {
         let mut controller = make_ipc_controller();
         let mut yank_buffer: Option<String> = None;
         let cmd = IpcCommand::Notify {
+            target: None,
             title: None,
             body: "Task done".to_string(),
         };
@@ -5537,6 +6920,7 @@ mod tests {
         let mut controller = make_ipc_controller();
         let mut yank_buffer: Option<String> = None;
         let cmd = IpcCommand::Notify {
+            target: None,
             title: None,
             body: String::new(),
         };
@@ -5544,6 +6928,390 @@ mod tests {
         assert_eq!(response, IpcResponse::Ok);
     }
 
+    #[test]
+    fn ipc_notify_with_target_marks_terminal_and_queues_desktop_notification() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let size = TerminalSize::new(80, 24);
+        controller.usecase_mut().create_terminal(None, size).unwrap();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::Notify {
+            target: Some(id.value()),
+            title: Some("Claude Code".to_string()),
+            body: "Needs input".to_string(),
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+        assert_eq!(response, IpcResponse::Ok);
+
+        let event = notify_event(&Some("Claude Code".to_string()), "Needs input");
+        let terminal = controller.usecase().get_terminal_by_id(id).unwrap();
+        assert_eq!(terminal.last_notification(), Some(&event));
+        assert_eq!(
+            controller.usecase_mut().take_pending_notifications(),
+            vec![("test-term".to_string(), event)]
+        );
+    }
+
+    #[test]
+    fn ipc_notify_with_unknown_target_is_error() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::Notify { target: Some(9), title: None, body: "x".to_string() };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("terminal not found")));
+    }
+
+    // =========================================================================
+    // Tests: IPC input / server-only commands
+    // =========================================================================
+
+    #[test]
+    fn ipc_input_writes_raw_bytes() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::Input { target: id.value(), data: "\x1b[Aq".to_string() };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written, vec![(id, b"\x1b[Aq".to_vec())]);
+    }
+
+    #[test]
+    fn ipc_input_not_found() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::Input { target: 999, data: "x".to_string() };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("terminal not found")));
+    }
+
+    #[test]
+    fn ipc_attach_is_rejected_outside_server() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::Attach { cols: 80, rows: 24 };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("cm server")));
+    }
+
+    // =========================================================================
+    // Tests: IPC target resolution
+    // =========================================================================
+
+    fn resolve_command(target: &str, command: IpcCommand) -> IpcCommand {
+        IpcCommand::Resolve {
+            target: TargetSpec::parse(target).unwrap(),
+            caller: None,
+            command: Box::new(command),
+        }
+    }
+
+    #[test]
+    fn ipc_target_by_name_runs_on_that_terminal() {
+        let (mut controller, _) = make_ipc_controller_with_terminal();
+        let size = TerminalSize::new(80, 24);
+        let other = controller.usecase_mut().create_terminal(Some("api".to_string()), size).unwrap();
+        let mut yank_buffer: Option<String> = None;
+
+        let cmd = resolve_command("api", IpcCommand::Input { target: 0, data: "x".to_string() });
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written, vec![(other, b"x".to_vec())]);
+    }
+
+    #[test]
+    fn ipc_target_glob_fans_out_with_per_terminal_results() {
+        let mut controller = make_ipc_controller();
+        let size = TerminalSize::new(80, 24);
+        let a1 = controller.usecase_mut().create_terminal(Some("agent-1".to_string()), size).unwrap();
+        controller.usecase_mut().create_terminal(Some("db".to_string()), size).unwrap();
+        let a2 = controller.usecase_mut().create_terminal(Some("agent-2".to_string()), size).unwrap();
+        let mut yank_buffer: Option<String> = None;
+
+        let cmd = resolve_command("agent-*", IpcCommand::Input { target: 0, data: "go".to_string() });
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+        assert_eq!(
+            response,
+            IpcResponse::OkWithData(IpcResponseData::Targets {
+                results: vec![(a1.value(), IpcResponse::Ok), (a2.value(), IpcResponse::Ok)],
+            })
+        );
+        assert_eq!(
+            controller.usecase().pty_port().written,
+            vec![(a1, b"go".to_vec()), (a2, b"go".to_vec())]
+        );
+    }
+
+    // =========================================================================
+    // Tests: default @self target
+    // =========================================================================
+
+    /// Run a request as it arrives on the socket.
+    fn run_wire_request(controller: &mut TuiController<TestPtyPort, TestScreenPort>, json: &str) -> IpcResponse {
+        let cmd = crate::infrastructure::ipc::protocol::parse_command(json).unwrap();
+        let mut yank_buffer: Option<String> = None;
+        handle_ipc_command(&cmd, controller, &mut yank_buffer, TerminalSize::new(80, 24))
+    }
+
+    #[test]
+    fn ipc_default_target_is_the_caller_terminal() {
+        let (mut controller, first) = make_ipc_controller_with_terminal();
+        let caller = controller.usecase_mut().create_terminal(Some("agent".to_string()), TerminalSize::new(80, 24)).unwrap();
+        controller.usecase_mut().select_by_id(first).unwrap();
+
+        // `cm ctl send-keys -l ls` run in terminal "agent"
+        let json = format!(r#"{{"cmd":"send-keys","target":"@self","keys":["ls"],"literal":true,"caller":{}}}"#, caller.value());
+        assert_eq!(run_wire_request(&mut controller, &json), IpcResponse::Ok);
+        // Goes to the caller, not the active terminal
+        assert_eq!(controller.usecase().pty_port().written, vec![(caller, b"ls".to_vec())]);
+
+        let json = format!(r#"{{"cmd":"kill-window","target":"@self","caller":{}}}"#, caller.value());
+        assert_eq!(run_wire_request(&mut controller, &json), IpcResponse::Ok);
+        assert!(controller.usecase().get_terminal_by_id(caller).is_none());
+        assert!(controller.usecase().get_terminal_by_id(first).is_some());
+    }
+
+    #[test]
+    fn ipc_default_target_without_caller_is_an_error() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+
+        // Run from outside any cm terminal: nothing to default to
+        let response = run_wire_request(&mut controller, r#"{"cmd":"kill-window","target":"@self"}"#);
+        assert_eq!(response, IpcResponse::Error(AppError::NoCallerTerminal.to_string()));
+        assert!(controller.usecase().get_terminal_by_id(id).is_some());
+    }
+
+    #[test]
+    fn ipc_explicit_target_overrides_the_caller() {
+        let (mut controller, caller) = make_ipc_controller_with_terminal();
+        let other = controller.usecase_mut().create_terminal(Some("api".to_string()), TerminalSize::new(80, 24)).unwrap();
+
+        let json = format!(r#"{{"cmd":"kill-window","target":"api","caller":{}}}"#, caller.value());
+        assert_eq!(run_wire_request(&mut controller, &json), IpcResponse::Ok);
+        let json = format!(r#"{{"cmd":"send-keys","target":{},"keys":["x"],"literal":true,"caller":{}}}"#, caller.value(), caller.value());
+        assert_eq!(run_wire_request(&mut controller, &json), IpcResponse::Ok);
+
+        assert!(controller.usecase().get_terminal_by_id(other).is_none());
+        assert!(controller.usecase().get_terminal_by_id(caller).is_some());
+    }
+
+    #[test]
+    fn ipc_created_terminals_know_their_id_and_name() {
+        let mut controller = make_ipc_controller();
+        controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+
+        let response = run_wire_request(&mut controller, r#"{"cmd":"create-window","name":"agent"}"#);
+        let IpcResponse::OkWithData(IpcResponseData::CreateWindow { id }) = response else {
+            panic!("Expected OkWithData(CreateWindow), got: {:?}", response);
+        };
+        let env = &controller.usecase().pty_port().spawned[1].3;
+        assert!(env.contains(&(TERMINAL_ID_ENV.to_string(), id.to_string())), "env: {env:?}");
+        assert!(env.contains(&(TERMINAL_NAME_ENV.to_string(), "agent".to_string())), "env: {env:?}");
+    }
+
+    #[test]
+    fn ipc_send_keys_to_a_target_list() {
+        let mut controller = make_ipc_controller();
+        let size = TerminalSize::new(80, 24);
+        let a = controller.usecase_mut().create_terminal(Some("a".to_string()), size).unwrap();
+        let b = controller.usecase_mut().create_terminal(Some("b".to_string()), size).unwrap();
+        let mut yank_buffer: Option<String> = None;
+
+        let cmd = IpcCommand::Resolve {
+            target: TargetSpec::List(vec![TargetSpec::Name("b".to_string()), TargetSpec::Id(a.value())]),
+            caller: None,
+            command: Box::new(IpcCommand::SendKeys { target: 0, keys: vec!["ls".to_string()], literal: true }),
+        };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+        assert_eq!(
+            response,
+            IpcResponse::OkWithData(IpcResponseData::Targets {
+                results: vec![(b.value(), IpcResponse::Ok), (a.value(), IpcResponse::Ok)],
+            })
+        );
+        assert_eq!(controller.usecase().pty_port().written, vec![(b, b"ls".to_vec()), (a, b"ls".to_vec())]);
+    }
+
+    #[test]
+    fn ipc_mark_window_and_sync_input() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let size = TerminalSize::new(80, 24);
+        controller.usecase_mut().create_terminal(None, size).unwrap();
+        let id3 = controller.usecase_mut().create_terminal(None, size).unwrap();
+        let mut yank_buffer: Option<String> = None;
+        let mut run = |controller: &mut TuiController<_, _>, cmd: IpcCommand| {
+            handle_ipc_command(&cmd, controller, &mut yank_buffer, size)
+        };
+
+        let cmd = IpcCommand::MarkWindow { target: id1.value(), marked: None };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        let cmd = IpcCommand::MarkWindow { target: 99, marked: Some(true) };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Error("terminal not found: 99".to_string()));
+        let IpcResponse::OkWithData(IpcResponseData::ListWindows { windows }) = run(&mut controller, IpcCommand::ListWindows) else {
+            panic!("Expected OkWithData(ListWindows)");
+        };
+        assert_eq!(windows.iter().map(|w| w.marked).collect::<Vec<_>>(), [true, false, false]);
+
+        // Input from an attached client follows synchronize mode
+        assert_eq!(run(&mut controller, IpcCommand::SyncInput { enabled: Some(true) }), IpcResponse::Ok);
+        let cmd = IpcCommand::Input { target: id3.value(), data: "x".to_string() };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written, vec![(id1, b"x".to_vec()), (id3, b"x".to_vec())]);
+
+        // send-keys writes to its target only
+        controller.usecase_mut().pty_port_mut().written.clear();
+        let cmd = IpcCommand::SendKeys { target: id3.value(), keys: vec!["y".to_string()], literal: true };
+        assert_eq!(run(&mut controller, cmd), IpcResponse::Ok);
+        assert_eq!(controller.usecase().pty_port().written, vec![(id3, b"y".to_vec())]);
+
+        assert_eq!(run(&mut controller, IpcCommand::SyncInput { enabled: None }), IpcResponse::Ok);
+        assert!(!controller.usecase().sync_input());
+    }
+
+    #[test]
+    fn ipc_target_without_match_is_error() {
+        let (mut controller, _) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = resolve_command("nope-*", IpcCommand::KillWindow { target: 0 });
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("No terminal matches: nope-*")));
+        assert_eq!(controller.usecase().get_terminals().len(), 1);
+    }
+
+    #[test]
+    fn runner_target_for_wait_for_needs_one_terminal() {
+        let mut controller = make_ipc_controller();
+        let size = TerminalSize::new(80, 24);
+        controller.usecase_mut().create_terminal(Some("agent-1".to_string()), size).unwrap();
+        let a2 = controller.usecase_mut().create_terminal(Some("agent-2".to_string()), size).unwrap();
+        let wait = |target| {
+            resolve_command(
+                target,
+                IpcCommand::WaitFor { target: 0, condition: crate::domain::primitive::WaitCondition::Exit, timeout_ms: None },
+            )
+        };
+
+        let resolved = resolve_runner_target(wait("agent-2"), controller.usecase()).unwrap();
+        assert!(matches!(resolved, IpcCommand::WaitFor { target, .. } if target == a2.value()));
+        let err = resolve_runner_target(wait("agent-*"), controller.usecase()).unwrap_err();
+        assert!(err.contains("matches 2 terminals"), "got: {err}");
+        // Other commands are left for handle_ipc_command
+        let kill = resolve_command("agent-*", IpcCommand::KillWindow { target: 0 });
+        assert_eq!(resolve_runner_target(kill.clone(), controller.usecase()).unwrap(), kill);
+    }
+
+    // =========================================================================
+    // Log commands
+    // =========================================================================
+
+    #[test]
+    fn ipc_log_start_without_log_port_fails() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::LogStart { target: id.value(), mode: LogMode::Text, max_bytes: None };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("not available")));
+    }
+
+    #[test]
+    fn ipc_log_start_status_stop() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        controller.usecase_mut().set_log_port(Box::new(
+            crate::interface_adapter::port::log_port::tests::MockLogPort::new(),
+        ));
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+
+        let start = IpcCommand::LogStart { target: id.value(), mode: LogMode::Raw, max_bytes: None };
+        let response = handle_ipc_command(&start, &mut controller, &mut yank_buffer, size);
+        let IpcResponse::OkWithData(IpcResponseData::Log { status: Some(status) }) = response else {
+            panic!("expected log status, got {response:?}");
+        };
+        assert_eq!(status.mode, LogMode::Raw);
+        assert_eq!(status.max_bytes, DEFAULT_LOG_MAX_BYTES);
+
+        let query = IpcCommand::LogStatus { target: id.value() };
+        let response = handle_ipc_command(&query, &mut controller, &mut yank_buffer, size);
+        assert_eq!(response, IpcResponse::OkWithData(IpcResponseData::Log { status: Some(status) }));
+
+        let stop = IpcCommand::LogStop { target: id.value() };
+        assert_eq!(handle_ipc_command(&stop, &mut controller, &mut yank_buffer, size), IpcResponse::Ok);
+        assert!(matches!(
+            handle_ipc_command(&stop, &mut controller, &mut yank_buffer, size),
+            IpcResponse::Error(msg) if msg.contains("not being logged")
+        ));
+        assert_eq!(
+            handle_ipc_command(&query, &mut controller, &mut yank_buffer, size),
+            IpcResponse::OkWithData(IpcResponseData::Log { status: None })
+        );
+    }
+
+    #[test]
+    fn ipc_log_commands_not_found() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+        for cmd in [
+            IpcCommand::LogStart { target: 999, mode: LogMode::Text, max_bytes: None },
+            IpcCommand::LogStop { target: 999 },
+            IpcCommand::LogStatus { target: 999 },
+        ] {
+            let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+            assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("terminal not found")));
+        }
+    }
+
+    // =========================================================================
+    // Record commands
+    // =========================================================================
+
+    #[test]
+    fn ipc_record_start_and_stop() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        controller.usecase_mut().set_recorder_port(Box::new(
+            crate::interface_adapter::port::recorder_port::tests::MockRecorderPort::new(),
+        ));
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+
+        let start = IpcCommand::RecordStart { target: id.value(), path: Some("/tmp/x.cast".to_string()) };
+        assert_eq!(
+            handle_ipc_command(&start, &mut controller, &mut yank_buffer, size),
+            IpcResponse::OkWithData(IpcResponseData::Recording { path: "/tmp/x.cast".to_string() })
+        );
+
+        let stop = IpcCommand::RecordStop { target: id.value() };
+        assert_eq!(
+            handle_ipc_command(&stop, &mut controller, &mut yank_buffer, size),
+            IpcResponse::OkWithData(IpcResponseData::Recording { path: "/tmp/x.cast".to_string() })
+        );
+        assert!(matches!(
+            handle_ipc_command(&stop, &mut controller, &mut yank_buffer, size),
+            IpcResponse::Error(msg) if msg.contains("not being recorded")
+        ));
+    }
+
+    #[test]
+    fn ipc_record_start_without_recorder_fails() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let mut yank_buffer: Option<String> = None;
+        let cmd = IpcCommand::RecordStart { target: id.value(), path: None };
+        let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, TerminalSize::new(80, 24));
+        assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("not available")));
+    }
+
+    #[test]
+    fn ipc_record_commands_not_found() {
+        let mut controller = make_ipc_controller();
+        let mut yank_buffer: Option<String> = None;
+        let size = TerminalSize::new(80, 24);
+        for cmd in [
+            IpcCommand::RecordStart { target: 999, path: None },
+            IpcCommand::RecordStop { target: 999 },
+        ] {
+            let response = handle_ipc_command(&cmd, &mut controller, &mut yank_buffer, size);
+            assert!(matches!(response, IpcResponse::Error(msg) if msg.contains("terminal not found")));
+        }
+    }
+
     // === scrollback_content_rows tests ===
 
     #[test]
@@ -5593,4 +7361,327 @@ mod tests {
         let size = TerminalSize::new(80, 30);
         assert_eq!(scrollback_content_rows(&target, size), 27);
     }
+
+    // =========================================================================
+    // pane_size tests
+    // =========================================================================
+
+    #[test]
+    fn pane_size_subtracts_sidebar_and_cwd_bar() {
+        let size = pane_size(Rect::new(0, 0, 100, 30), false);
+        assert_eq!(size, TerminalSize::new(75, 29));
+    }
+
+    #[test]
+    fn pane_size_with_mini_terminal_subtracts_its_height() {
+        let size = pane_size(Rect::new(0, 0, 100, 30), true);
+        assert_eq!(size, TerminalSize::new(75, 30 - MINI_TERMINAL_HEIGHT - 1));
+    }
+
+    // =========================================================================
+    // spawn_mini_terminal tests
+    // =========================================================================
+
+    #[test]
+    fn spawn_mini_terminal_marks_spawned_and_creates_screen() {
+        let mut controller = make_ipc_controller();
+        let mut mini = MiniTerminalState::new();
+
+        spawn_mini_terminal(&mut controller, &mut mini, 60).unwrap();
+
+        assert!(mini.spawned);
+        assert!(!mini.is_visible(), "spawning alone should not open the pane");
+        let spawned = &controller.usecase().pty_port().spawned;
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].0, mini.terminal_id);
+        assert!(controller.usecase().screen_port().get_cells(mini.terminal_id).is_ok());
+    }
+
+    // =========================================================================
+    // split pane tests
+    // =========================================================================
+
+    #[test]
+    fn resize_panes_sizes_each_pane_and_hidden_terminals_to_full_area() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let id2 = controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+        let hidden = controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+        let mut panes = PaneLayout::new();
+        panes.split(id1, id2, crate::domain::primitive::SplitDirection::Horizontal);
+        let mut applied = HashMap::new();
+
+        let area = Rect::new(25, 0, 81, 30);
+        resize_panes(&mut controller, &panes.layout(area), area, &mut applied);
+
+        let resized = &controller.usecase().pty_port().resized;
+        assert!(resized.contains(&(id1, TerminalSize::new(40, 29))));
+        assert!(resized.contains(&(id2, TerminalSize::new(40, 29))));
+        assert!(resized.contains(&(hidden, TerminalSize::new(81, 29))));
+    }
+
+    #[test]
+    fn resize_panes_skips_unchanged_sizes() {
+        let (mut controller, _id) = make_ipc_controller_with_terminal();
+        let panes = PaneLayout::new();
+        let mut applied = HashMap::new();
+        let area = Rect::new(25, 0, 75, 30);
+
+        resize_panes(&mut controller, &panes.layout(area), area, &mut applied);
+        resize_panes(&mut controller, &panes.layout(area), area, &mut applied);
+        assert_eq!(controller.usecase().pty_port().resized.len(), 1);
+
+        let smaller = Rect::new(25, 0, 75, 20);
+        resize_panes(&mut controller, &panes.layout(smaller), smaller, &mut applied);
+        assert_eq!(controller.usecase().pty_port().resized.len(), 2);
+    }
+
+    #[test]
+    fn resize_panes_forgets_closed_terminals() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        let panes = PaneLayout::new();
+        let mut applied = HashMap::new();
+
+        let area = Rect::new(25, 0, 75, 30);
+        resize_panes(&mut controller, &panes.layout(area), area, &mut applied);
+        controller.usecase_mut().close_by_id(id).unwrap();
+        resize_panes(&mut controller, &panes.layout(area), area, &mut applied);
+
+        assert!(applied.is_empty());
+    }
+
+    #[test]
+    fn frame_pane_rects_zoom_gives_active_the_whole_area_and_keeps_others() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let id2 = controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+        let mut panes = PaneLayout::new();
+        panes.split(id1, id2, crate::domain::primitive::SplitDirection::Horizontal);
+        let split_area = Rect::new(25, 0, 81, 30);
+        let full = Rect::new(0, 0, 106, 30);
+
+        let rects = frame_pane_rects(&panes, split_area, Some((id2, full)));
+
+        assert_eq!(rects.rect_of(id2), Some(full));
+        assert_eq!(rects.rect_of(id1), Some(Rect::new(25, 0, 40, 30)));
+        assert!(rects.dividers.is_empty());
+
+        // Zooming resizes only the zoomed terminal
+        let mut applied = HashMap::new();
+        resize_panes(&mut controller, &panes.layout(split_area), split_area, &mut applied);
+        let before = controller.usecase().pty_port().resized.len();
+        resize_panes(&mut controller, &rects, split_area, &mut applied);
+        assert_eq!(&controller.usecase().pty_port().resized[before..], &[(id2, TerminalSize::new(106, 29))]);
+    }
+
+    #[test]
+    fn frame_pane_rects_zoom_without_split_adds_active_pane() {
+        let panes = PaneLayout::new();
+        let full = Rect::new(0, 0, 100, 30);
+        let rects = frame_pane_rects(&panes, Rect::new(25, 0, 75, 30), Some((TerminalId::new(1), full)));
+        assert_eq!(rects.panes, vec![(TerminalId::new(1), full)]);
+    }
+
+    // =========================================================================
+    // resize_mini_terminal tests
+    // =========================================================================
+
+    #[test]
+    fn resize_mini_terminal_applies_inner_size_once() {
+        let mut controller = make_ipc_controller();
+        let mut mini = MiniTerminalState::new();
+        spawn_mini_terminal(&mut controller, &mut mini, 60).unwrap();
+        let area = Some(Rect::new(0, 20, 80, MINI_TERMINAL_HEIGHT));
+
+        resize_mini_terminal(&mut controller, &mut mini, area);
+        resize_mini_terminal(&mut controller, &mut mini, area);
+
+        let resized = &controller.usecase().pty_port().resized;
+        assert_eq!(resized, &vec![(mini.terminal_id, TerminalSize::new(78, MINI_TERMINAL_HEIGHT - 2))]);
+    }
+
+    #[test]
+    fn resize_mini_terminal_ignores_hidden_or_unspawned() {
+        let mut controller = make_ipc_controller();
+        let mut mini = MiniTerminalState::new();
+
+        resize_mini_terminal(&mut controller, &mut mini, Some(Rect::new(0, 20, 80, 10)));
+        spawn_mini_terminal(&mut controller, &mut mini, 60).unwrap();
+        resize_mini_terminal(&mut controller, &mut mini, None);
+
+        assert!(controller.usecase().pty_port().resized.is_empty());
+    }
+
+    // =========================================================================
+    // mouse tests
+    // =========================================================================
+
+    /// Mutable UI state threaded through `handle_mouse_event`.
+    struct MouseHarness {
+        controller: TuiController<TestPtyPort, TestScreenPort>,
+        input_handler: InputHandler,
+        focus: FocusPane,
+        scrollback_target: Option<ScrollbackTarget>,
+        search_state: Option<SearchState>,
+        ui: UiState,
+    }
+
+    impl MouseHarness {
+        fn new(controller: TuiController<TestPtyPort, TestScreenPort>) -> Self {
+            Self {
+                controller,
+                input_handler: InputHandler::new(),
+                focus: FocusPane::Terminal,
+                scrollback_target: None,
+                search_state: None,
+                ui: UiState::new(),
+            }
+        }
+
+        /// Send one event with the 100x30 layout and the given panes.
+        fn send(&mut self, kind: MouseEventKind, column: u16, row: u16, panes: &PaneLayout) {
+            let areas = layout::compute_layout_with(Rect::new(0, 0, 100, 30), false, &self.ui.view);
+            let rects = panes.layout(areas.main_pane);
+            let mouse = MouseEvent { kind, column, row, modifiers: crossterm::event::KeyModifiers::NONE };
+            handle_mouse_event(
+                mouse,
+                &mut self.controller,
+                &mut self.input_handler,
+                &DialogState::None,
+                &mut self.focus,
+                TerminalSize::new(75, 29),
+                &mut self.scrollback_target,
+                &mut self.search_state,
+                &mut self.ui,
+                &areas,
+                &rects,
+                0,
+            )
+            .unwrap();
+        }
+
+        fn active_id(&self) -> Option<TerminalId> {
+            self.controller.usecase().get_active_terminal().map(|t| t.id())
+        }
+    }
+
+    #[test]
+    fn content_rect_accounts_for_cwd_bar_and_scrollback_border() {
+        let area = Rect::new(25, 0, 75, 30);
+        assert_eq!(content_rect(area, ScrollbackTarget::MainTerminal, false), Rect::new(25, 1, 75, 29));
+        assert_eq!(content_rect(area, ScrollbackTarget::MainTerminal, true), Rect::new(26, 2, 73, 26));
+        assert_eq!(
+            content_rect(Rect::new(0, 20, 80, 10), ScrollbackTarget::MiniTerminal, false),
+            Rect::new(1, 21, 78, 8)
+        );
+    }
+
+    #[test]
+    fn mouse_click_on_sidebar_entry_selects_terminal() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let id2 = controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+        let mut h = MouseHarness::new(controller);
+        h.focus = FocusPane::Sidebar;
+        let panes = PaneLayout::new();
+
+        // Second entry starts 4 lines below the first (row 1 inside the border)
+        h.send(MouseEventKind::Down(MouseButton::Left), 5, 1, &panes);
+        assert_eq!(h.active_id(), Some(id1));
+        h.send(MouseEventKind::Down(MouseButton::Left), 5, 6, &panes);
+        assert_eq!(h.active_id(), Some(id2));
+        assert_eq!(h.focus, FocusPane::Terminal);
+    }
+
+    #[test]
+    fn mouse_drag_on_sidebar_border_resizes_it() {
+        let (controller, _id) = make_ipc_controller_with_terminal();
+        let mut h = MouseHarness::new(controller);
+        let panes = PaneLayout::new();
+        let border = layout::DEFAULT_SIDEBAR_WIDTH - 1;
+
+        h.send(MouseEventKind::Down(MouseButton::Left), border, 10, &panes);
+        assert_eq!(h.ui.mouse_drag, Some(MouseDrag::SidebarBorder));
+        h.send(MouseEventKind::Drag(MouseButton::Left), border + 10, 10, &panes);
+        assert_eq!(h.ui.view.sidebar_width, layout::DEFAULT_SIDEBAR_WIDTH + 10);
+        h.send(MouseEventKind::Up(MouseButton::Left), border + 10, 10, &panes);
+        assert_eq!(h.ui.mouse_drag, None);
+    }
+
+    #[test]
+    fn mouse_click_in_other_pane_focuses_it() {
+        let (mut controller, id1) = make_ipc_controller_with_terminal();
+        let id2 = controller.usecase_mut().create_terminal(None, TerminalSize::new(80, 24)).unwrap();
+        controller.usecase_mut().select_by_id(id1).unwrap();
+        let mut panes = PaneLayout::new();
+        panes.split(id1, id2, crate::domain::primitive::SplitDirection::Horizontal);
+        let mut h = MouseHarness::new(controller);
+
+        // Main area is x 25..100; the right pane starts after the divider
+        h.send(MouseEventKind::Down(MouseButton::Left), 90, 10, &panes);
+        assert_eq!(h.active_id(), Some(id2));
+        assert_eq!(h.focus, FocusPane::Terminal);
+    }
+
+    #[test]
+    fn mouse_events_are_forwarded_when_app_enables_reporting() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        controller.usecase_mut().screen_port_mut().mouse_modes.insert(id.value(), MouseMode::ButtonMotion);
+        let mut h = MouseHarness::new(controller);
+        let panes = PaneLayout::new();
+
+        // Content starts at (25, 1): below the CWD bar, right of the sidebar
+        h.send(MouseEventKind::Down(MouseButton::Left), 25, 1, &panes);
+        h.send(MouseEventKind::Drag(MouseButton::Left), 27, 3, &panes);
+        h.send(MouseEventKind::Up(MouseButton::Left), 27, 3, &panes);
+        h.send(MouseEventKind::ScrollUp, 30, 5, &panes);
+
+        let written: Vec<Vec<u8>> = h.controller.usecase().pty_port().written.iter().map(|(_, d)| d.clone()).collect();
+        assert_eq!(written, vec![
+            b"\x1b[<0;1;1M".to_vec(),
+            b"\x1b[<32;3;3M".to_vec(),
+            b"\x1b[<0;3;3m".to_vec(),
+            b"\x1b[<64;6;5M".to_vec(),
+        ]);
+        assert!(h.scrollback_target.is_none());
+    }
+
+    #[test]
+    fn mouse_wheel_enters_scrollback_and_returns_to_live_view() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        controller.usecase_mut().screen_port_mut().max_scrollback.insert(id.value(), 100);
+        let mut h = MouseHarness::new(controller);
+        let panes = PaneLayout::new();
+
+        h.send(MouseEventKind::ScrollUp, 50, 10, &panes);
+        h.send(MouseEventKind::ScrollUp, 50, 10, &panes);
+        assert_eq!(h.scrollback_target, Some(ScrollbackTarget::MainTerminal));
+        assert!(matches!(h.input_handler.mode(), InputMode::ScrollbackMode));
+        assert_eq!(h.controller.usecase().screen_port().get_scrollback_offset(id).unwrap(), 6);
+
+        h.send(MouseEventKind::ScrollDown, 50, 10, &panes);
+        assert_eq!(h.controller.usecase().screen_port().get_scrollback_offset(id).unwrap(), 3);
+        h.send(MouseEventKind::ScrollDown, 50, 10, &panes);
+        assert_eq!(h.scrollback_target, None);
+        assert!(matches!(h.input_handler.mode(), InputMode::Normal));
+    }
+
+    #[test]
+    fn mouse_drag_selects_and_release_yanks() {
+        let (mut controller, id) = make_ipc_controller_with_terminal();
+        set_screen_text(&mut controller, id, "hello world");
+        let mut h = MouseHarness::new(controller);
+        let panes = PaneLayout::new();
+
+        // Press on "w" of row 0 (content at x 25, y 1), then drag left to "h"
+        h.send(MouseEventKind::Down(MouseButton::Left), 31, 1, &panes);
+        assert!(h.ui.selection_state.is_none());
+        // Scrollback adds a border: row 0 is now at y 2, column 0 at x 26
+        h.send(MouseEventKind::Drag(MouseButton::Left), 26, 2, &panes);
+        assert!(matches!(h.input_handler.mode(), InputMode::VisualSelection));
+        let sel = h.ui.selection_state.as_ref().unwrap();
+        assert_eq!(sel.ordered(), (SelectionPos { row: 0, col: 0 }, SelectionPos { row: 0, col: 6 }));
+
+        h.send(MouseEventKind::Up(MouseButton::Left), 26, 2, &panes);
+        assert_eq!(h.ui.yank_buffer.as_deref(), Some("hello w"));
+        assert!(h.ui.selection_state.is_none());
+        assert!(matches!(h.input_handler.mode(), InputMode::ScrollbackMode));
+    }
 }
@@ -0,0 +1,698 @@
+//! `cm attach`: thin TUI client for a running `cm server`.
+//!
+//! Sends `attach` over the IPC socket and keeps the connection open. The
+//! server streams a `ScreenFrame` whenever the session changes; the client
+//! draws it with the same sidebar and terminal widgets as the TUI and sends
+//! key presses back as `input` commands on the same connection.
+//!
+//! Closing the client (Ctrl+b q) only detaches; the server and its
+//! terminals keep running.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
+
+use crate::domain::model::ManagedTerminal;
+use crate::domain::primitive::{ForegroundProcess, IpcEvent, NotificationEvent, ScreenFrame, TerminalFilter, TerminalId, TerminalStatus};
+use crate::infrastructure::ipc::{protocol, socket_discovery};
+use crate::infrastructure::tui::app_runner::pane_size;
+use crate::infrastructure::tui::input::{self, InputHandler};
+use crate::infrastructure::tui::widgets::{dialog, layout, sidebar, terminal_view};
+use crate::interface_adapter::controller::tui_controller::AppAction;
+
+/// What the client does in response to an `AppAction`.
+#[derive(Debug, PartialEq, Eq)]
+enum ClientStep {
+    /// Send this JSON command to the server.
+    Send(String),
+    /// Ask before killing a running terminal. `process` is what gets hung up.
+    ConfirmClose { id: u32, name: String, process: Option<String> },
+    /// Let the user pick a signal for the active terminal.
+    SignalMenu { id: u32, name: String, process: Option<String> },
+    /// Let the user pick an order to sort the sidebar by.
+    SortMenu,
+    /// Edit the sidebar filter, sending it to the server as it changes.
+    FilterEdit(TerminalFilter),
+    Detach,
+    Ignore,
+}
+
+/// Why the client loop ended.
+enum Exit {
+    Detached,
+    ServerGone,
+}
+
+/// Entry point for `cm attach [--instance <name|pid>]`.
+pub fn run(instance: Option<String>) -> anyhow::Result<()> {
+    let socket_path =
+        socket_discovery::resolve_socket_path(instance.as_deref()).map_err(anyhow::Error::msg)?;
+    let stream = UnixStream::connect(&socket_path)
+        .map_err(|e| anyhow::anyhow!("cannot connect to {socket_path}: {e}"))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+
+    // Attach and check the reply before taking over the screen, so errors
+    // (e.g. the socket belongs to a plain `cm` TUI) print normally.
+    let (cols, rows) = crossterm::terminal::size()?;
+    send_line(&mut writer, &attach_request(Rect::new(0, 0, cols, rows)))?;
+    let mut reply = String::new();
+    reader.read_line(&mut reply)?;
+    check_attach_reply(&reply).map_err(|e| anyhow::anyhow!("cannot attach: {e}"))?;
+
+    let lines = spawn_reader(reader);
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = client_loop(&mut terminal, &mut writer, &lines);
+
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableBracketedPaste);
+    let _ = terminal.show_cursor();
+
+    match result? {
+        Exit::Detached => println!("[detached from {socket_path}]"),
+        Exit::ServerGone => println!("[server exited]"),
+    }
+    Ok(())
+}
+
+fn client_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    writer: &mut UnixStream,
+    lines: &Receiver<String>,
+) -> anyhow::Result<Exit> {
+    let mut frame_state = ScreenFrame::default();
+    let mut input_handler = InputHandler::new();
+    // The prompt (ConfirmClose, SignalMenu, ...) while a dialog is open
+    let mut prompt: Option<ClientStep> = None;
+    let mut sidebar_scroll_offset: usize = 0;
+
+    loop {
+        // 1. Apply frames streamed by the server
+        loop {
+            match lines.try_recv() {
+                Ok(line) => {
+                    if let Ok(IpcEvent::Frame(frame)) = protocol::parse_event(&line) {
+                        input_handler.set_application_cursor_keys(frame.application_cursor_keys);
+                        input_handler.set_keyboard_flags(frame.keyboard_flags);
+                        frame_state = frame;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(Exit::ServerGone),
+            }
+        }
+
+        // 2. Draw
+        terminal.draw(|frame| {
+            let areas = layout::compute_layout(frame.area(), false);
+            let (terminals, cwds) = sidebar_entries(&frame_state);
+            let active_index = frame_state.active_index();
+
+            let sidebar_inner_height = areas.sidebar.height.saturating_sub(2);
+            let content_height = sidebar_inner_height.saturating_sub(1);
+            sidebar_scroll_offset = sidebar::compute_scroll_offset(
+                terminals.len(),
+                &frame_state.groups,
+                active_index,
+                content_height,
+                sidebar_scroll_offset,
+            );
+            sidebar::render(
+                frame,
+                areas.sidebar,
+                &terminals,
+                &frame_state.groups,
+                active_index,
+                false,
+                frame_state.sync,
+                sidebar_scroll_offset,
+                &cwds,
+            );
+            let mut filter_cursor = None;
+            match &prompt {
+                Some(ClientStep::FilterEdit(filter)) => {
+                    filter_cursor = sidebar::render_filter_line(frame, areas.sidebar, &filter.label(), true);
+                }
+                _ if frame_state.filter.is_active() => {
+                    sidebar::render_filter_line(frame, areas.sidebar, &frame_state.filter.label(), false);
+                }
+                _ => {}
+            }
+
+            let active = frame_state.active_window();
+            terminal_view::render(
+                frame,
+                areas.main_pane,
+                active.map(|_| &frame_state.cells),
+                active.map(|_| frame_state.cursor),
+                frame_state.cursor_visible,
+                active.map(|w| w.cwd.as_str()),
+                true,
+                None,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+
+            match &prompt {
+                Some(ClientStep::ConfirmClose { name, process, .. }) => {
+                    dialog::render_confirm_close_dialog(frame, name, process.as_deref(), true);
+                }
+                Some(ClientStep::SignalMenu { name, process, .. }) => {
+                    dialog::render_signal_dialog(frame, name, process.as_deref());
+                }
+                Some(ClientStep::SortMenu) => dialog::render_sort_dialog(frame),
+                _ => {}
+            }
+            if let Some(position) = filter_cursor {
+                frame.set_cursor_position(position);
+            }
+        })?;
+
+        // 3. Prefix timeout
+        if let Some(action) = input_handler.check_timeout()
+            && let ClientStep::Send(json) = step_for_action(action, &frame_state)
+        {
+            send_line(writer, &json)?;
+        }
+
+        // 4. Terminal events
+        if !event::poll(Duration::from_millis(20))? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if let Some(ClientStep::FilterEdit(filter)) = prompt.as_mut() {
+                    let editing = input::edit_filter(filter, key.code);
+                    send_line(writer, &filter_request(filter))?;
+                    if !editing {
+                        prompt = None;
+                    }
+                    continue;
+                }
+                if let Some(open) = prompt.take() {
+                    match prompt_key(&open, key.code) {
+                        Some(Some(json)) => send_line(writer, &json)?,
+                        Some(None) => {}
+                        None => prompt = Some(open),
+                    }
+                    continue;
+                }
+                let Some(action) = input_handler.handle_key(key) else {
+                    continue;
+                };
+                match step_for_action(action, &frame_state) {
+                    ClientStep::Send(json) => send_line(writer, &json)?,
+                    ClientStep::Detach => return Ok(Exit::Detached),
+                    ClientStep::Ignore => {}
+                    step => prompt = Some(step),
+                }
+            }
+            Event::Resize(cols, rows) => {
+                send_line(writer, &attach_request(Rect::new(0, 0, cols, rows)))?;
+            }
+            Event::Paste(text) => {
+                if let Some(id) = frame_state.active {
+                    let data = paste_data(&text, frame_state.bracketed_paste);
+                    send_line(writer, &input_request(id, &data))?;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read server lines on a background thread. The channel disconnects when
+/// the server closes the connection.
+fn spawn_reader(reader: BufReader<UnixStream>) -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in reader.lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn send_line(writer: &mut UnixStream, json: &str) -> io::Result<()> {
+    writer.write_all(json.as_bytes())?;
+    writer.write_all(b"\n")
+}
+
+/// `attach` request carrying the main pane size for a host terminal area.
+fn attach_request(area: Rect) -> String {
+    let size = pane_size(area, false);
+    serde_json::json!({"cmd": "attach", "cols": size.cols, "rows": size.rows}).to_string()
+}
+
+fn input_request(target: u32, data: &str) -> String {
+    serde_json::json!({"cmd": "input", "target": target, "data": data}).to_string()
+}
+
+fn filter_request(filter: &TerminalFilter) -> String {
+    serde_json::json!({"cmd": "filter-windows", "status": filter.status.as_str(), "text": filter.text}).to_string()
+}
+
+/// Interpret the server's reply to `attach`.
+fn check_attach_reply(reply: &str) -> Result<(), String> {
+    if reply.trim().is_empty() {
+        return Err("no reply from server".to_string());
+    }
+    let v: serde_json::Value =
+        serde_json::from_str(reply.trim()).map_err(|e| format!("invalid reply: {e}"))?;
+    if v.get("ok").and_then(|ok| ok.as_bool()) == Some(true) {
+        Ok(())
+    } else {
+        Err(v
+            .get("error")
+            .and_then(|e| e.as_str())
+            .unwrap_or("unknown error")
+            .to_string())
+    }
+}
+
+/// Wrap pasted text in bracketed-paste markers when the program asked for them.
+fn paste_data(text: &str, bracketed: bool) -> String {
+    if bracketed {
+        format!("\x1b[200~{text}\x1b[201~")
+    } else {
+        text.to_string()
+    }
+}
+
+/// What a key does while a dialog is open: `None` keeps the dialog open,
+/// `Some(reply)` closes it and sends `reply`, if any, to the server.
+fn prompt_key(prompt: &ClientStep, key: KeyCode) -> Option<Option<String>> {
+    match (prompt, key) {
+        (_, KeyCode::Esc) => Some(None),
+        (ClientStep::ConfirmClose { id, .. }, KeyCode::Char('y')) => {
+            Some(Some(serde_json::json!({"cmd": "kill-window", "target": id}).to_string()))
+        }
+        (ClientStep::ConfirmClose { .. }, KeyCode::Char('n')) => Some(None),
+        (ClientStep::SignalMenu { id, .. }, KeyCode::Char(c)) => dialog::signal_for_key(c).map(|signal| {
+            Some(serde_json::json!({"cmd": "send-signal", "target": id, "signal": signal.name()}).to_string())
+        }),
+        (ClientStep::SortMenu, KeyCode::Char(c)) => dialog::sort_for_key(c)
+            .map(|key| Some(serde_json::json!({"cmd": "sort-windows", "key": key.as_str()}).to_string())),
+        _ => None,
+    }
+}
+
+/// Map a key action to what the client should do. Actions that need
+/// client-local state the thin client does not keep (scrollback, memo,
+/// mini terminal, dialogs) are ignored.
+fn step_for_action(action: AppAction, frame: &ScreenFrame) -> ClientStep {
+    let select = |index: usize| match frame.windows.get(index) {
+        Some(w) => ClientStep::Send(serde_json::json!({"cmd": "select-window", "target": w.id}).to_string()),
+        None => ClientStep::Ignore,
+    };
+    let count = frame.windows.len();
+
+    match action {
+        AppAction::WriteToActive(bytes) => match (frame.active, String::from_utf8(bytes)) {
+            (Some(id), Ok(data)) => ClientStep::Send(input_request(id, &data)),
+            _ => ClientStep::Ignore,
+        },
+        AppAction::CreateTerminal { name } => {
+            let mut obj = serde_json::json!({"cmd": "create-window"});
+            if let Some(n) = name {
+                obj["name"] = serde_json::json!(n);
+            }
+            // New terminals join the active terminal's group
+            if let Some(group) = frame.active_window().and_then(|w| w.group.as_ref()) {
+                obj["group"] = serde_json::json!(group);
+            }
+            ClientStep::Send(obj.to_string())
+        }
+        AppAction::CloseTerminal => match frame.active_window() {
+            Some(w) if w.status.is_running() => ClientStep::ConfirmClose {
+                id: w.id,
+                name: w.name.clone(),
+                process: w.process.clone(),
+            },
+            Some(w) => ClientStep::Send(serde_json::json!({"cmd": "kill-window", "target": w.id}).to_string()),
+            None => ClientStep::Ignore,
+        },
+        AppAction::SelectNext => match frame.active_index() {
+            Some(i) => select((i + 1) % count),
+            None => ClientStep::Ignore,
+        },
+        AppAction::SelectPrev => match frame.active_index() {
+            Some(i) => select(i.checked_sub(1).unwrap_or(count - 1)),
+            None => ClientStep::Ignore,
+        },
+        AppAction::SelectByIndex(i) => select(i),
+        AppAction::RespawnTerminal => match frame.active_window() {
+            Some(w) if !w.status.is_running() => {
+                ClientStep::Send(serde_json::json!({"cmd": "respawn-window", "target": w.id}).to_string())
+            }
+            _ => ClientStep::Ignore,
+        },
+        AppAction::OpenSignalMenu => match frame.active_window() {
+            Some(w) if w.status.is_running() => ClientStep::SignalMenu {
+                id: w.id,
+                name: w.name.clone(),
+                process: w.process.clone(),
+            },
+            _ => ClientStep::Ignore,
+        },
+        AppAction::MoveTerminal(by) => match frame.active {
+            Some(id) => ClientStep::Send(serde_json::json!({"cmd": "move-window", "target": id, "by": by}).to_string()),
+            None => ClientStep::Ignore,
+        },
+        AppAction::OpenSortMenu => ClientStep::SortMenu,
+        AppAction::EditFilter => ClientStep::FilterEdit(frame.filter.clone()),
+        AppAction::ToggleGroup => match frame.active_window().and_then(|w| w.group.as_ref()) {
+            Some(group) => ClientStep::Send(serde_json::json!({"cmd": "toggle-group", "group": group}).to_string()),
+            None => ClientStep::Ignore,
+        },
+        // Sent as the state the client sees, so a stale frame cannot flip it back
+        AppAction::ToggleSync => {
+            ClientStep::Send(serde_json::json!({"cmd": "sync-input", "enabled": !frame.sync}).to_string())
+        }
+        AppAction::ToggleMark => match frame.active_window() {
+            Some(w) => ClientStep::Send(
+                serde_json::json!({"cmd": "mark-window", "target": w.id, "marked": !w.marked}).to_string(),
+            ),
+            None => ClientStep::Ignore,
+        },
+        AppAction::Quit => ClientStep::Detach,
+        _ => ClientStep::Ignore,
+    }
+}
+
+/// Build the sidebar model (terminals + display cwds) from a frame.
+fn sidebar_entries(frame: &ScreenFrame) -> (Vec<ManagedTerminal>, Vec<Option<String>>) {
+    let terminals = frame
+        .windows
+        .iter()
+        .map(|w| {
+            let mut t = ManagedTerminal::new(TerminalId::new(w.id), w.name.clone(), PathBuf::from(&w.cwd));
+            // Only the name is sent; an idle terminal has its shell in front
+            t.set_foreground(w.process.clone().map(|name| ForegroundProcess {
+                pid: 0,
+                cmdline: name.clone(),
+                name,
+                is_terminal_process: w.status == TerminalStatus::Idle,
+            }));
+            if let Some(at) = Instant::now().checked_sub(Duration::from_secs(w.quiet_mins * 60)) {
+                t.record_output(at);
+            }
+            if let TerminalStatus::Exited(code) = w.status {
+                t.mark_exited(code);
+            }
+            t.set_memo(w.memo.clone());
+            t.set_badge(w.badge.clone());
+            t.set_marked(w.marked);
+            if let Some(summary) = &w.notification {
+                t.set_notification(NotificationEvent::External {
+                    title: "CLI Manager".to_string(),
+                    body: summary.clone(),
+                });
+            }
+            t
+        })
+        .collect();
+    let cwds = frame.windows.iter().map(|w| Some(w.cwd.clone())).collect();
+    (terminals, cwds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::primitive::{Color, FrameWindow, StatusBadge};
+
+    fn window(id: u32, status: TerminalStatus) -> FrameWindow {
+        FrameWindow {
+            id,
+            name: format!("term-{id}"),
+            cwd: "/tmp".to_string(),
+            status,
+            memo: String::new(),
+            badge: None,
+            process: None,
+            quiet_mins: 0,
+            notification: None,
+            group: None,
+            marked: false,
+        }
+    }
+
+    fn frame_with(ids: &[u32], active: u32) -> ScreenFrame {
+        ScreenFrame {
+            windows: ids.iter().map(|id| window(*id, TerminalStatus::Running)).collect(),
+            active: Some(active),
+            ..Default::default()
+        }
+    }
+
+    fn sent(step: ClientStep) -> serde_json::Value {
+        match step {
+            ClientStep::Send(json) => serde_json::from_str(&json).unwrap(),
+            other => panic!("expected Send, got {other:?}"),
+        }
+    }
+
+    // =========================================================================
+    // Tests: step_for_action
+    // =========================================================================
+
+    #[test]
+    fn keys_are_sent_as_input_to_active_terminal() {
+        let frame = frame_with(&[1, 4], 4);
+        let v = sent(step_for_action(AppAction::WriteToActive(b"\x1b[A".to_vec()), &frame));
+        assert_eq!(v["cmd"], "input");
+        assert_eq!(v["target"], 4);
+        assert_eq!(v["data"], "\x1b[A");
+    }
+
+    #[test]
+    fn keys_without_active_terminal_are_ignored() {
+        let frame = ScreenFrame::default();
+        assert_eq!(step_for_action(AppAction::WriteToActive(b"a".to_vec()), &frame), ClientStep::Ignore);
+    }
+
+    #[test]
+    fn select_next_and_prev_wrap_around() {
+        let frame = frame_with(&[1, 4, 9], 9);
+        assert_eq!(sent(step_for_action(AppAction::SelectNext, &frame))["target"], 1);
+        assert_eq!(sent(step_for_action(AppAction::SelectPrev, &frame))["target"], 4);
+    }
+
+    #[test]
+    fn select_by_index_uses_sidebar_order() {
+        let frame = frame_with(&[3, 5], 3);
+        assert_eq!(sent(step_for_action(AppAction::SelectByIndex(1), &frame))["target"], 5);
+        assert_eq!(step_for_action(AppAction::SelectByIndex(7), &frame), ClientStep::Ignore);
+    }
+
+    #[test]
+    fn close_running_terminal_asks_first() {
+        let frame = frame_with(&[2], 2);
+        assert_eq!(
+            step_for_action(AppAction::CloseTerminal, &frame),
+            ClientStep::ConfirmClose { id: 2, name: "term-2".to_string(), process: None }
+        );
+    }
+
+    #[test]
+    fn confirm_close_prompt_kills_on_yes_only() {
+        let prompt = ClientStep::ConfirmClose { id: 2, name: "term-2".to_string(), process: Some("vim".to_string()) };
+        let reply = prompt_key(&prompt, KeyCode::Char('y')).unwrap().unwrap();
+        let v: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!((v["cmd"].as_str(), v["target"].as_u64()), (Some("kill-window"), Some(2)));
+        assert_eq!(prompt_key(&prompt, KeyCode::Char('n')), Some(None));
+        assert_eq!(prompt_key(&prompt, KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn signal_menu_sends_picked_signal() {
+        let frame = frame_with(&[4], 4);
+        let prompt = step_for_action(AppAction::OpenSignalMenu, &frame);
+        assert!(matches!(prompt, ClientStep::SignalMenu { id: 4, .. }));
+
+        let reply = prompt_key(&prompt, KeyCode::Char('i')).unwrap().unwrap();
+        let v: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(v["cmd"], "send-signal");
+        assert_eq!(v["target"], 4);
+        assert_eq!(v["signal"], "SIGINT");
+        assert_eq!(prompt_key(&prompt, KeyCode::Char('z')), None);
+        assert_eq!(prompt_key(&prompt, KeyCode::Esc), Some(None));
+    }
+
+    #[test]
+    fn move_sort_and_filter_actions() {
+        let mut frame = frame_with(&[4, 6], 6);
+        let v = sent(step_for_action(AppAction::MoveTerminal(-1), &frame));
+        assert_eq!((v["cmd"].as_str(), v["target"].as_u64(), v["by"].as_i64()), (Some("move-window"), Some(6), Some(-1)));
+
+        let prompt = step_for_action(AppAction::OpenSortMenu, &frame);
+        let reply = prompt_key(&prompt, KeyCode::Char('u')).unwrap().unwrap();
+        let v: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!((v["cmd"].as_str(), v["key"].as_str()), (Some("sort-windows"), Some("unread")));
+
+        // Editing starts from the server's filter
+        frame.filter.text = "api".to_string();
+        assert_eq!(step_for_action(AppAction::EditFilter, &frame), ClientStep::FilterEdit(frame.filter.clone()));
+        let v: serde_json::Value = serde_json::from_str(&filter_request(&frame.filter)).unwrap();
+        assert_eq!(v, serde_json::json!({"cmd": "filter-windows", "status": "all", "text": "api"}));
+    }
+
+    #[test]
+    fn close_exited_terminal_kills_immediately() {
+        let frame = ScreenFrame {
+            windows: vec![window(2, TerminalStatus::Exited(0))],
+            active: Some(2),
+            ..Default::default()
+        };
+        let v = sent(step_for_action(AppAction::CloseTerminal, &frame));
+        assert_eq!(v["cmd"], "kill-window");
+        assert_eq!(v["target"], 2);
+    }
+
+    #[test]
+    fn respawn_only_sent_for_exited_terminal() {
+        let frame = frame_with(&[2], 2);
+        assert_eq!(step_for_action(AppAction::RespawnTerminal, &frame), ClientStep::Ignore);
+
+        let frame = ScreenFrame {
+            windows: vec![window(2, TerminalStatus::Exited(1))],
+            active: Some(2),
+            ..Default::default()
+        };
+        let v = sent(step_for_action(AppAction::RespawnTerminal, &frame));
+        assert_eq!(v["cmd"], "respawn-window");
+        assert_eq!(v["target"], 2);
+    }
+
+    #[test]
+    fn quit_detaches() {
+        let frame = frame_with(&[1], 1);
+        assert_eq!(step_for_action(AppAction::Quit, &frame), ClientStep::Detach);
+    }
+
+    #[test]
+    fn create_terminal_sends_create_window() {
+        let v = sent(step_for_action(AppAction::CreateTerminal { name: None }, &ScreenFrame::default()));
+        assert_eq!(v["cmd"], "create-window");
+        assert!(v.get("group").is_none());
+    }
+
+    #[test]
+    fn group_actions_use_the_active_window_group() {
+        let mut frame = frame_with(&[1, 2], 2);
+        assert_eq!(step_for_action(AppAction::ToggleGroup, &frame), ClientStep::Ignore);
+
+        frame.windows[1].group = Some("api".to_string());
+        let v = sent(step_for_action(AppAction::ToggleGroup, &frame));
+        assert_eq!(v, serde_json::json!({"cmd": "toggle-group", "group": "api"}));
+
+        let v = sent(step_for_action(AppAction::CreateTerminal { name: None }, &frame));
+        assert_eq!(v["group"], "api");
+    }
+
+    #[test]
+    fn sync_actions_send_the_opposite_of_the_frame_state() {
+        let mut frame = frame_with(&[1, 2], 2);
+        let v = sent(step_for_action(AppAction::ToggleSync, &frame));
+        assert_eq!(v, serde_json::json!({"cmd": "sync-input", "enabled": true}));
+        let v = sent(step_for_action(AppAction::ToggleMark, &frame));
+        assert_eq!(v, serde_json::json!({"cmd": "mark-window", "target": 2, "marked": true}));
+
+        frame.sync = true;
+        frame.windows[1].marked = true;
+        let v = sent(step_for_action(AppAction::ToggleSync, &frame));
+        assert_eq!(v["enabled"], false);
+        let v = sent(step_for_action(AppAction::ToggleMark, &frame));
+        assert_eq!(v["marked"], false);
+    }
+
+    // =========================================================================
+    // Tests: helpers
+    // =========================================================================
+
+    #[test]
+    fn attach_reply_ok() {
+        assert!(check_attach_reply("{\"ok\":true}\n").is_ok());
+    }
+
+    #[test]
+    fn attach_reply_error_message() {
+        let err = check_attach_reply(r#"{"ok":false,"error":"only supported by `cm server`"}"#).unwrap_err();
+        assert!(err.contains("cm server"));
+    }
+
+    #[test]
+    fn attach_reply_empty_means_no_server_reply() {
+        assert_eq!(check_attach_reply("").unwrap_err(), "no reply from server");
+    }
+
+    #[test]
+    fn attach_request_excludes_sidebar() {
+        let v: serde_json::Value = serde_json::from_str(&attach_request(Rect::new(0, 0, 120, 40))).unwrap();
+        assert_eq!(v["cmd"], "attach");
+        assert!(v["cols"].as_u64().unwrap() < 120);
+        assert_eq!(v["rows"], 39);
+    }
+
+    #[test]
+    fn paste_is_bracketed_only_when_requested() {
+        assert_eq!(paste_data("ls", false), "ls");
+        assert_eq!(paste_data("ls", true), "\x1b[200~ls\x1b[201~");
+    }
+
+    #[test]
+    fn sidebar_entries_carry_status_memo_badge_and_unread() {
+        let mut frame = frame_with(&[1], 1);
+        let badge = StatusBadge { text: "done".to_string(), icon: None, color: Color::Indexed(2) };
+        frame.windows.push(FrameWindow {
+            memo: "notes".to_string(),
+            badge: Some(badge.clone()),
+            notification: Some("Bell".to_string()),
+            ..window(2, TerminalStatus::Exited(3))
+        });
+        frame.windows.push(FrameWindow {
+            process: Some("zsh".to_string()),
+            quiet_mins: 12,
+            ..window(3, TerminalStatus::Idle)
+        });
+        frame.windows.push(FrameWindow {
+            process: Some("vim".to_string()),
+            ..window(4, TerminalStatus::Running)
+        });
+
+        let (terminals, cwds) = sidebar_entries(&frame);
+
+        assert_eq!(terminals.len(), 4);
+        assert!(terminals[1].has_memo());
+        assert_eq!(terminals[1].badge(), Some(&badge));
+        assert!(terminals[1].has_unread_notification());
+        assert_eq!(terminals[1].status(), &TerminalStatus::Exited(3));
+        assert_eq!(terminals[2].status(), &TerminalStatus::Idle);
+        assert_eq!(terminals[2].activity_text(Instant::now()), "idle · quiet 12m");
+        assert_eq!(terminals[3].activity_text(Instant::now()), "vim running");
+        assert_eq!(cwds[0].as_deref(), Some("/tmp"));
+    }
+}
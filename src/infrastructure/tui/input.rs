@@ -1,7 +1,8 @@
 use std::time::Instant;
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
+use crate::domain::primitive::{MouseMode, PaneDirection, SplitDirection, TerminalFilter};
 use crate::interface_adapter::controller::tui_controller::AppAction;
 
 /// Represents the current mode of the input handler state machine.
@@ -36,6 +37,7 @@ pub enum InputMode {
 pub struct InputHandler {
     mode: InputMode,
     application_cursor_keys: bool,
+    keyboard_flags: u16,
     pending_target: Option<u32>,
 }
 
@@ -44,6 +46,7 @@ impl InputHandler {
         Self {
             mode: InputMode::Normal,
             application_cursor_keys: false,
+            keyboard_flags: 0,
             pending_target: None,
         }
     }
@@ -67,6 +70,14 @@ impl InputHandler {
         self.application_cursor_keys = enabled;
     }
 
+    /// Set the kitty keyboard protocol flags requested by the focused terminal.
+    ///
+    /// With the "disambiguate" flag set, Esc and modified Enter/Tab/Backspace
+    /// and Ctrl/Alt+character keys are sent as `CSI code ; modifiers u`.
+    pub fn set_keyboard_flags(&mut self, flags: u16) {
+        self.keyboard_flags = flags;
+    }
+
     /// Main entry point: translate a `KeyEvent` into an optional `AppAction`.
     ///
     /// Returns `None` when the key should be silently consumed (e.g. entering
@@ -114,7 +125,7 @@ impl InputHandler {
         }
 
         // All other keys -> forward to active terminal as raw bytes
-        let bytes = key_to_bytes(key, self.application_cursor_keys);
+        let bytes = key_to_bytes(key, self.application_cursor_keys, self.keyboard_flags);
         if bytes.is_empty() {
             None
         } else {
@@ -202,6 +213,28 @@ impl InputHandler {
             KeyCode::Char('`') if key.modifiers.is_empty() => Some(AppAction::ToggleMiniTerminal),
             KeyCode::Char('f') if key.modifiers.is_empty() => Some(AppAction::OpenQuickSwitcher),
             KeyCode::Char(']') if key.modifiers.is_empty() => Some(AppAction::PasteYankBuffer),
+            // Shift is reported inconsistently across terminals; accept both
+            KeyCode::Char('R') => Some(AppAction::RespawnTerminal),
+            KeyCode::Char('P') => Some(AppAction::ToggleRecording),
+            KeyCode::Char('k') if key.modifiers.is_empty() => Some(AppAction::OpenSignalMenu),
+            KeyCode::Char('{') => Some(AppAction::MoveTerminal(-1)),
+            KeyCode::Char('}') => Some(AppAction::MoveTerminal(1)),
+            KeyCode::Char('S') => Some(AppAction::OpenSortMenu),
+            KeyCode::Char('/') if key.modifiers.is_empty() => Some(AppAction::EditFilter),
+            KeyCode::Char('g') if key.modifiers.is_empty() => Some(AppAction::ToggleGroup),
+            KeyCode::Char('G') => Some(AppAction::SetGroup { group: String::new() }),
+            KeyCode::Char('y') if key.modifiers.is_empty() => Some(AppAction::ToggleSync),
+            KeyCode::Char('M') => Some(AppAction::ToggleMark),
+            KeyCode::Char('%') => Some(AppAction::SplitPane(SplitDirection::Horizontal)),
+            KeyCode::Char('"') => Some(AppAction::SplitPane(SplitDirection::Vertical)),
+            KeyCode::Char('s') if key.modifiers.is_empty() => Some(AppAction::ToggleSidebar),
+            KeyCode::Char('>') => Some(AppAction::WidenSidebar),
+            KeyCode::Char('<') => Some(AppAction::NarrowSidebar),
+            KeyCode::Char('z') if key.modifiers.is_empty() => Some(AppAction::ToggleZoom),
+            KeyCode::Left if key.modifiers.is_empty() => Some(AppAction::SelectPane(PaneDirection::Left)),
+            KeyCode::Right if key.modifiers.is_empty() => Some(AppAction::SelectPane(PaneDirection::Right)),
+            KeyCode::Up if key.modifiers.is_empty() => Some(AppAction::SelectPane(PaneDirection::Up)),
+            KeyCode::Down if key.modifiers.is_empty() => Some(AppAction::SelectPane(PaneDirection::Down)),
             // Ctrl+b again -> send literal Ctrl+b to child process
             KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 Some(AppAction::WriteToActive(vec![0x02]))
@@ -218,7 +251,7 @@ impl InputHandler {
         }
 
         // All other keys -> forward to mini terminal as raw bytes
-        let bytes = key_to_bytes(key, self.application_cursor_keys);
+        let bytes = key_to_bytes(key, self.application_cursor_keys, self.keyboard_flags);
         if bytes.is_empty() {
             None
         } else {
@@ -227,75 +260,215 @@ impl InputHandler {
     }
 }
 
-/// Convert a `KeyEvent` to the bytes that should be sent to the pty.
+/// Kitty keyboard protocol flag: disambiguate escape codes.
+const KITTY_DISAMBIGUATE: u16 = 0b1;
+
+/// Convert a `KeyEvent` to the bytes that should be sent to the pty, using
+/// xterm's encoding.
 ///
-/// When `application_cursor_keys` is true (DECCKM enabled), arrow keys send
-/// `ESC O A/B/C/D` instead of `ESC [ A/B/C/D`, and Home/End send
-/// `ESC O H`/`ESC O F` instead of `ESC [ H`/`ESC [ F`.
+/// - Alt+key prefixes the key's bytes with `ESC`.
+/// - Modified cursor and editing keys use the xterm modifier parameter,
+///   e.g. Ctrl+Right is `CSI 1;5C` and Shift+PageUp is `CSI 5;2~`.
+/// - When `application_cursor_keys` is true (DECCKM enabled), unmodified
+///   arrow keys send `ESC O A/B/C/D` instead of `ESC [ A/B/C/D`, and Home/End
+///   send `ESC O H`/`ESC O F` instead of `ESC [ H`/`ESC [ F`.
+/// - When the child enabled the kitty keyboard protocol (`keyboard_flags`),
+///   keys that are ambiguous in legacy encoding use `CSI code ; modifiers u`.
 ///
 /// Returns an empty `Vec` for key codes that have no meaningful byte
 /// representation (e.g. modifier-only presses).
-fn key_to_bytes(key: KeyEvent, application_cursor_keys: bool) -> Vec<u8> {
-    match key.code {
-        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            // Control codes: Ctrl+a = 0x01, Ctrl+b = 0x02, ...
-            let lower = c.to_ascii_lowercase();
-            vec![lower as u8 - b'a' + 1]
+pub(crate) fn key_to_bytes(key: KeyEvent, application_cursor_keys: bool, keyboard_flags: u16) -> Vec<u8> {
+    if keyboard_flags & KITTY_DISAMBIGUATE != 0
+        && let Some(bytes) = kitty_key_bytes(key)
+    {
+        return bytes;
+    }
+
+    let modifier = modifier_param(key.modifiers);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let with_alt = |mut bytes: Vec<u8>| {
+        if alt {
+            bytes.insert(0, 0x1B);
         }
+        bytes
+    };
+
+    match key.code {
         KeyCode::Char(c) => {
-            let mut buf = [0u8; 4];
-            let s = c.encode_utf8(&mut buf);
-            s.as_bytes().to_vec()
+            let bytes = match ctrl_byte(c).filter(|_| ctrl) {
+                Some(b) => vec![b],
+                None => c.to_string().into_bytes(),
+            };
+            with_alt(bytes)
         }
-        KeyCode::Enter => vec![0x0D],
-        KeyCode::Backspace => vec![0x7F],
-        KeyCode::Tab => vec![0x09],
-        KeyCode::Esc => vec![0x1B],
-        KeyCode::Up => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'A']
-            } else {
-                vec![0x1B, b'[', b'A']
-            }
-        }
-        KeyCode::Down => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'B']
-            } else {
-                vec![0x1B, b'[', b'B']
-            }
+        KeyCode::Enter => with_alt(vec![0x0D]),
+        KeyCode::Backspace => with_alt(vec![if ctrl { 0x08 } else { 0x7F }]),
+        KeyCode::Tab if shift => with_alt(b"\x1b[Z".to_vec()),
+        KeyCode::Tab => with_alt(vec![0x09]),
+        KeyCode::BackTab => with_alt(b"\x1b[Z".to_vec()),
+        KeyCode::Esc => with_alt(vec![0x1B]),
+        KeyCode::Up => cursor_key(b'A', modifier, application_cursor_keys),
+        KeyCode::Down => cursor_key(b'B', modifier, application_cursor_keys),
+        KeyCode::Right => cursor_key(b'C', modifier, application_cursor_keys),
+        KeyCode::Left => cursor_key(b'D', modifier, application_cursor_keys),
+        KeyCode::Home => cursor_key(b'H', modifier, application_cursor_keys),
+        KeyCode::End => cursor_key(b'F', modifier, application_cursor_keys),
+        KeyCode::Insert => tilde_key(2, modifier),
+        KeyCode::Delete => tilde_key(3, modifier),
+        KeyCode::PageUp => tilde_key(5, modifier),
+        KeyCode::PageDown => tilde_key(6, modifier),
+        // F1-F4 use SS3 P/Q/R/S, or CSI 1;m P/Q/R/S when modified
+        KeyCode::F(n @ 1..=4) => cursor_key(b'P' + (n - 1), modifier, true),
+        KeyCode::F(n @ 5..=12) => {
+            const CODES: [u8; 8] = [15, 17, 18, 19, 20, 21, 23, 24];
+            tilde_key(CODES[usize::from(n - 5)], modifier)
         }
-        KeyCode::Right => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'C']
-            } else {
-                vec![0x1B, b'[', b'C']
-            }
+        _ => Vec::new(),
+    }
+}
+
+/// xterm / kitty modifier parameter: 1 + Shift(1) + Alt(2) + Ctrl(4) + Super(8).
+fn modifier_param(modifiers: KeyModifiers) -> u8 {
+    let mut param = 1;
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        param += 1;
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        param += 2;
+    }
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        param += 4;
+    }
+    if modifiers.contains(KeyModifiers::SUPER) {
+        param += 8;
+    }
+    param
+}
+
+/// Control byte for Ctrl+`c`, following xterm (Ctrl+a = 0x01, Ctrl+[ = ESC,
+/// Ctrl+2 = NUL, ...). `None` for characters without a control code.
+pub(crate) fn ctrl_byte(c: char) -> Option<u8> {
+    match c {
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 1),
+        '@' | ' ' | '2' => Some(0x00),
+        '[' | '3' => Some(0x1B),
+        '\\' | '4' => Some(0x1C),
+        ']' | '5' => Some(0x1D),
+        '^' | '6' => Some(0x1E),
+        '_' | '/' | '-' | '7' => Some(0x1F),
+        '?' | '8' => Some(0x7F),
+        _ => None,
+    }
+}
+
+/// Cursor-style key: `ESC O x` / `ESC [ x` unmodified, `CSI 1 ; m x` modified.
+fn cursor_key(final_byte: u8, modifier: u8, ss3: bool) -> Vec<u8> {
+    if modifier > 1 {
+        format!("\x1b[1;{}{}", modifier, final_byte as char).into_bytes()
+    } else if ss3 {
+        vec![0x1B, b'O', final_byte]
+    } else {
+        vec![0x1B, b'[', final_byte]
+    }
+}
+
+/// Tilde-style key: `CSI n ~`, or `CSI n ; m ~` when modified.
+fn tilde_key(code: u8, modifier: u8) -> Vec<u8> {
+    if modifier > 1 {
+        format!("\x1b[{};{}~", code, modifier).into_bytes()
+    } else {
+        format!("\x1b[{}~", code).into_bytes()
+    }
+}
+
+/// Kitty "disambiguate escape codes" encoding for keys whose legacy bytes
+/// are ambiguous. `None` means the legacy encoding is used as is.
+fn kitty_key_bytes(key: KeyEvent) -> Option<Vec<u8>> {
+    let mut modifiers = key.modifiers;
+    let code = match key.code {
+        KeyCode::Esc => 27,
+        KeyCode::Enter if !modifiers.is_empty() => 13,
+        KeyCode::Tab if !modifiers.is_empty() => 9,
+        KeyCode::BackTab => {
+            modifiers |= KeyModifiers::SHIFT;
+            9
         }
-        KeyCode::Left => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'D']
-            } else {
-                vec![0x1B, b'[', b'D']
-            }
+        KeyCode::Backspace if !modifiers.is_empty() => 127,
+        KeyCode::Char(c)
+            if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER) =>
+        {
+            // Keys are reported by their unshifted code point
+            c.to_ascii_lowercase() as u32
         }
-        KeyCode::Home => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'H']
-            } else {
-                vec![0x1B, b'[', b'H']
-            }
+        _ => return None,
+    };
+    let modifier = modifier_param(modifiers);
+    let seq = if modifier > 1 {
+        format!("\x1b[{};{}u", code, modifier)
+    } else {
+        format!("\x1b[{}u", code)
+    };
+    Some(seq.into_bytes())
+}
+
+/// Encode a mouse event as an SGR (DECSET 1006) report for an application
+/// that enabled mouse reporting.
+///
+/// `col` / `row` are 0-based cell coordinates relative to the terminal's
+/// content area. Returns `None` when `mode` does not report this kind of
+/// event (e.g. releases in X10 mode, motion without `AnyMotion`).
+pub fn mouse_to_sgr(event: MouseEvent, col: u16, row: u16, mode: MouseMode) -> Option<Vec<u8>> {
+    let button_code = |button: MouseButton| match button {
+        MouseButton::Left => 0,
+        MouseButton::Middle => 1,
+        MouseButton::Right => 2,
+    };
+    let (code, release) = match event.kind {
+        MouseEventKind::Down(button) if mode.is_enabled() => (button_code(button), false),
+        MouseEventKind::Up(button) if mode.reports_release() => (button_code(button), true),
+        MouseEventKind::Drag(button) if mode.reports_drag() => (button_code(button) + 32, false),
+        MouseEventKind::Moved if mode.reports_motion() => (35, false),
+        MouseEventKind::ScrollUp if mode.is_enabled() => (64, false),
+        MouseEventKind::ScrollDown if mode.is_enabled() => (65, false),
+        MouseEventKind::ScrollLeft if mode.is_enabled() => (66, false),
+        MouseEventKind::ScrollRight if mode.is_enabled() => (67, false),
+        _ => return None,
+    };
+    let mut code = code;
+    if event.modifiers.contains(KeyModifiers::SHIFT) {
+        code += 4;
+    }
+    if event.modifiers.contains(KeyModifiers::ALT) {
+        code += 8;
+    }
+    if event.modifiers.contains(KeyModifiers::CONTROL) {
+        code += 16;
+    }
+    let suffix = if release { 'm' } else { 'M' };
+    Some(format!("\x1b[<{};{};{}{}", code, col + 1, row + 1, suffix).into_bytes())
+}
+
+/// Apply a key pressed while editing the filter line: typing narrows the
+/// text, Tab cycles the status filter, Enter keeps the filter and Esc
+/// clears it. Returns whether editing goes on.
+pub fn edit_filter(filter: &mut TerminalFilter, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char(c) => filter.text.push(c),
+        KeyCode::Backspace => {
+            filter.text.pop();
         }
-        KeyCode::End => {
-            if application_cursor_keys {
-                vec![0x1B, b'O', b'F']
-            } else {
-                vec![0x1B, b'[', b'F']
-            }
+        KeyCode::Tab => filter.status = filter.status.next(),
+        KeyCode::Enter => return false,
+        KeyCode::Esc => {
+            *filter = TerminalFilter::default();
+            return false;
         }
-        KeyCode::Delete => vec![0x1B, b'[', b'3', b'~'],
-        _ => Vec::new(),
+        _ => {}
     }
+    true
 }
 
 // =============================================================================
@@ -552,8 +725,8 @@ mod tests {
     #[test]
     fn normal_unhandled_key_returns_none() {
         let mut handler = InputHandler::new();
-        // F1 key is not mapped
-        let key = make_key(KeyCode::F(1), KeyModifiers::NONE);
+        // Caps Lock has no byte representation
+        let key = make_key(KeyCode::CapsLock, KeyModifiers::NONE);
 
         let action = handler.handle_key(key);
 
@@ -677,7 +850,7 @@ mod tests {
         let mut handler = InputHandler::new();
         enter_prefix(&mut handler);
 
-        let key = make_key(KeyCode::Char('z'), KeyModifiers::NONE);
+        let key = make_key(KeyCode::Char('j'), KeyModifiers::NONE);
         let action = handler.handle_key(key);
 
         assert!(action.is_none());
@@ -1142,6 +1315,109 @@ mod tests {
         assert!(matches!(action, Some(AppAction::WriteToActive(ref b)) if b == &[0x1B, b'[', b'A']));
     }
 
+    // =========================================================================
+    // Tests: xterm key encoding (function keys, modifiers, Alt)
+    // =========================================================================
+
+    /// Helper: bytes written to the active terminal for a Normal-mode key.
+    fn bytes_for(handler: &mut InputHandler, code: KeyCode, modifiers: KeyModifiers) -> Vec<u8> {
+        match handler.handle_key(make_key(code, modifiers)) {
+            Some(AppAction::WriteToActive(bytes)) => bytes,
+            _ => panic!("expected WriteToActive"),
+        }
+    }
+
+    #[test]
+    fn function_keys_use_xterm_sequences() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(1), KeyModifiers::NONE), b"\x1bOP");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(4), KeyModifiers::NONE), b"\x1bOS");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(5), KeyModifiers::NONE), b"\x1b[15~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(6), KeyModifiers::NONE), b"\x1b[17~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(12), KeyModifiers::NONE), b"\x1b[24~");
+    }
+
+    #[test]
+    fn modified_function_keys_carry_modifier_param() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(1), KeyModifiers::SHIFT), b"\x1b[1;2P");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(5), KeyModifiers::CONTROL), b"\x1b[15;5~");
+    }
+
+    #[test]
+    fn editing_keys_use_tilde_sequences() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::Insert, KeyModifiers::NONE), b"\x1b[2~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Delete, KeyModifiers::NONE), b"\x1b[3~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::PageUp, KeyModifiers::NONE), b"\x1b[5~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::PageDown, KeyModifiers::NONE), b"\x1b[6~");
+        assert_eq!(bytes_for(&mut handler, KeyCode::PageUp, KeyModifiers::SHIFT), b"\x1b[5;2~");
+    }
+
+    #[test]
+    fn modified_arrows_use_csi_1_m_form() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::Right, KeyModifiers::CONTROL), b"\x1b[1;5C");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Left, KeyModifiers::ALT), b"\x1b[1;3D");
+        assert_eq!(
+            bytes_for(&mut handler, KeyCode::Up, KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+            b"\x1b[1;6A"
+        );
+        assert_eq!(bytes_for(&mut handler, KeyCode::End, KeyModifiers::SHIFT), b"\x1b[1;2F");
+
+        // Modified arrows ignore DECCKM
+        handler.set_application_cursor_keys(true);
+        assert_eq!(bytes_for(&mut handler, KeyCode::Right, KeyModifiers::CONTROL), b"\x1b[1;5C");
+    }
+
+    #[test]
+    fn alt_key_prefixes_escape() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char('f'), KeyModifiers::ALT), b"\x1bf");
+        assert_eq!(
+            bytes_for(&mut handler, KeyCode::Char('x'), KeyModifiers::ALT | KeyModifiers::CONTROL),
+            b"\x1b\x18"
+        );
+        assert_eq!(bytes_for(&mut handler, KeyCode::Backspace, KeyModifiers::ALT), b"\x1b\x7f");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Enter, KeyModifiers::ALT), b"\x1b\r");
+    }
+
+    #[test]
+    fn shift_tab_sends_csi_z() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::BackTab, KeyModifiers::SHIFT), b"\x1b[Z");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Tab, KeyModifiers::SHIFT), b"\x1b[Z");
+    }
+
+    #[test]
+    fn ctrl_punctuation_maps_to_control_bytes() {
+        let mut handler = InputHandler::new();
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char(' '), KeyModifiers::CONTROL), [0x00]);
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char(']'), KeyModifiers::CONTROL), [0x1D]);
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char('_'), KeyModifiers::CONTROL), [0x1F]);
+        assert_eq!(bytes_for(&mut handler, KeyCode::Backspace, KeyModifiers::CONTROL), [0x08]);
+        // No control code: the character itself is sent
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char('1'), KeyModifiers::CONTROL), b"1");
+    }
+
+    #[test]
+    fn kitty_disambiguate_encodes_ambiguous_keys_as_csi_u() {
+        let mut handler = InputHandler::new();
+        handler.set_keyboard_flags(1);
+
+        assert_eq!(bytes_for(&mut handler, KeyCode::Esc, KeyModifiers::NONE), b"\x1b[27u");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char('i'), KeyModifiers::CONTROL), b"\x1b[105;5u");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Enter, KeyModifiers::SHIFT), b"\x1b[13;2u");
+        assert_eq!(bytes_for(&mut handler, KeyCode::BackTab, KeyModifiers::SHIFT), b"\x1b[9;2u");
+        // Unambiguous keys keep the legacy encoding
+        assert_eq!(bytes_for(&mut handler, KeyCode::Char('a'), KeyModifiers::NONE), b"a");
+        assert_eq!(bytes_for(&mut handler, KeyCode::Enter, KeyModifiers::NONE), b"\r");
+        assert_eq!(bytes_for(&mut handler, KeyCode::F(5), KeyModifiers::NONE), b"\x1b[15~");
+
+        handler.set_keyboard_flags(0);
+        assert_eq!(bytes_for(&mut handler, KeyCode::Esc, KeyModifiers::NONE), b"\x1b");
+    }
+
     // =========================================================================
     // Tests: Scrollback mode
     // =========================================================================
@@ -1527,8 +1803,8 @@ mod tests {
         let mut handler = InputHandler::new();
         handler.set_mode(InputMode::MiniTerminalInput);
 
-        // F1 key is not mapped in key_to_bytes
-        let key = make_key(KeyCode::F(1), KeyModifiers::NONE);
+        // Caps Lock is not mapped in key_to_bytes
+        let key = make_key(KeyCode::CapsLock, KeyModifiers::NONE);
         let action = handler.handle_key(key);
 
         assert!(action.is_none());
@@ -1692,6 +1968,185 @@ mod tests {
         assert_scrollback_mode(&handler);
     }
 
+    // =========================================================================
+    // Tests: Prefix P recording toggle / R respawn
+    // =========================================================================
+
+    #[test]
+    fn prefix_shift_p_toggles_recording() {
+        for modifiers in [KeyModifiers::SHIFT, KeyModifiers::NONE] {
+            let mut handler = InputHandler::new();
+            enter_prefix(&mut handler);
+
+            let action = handler.handle_key(make_key(KeyCode::Char('P'), modifiers));
+
+            assert!(matches!(action, Some(AppAction::ToggleRecording)));
+            assert_normal(&handler);
+        }
+    }
+
+    #[test]
+    fn prefix_shift_r_respawns_terminal() {
+        for modifiers in [KeyModifiers::SHIFT, KeyModifiers::NONE] {
+            let mut handler = InputHandler::new();
+            enter_prefix(&mut handler);
+
+            let action = handler.handle_key(make_key(KeyCode::Char('R'), modifiers));
+
+            assert!(matches!(action, Some(AppAction::RespawnTerminal)));
+            assert_normal(&handler);
+        }
+    }
+
+    #[test]
+    fn prefix_k_opens_signal_menu() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+
+        let action = handler.handle_key(make_key(KeyCode::Char('k'), KeyModifiers::NONE));
+
+        assert!(matches!(action, Some(AppAction::OpenSignalMenu)));
+        assert_normal(&handler);
+    }
+
+    #[test]
+    fn prefix_braces_move_terminal() {
+        for (c, delta) in [('{', -1), ('}', 1)] {
+            let mut handler = InputHandler::new();
+            enter_prefix(&mut handler);
+
+            let action = handler.handle_key(make_key(KeyCode::Char(c), KeyModifiers::SHIFT));
+
+            assert!(matches!(action, Some(AppAction::MoveTerminal(d)) if d == delta));
+            assert_normal(&handler);
+        }
+    }
+
+    #[test]
+    fn prefix_sort_and_filter_keys() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('S'), KeyModifiers::SHIFT));
+        assert!(matches!(action, Some(AppAction::OpenSortMenu)));
+
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('/'), KeyModifiers::NONE));
+        assert!(matches!(action, Some(AppAction::EditFilter)));
+        assert_normal(&handler);
+    }
+
+    #[test]
+    fn prefix_group_keys() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert!(matches!(action, Some(AppAction::ToggleGroup)));
+
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('G'), KeyModifiers::SHIFT));
+        assert!(matches!(action, Some(AppAction::SetGroup { .. })));
+        assert_normal(&handler);
+    }
+
+    #[test]
+    fn prefix_sync_keys() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(matches!(action, Some(AppAction::ToggleSync)));
+
+        enter_prefix(&mut handler);
+        let action = handler.handle_key(make_key(KeyCode::Char('M'), KeyModifiers::SHIFT));
+        assert!(matches!(action, Some(AppAction::ToggleMark)));
+        assert_normal(&handler);
+    }
+
+    // =========================================================================
+    // Tests: Prefix split panes
+    // =========================================================================
+
+    #[test]
+    fn prefix_percent_and_quote_split_pane() {
+        for (c, direction) in [('%', SplitDirection::Horizontal), ('"', SplitDirection::Vertical)] {
+            for modifiers in [KeyModifiers::SHIFT, KeyModifiers::NONE] {
+                let mut handler = InputHandler::new();
+                enter_prefix(&mut handler);
+
+                let action = handler.handle_key(make_key(KeyCode::Char(c), modifiers));
+
+                assert!(matches!(action, Some(AppAction::SplitPane(d)) if d == direction));
+                assert_normal(&handler);
+            }
+        }
+    }
+
+    #[test]
+    fn prefix_arrows_select_pane() {
+        let cases = [
+            (KeyCode::Left, PaneDirection::Left),
+            (KeyCode::Right, PaneDirection::Right),
+            (KeyCode::Up, PaneDirection::Up),
+            (KeyCode::Down, PaneDirection::Down),
+        ];
+        for (code, direction) in cases {
+            let mut handler = InputHandler::new();
+            enter_prefix(&mut handler);
+
+            let action = handler.handle_key(make_key(code, KeyModifiers::NONE));
+
+            assert!(matches!(action, Some(AppAction::SelectPane(d)) if d == direction));
+            assert_normal(&handler);
+        }
+    }
+
+    // =========================================================================
+    // Tests: Prefix sidebar and zoom
+    // =========================================================================
+
+    #[test]
+    fn prefix_s_toggles_sidebar() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+
+        let action = handler.handle_key(make_key(KeyCode::Char('s'), KeyModifiers::NONE));
+
+        assert!(matches!(action, Some(AppAction::ToggleSidebar)));
+        assert_normal(&handler);
+    }
+
+    #[test]
+    fn prefix_angle_brackets_resize_sidebar() {
+        for modifiers in [KeyModifiers::SHIFT, KeyModifiers::NONE] {
+            let mut handler = InputHandler::new();
+            enter_prefix(&mut handler);
+            let action = handler.handle_key(make_key(KeyCode::Char('>'), modifiers));
+            assert!(matches!(action, Some(AppAction::WidenSidebar)));
+
+            enter_prefix(&mut handler);
+            let action = handler.handle_key(make_key(KeyCode::Char('<'), modifiers));
+            assert!(matches!(action, Some(AppAction::NarrowSidebar)));
+            assert_normal(&handler);
+        }
+    }
+
+    #[test]
+    fn prefix_z_toggles_zoom() {
+        let mut handler = InputHandler::new();
+        enter_prefix(&mut handler);
+
+        let action = handler.handle_key(make_key(KeyCode::Char('z'), KeyModifiers::NONE));
+
+        assert!(matches!(action, Some(AppAction::ToggleZoom)));
+        assert_normal(&handler);
+    }
+
+    #[test]
+    fn arrows_outside_prefix_go_to_terminal() {
+        let mut handler = InputHandler::new();
+        let action = handler.handle_key(make_key(KeyCode::Left, KeyModifiers::NONE));
+        assert!(matches!(action, Some(AppAction::WriteToActive(_))));
+    }
+
     // =========================================================================
     // Tests: Prefix ] paste keybinding (Task #91)
     // =========================================================================
@@ -1849,7 +2304,7 @@ mod tests {
 
     #[test]
     fn prefix_digit_then_unknown_key_returns_none() {
-        // Ctrl+b 2 z -> digit consumed, z is unknown -> None
+        // Ctrl+b 2 j -> digit consumed, j is unknown -> None
         let mut handler = InputHandler::new();
         enter_prefix(&mut handler);
 
@@ -1857,8 +2312,8 @@ mod tests {
         assert!(action.is_none());
         assert_prefix_wait(&handler);
 
-        let action = handler.handle_key(make_key(KeyCode::Char('z'), KeyModifiers::NONE));
-        assert!(action.is_none(), "Ctrl+b 2 z should produce None (unknown key)");
+        let action = handler.handle_key(make_key(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert!(action.is_none(), "Ctrl+b 2 j should produce None (unknown key)");
         assert_normal(&handler);
     }
 
@@ -2003,4 +2458,63 @@ mod tests {
         let action = handler.handle_key(make_key(KeyCode::Char('g'), KeyModifiers::NONE));
         assert!(matches!(action, Some(AppAction::ScrollbackTop)), "g should still work");
     }
+
+    // =========================================================================
+    // mouse_to_sgr tests
+    // =========================================================================
+
+    fn mouse(kind: MouseEventKind, modifiers: KeyModifiers) -> MouseEvent {
+        MouseEvent { kind, column: 0, row: 0, modifiers }
+    }
+
+    #[test]
+    fn mouse_to_sgr_encodes_press_and_release_one_based() {
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE);
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), KeyModifiers::NONE);
+        assert_eq!(mouse_to_sgr(down, 0, 0, MouseMode::PressRelease), Some(b"\x1b[<0;1;1M".to_vec()));
+        assert_eq!(mouse_to_sgr(up, 9, 4, MouseMode::PressRelease), Some(b"\x1b[<0;10;5m".to_vec()));
+    }
+
+    #[test]
+    fn mouse_to_sgr_encodes_buttons_wheel_and_modifiers() {
+        let right = mouse(MouseEventKind::Down(MouseButton::Right), KeyModifiers::NONE);
+        assert_eq!(mouse_to_sgr(right, 2, 3, MouseMode::Press), Some(b"\x1b[<2;3;4M".to_vec()));
+        let wheel = mouse(MouseEventKind::ScrollDown, KeyModifiers::NONE);
+        assert_eq!(mouse_to_sgr(wheel, 0, 0, MouseMode::Press), Some(b"\x1b[<65;1;1M".to_vec()));
+        let ctrl_shift = mouse(MouseEventKind::Down(MouseButton::Middle), KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+        assert_eq!(mouse_to_sgr(ctrl_shift, 0, 0, MouseMode::Press), Some(b"\x1b[<21;1;1M".to_vec()));
+    }
+
+    #[test]
+    fn mouse_to_sgr_respects_reporting_mode() {
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), KeyModifiers::NONE);
+        let drag = mouse(MouseEventKind::Drag(MouseButton::Left), KeyModifiers::NONE);
+        let moved = mouse(MouseEventKind::Moved, KeyModifiers::NONE);
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE);
+
+        assert_eq!(mouse_to_sgr(down, 0, 0, MouseMode::Off), None);
+        assert_eq!(mouse_to_sgr(up, 0, 0, MouseMode::Press), None);
+        assert_eq!(mouse_to_sgr(drag, 0, 0, MouseMode::PressRelease), None);
+        assert_eq!(mouse_to_sgr(drag, 1, 1, MouseMode::ButtonMotion), Some(b"\x1b[<32;2;2M".to_vec()));
+        assert_eq!(mouse_to_sgr(moved, 0, 0, MouseMode::ButtonMotion), None);
+        assert_eq!(mouse_to_sgr(moved, 0, 0, MouseMode::AnyMotion), Some(b"\x1b[<35;1;1M".to_vec()));
+    }
+
+    // =========================================================================
+    // Tests: edit_filter
+    // =========================================================================
+
+    #[test]
+    fn edit_filter_keys() {
+        let mut filter = TerminalFilter::default();
+        for key in [KeyCode::Char('a'), KeyCode::Char('p'), KeyCode::Backspace, KeyCode::Tab] {
+            assert!(edit_filter(&mut filter, key));
+        }
+        assert_eq!(filter.label(), "running · a");
+
+        assert!(!edit_filter(&mut filter, KeyCode::Enter));
+        assert_eq!(filter.label(), "running · a");
+        assert!(!edit_filter(&mut filter, KeyCode::Esc));
+        assert!(!filter.is_active());
+    }
 }
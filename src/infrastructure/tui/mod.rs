@@ -1,4 +1,7 @@
 pub mod app_runner;
+pub mod attach_runner;
 pub mod fuzzy_matcher;
 pub mod input;
+pub mod pane_layout;
+pub mod play_runner;
 pub mod widgets;
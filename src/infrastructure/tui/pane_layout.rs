@@ -0,0 +1,458 @@
+use ratatui::layout::Rect;
+
+use crate::domain::primitive::{PaneDirection, SplitDirection, TerminalId};
+
+/// A node of the split tree: either a pane showing one terminal, or an area
+/// divided in two halves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PaneNode {
+    Leaf(TerminalId),
+    Split {
+        direction: SplitDirection,
+        first: Box<PaneNode>,
+        second: Box<PaneNode>,
+    },
+}
+
+impl PaneNode {
+    fn first_leaf(&self) -> TerminalId {
+        match self {
+            PaneNode::Leaf(id) => *id,
+            PaneNode::Split { first, .. } => first.first_leaf(),
+        }
+    }
+
+    fn collect_leaves(&self, out: &mut Vec<TerminalId>) {
+        match self {
+            PaneNode::Leaf(id) => out.push(*id),
+            PaneNode::Split { first, second, .. } => {
+                first.collect_leaves(out);
+                second.collect_leaves(out);
+            }
+        }
+    }
+
+    /// Replace the leaf showing `target` by `replacement`. Returns whether it was found.
+    fn replace_leaf(&mut self, target: TerminalId, replacement: PaneNode) -> bool {
+        match self {
+            PaneNode::Leaf(id) if *id == target => {
+                *self = replacement;
+                true
+            }
+            PaneNode::Leaf(_) => false,
+            PaneNode::Split { first, second, .. } => {
+                // `replacement` is only consumed by the branch that holds the leaf
+                if first.contains(target) {
+                    first.replace_leaf(target, replacement)
+                } else {
+                    second.replace_leaf(target, replacement)
+                }
+            }
+        }
+    }
+
+    fn contains(&self, target: TerminalId) -> bool {
+        match self {
+            PaneNode::Leaf(id) => *id == target,
+            PaneNode::Split { first, second, .. } => {
+                first.contains(target) || second.contains(target)
+            }
+        }
+    }
+
+    /// First terminal of the subtree that takes the place of `target` once
+    /// its pane is removed.
+    fn sibling_of(&self, target: TerminalId) -> Option<TerminalId> {
+        match self {
+            PaneNode::Leaf(_) => None,
+            PaneNode::Split { first, second, .. } => {
+                if **first == PaneNode::Leaf(target) {
+                    Some(second.first_leaf())
+                } else if **second == PaneNode::Leaf(target) {
+                    Some(first.first_leaf())
+                } else {
+                    first.sibling_of(target).or_else(|| second.sibling_of(target))
+                }
+            }
+        }
+    }
+
+    /// Remove the pane showing `target`; its sibling takes the freed space.
+    fn without(self, target: TerminalId) -> Option<PaneNode> {
+        match self {
+            PaneNode::Leaf(id) => (id != target).then_some(PaneNode::Leaf(id)),
+            PaneNode::Split { direction, first, second } => {
+                match (first.without(target), second.without(target)) {
+                    (Some(first), Some(second)) => Some(PaneNode::Split {
+                        direction,
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (Some(only), None) | (None, Some(only)) => Some(only),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    fn layout(&self, area: Rect, out: &mut PaneRects) {
+        match self {
+            PaneNode::Leaf(id) => out.panes.push((*id, area)),
+            PaneNode::Split { direction: SplitDirection::Horizontal, first, second } => {
+                // One column between the halves holds the divider line
+                let divider_width = area.width.min(1);
+                let first_width = (area.width - divider_width) / 2;
+                let second_width = area.width - divider_width - first_width;
+                first.layout(Rect::new(area.x, area.y, first_width, area.height), out);
+                out.dividers.push(Rect::new(area.x + first_width, area.y, divider_width, area.height));
+                second.layout(
+                    Rect::new(area.x + first_width + divider_width, area.y, second_width, area.height),
+                    out,
+                );
+            }
+            PaneNode::Split { direction: SplitDirection::Vertical, first, second } => {
+                // The lower pane's CWD bar already separates the halves
+                let first_height = area.height / 2;
+                first.layout(Rect::new(area.x, area.y, area.width, first_height), out);
+                second.layout(
+                    Rect::new(area.x, area.y + first_height, area.width, area.height - first_height),
+                    out,
+                );
+            }
+        }
+    }
+}
+
+/// Screen areas of every pane for a given main area.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct PaneRects {
+    /// Each visible terminal with the area it is drawn in (CWD bar included).
+    pub panes: Vec<(TerminalId, Rect)>,
+    /// One-column gaps between side-by-side panes.
+    pub dividers: Vec<Rect>,
+}
+
+impl PaneRects {
+    pub fn rect_of(&self, id: TerminalId) -> Option<Rect> {
+        self.panes.iter().find(|(pid, _)| *pid == id).map(|(_, rect)| *rect)
+    }
+}
+
+/// tmux-style splits of the main area.
+///
+/// When nothing is split the main area simply shows the active terminal and
+/// `root` is `None`. Once split, every pane shows a different terminal and
+/// the focused pane always shows the active one: `sync` keeps the tree in
+/// step with selections and closes made elsewhere (sidebar, IPC, ...).
+#[derive(Debug, Default)]
+pub(crate) struct PaneLayout {
+    root: Option<PaneNode>,
+    focused: Option<TerminalId>,
+}
+
+impl PaneLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Terminals shown in panes, in layout order (empty when not split).
+    pub fn terminal_ids(&self) -> Vec<TerminalId> {
+        let mut ids = Vec::new();
+        if let Some(root) = &self.root {
+            root.collect_leaves(&mut ids);
+        }
+        ids
+    }
+
+    /// Split the pane showing `current` in two, showing `new` in the second
+    /// half, and focus it. Returns `false` when `current` has no pane.
+    pub fn split(&mut self, current: TerminalId, new: TerminalId, direction: SplitDirection) -> bool {
+        let node = PaneNode::Split {
+            direction,
+            first: Box::new(PaneNode::Leaf(current)),
+            second: Box::new(PaneNode::Leaf(new)),
+        };
+        let replaced = match self.root.as_mut() {
+            None => {
+                self.root = Some(node);
+                true
+            }
+            Some(root) => root.contains(current) && root.replace_leaf(current, node),
+        };
+        if replaced {
+            self.focused = Some(new);
+        }
+        replaced
+    }
+
+    /// Bring the tree in line with the terminal list and the active terminal.
+    ///
+    /// Panes of terminals that no longer exist are removed (a single remaining
+    /// pane means the area is no longer split). If the active terminal is not
+    /// shown, the focused pane switches to it. When the focused pane itself
+    /// was removed, focus moves to its sibling and that terminal is returned
+    /// so the caller can make it active.
+    pub fn sync(&mut self, active: Option<TerminalId>, alive: &[TerminalId]) -> Option<TerminalId> {
+        let focus_lost = self.focused.is_some_and(|id| !alive.contains(&id));
+        for id in self.terminal_ids() {
+            if !alive.contains(&id) {
+                self.remove(id);
+            }
+        }
+        let root = self.root.as_mut()?;
+
+        match active {
+            Some(active) if root.contains(active) => {
+                self.focused = Some(active);
+                None
+            }
+            _ if focus_lost => self.focused,
+            Some(active) => {
+                if let Some(focused) = self.focused {
+                    root.replace_leaf(focused, PaneNode::Leaf(active));
+                }
+                self.focused = Some(active);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Compute the area of every pane inside `area`.
+    pub fn layout(&self, area: Rect) -> PaneRects {
+        let mut rects = PaneRects::default();
+        if let Some(root) = &self.root {
+            root.layout(area, &mut rects);
+        }
+        rects
+    }
+
+    /// Terminal of the nearest pane in `direction` from the focused one.
+    ///
+    /// Only panes sharing an edge range with the focused pane qualify; ties
+    /// go to the one with the largest shared edge.
+    pub fn neighbor(&self, area: Rect, direction: PaneDirection) -> Option<TerminalId> {
+        let rects = self.layout(area);
+        let from = rects.rect_of(self.focused?)?;
+        rects
+            .panes
+            .iter()
+            .filter_map(|(id, rect)| {
+                let (distance, overlap) = match direction {
+                    PaneDirection::Left if rect.right() <= from.x => {
+                        (from.x - rect.right(), overlap(rect.y, rect.bottom(), from.y, from.bottom()))
+                    }
+                    PaneDirection::Right if rect.x >= from.right() => {
+                        (rect.x - from.right(), overlap(rect.y, rect.bottom(), from.y, from.bottom()))
+                    }
+                    PaneDirection::Up if rect.bottom() <= from.y => {
+                        (from.y - rect.bottom(), overlap(rect.x, rect.right(), from.x, from.right()))
+                    }
+                    PaneDirection::Down if rect.y >= from.bottom() => {
+                        (rect.y - from.bottom(), overlap(rect.x, rect.right(), from.x, from.right()))
+                    }
+                    _ => return None,
+                };
+                (overlap > 0).then_some((distance, overlap, *id))
+            })
+            .min_by_key(|(distance, overlap, _)| (*distance, std::cmp::Reverse(*overlap)))
+            .map(|(_, _, id)| id)
+    }
+
+    fn remove(&mut self, id: TerminalId) {
+        let Some(root) = self.root.take() else { return };
+        if self.focused == Some(id) {
+            self.focused = root.sibling_of(id);
+        }
+        self.root = match root.without(id) {
+            Some(node @ PaneNode::Split { .. }) => Some(node),
+            // A lone pane is the unsplit main area
+            _ => {
+                self.focused = None;
+                None
+            }
+        };
+    }
+}
+
+/// Length of the intersection of `[a_start, a_end)` and `[b_start, b_end)`.
+fn overlap(a_start: u16, a_end: u16, b_start: u16, b_end: u16) -> u16 {
+    a_end.min(b_end).saturating_sub(a_start.max(b_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(id: u32) -> TerminalId {
+        TerminalId::new(id)
+    }
+
+    const AREA: Rect = Rect { x: 25, y: 0, width: 81, height: 40 };
+
+    /// 1 | 2 side by side, then 2 split into 2 over 3.
+    fn three_panes() -> PaneLayout {
+        let mut panes = PaneLayout::new();
+        panes.split(t(1), t(2), SplitDirection::Horizontal);
+        panes.split(t(2), t(3), SplitDirection::Vertical);
+        panes
+    }
+
+    // =========================================================================
+    // split
+    // =========================================================================
+
+    #[test]
+    fn new_layout_is_not_split() {
+        let panes = PaneLayout::new();
+        assert!(panes.root.is_none());
+        assert!(panes.terminal_ids().is_empty());
+        assert_eq!(panes.layout(AREA), PaneRects::default());
+    }
+
+    #[test]
+    fn split_shows_both_terminals_and_focuses_new_one() {
+        let mut panes = PaneLayout::new();
+        assert!(panes.split(t(1), t(2), SplitDirection::Horizontal));
+
+        assert!(panes.root.is_some());
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2)]);
+        assert_eq!(panes.focused, Some(t(2)));
+    }
+
+    #[test]
+    fn nested_split_replaces_only_the_split_pane() {
+        let panes = three_panes();
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2), t(3)]);
+        assert_eq!(panes.focused, Some(t(3)));
+    }
+
+    #[test]
+    fn split_of_unknown_pane_is_rejected() {
+        let mut panes = PaneLayout::new();
+        panes.split(t(1), t(2), SplitDirection::Horizontal);
+
+        assert!(!panes.split(t(9), t(3), SplitDirection::Vertical));
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2)]);
+        assert_eq!(panes.focused, Some(t(2)));
+    }
+
+    // =========================================================================
+    // layout
+    // =========================================================================
+
+    #[test]
+    fn horizontal_split_leaves_a_divider_column() {
+        let mut panes = PaneLayout::new();
+        panes.split(t(1), t(2), SplitDirection::Horizontal);
+
+        let rects = panes.layout(AREA);
+        assert_eq!(rects.rect_of(t(1)), Some(Rect::new(25, 0, 40, 40)));
+        assert_eq!(rects.dividers, vec![Rect::new(65, 0, 1, 40)]);
+        assert_eq!(rects.rect_of(t(2)), Some(Rect::new(66, 0, 40, 40)));
+    }
+
+    #[test]
+    fn vertical_split_stacks_panes_without_divider() {
+        let mut panes = PaneLayout::new();
+        panes.split(t(1), t(2), SplitDirection::Vertical);
+
+        let rects = panes.layout(Rect::new(0, 0, 80, 25));
+        assert_eq!(rects.rect_of(t(1)), Some(Rect::new(0, 0, 80, 12)));
+        assert_eq!(rects.rect_of(t(2)), Some(Rect::new(0, 12, 80, 13)));
+        assert!(rects.dividers.is_empty());
+    }
+
+    #[test]
+    fn nested_layout_covers_area() {
+        let rects = three_panes().layout(AREA);
+        assert_eq!(rects.rect_of(t(2)), Some(Rect::new(66, 0, 40, 20)));
+        assert_eq!(rects.rect_of(t(3)), Some(Rect::new(66, 20, 40, 20)));
+    }
+
+    #[test]
+    fn layout_of_zero_area_does_not_underflow() {
+        let rects = three_panes().layout(Rect::new(0, 0, 0, 0));
+        assert_eq!(rects.panes.len(), 3);
+        assert!(rects.panes.iter().all(|(_, r)| r.width == 0));
+    }
+
+    // =========================================================================
+    // neighbor
+    // =========================================================================
+
+    #[test]
+    fn neighbor_moves_across_and_along_splits() {
+        let mut panes = three_panes(); // focused: 3 (bottom right)
+
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Up), Some(t(2)));
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Left), Some(t(1)));
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Right), None);
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Down), None);
+
+        panes.sync(Some(t(1)), &[t(1), t(2), t(3)]);
+        // Both right panes share an edge with 1; the upper one wins the tie
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Right), Some(t(2)));
+    }
+
+    #[test]
+    fn neighbor_without_split_is_none() {
+        let panes = PaneLayout::new();
+        assert_eq!(panes.neighbor(AREA, PaneDirection::Left), None);
+    }
+
+    // =========================================================================
+    // sync
+    // =========================================================================
+
+    #[test]
+    fn sync_focuses_visible_active_terminal() {
+        let mut panes = three_panes();
+        assert_eq!(panes.sync(Some(t(1)), &[t(1), t(2), t(3)]), None);
+        assert_eq!(panes.focused, Some(t(1)));
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2), t(3)]);
+    }
+
+    #[test]
+    fn sync_shows_hidden_active_terminal_in_focused_pane() {
+        let mut panes = three_panes();
+        assert_eq!(panes.sync(Some(t(4)), &[t(1), t(2), t(3), t(4)]), None);
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2), t(4)]);
+        assert_eq!(panes.focused, Some(t(4)));
+    }
+
+    #[test]
+    fn sync_removes_closed_pane_and_focuses_sibling() {
+        let mut panes = three_panes(); // focused: 3
+
+        // The usecase picked a hidden terminal after closing 3; focus goes to
+        // the sibling pane instead and the caller selects it
+        assert_eq!(panes.sync(Some(t(4)), &[t(1), t(2), t(4)]), Some(t(2)));
+        assert_eq!(panes.terminal_ids(), vec![t(1), t(2)]);
+        assert_eq!(panes.focused, Some(t(2)));
+    }
+
+    #[test]
+    fn sync_keeps_visible_active_after_close() {
+        let mut panes = three_panes();
+        assert_eq!(panes.sync(Some(t(1)), &[t(1), t(2)]), None);
+        assert_eq!(panes.focused, Some(t(1)));
+    }
+
+    #[test]
+    fn sync_unsplits_when_one_pane_remains() {
+        let mut panes = PaneLayout::new();
+        panes.split(t(1), t(2), SplitDirection::Horizontal);
+
+        assert_eq!(panes.sync(Some(t(1)), &[t(1)]), None);
+        assert!(panes.root.is_none());
+        assert_eq!(panes.focused, None);
+    }
+
+    #[test]
+    fn sync_of_unsplit_layout_is_noop() {
+        let mut panes = PaneLayout::new();
+        assert_eq!(panes.sync(Some(t(1)), &[t(1)]), None);
+        assert!(panes.root.is_none());
+    }
+}
@@ -0,0 +1,412 @@
+//! `cm play <file>`: replay an asciicast recording in a read-only pane.
+//!
+//! The recording is fed through a `ScreenPort` (the same vt100 emulator as
+//! live terminals) and drawn with `terminal_view`. Seeking backwards
+//! replays from the start, which is fast enough for recordings of any
+//! practical length.
+
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+
+use crate::domain::primitive::TerminalId;
+use crate::infrastructure::log::asciicast::{self, Cast, CastEvent};
+use crate::infrastructure::tui::widgets::terminal_view;
+use crate::interface_adapter::adapter::screen_adapter_factory;
+use crate::interface_adapter::port::ScreenPort;
+use crate::shared::error::AppError;
+
+/// Seek step for the arrow keys, in seconds.
+const SEEK_STEP: f64 = 5.0;
+
+/// Playback speeds cycled with `+` / `-`.
+const SPEEDS: [f64; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+
+/// Screen id used for the replayed terminal.
+fn playback_id() -> TerminalId {
+    TerminalId::new(1)
+}
+
+/// Playback state: a recording, the screen it is replayed into and the
+/// current position.
+pub(crate) struct Player<S: ScreenPort> {
+    cast: Cast,
+    screen: S,
+    /// Index of the next event to apply.
+    next: usize,
+    /// Playback position in recording seconds.
+    position: f64,
+    speed_index: usize,
+    paused: bool,
+}
+
+impl<S: ScreenPort> Player<S> {
+    pub(crate) fn new(cast: Cast, mut screen: S) -> Result<Self, AppError> {
+        screen.create(playback_id(), cast.header.size)?;
+        Ok(Self {
+            cast,
+            screen,
+            next: 0,
+            position: 0.0,
+            speed_index: 2,
+            paused: false,
+        })
+    }
+
+    pub(crate) fn screen(&self) -> &S {
+        &self.screen
+    }
+
+    pub(crate) fn duration(&self) -> f64 {
+        self.cast.duration()
+    }
+
+    pub(crate) fn position(&self) -> f64 {
+        self.position
+    }
+
+    pub(crate) fn speed(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.next >= self.cast.events.len()
+    }
+
+    /// Move forward by `elapsed` wall-clock seconds (scaled by the speed).
+    pub(crate) fn advance(&mut self, elapsed: f64) {
+        if self.paused {
+            return;
+        }
+        self.position = (self.position + elapsed * self.speed()).min(self.duration());
+        self.apply_until(self.position);
+    }
+
+    /// Jump to `to` seconds, replaying from the start when going backwards.
+    pub(crate) fn seek(&mut self, to: f64) {
+        let to = to.clamp(0.0, self.duration());
+        if to < self.position {
+            let id = playback_id();
+            let _ = self.screen.remove(id);
+            let _ = self.screen.create(id, self.cast.header.size);
+            self.next = 0;
+        }
+        self.position = to;
+        self.apply_until(to);
+    }
+
+    /// Pause or resume. Resuming a finished recording starts it over.
+    pub(crate) fn toggle_pause(&mut self) {
+        if self.paused && self.is_finished() {
+            self.seek(0.0);
+        }
+        self.paused = !self.paused;
+    }
+
+    pub(crate) fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub(crate) fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    fn apply_until(&mut self, t: f64) {
+        let id = playback_id();
+        while let Some((time, event)) = self.cast.events.get(self.next) {
+            if *time > t {
+                break;
+            }
+            match event {
+                CastEvent::Output(text) => {
+                    let _ = self.screen.process(id, text.as_bytes());
+                    // Nobody answers the recorded program's queries
+                    let _ = self.screen.drain_pending_responses(id);
+                }
+                CastEvent::Resize(size) => {
+                    let _ = self.screen.resize(id, *size);
+                }
+            }
+            self.next += 1;
+        }
+    }
+}
+
+/// `mm:ss`, or `h:mm:ss` past an hour.
+fn format_time(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// Footer text: state, speed and position.
+fn status_text<S: ScreenPort>(player: &Player<S>) -> String {
+    let state = if player.is_paused() {
+        "paused"
+    } else if player.is_finished() {
+        "done"
+    } else {
+        "playing"
+    };
+    format!(
+        " {state} {}x  {} / {} ",
+        player.speed(),
+        format_time(player.position()),
+        format_time(player.duration()),
+    )
+}
+
+/// Entry point for `cm play <file>`.
+pub fn run(path: &Path) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("cannot read {}: {e}", path.display()))?;
+    let cast = asciicast::parse(&content)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+    let title = cast
+        .header
+        .title
+        .clone()
+        .unwrap_or_else(|| path.display().to_string());
+    let mut player = Player::new(cast, screen_adapter_factory::create_vt100_screen_adapter())?;
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = play_loop(&mut terminal, &mut player, &title);
+
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen);
+    let _ = terminal.show_cursor();
+
+    result
+}
+
+fn play_loop<S: ScreenPort>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    player: &mut Player<S>,
+    title: &str,
+) -> anyhow::Result<()> {
+    let mut last_tick = Instant::now();
+    loop {
+        let now = Instant::now();
+        player.advance(now.duration_since(last_tick).as_secs_f64());
+        last_tick = now;
+
+        terminal.draw(|frame| {
+            let [view_area, footer_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+            let id = playback_id();
+            let screen = player.screen();
+            terminal_view::render(
+                frame,
+                view_area,
+                screen.get_cells(id).ok(),
+                screen.get_cursor(id).ok(),
+                screen.get_cursor_visible(id).unwrap_or(true),
+                Some(title),
+                true,
+                None,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            let footer = Line::from(vec![
+                Span::styled(
+                    status_text(player),
+                    Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    "  space pause  \u{2190}/\u{2192} seek  +/- speed  0 restart  q quit",
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]);
+            frame.render_widget(Paragraph::new(footer), footer_area);
+        })?;
+
+        if event::poll(Duration::from_millis(16))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char(' ') => player.toggle_pause(),
+                KeyCode::Left | KeyCode::Char('h') => player.seek(player.position() - SEEK_STEP),
+                KeyCode::Right | KeyCode::Char('l') => player.seek(player.position() + SEEK_STEP),
+                KeyCode::Char('+') | KeyCode::Char('=') => player.faster(),
+                KeyCode::Char('-') => player.slower(),
+                KeyCode::Char('0') | KeyCode::Home => player.seek(0.0),
+                KeyCode::End => player.seek(player.duration()),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::primitive::TerminalSize;
+    use crate::infrastructure::log::asciicast::CastHeader;
+    use crate::infrastructure::screen::Vt100ScreenAdapter;
+
+    fn cast(events: Vec<(f64, CastEvent)>) -> Cast {
+        Cast {
+            header: CastHeader {
+                size: TerminalSize::new(20, 5),
+                timestamp: None,
+                title: None,
+            },
+            events,
+        }
+    }
+
+    fn output(t: f64, text: &str) -> (f64, CastEvent) {
+        (t, CastEvent::Output(text.to_string()))
+    }
+
+    fn player(events: Vec<(f64, CastEvent)>) -> Player<Vt100ScreenAdapter> {
+        Player::new(cast(events), Vt100ScreenAdapter::new()).unwrap()
+    }
+
+    fn first_row(player: &Player<Vt100ScreenAdapter>) -> String {
+        let cells = player.screen().get_cells(playback_id()).unwrap();
+        cells[0].iter().map(|c| c.ch).collect::<String>().trim_end().to_string()
+    }
+
+    // ========================================================================
+    // Tests: playback
+    // ========================================================================
+
+    #[test]
+    fn advance_applies_events_up_to_position() {
+        let mut p = player(vec![output(0.5, "a"), output(1.5, "b")]);
+
+        p.advance(1.0);
+        assert_eq!(first_row(&p), "a");
+        assert!(!p.is_finished());
+
+        p.advance(1.0);
+        assert_eq!(first_row(&p), "ab");
+        assert!(p.is_finished());
+        assert_eq!(p.position(), 1.5);
+    }
+
+    #[test]
+    fn paused_player_does_not_advance() {
+        let mut p = player(vec![output(0.5, "a")]);
+        p.toggle_pause();
+        p.advance(10.0);
+        assert_eq!(first_row(&p), "");
+        assert_eq!(p.position(), 0.0);
+    }
+
+    #[test]
+    fn speed_scales_elapsed_time() {
+        let mut p = player(vec![output(2.0, "a")]);
+        p.faster();
+        assert_eq!(p.speed(), 2.0);
+        p.advance(1.0);
+        assert_eq!(first_row(&p), "a");
+    }
+
+    #[test]
+    fn speed_is_clamped() {
+        let mut p = player(vec![]);
+        for _ in 0..20 {
+            p.faster();
+        }
+        assert_eq!(p.speed(), 16.0);
+        for _ in 0..20 {
+            p.slower();
+        }
+        assert_eq!(p.speed(), 0.25);
+    }
+
+    #[test]
+    fn seek_backwards_replays_from_start() {
+        let mut p = player(vec![output(1.0, "a"), output(2.0, "b"), output(3.0, "c")]);
+        p.seek(3.0);
+        assert_eq!(first_row(&p), "abc");
+
+        p.seek(2.5);
+
+        assert_eq!(first_row(&p), "ab");
+        assert_eq!(p.position(), 2.5);
+    }
+
+    #[test]
+    fn seek_is_clamped_to_recording() {
+        let mut p = player(vec![output(1.0, "a")]);
+        p.seek(-5.0);
+        assert_eq!(p.position(), 0.0);
+        p.seek(100.0);
+        assert_eq!(p.position(), 1.0);
+    }
+
+    #[test]
+    fn resize_event_resizes_screen() {
+        let mut p = player(vec![(0.1, CastEvent::Resize(TerminalSize::new(30, 8)))]);
+        p.advance(1.0);
+        let cells = p.screen().get_cells(playback_id()).unwrap();
+        assert_eq!(cells.len(), 8);
+        assert_eq!(cells[0].len(), 30);
+    }
+
+    #[test]
+    fn resume_after_finish_restarts() {
+        let mut p = player(vec![output(0.5, "a")]);
+        p.advance(1.0);
+        p.toggle_pause();
+
+        p.toggle_pause();
+
+        assert!(!p.is_paused());
+        assert_eq!(p.position(), 0.0);
+        assert_eq!(first_row(&p), "");
+    }
+
+    // ========================================================================
+    // Tests: status line
+    // ========================================================================
+
+    #[test]
+    fn format_time_minutes_and_hours() {
+        assert_eq!(format_time(0.0), "00:00");
+        assert_eq!(format_time(75.9), "01:15");
+        assert_eq!(format_time(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn status_text_shows_state_speed_and_position() {
+        let mut p = player(vec![output(90.0, "a")]);
+        p.advance(30.0);
+        assert_eq!(status_text(&p), " playing 1x  00:30 / 01:30 ");
+        p.toggle_pause();
+        assert!(status_text(&p).starts_with(" paused"));
+    }
+}
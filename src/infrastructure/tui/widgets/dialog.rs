@@ -5,6 +5,8 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use unicode_width::UnicodeWidthStr;
 
+use crate::domain::primitive::{Signal, SortKey};
+
 /// Calculate a centered rectangle within the given area
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + area.width.saturating_sub(width) / 2;
@@ -12,9 +14,11 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
-pub fn render_create_dialog(frame: &mut Frame, input: &str, cursor_pos: usize) {
+/// The new-terminal dialog. `on_group` says whether the cursor is in the
+/// group field rather than the name field; `cursor_pos` is in that field.
+pub fn render_create_dialog(frame: &mut Frame, input: &str, group: &str, cursor_pos: usize, on_group: bool) {
     let area = frame.area();
-    let dialog_area = centered_rect(30, 7, area);
+    let dialog_area = centered_rect(48, 8, area);
 
     // Clear background
     frame.render_widget(Clear, dialog_area);
@@ -30,12 +34,16 @@ pub fn render_create_dialog(frame: &mut Frame, input: &str, cursor_pos: usize) {
     let lines = vec![
         Line::from(""),
         Line::from(vec![
-            Span::raw("  Name: "),
+            Span::raw("  Name:  "),
             Span::styled(input, Style::default().fg(Color::Yellow)),
         ]),
+        Line::from(vec![
+            Span::raw("  Group: "),
+            Span::styled(group, Style::default().fg(Color::Yellow)),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
-            "  [Enter] Create  [Esc] Cancel",
+            "  [Tab] Switch  [Enter] Create  [Esc] Cancel",
             Style::default().fg(Color::DarkGray),
         )),
     ];
@@ -43,13 +51,14 @@ pub fn render_create_dialog(frame: &mut Frame, input: &str, cursor_pos: usize) {
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, inner);
 
-    // Set cursor position within the input field
+    // Set cursor position within the focused field
     // cursor_pos is a char count; compute display width of text before cursor
-    let display_width: usize = input.chars().take(cursor_pos)
+    let (field, row) = if on_group { (group, 2) } else { (input, 1) };
+    let display_width: usize = field.chars().take(cursor_pos)
         .collect::<String>()
         .width();
-    let cursor_x = inner.x + 8 + display_width as u16; // "  Name: " = 8 chars
-    let cursor_y = inner.y + 1; // Line 2 (0-indexed line 1)
+    let cursor_x = inner.x + 9 + display_width as u16; // "  Group: " = 9 chars
+    let cursor_y = inner.y + row;
     frame.set_cursor_position((cursor_x, cursor_y));
 }
 
@@ -92,19 +101,15 @@ pub fn render_rename_dialog(frame: &mut Frame, input: &str, cursor_pos: usize) {
     frame.set_cursor_position((cursor_x, cursor_y));
 }
 
-pub fn render_confirm_close_dialog(frame: &mut Frame, terminal_name: &str, is_running: bool) {
-    if !is_running {
-        return; // No dialog needed for exited terminals
-    }
-
+/// Move the active terminal to another group; an empty name ungroups it.
+pub fn render_group_dialog(frame: &mut Frame, input: &str, cursor_pos: usize) {
     let area = frame.area();
-    let dialog_area = centered_rect(30, 8, area);
+    let dialog_area = centered_rect(34, 7, area);
 
-    // Clear background
     frame.render_widget(Clear, dialog_area);
 
     let block = Block::default()
-        .title(" Close Terminal? ")
+        .title(" Terminal Group ")
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::Black));
 
@@ -112,20 +117,156 @@ pub fn render_confirm_close_dialog(frame: &mut Frame, terminal_name: &str, is_ru
     frame.render_widget(block, dialog_area);
 
     let lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("  Group: "),
+            Span::styled(input, Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  [Enter] Confirm  [Esc] Cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let paragraph = Paragraph::new(lines);
+    frame.render_widget(paragraph, inner);
+
+    let display_width: usize = input.chars().take(cursor_pos)
+        .collect::<String>()
+        .width();
+    let cursor_x = inner.x + 9 + display_width as u16;
+    let cursor_y = inner.y + 1;
+    frame.set_cursor_position((cursor_x, cursor_y));
+}
+
+/// Ask before closing a running terminal. `process` names what will be
+/// hung up, e.g. "vim (pid 4242)".
+pub fn render_confirm_close_dialog(frame: &mut Frame, terminal_name: &str, process: Option<&str>, is_running: bool) {
+    if !is_running {
+        return; // No dialog needed for exited terminals
+    }
+
+    let mut lines = vec![
         Line::from(""),
         Line::from(format!("  \"{}\" is running.", terminal_name)),
+    ];
+    if let Some(process) = process {
+        lines.push(Line::from(format!("  Hangs up {}.", process)));
+    }
+    lines.extend([
         Line::from("  Close anyway?"),
         Line::from(""),
         Line::from(Span::styled(
             "  [y] Yes    [n] No",
             Style::default().fg(Color::DarkGray),
         )),
-    ];
+    ]);
+
+    let area = frame.area();
+    let dialog_area = centered_rect(dialog_width(&lines), lines.len() as u16 + 2, area);
+
+    // Clear background
+    frame.render_widget(Clear, dialog_area);
+
+    let block = Block::default()
+        .title(" Close Terminal? ")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
 
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, inner);
 }
 
+/// Signals offered by the signal menu, with the key that sends each.
+pub const SIGNAL_KEYS: [(char, Signal); 6] = [
+    ('i', Signal::Int),
+    ('t', Signal::Term),
+    ('h', Signal::Hup),
+    ('k', Signal::Kill),
+    ('s', Signal::Stop),
+    ('c', Signal::Cont),
+];
+
+/// The signal the menu sends for `key`.
+pub fn signal_for_key(key: char) -> Option<Signal> {
+    SIGNAL_KEYS.iter().find(|(k, _)| *k == key).map(|(_, signal)| *signal)
+}
+
+/// Menu of signals to send to the active terminal's foreground process.
+pub fn render_signal_dialog(frame: &mut Frame, terminal_name: &str, process: Option<&str>) {
+    let heading = format!("  To: {}", process.unwrap_or(terminal_name));
+    let entries = SIGNAL_KEYS.iter().map(|(key, signal)| (*key, signal.name()));
+    render_key_menu(frame, " Send Signal ", Some(heading), entries);
+}
+
+/// Keys of the sort menu, with the order each one sorts the sidebar by.
+pub const SORT_KEYS: [(char, SortKey, &str); 4] = [
+    ('n', SortKey::Name, "Name"),
+    ('c', SortKey::Cwd, "Working directory"),
+    ('a', SortKey::Activity, "Last activity"),
+    ('u', SortKey::Unread, "Unread first"),
+];
+
+/// The sort order the menu picks for `key`.
+pub fn sort_for_key(key: char) -> Option<SortKey> {
+    SORT_KEYS.iter().find(|(k, _, _)| *k == key).map(|(_, sort, _)| *sort)
+}
+
+/// Menu of orders to sort the sidebar by.
+pub fn render_sort_dialog(frame: &mut Frame) {
+    let entries = SORT_KEYS.iter().map(|(key, _, label)| (*key, *label));
+    render_key_menu(frame, " Sort Terminals ", None, entries);
+}
+
+/// A dialog listing `[key] label` entries below an optional heading.
+fn render_key_menu<'a>(
+    frame: &mut Frame,
+    title: &str,
+    heading: Option<String>,
+    entries: impl Iterator<Item = (char, &'a str)>,
+) {
+    let mut lines = vec![Line::from("")];
+    if let Some(heading) = heading {
+        lines.extend([Line::from(heading), Line::from("")]);
+    }
+    for (key, label) in entries {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  [{}] ", key), Style::default().fg(Color::Yellow)),
+            Span::raw(label),
+        ]));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(Span::styled("  [Esc] Cancel", Style::default().fg(Color::DarkGray))),
+    ]);
+
+    let area = frame.area();
+    let dialog_area = centered_rect(dialog_width(&lines), lines.len() as u16 + 2, area);
+
+    frame.render_widget(Clear, dialog_area);
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Width that fits the longest line plus borders and a right margin,
+/// at least the 30 columns of the other dialogs.
+fn dialog_width(lines: &[Line]) -> u16 {
+    let longest = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    (longest as u16).saturating_add(4).max(30)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,7 +349,7 @@ mod tests {
 
         terminal
             .draw(|frame| {
-                render_create_dialog(frame, "my-term", 7);
+                render_create_dialog(frame, "my-term", "api", 7, false);
             })
             .unwrap();
 
@@ -217,6 +358,7 @@ mod tests {
         // Find the dialog content in the buffer
         let mut found_title = false;
         let mut found_name = false;
+        let mut found_group = false;
         let mut found_help = false;
 
         for y in 0..24u16 {
@@ -229,6 +371,9 @@ mod tests {
             if row.contains("Name:") && row.contains("my-term") {
                 found_name = true;
             }
+            if row.contains("Group:") && row.contains("api") {
+                found_group = true;
+            }
             if row.contains("[Enter] Create") {
                 found_help = true;
             }
@@ -236,6 +381,7 @@ mod tests {
 
         assert!(found_title, "Expected dialog title 'New Terminal'");
         assert!(found_name, "Expected 'Name:' with input 'my-term'");
+        assert!(found_group, "Expected 'Group:' with input 'api'");
         assert!(found_help, "Expected help text '[Enter] Create'");
     }
 
@@ -246,7 +392,7 @@ mod tests {
 
         terminal
             .draw(|frame| {
-                render_create_dialog(frame, "", 0);
+                render_create_dialog(frame, "", "", 0, false);
             })
             .unwrap();
 
@@ -264,6 +410,43 @@ mod tests {
         assert!(found_name_label, "Expected 'Name:' label even with empty input");
     }
 
+    #[test]
+    fn render_create_dialog_puts_cursor_in_group_field() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|frame| {
+                render_create_dialog(frame, "my-term", "api", 3, true);
+            })
+            .unwrap();
+
+        // Dialog at (16, 8); inner starts at (17, 9); group line is row 11
+        let cursor = terminal.get_cursor_position().unwrap();
+        assert_eq!((cursor.x, cursor.y), (17 + 9 + 3, 11));
+    }
+
+    // --- render_group_dialog tests ---
+
+    #[test]
+    fn render_group_dialog_shows_title_and_input() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|frame| {
+                render_group_dialog(frame, "backend", 7);
+            })
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..24u16)
+            .map(|y| (0..80u16).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect())
+            .collect();
+        assert!(rows.iter().any(|r| r.contains("Terminal Group")));
+        assert!(rows.iter().any(|r| r.contains("Group: backend")));
+    }
+
     // --- render_confirm_close_dialog tests ---
 
     #[test]
@@ -273,7 +456,7 @@ mod tests {
 
         terminal
             .draw(|frame| {
-                render_confirm_close_dialog(frame, "my-shell", true);
+                render_confirm_close_dialog(frame, "my-shell", None, true);
             })
             .unwrap();
 
@@ -310,7 +493,7 @@ mod tests {
 
         terminal
             .draw(|frame| {
-                render_confirm_close_dialog(frame, "my-shell", false);
+                render_confirm_close_dialog(frame, "my-shell", None, false);
             })
             .unwrap();
 
@@ -329,6 +512,77 @@ mod tests {
         assert!(!found_close, "Should not render dialog for exited terminal");
     }
 
+    #[test]
+    fn render_confirm_close_dialog_names_the_process() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|frame| {
+                render_confirm_close_dialog(frame, "editor", Some("vim (pid 4242)"), true);
+            })
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        let found = (0..24u16).any(|y| {
+            let row: String = (0..80u16).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect();
+            row.contains("Hangs up vim (pid 4242).")
+        });
+        assert!(found, "Expected the process that will be hung up");
+    }
+
+    // --- render_signal_dialog tests ---
+
+    #[test]
+    fn render_signal_dialog_lists_every_signal() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|frame| {
+                render_signal_dialog(frame, "build", Some("make (pid 77)"));
+            })
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        let screen: String = (0..24u16)
+            .map(|y| (0..80u16).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(screen.contains("Send Signal"));
+        assert!(screen.contains("To: make (pid 77)"));
+        for (key, signal) in SIGNAL_KEYS {
+            assert!(screen.contains(&format!("[{}] {}", key, signal.name())), "missing {}", signal.name());
+        }
+    }
+
+    #[test]
+    fn render_sort_dialog_lists_every_order() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal.draw(render_sort_dialog).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let screen: String = (0..24u16)
+            .map(|y| (0..80u16).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(screen.contains("Sort Terminals"));
+        for (key, _, label) in SORT_KEYS {
+            assert!(screen.contains(&format!("[{}] {}", key, label)), "missing {}", label);
+        }
+        assert_eq!(sort_for_key('a'), Some(SortKey::Activity));
+        assert_eq!(sort_for_key('x'), None);
+    }
+
+    #[test]
+    fn signal_for_key_maps_menu_keys() {
+        assert_eq!(signal_for_key('i'), Some(Signal::Int));
+        assert_eq!(signal_for_key('k'), Some(Signal::Kill));
+        assert_eq!(signal_for_key('x'), None);
+    }
+
     // --- render_rename_dialog tests ---
 
     #[test]
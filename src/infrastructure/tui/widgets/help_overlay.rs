@@ -66,6 +66,14 @@ pub fn render_help_overlay(frame: &mut Frame, area: Rect) {
             ("d", "Close terminal"),
             ("r", "Rename"),
             ("m", "Memo"),
+            ("R", "Respawn exited"),
+            ("k", "Send signal"),
+            ("G", "Set group"),
+            ("y", "Sync input on/off"),
+            ("M", "Mark for sync"),
+            ("P", "Record on/off"),
+            ("%", "Split left/right"),
+            ("\"", "Split top/bottom"),
             ("`", "Mini Terminal"),
         ],
     );
@@ -79,7 +87,15 @@ pub fn render_help_overlay(frame: &mut Frame, area: Rect) {
             ("n/\u{2193}", "Next terminal"),
             ("p/\u{2191}", "Previous terminal"),
             ("f", "Quick switch"),
+            ("{/}", "Move up/down"),
+            ("S", "Sort terminals"),
+            ("/", "Filter (Tab: status)"),
+            ("g", "Fold group"),
             ("o", "Toggle pane"),
+            ("\u{2190}\u{2192}\u{2191}\u{2193}", "Move to split"),
+            ("z", "Zoom pane"),
+            ("s", "Sidebar on/off"),
+            ("</>", "Sidebar width"),
         ],
     );
     frame.render_widget(Paragraph::new(nav_lines), columns[1]);
@@ -406,6 +422,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn help_overlay_renders_respawn_and_record_keybindings() {
+        let content = buffer_to_string(&render_help(80, 24));
+        assert!(content.contains("Respawn exited"), "Expected respawn keybinding");
+        assert!(content.contains("Record on/off"), "Expected record keybinding");
+        assert!(content.contains("Send signal"), "Expected signal keybinding");
+    }
+
+    #[test]
+    fn help_overlay_renders_sidebar_order_keybindings() {
+        let content = buffer_to_string(&render_help(100, 24));
+        assert!(content.contains("Move up/down"), "Expected move keybinding");
+        assert!(content.contains("Sort terminals"), "Expected sort keybinding");
+        assert!(content.contains("Filter (Tab: status)"), "Expected filter keybinding");
+    }
+
+    #[test]
+    fn help_overlay_renders_group_keybindings() {
+        let content = buffer_to_string(&render_help(100, 30));
+        assert!(content.contains("Set group"), "Expected set group keybinding");
+        assert!(content.contains("Fold group"), "Expected fold group keybinding");
+    }
+
+    #[test]
+    fn help_overlay_renders_sync_keybindings() {
+        let content = buffer_to_string(&render_help(100, 30));
+        assert!(content.contains("Sync input on/off"), "Expected sync keybinding");
+        assert!(content.contains("Mark for sync"), "Expected mark keybinding");
+    }
+
     #[test]
     fn help_overlay_renders_quick_switch_keybinding() {
         let buf = render_help(80, 24);
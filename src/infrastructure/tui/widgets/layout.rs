@@ -2,14 +2,76 @@ use ratatui::layout::{Constraint, Layout, Rect};
 
 use crate::infrastructure::tui::app_runner::MINI_TERMINAL_HEIGHT;
 
+/// Sidebar width on startup.
+pub const DEFAULT_SIDEBAR_WIDTH: u16 = 25;
+/// Narrowest sidebar that still fits the help hint.
+pub const MIN_SIDEBAR_WIDTH: u16 = 18;
+/// Widest sidebar the resize keys allow.
+pub const MAX_SIDEBAR_WIDTH: u16 = 80;
+/// Columns added or removed per resize key press.
+pub const SIDEBAR_WIDTH_STEP: u16 = 2;
+
 pub struct LayoutAreas {
     pub sidebar: Rect,
     pub main_pane: Rect,
     pub mini_terminal: Option<Rect>,
 }
 
+/// Runtime-adjustable layout: sidebar width and visibility, and zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutSettings {
+    pub sidebar_width: u16,
+    pub sidebar_visible: bool,
+    /// The active terminal fills the whole screen (no sidebar, splits or
+    /// mini terminal).
+    pub zoomed: bool,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self {
+            sidebar_width: DEFAULT_SIDEBAR_WIDTH,
+            sidebar_visible: true,
+            zoomed: false,
+        }
+    }
+}
+
+impl LayoutSettings {
+    /// Whether the sidebar takes up space on screen.
+    pub fn shows_sidebar(&self) -> bool {
+        self.sidebar_visible && !self.zoomed
+    }
+
+    /// Widen (positive `delta`) or narrow the sidebar within its limits.
+    pub fn resize_sidebar(&mut self, delta: i32) {
+        let width = i32::from(self.sidebar_width) + delta;
+        self.sidebar_width =
+            width.clamp(i32::from(MIN_SIDEBAR_WIDTH), i32::from(MAX_SIDEBAR_WIDTH)) as u16;
+    }
+
+    /// The same settings with zoom turned off.
+    pub fn unzoomed(self) -> Self {
+        Self { zoomed: false, ..self }
+    }
+}
+
 pub fn compute_layout(area: Rect, mini_terminal_visible: bool) -> LayoutAreas {
-    let chunks = Layout::horizontal([Constraint::Length(25), Constraint::Min(0)]).split(area);
+    compute_layout_with(area, mini_terminal_visible, &LayoutSettings::default())
+}
+
+/// Like `compute_layout`, honouring the sidebar and zoom settings. A hidden
+/// sidebar gets a zero-width rect at the left edge.
+pub fn compute_layout_with(area: Rect, mini_terminal_visible: bool, settings: &LayoutSettings) -> LayoutAreas {
+    if settings.zoomed {
+        return LayoutAreas {
+            sidebar: Rect::new(area.x, area.y, 0, area.height),
+            main_pane: area,
+            mini_terminal: None,
+        };
+    }
+    let sidebar_width = if settings.sidebar_visible { settings.sidebar_width } else { 0 };
+    let chunks = Layout::horizontal([Constraint::Length(sidebar_width), Constraint::Min(0)]).split(area);
     let sidebar = chunks[0];
     let main_pane = chunks[1];
 
@@ -180,4 +242,69 @@ mod tests {
         // height should be MINI_TERMINAL_HEIGHT
         assert_eq!(mini.height, MINI_TERMINAL_HEIGHT);
     }
+
+    // === Sidebar width / visibility and zoom ===
+
+    #[test]
+    fn compute_layout_with_custom_sidebar_width() {
+        let settings = LayoutSettings { sidebar_width: 40, ..LayoutSettings::default() };
+        let result = compute_layout_with(Rect::new(0, 0, 100, 30), false, &settings);
+
+        assert_eq!(result.sidebar, Rect::new(0, 0, 40, 30));
+        assert_eq!(result.main_pane, Rect::new(40, 0, 60, 30));
+    }
+
+    #[test]
+    fn compute_layout_with_hidden_sidebar_gives_main_pane_full_width() {
+        let settings = LayoutSettings { sidebar_visible: false, ..LayoutSettings::default() };
+        let result = compute_layout_with(Rect::new(0, 0, 100, 30), true, &settings);
+
+        assert_eq!(result.sidebar.width, 0);
+        assert_eq!(result.main_pane, Rect::new(0, 0, 100, 30 - MINI_TERMINAL_HEIGHT));
+        assert_eq!(result.mini_terminal, Some(Rect::new(0, 30 - MINI_TERMINAL_HEIGHT, 100, MINI_TERMINAL_HEIGHT)));
+    }
+
+    #[test]
+    fn compute_layout_zoomed_uses_whole_area_and_hides_mini_terminal() {
+        let settings = LayoutSettings { zoomed: true, ..LayoutSettings::default() };
+        let area = Rect::new(2, 1, 100, 30);
+        let result = compute_layout_with(area, true, &settings);
+
+        assert_eq!(result.sidebar.width, 0);
+        assert_eq!(result.main_pane, area);
+        assert!(result.mini_terminal.is_none());
+    }
+
+    #[test]
+    fn layout_settings_default_matches_fixed_layout() {
+        let area = Rect::new(0, 0, 80, 24);
+        let fixed = compute_layout(area, true);
+        let with = compute_layout_with(area, true, &LayoutSettings::default());
+
+        assert_eq!(fixed.sidebar, with.sidebar);
+        assert_eq!(fixed.main_pane, with.main_pane);
+        assert_eq!(fixed.mini_terminal, with.mini_terminal);
+    }
+
+    #[test]
+    fn resize_sidebar_clamps_to_limits() {
+        let mut settings = LayoutSettings::default();
+        settings.resize_sidebar(i32::from(SIDEBAR_WIDTH_STEP));
+        assert_eq!(settings.sidebar_width, DEFAULT_SIDEBAR_WIDTH + SIDEBAR_WIDTH_STEP);
+
+        settings.resize_sidebar(-1000);
+        assert_eq!(settings.sidebar_width, MIN_SIDEBAR_WIDTH);
+
+        settings.resize_sidebar(1000);
+        assert_eq!(settings.sidebar_width, MAX_SIDEBAR_WIDTH);
+    }
+
+    #[test]
+    fn shows_sidebar_is_false_when_hidden_or_zoomed() {
+        let settings = LayoutSettings::default();
+        assert!(settings.shows_sidebar());
+        assert!(!LayoutSettings { sidebar_visible: false, ..settings }.shows_sidebar());
+        assert!(!LayoutSettings { zoomed: true, ..settings }.shows_sidebar());
+        assert!(!LayoutSettings { zoomed: true, ..settings }.unzoomed().zoomed);
+    }
 }
@@ -1,23 +1,55 @@
+use std::borrow::Borrow;
+use std::time::Instant;
+
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use unicode_width::UnicodeWidthStr;
 
 use crate::domain::model::ManagedTerminal;
+use crate::domain::primitive::{Color as DomainColor, GroupHeader, StatusBadge, TerminalStatus};
+
+use super::terminal_view::to_ratatui_color;
 
 /// Lines per terminal entry: name + cwd + status + separator.
 const LINES_PER_TERMINAL: usize = 4;
-/// The last terminal omits the separator, so it uses 3 lines.
-const LINES_LAST_TERMINAL: usize = 3;
 
-/// Compute the total content height (in lines) for a terminal list.
-fn total_content_lines(terminal_count: usize) -> usize {
-    if terminal_count == 0 {
-        return 0;
+/// Where terminal entries and group headers start in the scrollable list.
+///
+/// A header takes one line above the first terminal of its group (at the
+/// end for a collapsed group with nothing shown below). Every entry but the
+/// last line of the list is followed by a separator.
+struct ListLayout {
+    entries: Vec<usize>,
+    headers: Vec<usize>,
+    total: usize,
+}
+
+impl ListLayout {
+    fn new(terminal_count: usize, groups: &[GroupHeader]) -> Self {
+        let mut entries = Vec::with_capacity(terminal_count);
+        let mut headers = Vec::with_capacity(groups.len());
+        let mut line = 0;
+        for i in 0..terminal_count {
+            while groups.get(headers.len()).is_some_and(|g| g.at <= i) {
+                headers.push(line);
+                line += 1;
+            }
+            entries.push(line);
+            line += LINES_PER_TERMINAL;
+        }
+        // The last entry has no separator unless headers follow it
+        if terminal_count > 0 && headers.len() == groups.len() {
+            line -= 1;
+        }
+        while headers.len() < groups.len() {
+            headers.push(line);
+            line += 1;
+        }
+        Self { entries, headers, total: line }
     }
-    // All but the last have 4 lines; the last has 3 lines.
-    (terminal_count - 1) * LINES_PER_TERMINAL + LINES_LAST_TERMINAL
 }
 
 /// Compute the scroll offset so the active terminal is always visible.
@@ -25,6 +57,7 @@ fn total_content_lines(terminal_count: usize) -> usize {
 /// Returns the line offset to pass to `Paragraph::scroll()`.
 pub fn compute_scroll_offset(
     terminal_count: usize,
+    groups: &[GroupHeader],
     active_index: Option<usize>,
     visible_height: u16,
     current_offset: usize,
@@ -37,21 +70,21 @@ pub fn compute_scroll_offset(
     }
 
     let visible = visible_height as usize;
-    let total = total_content_lines(terminal_count);
+    let layout = ListLayout::new(terminal_count, groups);
+    let total = layout.total;
 
     // If everything fits, no scrolling needed
     if total <= visible {
         return 0;
     }
 
-    // Start line of the active terminal
-    let active_start = active * LINES_PER_TERMINAL;
+    // Start line of the active terminal, including its group header
+    let mut active_start = layout.entries[active];
+    if active_start > 0 && layout.headers.contains(&(active_start - 1)) {
+        active_start -= 1;
+    }
     // End line (exclusive) of the active terminal
-    let active_end = if active == terminal_count - 1 {
-        active_start + LINES_LAST_TERMINAL
-    } else {
-        active_start + LINES_PER_TERMINAL
-    };
+    let active_end = (layout.entries[active] + LINES_PER_TERMINAL).min(total);
 
     // Scroll up if active is above visible area
     if active_start < current_offset {
@@ -68,24 +101,97 @@ pub fn compute_scroll_offset(
     current_offset.min(max_offset)
 }
 
+/// Line of the scrollable list drawn at a screen row inside the sidebar.
+///
+/// Accounts for the border, the help line at the bottom and the current
+/// scroll offset. Returns `None` for rows outside the list area.
+fn line_at(area: Rect, row: u16, scroll_offset: usize) -> Option<usize> {
+    let top = area.y + 1;
+    // Bottom border and help line are not part of the list
+    let bottom = (area.y + area.height).saturating_sub(2);
+    (row >= top && row < bottom).then(|| (row - top) as usize + scroll_offset)
+}
+
+/// Map a screen row inside the sidebar to the terminal entry drawn there.
+/// Returns `None` for group headers and rows outside the entry list.
+pub fn index_at(
+    area: Rect,
+    row: u16,
+    terminal_count: usize,
+    groups: &[GroupHeader],
+    scroll_offset: usize,
+) -> Option<usize> {
+    let line = line_at(area, row, scroll_offset)?;
+    let layout = ListLayout::new(terminal_count, groups);
+    if line >= layout.total {
+        return None;
+    }
+    layout
+        .entries
+        .iter()
+        .position(|&start| (start..start + LINES_PER_TERMINAL).contains(&line))
+}
+
+/// Map a screen row inside the sidebar to the group header drawn there.
+pub fn group_at(
+    area: Rect,
+    row: u16,
+    terminal_count: usize,
+    groups: &[GroupHeader],
+    scroll_offset: usize,
+) -> Option<usize> {
+    let line = line_at(area, row, scroll_offset)?;
+    ListLayout::new(terminal_count, groups).headers.iter().position(|&start| start == line)
+}
+
+/// Header line of a group, e.g. "▾ api (3)", or "▸ api (3)" when collapsed.
+fn group_line(group: &GroupHeader) -> Line<'static> {
+    let arrow = if group.collapsed { '\u{25b8}' } else { '\u{25be}' };
+    Line::from(Span::styled(
+        format!("{} {} ({})", arrow, group.name, group.count),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// Which of `terminals` keystrokes go to in synchronize mode: the marked
+/// ones, or all of them when none is marked, plus the active one.
+fn sync_receivers(terminals: &[impl Borrow<ManagedTerminal>], active_index: Option<usize>, sync: bool) -> Vec<bool> {
+    let any_marked = terminals.iter().any(|t| t.borrow().is_marked());
+    terminals
+        .iter()
+        .enumerate()
+        .map(|(i, t)| sync && (!any_marked || t.borrow().is_marked() || active_index == Some(i)))
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     frame: &mut Frame,
     area: Rect,
-    terminals: &[ManagedTerminal],
+    terminals: &[impl Borrow<ManagedTerminal>],
+    groups: &[GroupHeader],
     active_index: Option<usize>,
     is_focused: bool,
+    sync: bool,
     scroll_offset: usize,
     dynamic_cwds: &[Option<String>],
 ) {
-    let border_style = if is_focused {
+    let border_style = if sync {
+        Style::default().fg(Color::Red)
+    } else if is_focused {
         Style::default().fg(Color::Cyan)
     } else {
         Style::default()
     };
+    let mut title = vec![Span::raw(format!("Terminals  {}", terminals.len()))];
+    if sync {
+        title.push(Span::styled(" SYNC ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+    }
     let block = Block::default()
-        .title(format!("Terminals  {}", terminals.len()))
+        .title(Line::from(title))
         .borders(Borders::ALL)
         .border_style(border_style);
+    let receivers = sync_receivers(terminals, active_index, sync);
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -100,8 +206,12 @@ pub fn render(
     let help_area = chunks[1];
 
     let mut lines: Vec<Line> = Vec::new();
+    let mut headers = groups.iter().peekable();
 
-    for (i, terminal) in terminals.iter().enumerate() {
+    for (i, terminal) in terminals.iter().map(Borrow::borrow).enumerate() {
+        while let Some(group) = headers.next_if(|g| g.at <= i) {
+            lines.push(group_line(group));
+        }
         let is_active = active_index == Some(i);
         let style = if is_active {
             Style::default().bg(Color::DarkGray)
@@ -109,7 +219,7 @@ pub fn render(
             Style::default()
         };
 
-        // Line 1: icon + display name + memo mark + notification mark
+        // Line 1: icon + display name + badge icon + memo mark + notification mark
         let icon = terminal.status().icon();
         let memo_mark = if terminal.has_memo() { " [\u{2261}]" } else { "" };
         let notification_mark = if terminal.has_unread_notification() {
@@ -125,11 +235,22 @@ pub fn render(
         } else {
             style
         };
-        let line1 = Line::from(vec![Span::styled(
-            format!("{} {}{}{}", icon, terminal.display_name(), memo_mark, notification_mark),
-            name_style,
-        )]);
-        lines.push(line1);
+        let badge_style = |badge: &StatusBadge| match badge.color {
+            DomainColor::Default => style,
+            color => style.fg(to_ratatui_color(color)),
+        };
+        let mut line1 = vec![Span::styled(format!("{} {}", icon, terminal.display_name()), name_style)];
+        if let Some(badge @ StatusBadge { icon: Some(badge_icon), .. }) = terminal.badge() {
+            line1.push(Span::styled(format!(" {}", badge_icon), badge_style(badge)));
+        }
+        line1.push(Span::styled(format!("{}{}", memo_mark, notification_mark), name_style));
+        if terminal.is_marked() {
+            line1.push(Span::styled(" \u{25c6}", style.fg(Color::Magenta)));
+        }
+        if receivers[i] {
+            line1.push(Span::styled(" \u{00bb}", style.fg(Color::Red).add_modifier(Modifier::BOLD)));
+        }
+        lines.push(Line::from(line1));
 
         // Line 2: cwd (truncated to fit sidebar width)
         let max_width = inner.width.saturating_sub(2) as usize;
@@ -144,15 +265,21 @@ pub fn render(
         };
         lines.push(Line::from(Span::styled(cwd_display, style)));
 
-        // Line 3: status text
-        let status_text = format!("  {}", terminal.status().status_text());
-        lines.push(Line::from(Span::styled(status_text, style)));
+        // Line 3: status badge while alive, otherwise what the terminal is doing
+        let status_line = match (terminal.status(), terminal.badge()) {
+            (TerminalStatus::Running | TerminalStatus::Idle, Some(badge)) => {
+                Span::styled(format!("  {}", badge.label()), badge_style(badge))
+            }
+            _ => Span::styled(format!("  {}", terminal.activity_text(Instant::now())), style),
+        };
+        lines.push(Line::from(status_line));
 
         // Separator line (except after last item)
-        if i < terminals.len() - 1 {
-            lines.push(Line::from("\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}\u{2500}"));
+        if i < terminals.len() - 1 || headers.peek().is_some() {
+            lines.push(Line::from("\u{2500}".repeat(inner.width as usize)));
         }
     }
+    lines.extend(headers.map(group_line));
 
     // Render terminal list with scroll offset
     let total_lines = lines.len();
@@ -180,15 +307,36 @@ pub fn render(
     frame.render_widget(help_paragraph, help_area);
 }
 
+/// Draw the sidebar filter over the help line, e.g. "/ running · api".
+/// While `editing`, returns where the cursor goes.
+pub fn render_filter_line(frame: &mut Frame, area: Rect, label: &str, editing: bool) -> Option<(u16, u16)> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if inner.height == 0 {
+        return None;
+    }
+    let line_area = Rect::new(inner.x, inner.bottom() - 1, inner.width, 1);
+    let text = format!("/ {}", label);
+    let style = if editing {
+        Style::default()
+    } else {
+        Style::default().fg(Color::Yellow)
+    };
+    let cursor_x = line_area.x + (text.width() as u16).min(line_area.width.saturating_sub(1));
+    frame.render_widget(Clear, line_area);
+    frame.render_widget(Paragraph::new(Line::from(Span::styled(text, style))), line_area);
+    editing.then_some((cursor_x, line_area.y))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
+    use std::time::Duration;
 
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
 
-    use crate::domain::primitive::TerminalId;
+    use crate::domain::primitive::{ForegroundProcess, TerminalId};
 
     fn create_terminal(id: u32, name: &str) -> ManagedTerminal {
         ManagedTerminal::new(
@@ -213,7 +361,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -233,7 +381,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -252,7 +400,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -274,7 +422,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -293,7 +441,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -316,7 +464,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -339,7 +487,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -358,7 +506,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, None, true, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, true, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -377,7 +525,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -391,18 +539,18 @@ mod tests {
 
     #[test]
     fn compute_scroll_offset_no_active_returns_zero() {
-        assert_eq!(compute_scroll_offset(5, None, 20, 0), 0);
+        assert_eq!(compute_scroll_offset(5, &[], None, 20, 0), 0);
     }
 
     #[test]
     fn compute_scroll_offset_no_terminals_returns_zero() {
-        assert_eq!(compute_scroll_offset(0, Some(0), 20, 0), 0);
+        assert_eq!(compute_scroll_offset(0, &[], Some(0), 20, 0), 0);
     }
 
     #[test]
     fn compute_scroll_offset_all_fit_returns_zero() {
         // 2 terminals = 4 + 3 = 7 lines, visible = 20 → fits
-        assert_eq!(compute_scroll_offset(2, Some(1), 20, 0), 0);
+        assert_eq!(compute_scroll_offset(2, &[], Some(1), 20, 0), 0);
     }
 
     #[test]
@@ -410,7 +558,7 @@ mod tests {
         // 5 terminals: 4*4 + 3 = 19 lines total, visible = 10
         // Active = 4 (last), starts at line 16, ends at 19
         // current_offset = 0 → active_end (19) > 0 + 10 → scroll to 19 - 10 = 9
-        assert_eq!(compute_scroll_offset(5, Some(4), 10, 0), 9);
+        assert_eq!(compute_scroll_offset(5, &[], Some(4), 10, 0), 9);
     }
 
     #[test]
@@ -418,7 +566,7 @@ mod tests {
         // 5 terminals, visible = 10, current_offset = 12
         // Active = 1, starts at line 4
         // 4 < 12 → scroll up to 4
-        assert_eq!(compute_scroll_offset(5, Some(1), 10, 12), 4);
+        assert_eq!(compute_scroll_offset(5, &[], Some(1), 10, 12), 4);
     }
 
     #[test]
@@ -426,7 +574,7 @@ mod tests {
         // 5 terminals: 19 lines total, visible = 10, current_offset = 4
         // Active = 2, starts at line 8, ends at 12
         // 8 >= 4 and 12 <= 4 + 10 = 14 → keep offset 4
-        assert_eq!(compute_scroll_offset(5, Some(2), 10, 4), 4);
+        assert_eq!(compute_scroll_offset(5, &[], Some(2), 10, 4), 4);
     }
 
     #[test]
@@ -434,7 +582,28 @@ mod tests {
         // 3 terminals: 4 + 4 + 3 = 11 lines, visible = 8
         // Active = 2 (last), starts at line 8, ends at 11
         // 11 > 0 + 8 → scroll to 11 - 8 = 3
-        assert_eq!(compute_scroll_offset(3, Some(2), 8, 0), 3);
+        assert_eq!(compute_scroll_offset(3, &[], Some(2), 8, 0), 3);
+    }
+
+    // ===== index_at tests =====
+
+    #[test]
+    fn index_at_maps_rows_to_entries() {
+        let area = Rect::new(0, 0, 25, 20);
+        assert_eq!(index_at(area, 0, 3, &[], 0), None); // top border
+        assert_eq!(index_at(area, 1, 3, &[], 0), Some(0));
+        assert_eq!(index_at(area, 4, 3, &[], 0), Some(0)); // separator line
+        assert_eq!(index_at(area, 5, 3, &[], 0), Some(1));
+        assert_eq!(index_at(area, 9, 3, &[], 0), Some(2));
+        assert_eq!(index_at(area, 13, 3, &[], 0), None); // below the last entry
+    }
+
+    #[test]
+    fn index_at_excludes_help_line_and_applies_scroll() {
+        let area = Rect::new(0, 2, 25, 10);
+        assert_eq!(index_at(area, 10, 9, &[], 0), None); // help line
+        assert_eq!(index_at(area, 11, 9, &[], 0), None); // bottom border
+        assert_eq!(index_at(area, 3, 9, &[], 8), Some(2));
     }
 
     // ===== Scroll rendering tests =====
@@ -453,7 +622,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 12);
-                render(frame, area, &terminals, Some(2), false, 4, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(2), false, false, 4, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -485,7 +654,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 12);
-                render(frame, area, &terminals, Some(2), false, 4, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(2), false, false, 4, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -521,7 +690,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 8);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -550,7 +719,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -579,7 +748,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &dynamic_cwds);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &dynamic_cwds);
             })
             .unwrap();
 
@@ -601,7 +770,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &dynamic_cwds);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &dynamic_cwds);
             })
             .unwrap();
 
@@ -626,7 +795,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 30, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &dynamic_cwds);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &dynamic_cwds);
             })
             .unwrap();
 
@@ -661,7 +830,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -686,7 +855,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -711,7 +880,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -746,7 +915,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -778,7 +947,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, Some(1), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(1), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -814,7 +983,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -867,7 +1036,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -891,7 +1060,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 40, 20);
-                render(frame, area, &terminals, Some(0), false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], Some(0), false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -918,7 +1087,7 @@ mod tests {
         terminal
             .draw(|frame| {
                 let area = Rect::new(0, 0, 50, 20);
-                render(frame, area, &terminals, None, false, 0, &vec![None; terminals.len()]);
+                render(frame, area, &terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
             })
             .unwrap();
 
@@ -937,4 +1106,243 @@ mod tests {
             row1
         );
     }
+
+    // =========================================================================
+    // Tests: status badge
+    // =========================================================================
+
+    fn create_terminal_with_badge(id: u32, name: &str) -> ManagedTerminal {
+        let mut t = create_terminal(id, name);
+        t.set_badge(Some(StatusBadge {
+            text: "tests failing".to_string(),
+            icon: Some("!".to_string()),
+            color: DomainColor::Indexed(1),
+        }));
+        t
+    }
+
+    fn render_rows(terminals: &[ManagedTerminal]) -> (Vec<String>, ratatui::buffer::Buffer) {
+        let backend = TestBackend::new(40, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                let area = Rect::new(0, 0, 40, 20);
+                render(frame, area, terminals, &[], None, false, false, 0, &vec![None; terminals.len()]);
+            })
+            .unwrap();
+        let buf = terminal.backend().buffer().clone();
+        let rows = (0..20)
+            .map(|y| (0..40).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect())
+            .collect();
+        (rows, buf)
+    }
+
+    #[test]
+    fn render_badge_icon_after_name_and_label_on_status_line() {
+        let (rows, buf) = render_rows(&[create_terminal_with_badge(1, "agent")]);
+        assert!(rows[1].contains("1: agent !"), "got: {}", rows[1]);
+        assert!(rows[3].contains("! tests failing"), "got: {}", rows[3]);
+        assert!(!rows[3].contains("running"), "got: {}", rows[3]);
+
+        let x = rows[3].find('!').unwrap() as u16;
+        assert_eq!(buf[(x, 3)].fg, Color::Indexed(1));
+    }
+
+    #[test]
+    fn render_exited_terminal_shows_exit_code_over_badge() {
+        let mut t = create_terminal_with_badge(1, "agent");
+        t.mark_exited(2);
+        let (rows, _) = render_rows(&[t]);
+        assert!(rows[3].contains("exited (2)"), "got: {}", rows[3]);
+        // The icon stays next to the name
+        assert!(rows[1].contains("1: agent !"), "got: {}", rows[1]);
+    }
+
+    // =========================================================================
+    // Tests: foreground activity
+    // =========================================================================
+
+    fn foreground(name: &str, is_terminal_process: bool) -> ForegroundProcess {
+        ForegroundProcess {
+            pid: 42,
+            name: name.to_string(),
+            cmdline: name.to_string(),
+            is_terminal_process,
+        }
+    }
+
+    #[test]
+    fn render_idle_shell_shows_hollow_icon_and_idle() {
+        let mut t = create_terminal(1, "shell");
+        t.set_foreground(Some(foreground("zsh", true)));
+        let (rows, _) = render_rows(&[t]);
+        assert!(rows[1].contains("\u{25cb} 1: shell"), "got: {}", rows[1]);
+        assert!(rows[3].contains("idle"), "got: {}", rows[3]);
+    }
+
+    #[test]
+    fn render_running_program_shows_its_name() {
+        let mut t = create_terminal(1, "edit");
+        t.set_foreground(Some(foreground("vim", false)));
+        let (rows, _) = render_rows(&[t]);
+        assert!(rows[1].contains("\u{25cf} 1: edit"), "got: {}", rows[1]);
+        assert!(rows[3].contains("vim running"), "got: {}", rows[3]);
+    }
+
+    #[test]
+    fn render_quiet_terminal_shows_minutes_without_output() {
+        let mut t = create_terminal(1, "build");
+        t.record_output(Instant::now() - Duration::from_secs(7 * 60));
+        let (rows, _) = render_rows(&[t]);
+        assert!(rows[3].contains("running · quiet 7m"), "got: {}", rows[3]);
+    }
+
+    #[test]
+    fn render_badge_replaces_activity_on_idle_terminal() {
+        let mut t = create_terminal_with_badge(1, "agent");
+        t.set_foreground(Some(foreground("zsh", true)));
+        let (rows, _) = render_rows(&[t]);
+        assert!(rows[3].contains("! tests failing"), "got: {}", rows[3]);
+    }
+
+    // =========================================================================
+    // Tests: filter line
+    // =========================================================================
+
+    #[test]
+    fn render_borrowed_terminals_and_filter_line() {
+        let terminals = [create_terminal(1, "api"), create_terminal(2, "web")];
+        let shown: Vec<&ManagedTerminal> = vec![&terminals[1]];
+        let backend = TestBackend::new(40, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut cursor = None;
+        terminal
+            .draw(|frame| {
+                let area = Rect::new(0, 0, 40, 20);
+                render(frame, area, &shown, &[], Some(0), false, false, 0, &[None]);
+                cursor = render_filter_line(frame, area, "running · we", true);
+            })
+            .unwrap();
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..20)
+            .map(|y| (0..40).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect())
+            .collect();
+
+        assert!(rows[0].contains("Terminals  1"), "got: {}", rows[0]);
+        assert!(rows[1].contains("2: web"), "got: {}", rows[1]);
+        assert!(!rows.iter().any(|r| r.contains("1: api")));
+        // The filter replaces the help line, with the cursor after the text
+        assert!(rows[18].contains("/ running · we"), "got: {}", rows[18]);
+        assert!(!rows[18].contains("Help"), "got: {}", rows[18]);
+        assert_eq!(cursor, Some((1 + "/ running · we".width() as u16, 18)));
+    }
+
+    // =========================================================================
+    // Tests: group headers
+    // =========================================================================
+
+    fn header(name: &str, count: usize, collapsed: bool, at: usize) -> GroupHeader {
+        GroupHeader { name: name.to_string(), count, collapsed, at }
+    }
+
+    #[test]
+    fn render_group_headers_above_their_terminals() {
+        let terminals = [create_terminal(1, "shell"), create_terminal(2, "api"), create_terminal(3, "web")];
+        let groups = [header("backend", 1, false, 1), header("docs", 2, true, 3)];
+        let backend = TestBackend::new(40, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                render(frame, Rect::new(0, 0, 40, 20), &terminals, &groups, None, false, false, 0, &[None, None, None]);
+            })
+            .unwrap();
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..20)
+            .map(|y| (0..40).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect())
+            .collect();
+
+        assert!(rows[1].contains("1: shell"), "got: {}", rows[1]);
+        assert!(rows[5].contains("\u{25be} backend (1)"), "got: {}", rows[5]);
+        assert!(rows[6].contains("2: api"), "got: {}", rows[6]);
+        assert!(rows[10].contains("3: web"), "got: {}", rows[10]);
+        // A collapsed group with nothing shown keeps its header at the end
+        assert!(rows[14].contains("\u{25b8} docs (2)"), "got: {}", rows[14]);
+    }
+
+    #[test]
+    fn index_at_and_group_at_skip_over_headers() {
+        let area = Rect::new(0, 0, 25, 20);
+        let groups = [header("api", 2, false, 1)];
+        // Row 4 is the separator of entry 0, row 5 the header
+        assert_eq!(index_at(area, 4, 3, &groups, 0), Some(0));
+        assert_eq!(index_at(area, 5, 3, &groups, 0), None);
+        assert_eq!(group_at(area, 5, 3, &groups, 0), Some(0));
+        assert_eq!(index_at(area, 6, 3, &groups, 0), Some(1));
+        assert_eq!(index_at(area, 10, 3, &groups, 0), Some(2));
+        assert_eq!(group_at(area, 6, 3, &groups, 0), None);
+    }
+
+    #[test]
+    fn compute_scroll_offset_counts_header_lines() {
+        let groups = [header("api", 2, false, 1), header("web", 2, false, 3)];
+        // Lines: entry 0 (0-3), header (4), entry 1 (5-8), entry 2 (9-12),
+        // header (13), entry 3 (14-16) = 17 lines, visible = 8
+        assert_eq!(compute_scroll_offset(4, &groups, Some(3), 8, 0), 9);
+        // Scrolling up to the first terminal of a group shows its header
+        assert_eq!(compute_scroll_offset(4, &groups, Some(1), 8, 9), 4);
+    }
+
+    // =========================================================================
+    // Tests: synchronize mode
+    // =========================================================================
+
+    #[test]
+    fn sync_receivers_are_marked_terminals_and_the_active_one() {
+        let mut terminals = vec![create_terminal(1, "a"), create_terminal(2, "b"), create_terminal(3, "c")];
+        assert_eq!(sync_receivers(&terminals, Some(0), false), vec![false, false, false]);
+        // Nothing marked: every shown terminal
+        assert_eq!(sync_receivers(&terminals, Some(0), true), vec![true, true, true]);
+        terminals[2].set_marked(true);
+        assert_eq!(sync_receivers(&terminals, Some(0), true), vec![true, false, true]);
+    }
+
+    #[test]
+    fn render_sync_title_and_marks() {
+        let mut terminals = vec![create_terminal(1, "a"), create_terminal(2, "b")];
+        terminals[1].set_marked(true);
+        let backend = TestBackend::new(30, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                render(frame, Rect::new(0, 0, 30, 12), &terminals, &[], Some(0), false, true, 0, &[None, None]);
+            })
+            .unwrap();
+        let buf = terminal.backend().buffer();
+        let rows: Vec<String> = (0..12)
+            .map(|y| (0..30).map(|x| buf[(x, y)].symbol().chars().next().unwrap_or(' ')).collect())
+            .collect();
+
+        assert!(rows[0].contains("SYNC"), "got: {}", rows[0]);
+        assert_eq!(buf[(0, 0)].fg, Color::Red);
+        assert!(rows[1].contains("1: a \u{00bb}"), "got: {}", rows[1]);
+        assert!(rows[5].contains("2: b \u{25c6} \u{00bb}"), "got: {}", rows[5]);
+    }
+
+    #[test]
+    fn render_without_sync_shows_marks_only() {
+        let mut terminals = vec![create_terminal(1, "a")];
+        terminals[0].set_marked(true);
+        let backend = TestBackend::new(30, 8);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                render(frame, Rect::new(0, 0, 30, 8), &terminals, &[], None, false, false, 0, &[None]);
+            })
+            .unwrap();
+        let buf = terminal.backend().buffer();
+        let top: String = (0..30).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        let entry: String = (0..30).map(|x| buf[(x, 1)].symbol().to_string()).collect();
+        assert!(!top.contains("SYNC"), "got: {top}");
+        assert!(entry.contains("\u{25c6}") && !entry.contains("\u{00bb}"), "got: {entry}");
+    }
 }
@@ -32,7 +32,7 @@ pub struct ScrollbackCursorHighlight {
 }
 
 /// Convert domain Color to ratatui Color
-fn to_ratatui_color(color: Color) -> RatColor {
+pub(crate) fn to_ratatui_color(color: Color) -> RatColor {
     match color {
         Color::Default => RatColor::Reset,
         Color::Indexed(n) => RatColor::Indexed(n),
@@ -63,6 +63,13 @@ pub fn render(
     }
 }
 
+/// Draw the vertical line between two side-by-side split panes.
+pub fn render_divider(frame: &mut Frame, area: Rect) {
+    let lines = vec![Line::from("│"); area.height as usize];
+    let paragraph = Paragraph::new(lines).style(Style::default().fg(RatColor::DarkGray));
+    frame.render_widget(paragraph, area);
+}
+
 /// Build a progress bar string: `████░░░░░░` where filled proportion = offset/max.
 fn build_progress_bar(width: usize, offset: usize, max: usize) -> String {
     if width == 0 || max == 0 {
@@ -2252,4 +2259,25 @@ mod tests {
         // Without cursor_hl, row should have normal (Reset) background
         assert_ne!(buf[(1, 3)].bg, RatColor::DarkGray, "Without cursor_hl, no dark gray bg expected");
     }
+
+    // =========================================================================
+    // Tests: split pane divider
+    // =========================================================================
+
+    #[test]
+    fn render_divider_draws_vertical_line() {
+        let backend = TestBackend::new(10, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|frame| render_divider(frame, Rect::new(4, 0, 1, 4)))
+            .unwrap();
+
+        let buf = terminal.backend().buffer();
+        for y in 0..4 {
+            assert_eq!(buf[(4, y)].symbol(), "│");
+            assert_eq!(buf[(4, y)].fg, RatColor::DarkGray);
+        }
+        assert_eq!(buf[(3, 0)].symbol(), " ");
+    }
 }
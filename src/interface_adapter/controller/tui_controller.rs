@@ -1,4 +1,4 @@
-use crate::domain::primitive::TerminalSize;
+use crate::domain::primitive::{group_name, PaneDirection, Signal, SortKey, SplitDirection, TerminalSize};
 use crate::interface_adapter::port::{PtyPort, ScreenPort};
 use crate::shared::error::AppError;
 use crate::usecase::terminal_usecase::TerminalUsecase;
@@ -48,6 +48,29 @@ pub enum AppAction {
     ScrollbackCursorRight,
     ScrollbackCursorLineStart,
     ScrollbackCursorLineEnd,
+    ToggleRecording,
+    RespawnTerminal,
+    OpenSignalMenu,
+    SignalTerminal(Signal),
+    /// Move the active terminal up (negative) or down the sidebar.
+    MoveTerminal(isize),
+    OpenSortMenu,
+    SortTerminals(SortKey),
+    EditFilter,
+    /// Collapse or expand the active terminal's group in the sidebar.
+    ToggleGroup,
+    /// Put the active terminal in a group; an empty name ungroups it.
+    SetGroup { group: String },
+    /// Turn synchronize mode on or off.
+    ToggleSync,
+    /// Mark or unmark the active terminal for synchronized input.
+    ToggleMark,
+    SplitPane(SplitDirection),
+    SelectPane(PaneDirection),
+    ToggleSidebar,
+    WidenSidebar,
+    NarrowSidebar,
+    ToggleZoom,
 }
 
 /// Thin controller that translates `AppAction`s into usecase calls.
@@ -115,6 +138,9 @@ impl<P: PtyPort, S: ScreenPort> TuiController<P, S> {
             AppAction::ToggleMiniTerminal => {}    // Handled by caller (app_runner)
             AppAction::WriteToMiniTerminal(_) => {} // Handled by caller (app_runner)
             AppAction::OpenQuickSwitcher => {}     // Handled by caller (app_runner)
+            AppAction::OpenSignalMenu => {}        // Handled by caller (app_runner)
+            AppAction::OpenSortMenu => {}          // Handled by caller (app_runner)
+            AppAction::EditFilter => {}            // Handled by caller (app_runner)
             AppAction::EnterScrollbackSearch
             | AppAction::ScrollbackSearchNext
             | AppAction::ScrollbackSearchPrev
@@ -130,6 +156,36 @@ impl<P: PtyPort, S: ScreenPort> TuiController<P, S> {
             | AppAction::ScrollbackCursorRight
             | AppAction::ScrollbackCursorLineStart
             | AppAction::ScrollbackCursorLineEnd => {} // Handled by caller (app_runner)
+            AppAction::SplitPane(_) | AppAction::SelectPane(_) => {} // Handled by caller (pane layout)
+            AppAction::ToggleSidebar
+            | AppAction::WidenSidebar
+            | AppAction::NarrowSidebar
+            | AppAction::ToggleZoom => {} // Handled by caller (layout settings)
+            AppAction::ToggleRecording => {
+                self.usecase.toggle_active_recording()?;
+            }
+            AppAction::RespawnTerminal => {
+                self.usecase.respawn_active()?;
+            }
+            AppAction::SignalTerminal(signal) => {
+                self.usecase.signal_active(signal)?;
+            }
+            AppAction::MoveTerminal(delta) => {
+                self.usecase.move_active(delta)?;
+            }
+            AppAction::SortTerminals(key) => self.usecase.sort_terminals(key),
+            AppAction::ToggleGroup => {
+                self.usecase.toggle_active_group()?;
+            }
+            AppAction::SetGroup { group } => {
+                self.usecase.set_active_group(group_name(&group))?;
+            }
+            AppAction::ToggleSync => {
+                self.usecase.set_sync_input(None);
+            }
+            AppAction::ToggleMark => {
+                self.usecase.toggle_active_mark()?;
+            }
         }
         Ok(())
     }
@@ -161,12 +217,17 @@ mod tests {
     // Mock implementations
     // =========================================================================
 
+    /// Arguments recorded for a single `spawn` call: (id, argv, cwd, size, env).
+    type SpawnCall = (TerminalId, Vec<String>, PathBuf, TerminalSize, Vec<(String, String)>);
+
     /// Records all calls made to the PtyPort methods for assertion.
     /// Uses Arc<Mutex<...>> for shared call tracking (Send + Sync safe).
     #[derive(Clone)]
     struct MockPtyPort {
-        spawn_calls: Arc<Mutex<Vec<(TerminalId, String, PathBuf, TerminalSize)>>>,
+        spawn_calls: Arc<Mutex<Vec<SpawnCall>>>,
         kill_calls: Arc<Mutex<Vec<TerminalId>>>,
+        close_calls: Arc<Mutex<Vec<TerminalId>>>,
+        signal_calls: Arc<Mutex<Vec<(TerminalId, Signal)>>>,
         write_calls: Arc<Mutex<Vec<(TerminalId, Vec<u8>)>>>,
         resize_calls: Arc<Mutex<Vec<(TerminalId, TerminalSize)>>>,
         read_results: Arc<Mutex<HashMap<u32, Result<Vec<u8>, AppError>>>>,
@@ -178,6 +239,8 @@ mod tests {
             Self {
                 spawn_calls: Arc::new(Mutex::new(Vec::new())),
                 kill_calls: Arc::new(Mutex::new(Vec::new())),
+                close_calls: Arc::new(Mutex::new(Vec::new())),
+                signal_calls: Arc::new(Mutex::new(Vec::new())),
                 write_calls: Arc::new(Mutex::new(Vec::new())),
                 resize_calls: Arc::new(Mutex::new(Vec::new())),
                 read_results: Arc::new(Mutex::new(HashMap::new())),
@@ -190,15 +253,17 @@ mod tests {
         fn spawn(
             &mut self,
             id: TerminalId,
-            shell: &str,
+            argv: &[String],
             cwd: &Path,
+            env: &[(String, String)],
             size: TerminalSize,
         ) -> Result<(), AppError> {
             self.spawn_calls.lock().unwrap().push((
                 id,
-                shell.to_string(),
+                argv.to_vec(),
                 cwd.to_path_buf(),
                 size,
+                env.to_vec(),
             ));
             Ok(())
         }
@@ -232,6 +297,20 @@ mod tests {
                 .unwrap_or(Ok(None))
         }
 
+        fn foreground_process(&mut self, _id: TerminalId) -> Result<Option<ForegroundProcess>, AppError> {
+            Ok(None)
+        }
+
+        fn signal(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError> {
+            self.signal_calls.lock().unwrap().push((id, signal));
+            Ok(())
+        }
+
+        fn close(&mut self, id: TerminalId, _grace: std::time::Duration) -> Result<(), AppError> {
+            self.close_calls.lock().unwrap().push(id);
+            Ok(())
+        }
+
         fn kill(&mut self, id: TerminalId) -> Result<(), AppError> {
             self.kill_calls.lock().unwrap().push(id);
             Ok(())
@@ -328,6 +407,10 @@ mod tests {
             Ok(0)
         }
 
+        fn get_scrolled_off(&self, _id: TerminalId) -> Result<usize, AppError> {
+            Ok(0)
+        }
+
         fn is_alternate_screen(&self, _id: TerminalId) -> Result<bool, AppError> {
             Ok(false)
         }
@@ -336,6 +419,14 @@ mod tests {
             Ok(CursorStyle::DefaultUserShape)
         }
 
+        fn get_mouse_mode(&self, _id: TerminalId) -> Result<MouseMode, AppError> {
+            Ok(MouseMode::Off)
+        }
+
+        fn get_keyboard_flags(&self, _id: TerminalId) -> Result<u16, AppError> {
+            Ok(0)
+        }
+
         fn drain_pending_responses(&mut self, _id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> {
             Ok(vec![])
         }
@@ -441,7 +532,7 @@ mod tests {
     #[test]
     fn dispatch_close_terminal_calls_usecase_close() {
         let pty = MockPtyPort::new();
-        let kill_calls = pty.kill_calls.clone();
+        let close_calls = pty.close_calls.clone();
         let screen = MockScreenPort::new();
         let mut ctrl = make_controller_with_ports(pty, screen);
         let size = default_size();
@@ -451,7 +542,7 @@ mod tests {
         let result = ctrl.dispatch(AppAction::CloseTerminal, size);
 
         assert!(result.is_ok());
-        let calls = kill_calls.lock().unwrap();
+        let calls = close_calls.lock().unwrap();
         assert_eq!(calls.len(), 1);
         assert_eq!(calls[0], TerminalId::new(1));
         assert!(ctrl.usecase().get_terminals().is_empty());
@@ -1223,4 +1314,117 @@ mod tests {
         assert_eq!(ctrl.usecase().get_terminals().len(), 1);
     }
 
+    #[test]
+    fn dispatch_toggle_recording_without_terminal_returns_error() {
+        let mut ctrl = make_controller();
+        let result = ctrl.dispatch(AppAction::ToggleRecording, default_size());
+        assert!(matches!(result, Err(AppError::NoActiveTerminal)));
+    }
+
+    #[test]
+    fn dispatch_toggle_recording_without_recorder_is_unavailable() {
+        let mut ctrl = make_controller();
+        let size = default_size();
+        ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+        let result = ctrl.dispatch(AppAction::ToggleRecording, size);
+        assert!(matches!(result, Err(AppError::RecordUnavailable)));
+    }
+
+    #[test]
+    fn dispatch_respawn_restarts_exited_terminal() {
+        let pty = MockPtyPort::new();
+        let spawn_calls = pty.spawn_calls.clone();
+        let try_wait_results = pty.try_wait_results.clone();
+        let mut ctrl = make_controller_with_ports(pty, MockScreenPort::new());
+        let size = default_size();
+        ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+
+        // Still running: nothing to respawn
+        let result = ctrl.dispatch(AppAction::RespawnTerminal, size);
+        assert!(matches!(result, Err(AppError::StillRunning(_))));
+
+        try_wait_results.lock().unwrap().insert(1, Ok(Some(0)));
+        ctrl.dispatch(AppAction::PollAll, size).unwrap();
+        ctrl.dispatch(AppAction::RespawnTerminal, size).unwrap();
+        assert_eq!(ctrl.usecase().get_terminals()[0].status(), &TerminalStatus::Running);
+        assert_eq!(spawn_calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn dispatch_signal_terminal_signals_active_terminal() {
+        let pty = MockPtyPort::new();
+        let signal_calls = pty.signal_calls.clone();
+        let mut ctrl = make_controller_with_ports(pty, MockScreenPort::new());
+        let size = default_size();
+        ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+
+        ctrl.dispatch(AppAction::SignalTerminal(Signal::Int), size).unwrap();
+
+        assert_eq!(*signal_calls.lock().unwrap(), vec![(TerminalId::new(1), Signal::Int)]);
+    }
+
+    #[test]
+    fn dispatch_move_and_sort_keep_active_terminal() {
+        let mut ctrl = make_controller();
+        let size = default_size();
+        for name in ["b", "c", "a"] {
+            ctrl.dispatch(AppAction::CreateTerminal { name: Some(name.to_string()) }, size).unwrap();
+        }
+        let names = |ctrl: &TuiController<MockPtyPort, MockScreenPort>| {
+            ctrl.usecase().get_terminals().iter().map(|t| t.name().to_string()).collect::<Vec<_>>()
+        };
+
+        ctrl.dispatch(AppAction::MoveTerminal(-1), size).unwrap();
+        assert_eq!(names(&ctrl), ["b", "a", "c"]);
+        assert_eq!(ctrl.usecase().get_active_terminal().unwrap().name(), "a");
+
+        ctrl.dispatch(AppAction::SortTerminals(SortKey::Name), size).unwrap();
+        assert_eq!(names(&ctrl), ["a", "b", "c"]);
+        assert_eq!(ctrl.usecase().get_active_index(), Some(0));
+    }
+
+    #[test]
+    fn dispatch_set_and_toggle_group() {
+        let mut ctrl = make_controller();
+        let size = default_size();
+        ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+        ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+
+        ctrl.dispatch(AppAction::SetGroup { group: "#api ".to_string() }, size).unwrap();
+        assert_eq!(ctrl.usecase().get_active_terminal().unwrap().group(), Some("api"));
+
+        ctrl.dispatch(AppAction::ToggleGroup, size).unwrap();
+        assert!(ctrl.usecase().group_headers()[0].collapsed);
+
+        ctrl.dispatch(AppAction::SetGroup { group: String::new() }, size).unwrap();
+        assert_eq!(ctrl.usecase().get_active_terminal().unwrap().group(), None);
+        assert!(ctrl.usecase().group_headers().is_empty());
+    }
+
+    #[test]
+    fn dispatch_sync_broadcasts_keystrokes_to_marked_terminals() {
+        let pty = MockPtyPort::new();
+        let writes = pty.write_calls.clone();
+        let mut ctrl = make_controller_with_ports(pty, MockScreenPort::new());
+        let size = default_size();
+        for _ in 0..3 {
+            ctrl.dispatch(AppAction::CreateTerminal { name: None }, size).unwrap();
+        }
+        // Mark terminal 2, then type in terminal 3
+        ctrl.dispatch(AppAction::SelectByIndex(1), size).unwrap();
+        ctrl.dispatch(AppAction::ToggleMark, size).unwrap();
+        ctrl.dispatch(AppAction::SelectByIndex(2), size).unwrap();
+        ctrl.dispatch(AppAction::ToggleSync, size).unwrap();
+        assert!(ctrl.usecase().sync_input());
+
+        ctrl.dispatch(AppAction::WriteToActive(b"ls".to_vec()), size).unwrap();
+        let targets: Vec<u32> = writes.lock().unwrap().iter().map(|(id, _)| id.value()).collect();
+        assert_eq!(targets, vec![2, 3]);
+
+        ctrl.dispatch(AppAction::ToggleSync, size).unwrap();
+        writes.lock().unwrap().clear();
+        ctrl.dispatch(AppAction::WriteToActive(b"ls".to_vec()), size).unwrap();
+        let targets: Vec<u32> = writes.lock().unwrap().iter().map(|(id, _)| id.value()).collect();
+        assert_eq!(targets, vec![3]);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::domain::primitive::{IpcCommand, IpcResponse};
+use crate::domain::primitive::{IpcCommand, IpcEvent, IpcResponse};
 
 /// Unique identifier for an IPC client connection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -13,8 +13,22 @@ pub trait IpcPort: Send + Sync {
     fn poll_commands(&mut self) -> Vec<(ConnectionId, IpcCommand)>;
 
     /// Send a response to the specified connection.
+    ///
+    /// Closes the connection unless it was turned into a stream.
     fn send_response(&mut self, conn_id: ConnectionId, response: IpcResponse);
 
+    /// Keep the connection open after responses so events can be pushed to
+    /// it and further commands read from it (e.g. `attach`).
+    fn open_stream(&mut self, conn_id: ConnectionId);
+
+    /// Push an event to a stream connection.
+    ///
+    /// Returns `false` if the client is gone (or too slow and was dropped).
+    fn send_event(&mut self, conn_id: ConnectionId, event: &IpcEvent) -> bool;
+
+    /// Whether the connection is still open.
+    fn is_connected(&self, conn_id: ConnectionId) -> bool;
+
     /// Return the socket path (for exposing to child processes via env var).
     fn socket_path(&self) -> &str;
 
@@ -29,6 +43,9 @@ pub mod tests {
     pub struct MockIpcPort {
         pub pending_commands: Vec<(ConnectionId, IpcCommand)>,
         pub sent_responses: Vec<(ConnectionId, IpcResponse)>,
+        pub streams: Vec<ConnectionId>,
+        pub sent_events: Vec<(ConnectionId, IpcEvent)>,
+        pub disconnected: Vec<ConnectionId>,
         pub path: String,
     }
 
@@ -37,6 +54,9 @@ pub mod tests {
             Self {
                 pending_commands: Vec::new(),
                 sent_responses: Vec::new(),
+                streams: Vec::new(),
+                sent_events: Vec::new(),
+                disconnected: Vec::new(),
                 path: "/tmp/test-cli-manager.sock".to_string(),
             }
         }
@@ -51,6 +71,22 @@ pub mod tests {
             self.sent_responses.push((conn_id, response));
         }
 
+        fn open_stream(&mut self, conn_id: ConnectionId) {
+            self.streams.push(conn_id);
+        }
+
+        fn send_event(&mut self, conn_id: ConnectionId, event: &IpcEvent) -> bool {
+            if !self.is_connected(conn_id) {
+                return false;
+            }
+            self.sent_events.push((conn_id, event.clone()));
+            true
+        }
+
+        fn is_connected(&self, conn_id: ConnectionId) -> bool {
+            !self.disconnected.contains(&conn_id)
+        }
+
         fn socket_path(&self) -> &str {
             &self.path
         }
@@ -106,6 +142,17 @@ pub mod tests {
         assert_eq!(mock.sent_responses[1].1, IpcResponse::Error("test".to_string()));
     }
 
+    #[test]
+    fn mock_send_event_to_disconnected_returns_false() {
+        let mut mock = MockIpcPort::new();
+        let event = IpcEvent::Frame(Default::default());
+        mock.disconnected.push(ConnectionId(2));
+
+        assert!(mock.send_event(ConnectionId(1), &event));
+        assert!(!mock.send_event(ConnectionId(2), &event));
+        assert_eq!(mock.sent_events.len(), 1);
+    }
+
     #[test]
     fn mock_socket_path() {
         let mock = MockIpcPort::new();
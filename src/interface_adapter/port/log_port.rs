@@ -0,0 +1,113 @@
+use crate::domain::primitive::{LogOptions, LogStatus, TerminalId};
+use crate::shared::error::AppError;
+
+/// Port for persisting terminal output to disk.
+///
+/// The usecase feeds every chunk of pty output to `write`; implementations
+/// drop data for terminals that are not being logged.
+pub trait LogPort: Send + Sync {
+    /// Start logging a terminal, replacing any previous log settings for it.
+    fn start(
+        &mut self,
+        id: TerminalId,
+        name: &str,
+        options: &LogOptions,
+    ) -> Result<LogStatus, AppError>;
+
+    /// Stop logging a terminal. Returns `false` if it was not being logged.
+    fn stop(&mut self, id: TerminalId) -> bool;
+
+    /// Append pty output. No-op for terminals that are not being logged.
+    fn write(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError>;
+
+    /// Current log state, `None` if the terminal is not being logged.
+    fn status(&self, id: TerminalId) -> Option<LogStatus>;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    pub type LogWrites = Arc<Mutex<Vec<(TerminalId, Vec<u8>)>>>;
+
+    /// In-memory log port. `written` is shared so tests can inspect it after
+    /// handing the port to the usecase.
+    pub struct MockLogPort {
+        pub active: HashMap<TerminalId, LogStatus>,
+        pub written: LogWrites,
+        pub fail_writes: bool,
+    }
+
+    impl MockLogPort {
+        pub fn new() -> Self {
+            Self {
+                active: HashMap::new(),
+                written: Arc::new(Mutex::new(Vec::new())),
+                fail_writes: false,
+            }
+        }
+    }
+
+    impl LogPort for MockLogPort {
+        fn start(
+            &mut self,
+            id: TerminalId,
+            name: &str,
+            options: &LogOptions,
+        ) -> Result<LogStatus, AppError> {
+            let status = LogStatus {
+                path: PathBuf::from(format!("/tmp/logs/{}-{}.log", id.value(), name)),
+                mode: options.mode,
+                max_bytes: options.max_bytes,
+                bytes_written: 0,
+            };
+            self.active.insert(id, status.clone());
+            Ok(status)
+        }
+
+        fn stop(&mut self, id: TerminalId) -> bool {
+            self.active.remove(&id).is_some()
+        }
+
+        fn write(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+            let Some(status) = self.active.get_mut(&id) else {
+                return Ok(());
+            };
+            if self.fail_writes {
+                return Err(AppError::Log {
+                    id,
+                    source: std::io::Error::other("disk full"),
+                });
+            }
+            status.bytes_written += data.len() as u64;
+            self.written.lock().unwrap().push((id, data.to_vec()));
+            Ok(())
+        }
+
+        fn status(&self, id: TerminalId) -> Option<LogStatus> {
+            self.active.get(&id).cloned()
+        }
+    }
+
+    #[test]
+    fn mock_ignores_writes_for_unlogged_terminals() {
+        let mut mock = MockLogPort::new();
+        mock.write(TerminalId::new(1), b"hello").unwrap();
+        assert!(mock.written.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn mock_records_writes_after_start() {
+        let mut mock = MockLogPort::new();
+        let id = TerminalId::new(1);
+        mock.start(id, "shell", &LogOptions::default()).unwrap();
+        mock.write(id, b"hello").unwrap();
+        assert_eq!(mock.status(id).unwrap().bytes_written, 5);
+        assert!(mock.stop(id));
+        assert!(!mock.stop(id));
+    }
+}
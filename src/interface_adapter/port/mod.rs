@@ -1,7 +1,11 @@
 pub mod pty_port;
 pub mod screen_port;
 pub mod ipc_port;
+pub mod log_port;
+pub mod recorder_port;
 
 pub use pty_port::PtyPort;
 pub use screen_port::ScreenPort;
 pub use ipc_port::{IpcPort, ConnectionId};
+pub use log_port::LogPort;
+pub use recorder_port::RecorderPort;
@@ -1,6 +1,7 @@
 use std::path::Path;
+use std::time::Duration;
 
-use crate::domain::primitive::{TerminalId, TerminalSize};
+use crate::domain::primitive::{ForegroundProcess, Signal, TerminalId, TerminalSize};
 use crate::shared::error::AppError;
 
 /// PTY (pseudo-terminal) operations port.
@@ -8,12 +9,16 @@ use crate::shared::error::AppError;
 /// Defines the boundary between usecase and infrastructure for pty management.
 /// Concrete implementations (e.g., PortablePtyAdapter) live in infrastructure.
 pub trait PtyPort: Send + Sync {
-    /// Spawn a shell process on a pty, associating it with the given id.
+    /// Spawn a process on a pty, associating it with the given id.
+    ///
+    /// `argv[0]` is the program; `env` entries are added on top of the
+    /// inherited environment.
     fn spawn(
         &mut self,
         id: TerminalId,
-        shell: &str,
+        argv: &[String],
         cwd: &Path,
+        env: &[(String, String)],
         size: TerminalSize,
     ) -> Result<(), AppError>;
 
@@ -29,6 +34,18 @@ pub trait PtyPort: Send + Sync {
     /// Non-blocking check for process exit. Returns exit code if exited.
     fn try_wait(&mut self, id: TerminalId) -> Result<Option<i32>, AppError>;
 
+    /// The leader of the pty's foreground process group, `None` when it
+    /// cannot be determined.
+    fn foreground_process(&mut self, id: TerminalId) -> Result<Option<ForegroundProcess>, AppError>;
+
+    /// Send a signal to the pty's foreground process group, or to the
+    /// process itself when the group cannot be determined.
+    fn signal(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError>;
+
+    /// Hang up the process group and release resources without blocking.
+    /// Processes still alive after `grace` are killed.
+    fn close(&mut self, id: TerminalId, grace: Duration) -> Result<(), AppError>;
+
     /// Force-kill the process and release resources.
     fn kill(&mut self, id: TerminalId) -> Result<(), AppError>;
 }
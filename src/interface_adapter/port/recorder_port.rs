@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use crate::domain::primitive::{TerminalId, TerminalSize};
+use crate::shared::error::AppError;
+
+/// Port for recording terminal sessions (output with timing) to disk.
+///
+/// Like `LogPort`, the usecase feeds it all pty output and resizes;
+/// implementations ignore terminals that are not being recorded.
+pub trait RecorderPort: Send + Sync {
+    /// Start recording a terminal of the given size. `path` overrides the
+    /// default file location. Returns the file being written.
+    fn start(
+        &mut self,
+        id: TerminalId,
+        name: &str,
+        size: TerminalSize,
+        path: Option<&Path>,
+    ) -> Result<PathBuf, AppError>;
+
+    /// Stop recording a terminal, returning the finished file.
+    fn stop(&mut self, id: TerminalId) -> Option<PathBuf>;
+
+    /// Record pty output.
+    fn output(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError>;
+
+    /// Record a terminal resize.
+    fn resize(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError>;
+
+    /// File being written for the terminal, if it is being recorded.
+    fn recording_path(&self, id: TerminalId) -> Option<PathBuf>;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum RecordedEvent {
+        Output(Vec<u8>),
+        Resize(TerminalSize),
+    }
+
+    pub type RecordedEvents = Arc<Mutex<Vec<(TerminalId, RecordedEvent)>>>;
+
+    /// In-memory recorder. `events` is shared so tests can inspect it after
+    /// handing the port to the usecase.
+    pub struct MockRecorderPort {
+        pub active: HashMap<TerminalId, PathBuf>,
+        pub events: RecordedEvents,
+    }
+
+    impl MockRecorderPort {
+        pub fn new() -> Self {
+            Self {
+                active: HashMap::new(),
+                events: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    impl RecorderPort for MockRecorderPort {
+        fn start(
+            &mut self,
+            id: TerminalId,
+            name: &str,
+            _size: TerminalSize,
+            path: Option<&Path>,
+        ) -> Result<PathBuf, AppError> {
+            let path = path
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from(format!("/tmp/rec/{}-{}.cast", id.value(), name)));
+            self.active.insert(id, path.clone());
+            Ok(path)
+        }
+
+        fn stop(&mut self, id: TerminalId) -> Option<PathBuf> {
+            self.active.remove(&id)
+        }
+
+        fn output(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+            if self.active.contains_key(&id) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push((id, RecordedEvent::Output(data.to_vec())));
+            }
+            Ok(())
+        }
+
+        fn resize(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+            if self.active.contains_key(&id) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push((id, RecordedEvent::Resize(size)));
+            }
+            Ok(())
+        }
+
+        fn recording_path(&self, id: TerminalId) -> Option<PathBuf> {
+            self.active.get(&id).cloned()
+        }
+    }
+
+    #[test]
+    fn mock_records_only_active_terminals() {
+        let mut mock = MockRecorderPort::new();
+        let id = TerminalId::new(1);
+        mock.output(id, b"ignored").unwrap();
+        mock.start(id, "t", TerminalSize::new(80, 24), None).unwrap();
+        mock.output(id, b"kept").unwrap();
+        assert_eq!(
+            mock.events.lock().unwrap().as_slice(),
+            &[(id, RecordedEvent::Output(b"kept".to_vec()))]
+        );
+        assert!(mock.stop(id).is_some());
+        assert!(mock.stop(id).is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::domain::primitive::{Cell, CursorPos, CursorStyle, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
+use crate::domain::primitive::{Cell, CursorPos, CursorStyle, MouseMode, NotificationEvent, SearchMatch, TerminalId, TerminalSize};
 use crate::shared::error::AppError;
 
 /// Screen buffer operations port.
@@ -52,12 +52,24 @@ pub trait ScreenPort: Send + Sync {
     /// Get the maximum scrollback offset (total scrollback lines available).
     fn get_max_scrollback(&self, id: TerminalId) -> Result<usize, AppError>;
 
+    /// Get how many rows have dropped off the top of the full scrollback
+    /// since the screen was created. Every dropped row moves the absolute
+    /// rows of `get_row_cells` up by one.
+    fn get_scrolled_off(&self, id: TerminalId) -> Result<usize, AppError>;
+
     /// Check whether the terminal is currently in alternate screen mode.
     fn is_alternate_screen(&self, id: TerminalId) -> Result<bool, AppError>;
 
     /// Get the current cursor style/shape (set by DECSCUSR: CSI Ps SP q).
     fn get_cursor_style(&self, id: TerminalId) -> Result<CursorStyle, AppError>;
 
+    /// Get the mouse reporting mode requested by the application (DECSET 1000 etc.).
+    fn get_mouse_mode(&self, id: TerminalId) -> Result<MouseMode, AppError>;
+
+    /// Get the kitty keyboard protocol flags pushed by the application
+    /// (`CSI > flags u`). 0 means legacy xterm key encoding.
+    fn get_keyboard_flags(&self, id: TerminalId) -> Result<u16, AppError>;
+
     /// Drain and return any pending terminal responses (e.g., DSR cursor position replies).
     /// These bytes should be written back to the PTY stdin.
     fn drain_pending_responses(&mut self, id: TerminalId) -> Result<Vec<Vec<u8>>, AppError>;
@@ -4,13 +4,18 @@ mod interface_adapter;
 mod infrastructure;
 mod shared;
 
+use crate::domain::primitive::SessionSpec;
 use crate::interface_adapter::adapter::pty_adapter_factory;
 use crate::interface_adapter::adapter::screen_adapter_factory;
 use crate::usecase::terminal_usecase::TerminalUsecase;
 use crate::interface_adapter::controller::tui_controller::TuiController;
-use crate::infrastructure::tui::app_runner;
+use crate::infrastructure::tui::{app_runner, attach_runner, play_runner};
 use crate::infrastructure::ipc::UnixSocketServer;
 use crate::infrastructure::ipc::socket_discovery;
+use crate::infrastructure::log::cast_recorder::{self, CastRecorder};
+use crate::infrastructure::log::file_logger::{self, FileLogger};
+use crate::infrastructure::server::{daemon, server_runner};
+use crate::infrastructure::session::session_file;
 use crate::interface_adapter::port::IpcPort;
 
 fn main() -> anyhow::Result<()> {
@@ -20,9 +25,138 @@ fn main() -> anyhow::Result<()> {
         crate::infrastructure::ipc::cli_client::run(&args);
     }
     if args.len() >= 2 && args[1] == "mcp-server" {
-        crate::infrastructure::mcp::mcp_server::run();
+        crate::infrastructure::mcp::mcp_server::run(instance_arg(&args));
     }
+    if args.len() >= 2 && args[1] == "attach" {
+        return attach_runner::run(instance_arg(&args));
+    }
+    if args.len() >= 2 && args[1] == "server" {
+        return run_server(&args);
+    }
+    if args.len() >= 2 && args[1] == "play" {
+        let path = args
+            .get(2)
+            .ok_or_else(|| anyhow::anyhow!("usage: cm play <file.cast>"))?;
+        return play_runner::run(std::path::Path::new(path));
+    }
+
+    let snapshot_path = session_file::snapshot_file_path();
+    let session = load_session_arg(&args, &snapshot_path)?;
+
+    let (ipc_server, mut usecase) = start_instance(instance_name(&args))?;
+
+    // Create the session's terminals before the TUI takes over the screen,
+    // after CLI_MANAGER_SOCK is set so they inherit it.
+    if let Some(spec) = &session {
+        let size = app_runner::initial_pane_size()?;
+        if let Err(e) = usecase.open_session(spec, size) {
+            socket_discovery::unregister_instance(std::process::id());
+            return Err(e.into());
+        }
+    }
+
+    // Controller
+    let controller = TuiController::new(usecase);
+
+    // Run TUI with IPC
+    let options = app_runner::RunOptions {
+        mini_terminal_visible: session.as_ref().is_some_and(|s| s.mini_terminal_visible),
+        snapshot_path: Some(snapshot_path),
+    };
+    app_runner::run(controller, Some(Box::new(ipc_server)), options)?;
+
+    // Leave the instance registry on exit
+    socket_discovery::unregister_instance(std::process::id());
+
+    Ok(())
+}
+
+/// `cm server [--foreground] [--session <file> | --restore]`
+///
+/// Without `--foreground`, re-executes itself in the background and returns
+/// once the daemon accepts connections.
+fn run_server(args: &[String]) -> anyhow::Result<()> {
+    if !args.iter().any(|a| a == "--foreground") {
+        let server = daemon::spawn(&args[2..])?;
+        println!("cm server started (pid {})", server.pid);
+        println!("socket: {}", server.socket_path);
+        println!("log:    {}", server.log_path.display());
+        println!("attach with `cm attach`, stop with `cm ctl kill-server`");
+        return Ok(());
+    }
+
+    let snapshot_path = session_file::snapshot_file_path();
+    let session = load_session_arg(args, &snapshot_path)?;
+
+    let (ipc_server, mut usecase) = start_instance(instance_name(args))?;
+
+    if let Some(spec) = &session
+        && let Err(e) = usecase.open_session(spec, server_runner::DEFAULT_SIZE)
+    {
+        socket_discovery::unregister_instance(std::process::id());
+        return Err(e.into());
+    }
+
+    let options = server_runner::ServerOptions {
+        snapshot_path: Some(snapshot_path),
+    };
+    server_runner::run(TuiController::new(usecase), Box::new(ipc_server), options)?;
+
+    socket_discovery::unregister_instance(std::process::id());
+
+    Ok(())
+}
+
+/// `--instance <name|pid>`: which running instance a client talks to.
+fn instance_arg(args: &[String]) -> Option<String> {
+    let i = args.iter().position(|a| a == "--instance")?;
+    args.get(i + 1).cloned()
+}
+
+/// Name to register the instance under: `--name <name>`, or the stem of the
+/// `--session` file.
+fn instance_name(args: &[String]) -> Option<String> {
+    let value_of = |flag: &str| {
+        let i = args.iter().position(|a| a == flag)?;
+        args.get(i + 1)
+    };
+    if let Some(name) = value_of("--name") {
+        return Some(name.clone());
+    }
+    let session = std::path::Path::new(value_of("--session")?);
+    Some(session.file_stem()?.to_string_lossy().to_string())
+}
 
+/// Optional declarative workspace: `--session <file>`, or the snapshot
+/// written on the last quit: `--restore`.
+fn load_session_arg(
+    args: &[String],
+    snapshot_path: &std::path::Path,
+) -> anyhow::Result<Option<SessionSpec>> {
+    if let Some(i) = args.iter().position(|a| a == "--session") {
+        let path = args
+            .get(i + 1)
+            .ok_or_else(|| anyhow::anyhow!("--session requires a file path"))?;
+        Ok(Some(session_file::load_session(std::path::Path::new(path))?))
+    } else if args.iter().any(|a| a == "--restore") {
+        if !snapshot_path.exists() {
+            anyhow::bail!("no snapshot to restore ({})", snapshot_path.display());
+        }
+        Ok(Some(session_file::load_snapshot(snapshot_path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Bind the IPC socket, publish it (env var for children, instance registry
+/// for external tools) and create the usecase with the concrete adapters.
+fn start_instance(name: Option<String>) -> anyhow::Result<(
+    UnixSocketServer,
+    TerminalUsecase<
+        impl crate::interface_adapter::port::PtyPort,
+        impl crate::interface_adapter::port::ScreenPort,
+    >,
+)> {
     let cwd = std::env::current_dir()?;
 
     // Infrastructure concrete adapters
@@ -30,7 +164,7 @@ fn main() -> anyhow::Result<()> {
     let screen_adapter = screen_adapter_factory::create_vt100_screen_adapter();
 
     // IPC server
-    let socket_path = format!("/tmp/cli-manager-{}.sock", std::process::id());
+    let socket_path = daemon::socket_path_for(std::process::id());
     let ipc_server = UnixSocketServer::new(&socket_path)?;
 
     // Set CLI_MANAGER_SOCK env var for child processes
@@ -40,21 +174,22 @@ fn main() -> anyhow::Result<()> {
         std::env::set_var("CLI_MANAGER_SOCK", ipc_server.socket_path());
     }
 
-    // Write socket path to discovery file (~/.cli-manager/socket)
-    // so external tools can find the IPC socket without env var
-    let _ = socket_discovery::write_socket_path(ipc_server.socket_path());
+    // Register in the instance registry so external tools can find the IPC
+    // socket without the env var
+    let _ = socket_discovery::register_instance(&socket_discovery::InstanceInfo {
+        pid: std::process::id(),
+        socket: ipc_server.socket_path().to_string(),
+        started_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        cwd: cwd.to_string_lossy().to_string(),
+        session: name,
+    });
 
     // Usecase (depends on port traits via generics)
-    let usecase = TerminalUsecase::new(cwd, pty_adapter, screen_adapter);
-
-    // Controller
-    let controller = TuiController::new(usecase);
-
-    // Run TUI with IPC
-    app_runner::run(controller, Some(Box::new(ipc_server)))?;
+    let mut usecase = TerminalUsecase::new(cwd, pty_adapter, screen_adapter);
+    usecase.set_log_port(Box::new(FileLogger::new(file_logger::session_log_dir())));
+    usecase.set_recorder_port(Box::new(CastRecorder::new(cast_recorder::recordings_dir())));
 
-    // Clean up discovery file on exit
-    socket_discovery::remove_socket_path();
-
-    Ok(())
+    Ok((ipc_server, usecase))
 }
@@ -26,4 +26,48 @@ pub enum AppError {
 
     #[error("IPC error: {0}")]
     IpcError(String),
+
+    #[error("Session file error: {0}")]
+    SessionFile(String),
+
+    #[error("Log error for terminal {id}: {source}")]
+    Log {
+        id: TerminalId,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Logging is not available")]
+    LogUnavailable,
+
+    #[error("Recording error for terminal {id}: {source}")]
+    Record {
+        id: TerminalId,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Recording is not available")]
+    RecordUnavailable,
+
+    #[error("No terminal matches: {0}")]
+    NoMatchingTerminal(String),
+
+    #[error("Ambiguous target {target}: matches terminals {ids}; use an id")]
+    AmbiguousTarget { target: String, ids: String },
+
+    #[error("@self, the default target, is only available inside a cm terminal; give a target")]
+    NoCallerTerminal,
+
+    #[error("No previously active terminal")]
+    NoLastTerminal,
+
+    #[error("Terminal {0} is still running; kill it to respawn")]
+    StillRunning(TerminalId),
+
+    #[error("Terminal {0} has exited")]
+    NotRunning(TerminalId),
+
+    #[error("Terminals {0} and {1} are in different groups")]
+    DifferentGroups(TerminalId, TerminalId),
 }
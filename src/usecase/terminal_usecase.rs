@@ -1,10 +1,17 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use crate::domain::model::ManagedTerminal;
 use crate::domain::primitive::*;
-use crate::interface_adapter::port::{PtyPort, ScreenPort};
+use crate::interface_adapter::port::{LogPort, PtyPort, RecorderPort, ScreenPort};
 use crate::shared::error::AppError;
 
+/// Environment variable holding the id of the terminal a process runs in.
+pub const TERMINAL_ID_ENV: &str = "CLI_MANAGER_TERMINAL_ID";
+/// Environment variable holding the terminal's name at spawn time.
+pub const TERMINAL_NAME_ENV: &str = "CLI_MANAGER_TERMINAL_NAME";
+
 pub struct TerminalUsecase<P: PtyPort, S: ScreenPort> {
     terminals: Vec<ManagedTerminal>,
     active_index: Option<usize>,
@@ -13,8 +20,31 @@ pub struct TerminalUsecase<P: PtyPort, S: ScreenPort> {
     pty_port: P,
     screen_port: S,
     pending_notifications: Vec<(String, NotificationEvent)>,
+    log_port: Option<Box<dyn LogPort>>,
+    recorder_port: Option<Box<dyn RecorderPort>>,
+    watched_output: HashSet<TerminalId>,
+    pending_output: Vec<(TerminalId, Vec<u8>)>,
+    /// Active terminal as of the last `note_active_change`.
+    seen_active: Option<TerminalId>,
+    /// Terminal that was active before `seen_active` (`@last`).
+    last_active: Option<TerminalId>,
+    /// When `poll_all` last refreshed the foreground processes.
+    foreground_checked: Option<Instant>,
+    /// Which terminals the sidebar shows.
+    filter: TerminalFilter,
+    /// Groups whose terminals the sidebar hides.
+    collapsed_groups: HashSet<String>,
+    /// Whether keystrokes go to every synchronized terminal.
+    sync_input: bool,
 }
 
+/// How often `poll_all` looks up each terminal's foreground process.
+const FOREGROUND_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a closed terminal's processes get to exit after SIGHUP before
+/// they are killed.
+pub const CLOSE_GRACE: Duration = Duration::from_secs(3);
+
 impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
     pub fn new(cwd: PathBuf, pty_port: P, screen_port: S) -> Self {
         Self {
@@ -25,47 +55,325 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
             pty_port,
             screen_port,
             pending_notifications: Vec::new(),
+            log_port: None,
+            recorder_port: None,
+            watched_output: HashSet::new(),
+            pending_output: Vec::new(),
+            seen_active: None,
+            last_active: None,
+            foreground_checked: None,
+            filter: TerminalFilter::default(),
+            collapsed_groups: HashSet::new(),
+            sync_input: false,
         }
     }
 
+    /// Enable per-terminal output logging. Without a log port,
+    /// `start_logging` fails with `AppError::LogUnavailable`.
+    pub fn set_log_port(&mut self, log_port: Box<dyn LogPort>) {
+        self.log_port = Some(log_port);
+    }
+
+    /// Enable session recording. Without a recorder port,
+    /// `start_recording` fails with `AppError::RecordUnavailable`.
+    pub fn set_recorder_port(&mut self, recorder_port: Box<dyn RecorderPort>) {
+        self.recorder_port = Some(recorder_port);
+    }
+
     pub fn create_terminal(
         &mut self,
         name: Option<String>,
         size: TerminalSize,
+    ) -> Result<TerminalId, AppError> {
+        self.create_terminal_with_options(name, &SpawnOptions::default(), size)
+    }
+
+    /// Create a terminal whose process is started with the given overrides
+    /// (command, cwd, env). See `SpawnOptions` for the defaults.
+    pub fn create_terminal_with_options(
+        &mut self,
+        name: Option<String>,
+        options: &SpawnOptions,
+        size: TerminalSize,
     ) -> Result<TerminalId, AppError> {
         let id = TerminalId::new(self.next_id);
         self.next_id += 1;
 
         let name = name.unwrap_or_else(|| format!("term-{}", id.value()));
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
-
-        self.pty_port.spawn(id, &shell, &self.cwd, size)?;
+        let cwd = options.cwd.clone().unwrap_or_else(|| self.cwd.clone());
+        self.spawn_process(id, &name, options, &cwd, size)?;
         self.screen_port.create(id, size)?;
 
-        let terminal = ManagedTerminal::new(id, name, self.cwd.clone());
+        let mut terminal = ManagedTerminal::new(id, name, cwd);
+        terminal.set_spawn_options(options.clone());
         self.terminals.push(terminal);
         self.active_index = Some(self.terminals.len() - 1);
+        self.regroup();
+        self.note_active_change();
 
         Ok(id)
     }
 
+    fn spawn_process(
+        &mut self,
+        id: TerminalId,
+        name: &str,
+        options: &SpawnOptions,
+        cwd: &Path,
+        size: TerminalSize,
+    ) -> Result<(), AppError> {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        let argv = options.argv(&shell);
+
+        // Tell the process which terminal it runs in, for `-t @self`
+        let mut env = options.env.clone();
+        env.push((TERMINAL_ID_ENV.to_string(), id.value().to_string()));
+        env.push((TERMINAL_NAME_ENV.to_string(), name.to_string()));
+        self.pty_port.spawn(id, &argv, cwd, &env, size)
+    }
+
+    /// Start a terminal's process again in the same slot: same id, name,
+    /// memo, command, cwd and env, with the old output kept above a
+    /// separator line. A live process is killed first only when `kill` is
+    /// set; otherwise respawning it fails with `AppError::StillRunning`.
+    pub fn respawn_by_id(&mut self, id: TerminalId, kill: bool) -> Result<(), AppError> {
+        let index = self
+            .terminals
+            .iter()
+            .position(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
+        if self.terminals[index].status().is_running() && !kill {
+            return Err(AppError::StillRunning(id));
+        }
+        self.respawn(index, false)
+    }
+
+    /// Respawn the active terminal if its process has exited.
+    pub fn respawn_active(&mut self) -> Result<(), AppError> {
+        let id = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id();
+        self.respawn_by_id(id, false)
+    }
+
+    fn respawn(&mut self, index: usize, automatic: bool) -> Result<(), AppError> {
+        let id = self.terminals[index].id();
+        let name = self.terminals[index].name().to_string();
+        let options = self.terminals[index].spawn_options().clone();
+        let cwd = self.terminals[index].cwd().to_path_buf();
+        let size = self.screen_size(id);
+
+        // Releases the old pty; a live process is hung up and killed after a
+        // grace period, like a closed terminal. A respawn that failed to
+        // spawn has left no pty behind.
+        match self.pty_port.close(id, CLOSE_GRACE) {
+            Ok(()) | Err(AppError::TerminalNotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        self.spawn_process(id, &name, &options, &cwd, size)?;
+
+        // Leave the alternate screen a crashed program may have left on,
+        // then mark where the new process starts
+        let mut separator = String::new();
+        if self.screen_port.is_alternate_screen(id).unwrap_or(false) {
+            separator.push_str("\x1b[?1049l");
+        }
+        let label = "\u{2500}\u{2500} respawned ";
+        let rule = "\u{2500}".repeat((size.cols as usize).saturating_sub(label.chars().count()));
+        separator.push_str("\x1b[0m");
+        if self.screen_port.get_cursor(id).is_ok_and(|c| c.col > 0) {
+            separator.push_str("\r\n");
+        }
+        separator.push_str(&format!("\x1b[2m{}{}\x1b[0m\r\n", label, rule));
+        self.screen_port.process(id, separator.as_bytes())?;
+
+        self.terminals[index].mark_respawned(Instant::now(), automatic);
+        Ok(())
+    }
+
+    /// Current size of a terminal's screen.
+    fn screen_size(&self, id: TerminalId) -> TerminalSize {
+        let cells = self.screen_port.get_cells(id).ok();
+        let rows = cells.map_or(24, |c| c.len() as u16);
+        let cols = cells.and_then(|c| c.first()).map_or(80, |row| row.len() as u16);
+        TerminalSize::new(cols, rows)
+    }
+
+    /// Create every terminal of a session definition, in order, then activate
+    /// the one marked active. Stops at the first terminal that fails to spawn;
+    /// terminals created before it are kept.
+    pub fn open_session(
+        &mut self,
+        spec: &SessionSpec,
+        size: TerminalSize,
+    ) -> Result<Vec<TerminalId>, AppError> {
+        let mut ids = Vec::with_capacity(spec.terminals.len());
+        for entry in &spec.terminals {
+            let id = self.create_terminal_with_options(entry.name.clone(), &entry.spawn, size)?;
+            if entry.group.is_some() {
+                self.set_group(id, entry.group.clone())?;
+            }
+            if let Some(memo) = &entry.memo {
+                self.set_active_memo(memo.clone())?;
+            }
+            if !entry.scrollback.is_empty() {
+                // Feed the screen directly (not the pty) so the old output sits
+                // above whatever the new process prints.
+                let mut data = b"\x1b[2m".to_vec();
+                data.extend_from_slice(entry.scrollback.join("\r\n").as_bytes());
+                data.extend_from_slice(b"\x1b[0m\r\n");
+                self.screen_port.process(id, &data)?;
+            }
+            ids.push(id);
+        }
+
+        if let Some(id) = spec.active.and_then(|i| ids.get(i)) {
+            self.select_by_id(*id)?;
+        }
+
+        Ok(ids)
+    }
+
+    /// Describe the current terminals as a session that `open_session` can
+    /// re-create: names, memos, last-known cwds (OSC 7 when available) and the
+    /// active terminal. Keeps up to `scrollback_lines` trailing lines of output
+    /// per terminal. Exited terminals are skipped.
+    pub fn snapshot(&mut self, scrollback_lines: usize) -> SessionSpec {
+        let mut terminals = Vec::new();
+        let mut active = None;
+        for i in 0..self.terminals.len() {
+            if !self.terminals[i].status().is_running() {
+                continue;
+            }
+            let id = self.terminals[i].id();
+            let cwd = self
+                .screen_port
+                .get_cwd(id)
+                .ok()
+                .flatten()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| self.terminals[i].cwd().to_path_buf());
+            let memo = Some(self.terminals[i].memo().to_string()).filter(|m| !m.is_empty());
+            let scrollback = self.output_tail(id, scrollback_lines);
+
+            if self.active_index == Some(i) {
+                active = Some(terminals.len());
+            }
+            terminals.push(TerminalSpec {
+                name: Some(self.terminals[i].name().to_string()),
+                spawn: SpawnOptions {
+                    cwd: Some(cwd),
+                    ..Default::default()
+                },
+                memo,
+                group: self.terminals[i].group().map(str::to_string),
+                scrollback,
+            });
+        }
+
+        SessionSpec {
+            terminals,
+            active,
+            mini_terminal_visible: false,
+        }
+    }
+
+    /// Last `max_lines` non-empty-trailing lines of a terminal's scrollback
+    /// and visible screen, as plain text.
+    fn output_tail(&mut self, id: TerminalId, max_lines: usize) -> Vec<String> {
+        if max_lines == 0 {
+            return Vec::new();
+        }
+        let max_sb = self.screen_port.get_max_scrollback(id).unwrap_or(0);
+        let visible_rows = self.screen_port.get_cells(id).map(|c| c.len()).unwrap_or(0);
+
+        let mut lines = Vec::new();
+        for abs_row in 0..max_sb + visible_rows {
+            match self.screen_port.get_row_cells(id, abs_row) {
+                Ok(row) => {
+                    let line: String = row.iter().filter(|c| c.width > 0).map(|c| c.ch).collect();
+                    lines.push(line.trim_end().to_string());
+                }
+                Err(_) => break,
+            }
+        }
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let start = lines.len().saturating_sub(max_lines);
+        lines.split_off(start)
+    }
+
+    /// Describe what an attached client should draw: the terminals the
+    /// sidebar shows plus the live screen of the active one.
+    pub fn screen_frame(&self) -> ScreenFrame {
+        let now = Instant::now();
+        let windows = self
+            .visible_terminals()
+            .into_iter()
+            .map(|t| FrameWindow {
+                id: t.id().value(),
+                name: t.name().to_string(),
+                cwd: display_cwd(&self.screen_port, t),
+                status: t.status().clone(),
+                memo: t.memo().to_string(),
+                badge: t.badge().cloned(),
+                group: t.group().map(str::to_string),
+                marked: t.is_marked(),
+                process: t.foreground().map(|fg| fg.name.clone()),
+                quiet_mins: t.quiet_minutes(now).unwrap_or(0),
+                notification: if t.has_unread_notification() {
+                    t.last_notification().map(|n| n.summary().to_string())
+                } else {
+                    None
+                },
+            })
+            .collect();
+
+        let mut frame = ScreenFrame {
+            windows,
+            groups: self.group_headers(),
+            filter: self.filter.clone(),
+            sync: self.sync_input,
+            ..Default::default()
+        };
+        if let Some(t) = self.get_active_terminal() {
+            let id = t.id();
+            frame.active = Some(id.value());
+            frame.cells = self.screen_port.get_cells(id).cloned().unwrap_or_default();
+            frame.cursor = self.screen_port.get_cursor(id).unwrap_or_default();
+            frame.cursor_visible = self.screen_port.get_cursor_visible(id).unwrap_or(true);
+            frame.application_cursor_keys =
+                self.screen_port.get_application_cursor_keys(id).unwrap_or(false);
+            frame.bracketed_paste = self.screen_port.get_bracketed_paste(id).unwrap_or(false);
+            frame.keyboard_flags = self.screen_port.get_keyboard_flags(id).unwrap_or(0);
+        }
+        frame
+    }
+
     pub fn close_active_terminal(&mut self) -> Result<(), AppError> {
         let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
         let terminal = &self.terminals[index];
         let id = terminal.id();
 
-        // Best-effort cleanup: always complete removal even if kill/remove fails.
-        // Always call kill() regardless of terminal status so the PTY instance
+        // Best-effort cleanup: always complete removal even if close/remove fails.
+        // Always call close() regardless of terminal status so the PTY instance
         // (FD, reader, writer, child handle) is removed from the HashMap.
-        let _ = self.pty_port.kill(id);
+        let _ = self.pty_port.close(id, CLOSE_GRACE);
         let _ = self.screen_port.remove(id);
+        if let Some(log_port) = self.log_port.as_mut() {
+            log_port.stop(id);
+        }
+        if let Some(recorder_port) = self.recorder_port.as_mut() {
+            recorder_port.stop(id);
+        }
         self.terminals.remove(index);
+        self.forget_empty_groups();
 
         if self.terminals.is_empty() {
             self.active_index = None;
         } else if index >= self.terminals.len() {
             self.active_index = Some(self.terminals.len() - 1);
         }
+        self.note_active_change();
 
         Ok(())
     }
@@ -86,6 +394,22 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
             // Read pty output
             match self.pty_port.read(id) {
                 Ok(data) if !data.is_empty() => {
+                    self.terminals[i].record_output(Instant::now());
+                    // A failing log (disk full, file removed) must not take
+                    // the terminal down: stop logging it and keep going.
+                    if let Some(log_port) = self.log_port.as_mut()
+                        && log_port.write(id, &data).is_err()
+                    {
+                        log_port.stop(id);
+                    }
+                    if let Some(recorder_port) = self.recorder_port.as_mut()
+                        && recorder_port.output(id, &data).is_err()
+                    {
+                        recorder_port.stop(id);
+                    }
+                    if self.watched_output.contains(&id) {
+                        self.pending_output.push((id, data.clone()));
+                    }
                     self.screen_port.process(id, &data)?;
                     // Write back any synthesized terminal responses (e.g., DSR cursor position)
                     if let Ok(responses) = self.screen_port.drain_pending_responses(id) {
@@ -97,6 +421,7 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
                 Ok(_) => {}
                 Err(_) => {
                     self.terminals[i].mark_exited(-1);
+                    self.terminals[i].schedule_restart(Instant::now());
                     continue;
                 }
             }
@@ -106,6 +431,7 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
                 && let Ok(Some(code)) = self.pty_port.try_wait(id)
             {
                 self.terminals[i].mark_exited(code);
+                self.terminals[i].schedule_restart(Instant::now());
             }
 
             // Collect notifications for all terminals (including active)
@@ -125,53 +451,412 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
                 self.pending_notifications.push((name, last));
             }
         }
+
+        let now = Instant::now();
+        self.run_due_restarts(now);
+        if self.foreground_checked.is_none_or(|at| now.duration_since(at) >= FOREGROUND_INTERVAL) {
+            self.foreground_checked = Some(now);
+            self.refresh_foreground();
+        }
         Ok(())
     }
 
+    /// Respawn the exited terminals whose restart policy delay is over.
+    fn run_due_restarts(&mut self, now: Instant) {
+        for i in 0..self.terminals.len() {
+            if self.terminals[i].restart_at().is_some_and(|at| at <= now)
+                && self.respawn(i, true).is_err()
+            {
+                // Could not start it: count it as a failed run and retry
+                // after the next backoff
+                let terminal = &mut self.terminals[i];
+                terminal.mark_respawned(now, true);
+                terminal.mark_exited(-1);
+                terminal.schedule_restart(now);
+            }
+        }
+    }
+
+    /// Look up the foreground process of every live terminal, which also
+    /// moves it between `Running` and `Idle`.
+    pub fn refresh_foreground(&mut self) {
+        for terminal in self.terminals.iter_mut().filter(|t| t.status().is_running()) {
+            let foreground = self.pty_port.foreground_process(terminal.id()).ok().flatten();
+            terminal.set_foreground(foreground);
+        }
+    }
+
     pub fn write_to_active(&mut self, data: &[u8]) -> Result<(), AppError> {
         let id = self
             .get_active_terminal()
             .ok_or(AppError::NoActiveTerminal)?
             .id();
-        self.pty_port.write(id, data)
+        self.write_input(id, data)
+    }
+
+    /// Write keystrokes typed into terminal `id`. In synchronize mode they
+    /// also go to the other synchronized terminals, when `id` is one of
+    /// them; only the write to `id` itself reports an error.
+    pub fn write_input(&mut self, id: TerminalId, data: &[u8]) -> Result<(), AppError> {
+        let targets = self.sync_targets();
+        if !targets.contains(&id) {
+            return self.pty_port.write(id, data);
+        }
+        let mut result = Ok(());
+        for target in targets {
+            let running = self.get_terminal_by_id(target).is_some_and(|t| t.status().is_running());
+            if target == id {
+                result = self.pty_port.write(id, data);
+            } else if running {
+                self.pty_port.write(target, data).ok();
+            }
+        }
+        result
+    }
+
+    /// Whether synchronize mode is on.
+    pub fn sync_input(&self) -> bool {
+        self.sync_input
+    }
+
+    /// Turn synchronize mode on or off; `None` toggles it. Returns whether
+    /// it is on now.
+    pub fn set_sync_input(&mut self, enabled: Option<bool>) -> bool {
+        self.sync_input = enabled.unwrap_or(!self.sync_input);
+        self.sync_input
+    }
+
+    /// Terminals that keystrokes go to in synchronize mode, in sidebar
+    /// order: the marked terminals the sidebar shows, or every terminal
+    /// it shows when none of those is marked, plus the active terminal.
+    /// Empty when synchronize mode is off.
+    pub fn sync_targets(&self) -> Vec<TerminalId> {
+        if !self.sync_input {
+            return Vec::new();
+        }
+        let shown = self.visible_indices();
+        let any_marked = shown.iter().any(|&i| self.terminals[i].is_marked());
+        shown
+            .into_iter()
+            .filter(|&i| !any_marked || self.terminals[i].is_marked() || Some(i) == self.active_index)
+            .map(|i| self.terminals[i].id())
+            .collect()
+    }
+
+    /// Mark a terminal for synchronized input, or unmark it; `None`
+    /// toggles the mark. Returns whether it is marked now.
+    pub fn set_marked(&mut self, id: TerminalId, marked: Option<bool>) -> Result<bool, AppError> {
+        let index = self.index_of(id)?;
+        let terminal = &mut self.terminals[index];
+        let marked = marked.unwrap_or(!terminal.is_marked());
+        terminal.set_marked(marked);
+        Ok(marked)
+    }
+
+    /// Toggle the active terminal's mark.
+    pub fn toggle_active_mark(&mut self) -> Result<(), AppError> {
+        let id = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id();
+        self.set_marked(id, None).map(|_| ())
     }
 
     pub fn resize_all(&mut self, size: TerminalSize) -> Result<(), AppError> {
-        for terminal in &self.terminals {
-            let id = terminal.id();
-            let _ = self.pty_port.resize(id, size);
-            let _ = self.screen_port.resize(id, size);
+        let ids: Vec<TerminalId> = self.terminals.iter().map(|t| t.id()).collect();
+        for id in ids {
+            self.resize_terminal(id, size)?;
         }
         Ok(())
     }
 
-    pub fn select_next(&mut self) {
-        if let Some(ref mut idx) = self.active_index
-            && !self.terminals.is_empty()
-        {
-            *idx = (*idx + 1) % self.terminals.len();
+    /// Resize a single terminal (pty, screen and recording), e.g. the one
+    /// shown in a split pane.
+    pub fn resize_terminal(&mut self, id: TerminalId, size: TerminalSize) -> Result<(), AppError> {
+        if self.get_terminal_by_id(id).is_none() {
+            return Err(AppError::TerminalNotFound(id));
         }
-        if let Some(idx) = self.active_index {
-            self.terminals[idx].clear_notification();
+        let _ = self.pty_port.resize(id, size);
+        let _ = self.screen_port.resize(id, size);
+        if let Some(recorder_port) = self.recorder_port.as_mut()
+            && recorder_port.resize(id, size).is_err()
+        {
+            recorder_port.stop(id);
         }
+        Ok(())
+    }
+
+    pub fn select_next(&mut self) {
+        self.select_relative(1);
     }
 
     pub fn select_prev(&mut self) {
-        if let Some(ref mut idx) = self.active_index
-            && !self.terminals.is_empty()
+        self.select_relative(-1);
+    }
+
+    /// Step through the terminals the sidebar shows, wrapping around.
+    fn select_relative(&mut self, delta: isize) {
+        let visible = self.visible_indices();
+        if let Some(active) = self.active_index
+            && let Some(pos) = visible.iter().position(|&i| i == active)
         {
-            *idx = idx.checked_sub(1).unwrap_or(self.terminals.len() - 1);
+            let len = visible.len() as isize;
+            self.active_index = Some(visible[(pos as isize + delta).rem_euclid(len) as usize]);
         }
         if let Some(idx) = self.active_index {
             self.terminals[idx].clear_notification();
         }
+        self.note_active_change();
     }
 
+    /// Select the terminal at `index` in the sidebar, counting only the
+    /// terminals the filter shows.
     pub fn select_by_index(&mut self, index: usize) {
-        if index < self.terminals.len() {
+        if let Some(&index) = self.visible_indices().get(index) {
             self.active_index = Some(index);
             self.terminals[index].clear_notification();
+            self.note_active_change();
+        }
+    }
+
+    /// The sidebar filter.
+    pub fn filter(&self) -> &TerminalFilter {
+        &self.filter
+    }
+
+    /// Change the sidebar filter. When it hides the active terminal, the
+    /// first terminal it shows becomes active.
+    pub fn set_filter(&mut self, filter: TerminalFilter) {
+        self.filter = filter;
+        if let Some(active) = self.active_index
+            && !self.matches_filter(&self.terminals[active])
+            && let Some(&first) = self.visible_indices().first()
+        {
+            self.select_by_id(self.terminals[first].id()).ok();
+        }
+    }
+
+    fn matches_filter(&self, terminal: &ManagedTerminal) -> bool {
+        terminal.matches_filter(&self.filter, &display_cwd(&self.screen_port, terminal))
+    }
+
+    /// Whether the sidebar shows the terminal at `index`: it passes the
+    /// filter and its group is expanded. The active terminal is always
+    /// shown, so it does not vanish when e.g. viewing it clears the
+    /// notification a `notified` filter selected it by.
+    fn is_shown(&self, index: usize) -> bool {
+        let terminal = &self.terminals[index];
+        Some(index) == self.active_index
+            || (self.matches_filter(terminal)
+                && !terminal.group().is_some_and(|g| self.collapsed_groups.contains(g)))
+    }
+
+    /// Indices of the terminals the sidebar shows.
+    fn visible_indices(&self) -> Vec<usize> {
+        (0..self.terminals.len()).filter(|&i| self.is_shown(i)).collect()
+    }
+
+    /// The terminals the sidebar shows, in order.
+    pub fn visible_terminals(&self) -> Vec<&ManagedTerminal> {
+        self.visible_indices().into_iter().map(|i| &self.terminals[i]).collect()
+    }
+
+    /// Position of the active terminal in `visible_terminals`.
+    pub fn visible_active_index(&self) -> Option<usize> {
+        let active = self.get_active_index()?;
+        self.visible_indices().iter().position(|&i| i == active)
+    }
+
+    /// Headers of the groups the sidebar shows, each placed above the
+    /// group's first shown terminal. A collapsed group keeps its header;
+    /// a group none of whose terminals pass the filter has none.
+    pub fn group_headers(&self) -> Vec<GroupHeader> {
+        let mut headers: Vec<(GroupHeader, bool)> = Vec::new();
+        let mut shown = 0;
+        for (i, terminal) in self.terminals.iter().enumerate() {
+            if let Some(group) = terminal.group() {
+                let passes = Some(i) == self.active_index || self.matches_filter(terminal);
+                // Groups are contiguous, so a new name starts a new group
+                match headers.last_mut() {
+                    Some((header, any_passes)) if header.name == group => {
+                        header.count += 1;
+                        *any_passes |= passes;
+                    }
+                    _ => headers.push((
+                        GroupHeader {
+                            name: group.to_string(),
+                            count: 1,
+                            collapsed: self.collapsed_groups.contains(group),
+                            at: shown,
+                        },
+                        passes,
+                    )),
+                }
+            }
+            if self.is_shown(i) {
+                shown += 1;
+            }
+        }
+        headers.into_iter().filter(|(_, any_passes)| *any_passes).map(|(header, _)| header).collect()
+    }
+
+    /// Put a terminal in `group`, or take it out with `None`. It joins the
+    /// end of the group; a new group starts below the existing ones.
+    pub fn set_group(&mut self, id: TerminalId, group: Option<String>) -> Result<(), AppError> {
+        let index = self.index_of(id)?;
+        if self.terminals[index].group() == group.as_deref() {
+            return Ok(());
+        }
+        self.reorder(|terminals| {
+            let mut terminal = terminals.remove(index);
+            terminal.set_group(group);
+            terminals.push(terminal);
+        });
+        self.regroup();
+        self.forget_empty_groups();
+        Ok(())
+    }
+
+    /// Put the active terminal in `group`, or take it out with `None`.
+    pub fn set_active_group(&mut self, group: Option<String>) -> Result<(), AppError> {
+        let id = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id();
+        self.set_group(id, group)
+    }
+
+    /// Collapse or expand a group in the sidebar; `None` toggles it.
+    /// Returns whether the group is collapsed now.
+    pub fn set_group_collapsed(&mut self, group: &str, collapsed: Option<bool>) -> Result<bool, AppError> {
+        if !self.terminals.iter().any(|t| t.group() == Some(group)) {
+            return Err(AppError::NoMatchingTerminal(format!("#{group}")));
+        }
+        let collapsed = collapsed.unwrap_or(!self.collapsed_groups.contains(group));
+        if collapsed {
+            self.collapsed_groups.insert(group.to_string());
+        } else {
+            self.collapsed_groups.remove(group);
+        }
+        Ok(collapsed)
+    }
+
+    /// Collapse or expand the active terminal's group, if it has one.
+    pub fn toggle_active_group(&mut self) -> Result<(), AppError> {
+        let terminal = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?;
+        if let Some(group) = terminal.group().map(str::to_string) {
+            self.set_group_collapsed(&group, None)?;
+        }
+        Ok(())
+    }
+
+    /// Distinct groups in sidebar order.
+    fn group_order(&self) -> Vec<String> {
+        let mut order: Vec<String> = Vec::new();
+        for group in self.terminals.iter().filter_map(|t| t.group()) {
+            if !order.iter().any(|g| g == group) {
+                order.push(group.to_string());
+            }
+        }
+        order
+    }
+
+    /// Keep each group's terminals together below the ungrouped ones,
+    /// with the groups in the order they first appear.
+    fn regroup(&mut self) {
+        let order = self.group_order();
+        self.cluster(&order);
+    }
+
+    /// Stable-sort the terminals into ungrouped ones followed by the
+    /// groups in `order`.
+    fn cluster(&mut self, order: &[String]) {
+        let rank = |t: &ManagedTerminal| {
+            t.group().map_or(0, |g| 1 + order.iter().position(|o| o == g).unwrap_or(order.len()))
+        };
+        self.reorder(|terminals| terminals.sort_by_key(rank));
+    }
+
+    /// Drop the collapsed state of groups that no longer have terminals.
+    fn forget_empty_groups(&mut self) {
+        let terminals = &self.terminals;
+        self.collapsed_groups.retain(|g| terminals.iter().any(|t| t.group() == Some(g.as_str())));
+    }
+
+    /// Move a terminal `delta` places up (negative) or down its group in
+    /// the sidebar (ungrouped terminals among themselves), stepping over
+    /// the terminals the filter hides. Stops at either end.
+    pub fn move_by(&mut self, id: TerminalId, delta: isize) -> Result<(), AppError> {
+        let from = self.index_of(id)?;
+        let group = self.terminals[from].group();
+        let visible = self.visible_indices();
+        let order: Vec<usize> =
+            if visible.contains(&from) { visible } else { (0..self.terminals.len()).collect() };
+        let order: Vec<usize> = order.into_iter().filter(|&i| self.terminals[i].group() == group).collect();
+        let pos = order.iter().position(|&i| i == from).unwrap_or(0) as isize;
+        let to = order[(pos + delta).clamp(0, order.len() as isize - 1) as usize];
+        self.reorder(|terminals| {
+            let terminal = terminals.remove(from);
+            terminals.insert(to, terminal);
+        });
+        Ok(())
+    }
+
+    /// Move the active terminal `delta` places up or down the sidebar.
+    pub fn move_active(&mut self, delta: isize) -> Result<(), AppError> {
+        let id = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id();
+        self.move_by(id, delta)
+    }
+
+    /// Move a terminal to `index` in the full sidebar order, clamped to
+    /// the positions of its group.
+    pub fn move_to(&mut self, id: TerminalId, index: usize) -> Result<(), AppError> {
+        let from = self.index_of(id)?;
+        let group = self.terminals[from].group();
+        let first = self.terminals.iter().position(|t| t.group() == group).unwrap_or(from);
+        let last = self.terminals.iter().rposition(|t| t.group() == group).unwrap_or(from);
+        let to = index.clamp(first, last);
+        self.reorder(|terminals| {
+            let terminal = terminals.remove(from);
+            terminals.insert(to, terminal);
+        });
+        Ok(())
+    }
+
+    /// Swap the places of two terminals in the same group.
+    pub fn swap(&mut self, a: TerminalId, b: TerminalId) -> Result<(), AppError> {
+        let (ia, ib) = (self.index_of(a)?, self.index_of(b)?);
+        if self.terminals[ia].group() != self.terminals[ib].group() {
+            return Err(AppError::DifferentGroups(a, b));
+        }
+        let (a, b) = (ia, ib);
+        self.reorder(|terminals| terminals.swap(a, b));
+        Ok(())
+    }
+
+    /// Sort the sidebar once by `key`, within each group. The sort is
+    /// stable, so terminals that compare equal keep their relative order.
+    pub fn sort_terminals(&mut self, key: SortKey) {
+        let groups = self.group_order();
+        let screen_port = &self.screen_port;
+        let terminals = &mut self.terminals;
+        let active = self.active_index.map(|i| terminals[i].id());
+        match key {
+            SortKey::Name => terminals.sort_by_cached_key(|t| t.name().to_lowercase()),
+            SortKey::Cwd => terminals.sort_by_cached_key(|t| display_cwd(screen_port, t)),
+            SortKey::Activity => terminals.sort_by_key(|t| std::cmp::Reverse(t.last_output())),
+            SortKey::Unread => terminals.sort_by_key(|t| !t.has_unread_notification()),
         }
+        self.active_index = active.and_then(|id| self.terminals.iter().position(|t| t.id() == id));
+        self.cluster(&groups);
+    }
+
+    /// Rearrange the terminals, keeping the same terminal active.
+    fn reorder(&mut self, rearrange: impl FnOnce(&mut Vec<ManagedTerminal>)) {
+        let active = self.get_active_terminal().map(|t| t.id());
+        rearrange(&mut self.terminals);
+        self.active_index = active.and_then(|id| self.terminals.iter().position(|t| t.id() == id));
+    }
+
+    fn index_of(&self, id: TerminalId) -> Result<usize, AppError> {
+        self.terminals
+            .iter()
+            .position(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))
     }
 
     pub fn get_terminals(&self) -> &[ManagedTerminal] {
@@ -197,12 +882,19 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
             .position(|t| t.id() == id)
             .ok_or(AppError::TerminalNotFound(id))?;
 
-        // Best-effort cleanup: always complete removal even if kill/remove fails.
-        // Always call kill() regardless of terminal status so the PTY instance
+        // Best-effort cleanup: always complete removal even if close/remove fails.
+        // Always call close() regardless of terminal status so the PTY instance
         // (FD, reader, writer, child handle) is removed from the HashMap.
-        let _ = self.pty_port.kill(id);
+        let _ = self.pty_port.close(id, CLOSE_GRACE);
         let _ = self.screen_port.remove(id);
+        if let Some(log_port) = self.log_port.as_mut() {
+            log_port.stop(id);
+        }
+        if let Some(recorder_port) = self.recorder_port.as_mut() {
+            recorder_port.stop(id);
+        }
         self.terminals.remove(index);
+        self.forget_empty_groups();
 
         if self.terminals.is_empty() {
             self.active_index = None;
@@ -215,6 +907,7 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
             }
             // index > active → no change needed
         }
+        self.note_active_change();
 
         Ok(())
     }
@@ -228,9 +921,85 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
 
         self.active_index = Some(index);
         self.terminals[index].clear_notification();
+        self.note_active_change();
         Ok(())
     }
 
+    /// Terminals a target selects, in sidebar order. Names must match
+    /// exactly one terminal; globs and groups may match several. A list
+    /// selects the union of its items, in the order they are given.
+    /// `caller` is the terminal the request comes from, for `@self`. Ids
+    /// are returned as they are, for the command to report a missing
+    /// terminal.
+    pub fn resolve_target(
+        &self,
+        target: &TargetSpec,
+        caller: Option<TerminalId>,
+    ) -> Result<Vec<TerminalId>, AppError> {
+        let id = match target {
+            TargetSpec::Id(id) => TerminalId::new(*id),
+            TargetSpec::Active => self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id(),
+            TargetSpec::Caller => caller.ok_or(AppError::NoCallerTerminal)?,
+            TargetSpec::Last => self
+                .last_active
+                .filter(|id| self.get_terminal_by_id(*id).is_some())
+                .ok_or(AppError::NoLastTerminal)?,
+            TargetSpec::Name(_) | TargetSpec::Pattern(_) => {
+                let ids: Vec<TerminalId> = self
+                    .terminals
+                    .iter()
+                    .filter(|t| target.matches_name(t.name()))
+                    .map(|t| t.id())
+                    .collect();
+                if ids.is_empty() {
+                    return Err(AppError::NoMatchingTerminal(target.to_string()));
+                }
+                if matches!(target, TargetSpec::Name(_)) && ids.len() > 1 {
+                    return Err(AppError::AmbiguousTarget {
+                        target: target.to_string(),
+                        ids: ids.iter().map(|id| id.value().to_string()).collect::<Vec<_>>().join(", "),
+                    });
+                }
+                return Ok(ids);
+            }
+            TargetSpec::Group(group) => {
+                let ids: Vec<TerminalId> = self
+                    .terminals
+                    .iter()
+                    .filter(|t| t.group() == Some(group.as_str()))
+                    .map(|t| t.id())
+                    .collect();
+                if ids.is_empty() {
+                    return Err(AppError::NoMatchingTerminal(target.to_string()));
+                }
+                return Ok(ids);
+            }
+            TargetSpec::List(specs) => {
+                let mut ids: Vec<TerminalId> = Vec::new();
+                for spec in specs {
+                    for id in self.resolve_target(spec, caller)? {
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                }
+                return Ok(ids);
+            }
+        };
+        Ok(vec![id])
+    }
+
+    /// Remember the previously active terminal when the active one changed.
+    fn note_active_change(&mut self) {
+        let current = self.get_active_terminal().map(|t| t.id());
+        if current != self.seen_active {
+            if self.seen_active.is_some() {
+                self.last_active = self.seen_active;
+            }
+            self.seen_active = current;
+        }
+    }
+
     pub fn rename_by_id(&mut self, id: TerminalId, name: String) -> Result<(), AppError> {
         let terminal = self
             .terminals
@@ -242,48 +1011,208 @@ impl<P: PtyPort, S: ScreenPort> TerminalUsecase<P, S> {
         Ok(())
     }
 
-    /// Drain and return all pending notification events collected during `poll_all()`.
-    /// Each entry is a `(terminal_name, notification_event)` pair.
-    /// After calling this method, the internal pending list is cleared.
-    pub fn take_pending_notifications(&mut self) -> Vec<(String, NotificationEvent)> {
-        std::mem::take(&mut self.pending_notifications)
+    /// Send a signal to a terminal's foreground process group.
+    pub fn signal_by_id(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError> {
+        let terminal = self
+            .terminals
+            .iter()
+            .find(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
+        if !terminal.status().is_running() {
+            return Err(AppError::NotRunning(id));
+        }
+        self.pty_port.signal(id, signal)
     }
 
-    pub fn rename_active_terminal(&mut self, name: String) -> Result<(), AppError> {
-        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
-        self.terminals[index].set_name(name);
-        Ok(())
+    /// Send a signal to the active terminal's foreground process group.
+    pub fn signal_active(&mut self, signal: Signal) -> Result<(), AppError> {
+        let id = self.get_active_terminal().ok_or(AppError::NoActiveTerminal)?.id();
+        self.signal_by_id(id, signal)
     }
 
-    pub fn get_active_memo(&self) -> Result<&str, AppError> {
-        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
-        Ok(self.terminals[index].memo())
-    }
+    /// Set or clear (`None`) a terminal's status badge.
+    pub fn set_badge_by_id(&mut self, id: TerminalId, badge: Option<StatusBadge>) -> Result<(), AppError> {
+        let terminal = self
+            .terminals
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
 
-    pub fn set_active_memo(&mut self, memo: String) -> Result<(), AppError> {
-        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
-        self.terminals[index].set_memo(memo);
+        terminal.set_badge(badge);
         Ok(())
     }
 
-    pub fn pty_port(&self) -> &P {
-        &self.pty_port
+    /// Start writing a terminal's output to its log file.
+    pub fn start_logging(
+        &mut self,
+        id: TerminalId,
+        options: &LogOptions,
+    ) -> Result<LogStatus, AppError> {
+        let terminal = self
+            .terminals
+            .iter()
+            .find(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
+        let log_port = self.log_port.as_mut().ok_or(AppError::LogUnavailable)?;
+        log_port.start(id, terminal.name(), options)
     }
 
-    pub fn pty_port_mut(&mut self) -> &mut P {
-        &mut self.pty_port
+    /// Stop logging a terminal. Returns `false` if it was not being logged.
+    pub fn stop_logging(&mut self, id: TerminalId) -> Result<bool, AppError> {
+        if self.get_terminal_by_id(id).is_none() {
+            return Err(AppError::TerminalNotFound(id));
+        }
+        Ok(self.log_port.as_mut().is_some_and(|log_port| log_port.stop(id)))
     }
 
-    pub fn screen_port(&self) -> &S {
-        &self.screen_port
+    /// Current log state of a terminal, `None` if it is not being logged.
+    pub fn logging_status(&self, id: TerminalId) -> Result<Option<LogStatus>, AppError> {
+        if self.get_terminal_by_id(id).is_none() {
+            return Err(AppError::TerminalNotFound(id));
+        }
+        Ok(self.log_port.as_ref().and_then(|log_port| log_port.status(id)))
     }
 
-    pub fn screen_port_mut(&mut self) -> &mut S {
-        &mut self.screen_port
+    /// Start recording a terminal's output from its current size on.
+    /// `path` overrides the recorder's default file location.
+    pub fn start_recording(
+        &mut self,
+        id: TerminalId,
+        path: Option<&Path>,
+    ) -> Result<PathBuf, AppError> {
+        let terminal = self
+            .terminals
+            .iter()
+            .find(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
+        let size = self
+            .screen_port
+            .get_cells(id)
+            .map(|cells| {
+                TerminalSize::new(
+                    cells.first().map_or(0, |row| row.len()) as u16,
+                    cells.len() as u16,
+                )
+            })
+            .unwrap_or(TerminalSize::new(80, 24));
+        let recorder_port = self
+            .recorder_port
+            .as_mut()
+            .ok_or(AppError::RecordUnavailable)?;
+        recorder_port.start(id, terminal.name(), size, path)
     }
-}
 
-#[cfg(test)]
+    /// Stop recording a terminal. Returns the finished file, or `None` if it
+    /// was not being recorded.
+    pub fn stop_recording(&mut self, id: TerminalId) -> Result<Option<PathBuf>, AppError> {
+        if self.get_terminal_by_id(id).is_none() {
+            return Err(AppError::TerminalNotFound(id));
+        }
+        Ok(self.recorder_port.as_mut().and_then(|r| r.stop(id)))
+    }
+
+    /// File being recorded for the terminal, if any.
+    pub fn recording_path(&self, id: TerminalId) -> Option<PathBuf> {
+        self.recorder_port.as_ref().and_then(|r| r.recording_path(id))
+    }
+
+    /// Start recording the active terminal, or stop if it is already
+    /// being recorded.
+    pub fn toggle_active_recording(&mut self) -> Result<(), AppError> {
+        let id = self
+            .get_active_terminal()
+            .ok_or(AppError::NoActiveTerminal)?
+            .id();
+        if self.recording_path(id).is_some() {
+            self.stop_recording(id)?;
+        } else {
+            self.start_recording(id, None)?;
+        }
+        Ok(())
+    }
+
+    /// Raise a notification for a terminal from outside its output (e.g.
+    /// `cm ctl notify` from a hook running in it). Handled like an OSC 9/777
+    /// notification: an unread mark unless the terminal is active, and a
+    /// desktop notification under its name.
+    pub fn notify_terminal(&mut self, id: TerminalId, event: NotificationEvent) -> Result<(), AppError> {
+        let index = self
+            .terminals
+            .iter()
+            .position(|t| t.id() == id)
+            .ok_or(AppError::TerminalNotFound(id))?;
+        if Some(index) != self.active_index {
+            self.terminals[index].set_notification(event.clone());
+        }
+        let name = self.terminals[index].name().to_string();
+        self.pending_notifications.push((name, event));
+        Ok(())
+    }
+
+    /// Drain and return all pending notification events collected during `poll_all()`.
+    /// Each entry is a `(terminal_name, notification_event)` pair.
+    /// After calling this method, the internal pending list is cleared.
+    pub fn take_pending_notifications(&mut self) -> Vec<(String, NotificationEvent)> {
+        std::mem::take(&mut self.pending_notifications)
+    }
+
+    /// Keep a copy of the pty output read from these terminals for
+    /// `take_watched_output` (event subscribers). Replaces the previous set.
+    pub fn set_watched_output(&mut self, ids: HashSet<TerminalId>) {
+        self.pending_output.retain(|(id, _)| ids.contains(id));
+        self.watched_output = ids;
+    }
+
+    /// Output chunks read from watched terminals since the last call, in order.
+    pub fn take_watched_output(&mut self) -> Vec<(TerminalId, Vec<u8>)> {
+        std::mem::take(&mut self.pending_output)
+    }
+
+    pub fn rename_active_terminal(&mut self, name: String) -> Result<(), AppError> {
+        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
+        self.terminals[index].set_name(name);
+        Ok(())
+    }
+
+    pub fn get_active_memo(&self) -> Result<&str, AppError> {
+        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
+        Ok(self.terminals[index].memo())
+    }
+
+    pub fn set_active_memo(&mut self, memo: String) -> Result<(), AppError> {
+        let index = self.active_index.ok_or(AppError::NoActiveTerminal)?;
+        self.terminals[index].set_memo(memo);
+        Ok(())
+    }
+
+    pub fn pty_port(&self) -> &P {
+        &self.pty_port
+    }
+
+    pub fn pty_port_mut(&mut self) -> &mut P {
+        &mut self.pty_port
+    }
+
+    pub fn screen_port(&self) -> &S {
+        &self.screen_port
+    }
+
+    pub fn screen_port_mut(&mut self) -> &mut S {
+        &mut self.screen_port
+    }
+}
+
+/// A terminal's cwd as the sidebar shows it: the OSC 7 cwd if reported,
+/// otherwise the cwd it was spawned in.
+fn display_cwd<S: ScreenPort>(screen_port: &S, terminal: &ManagedTerminal) -> String {
+    screen_port
+        .get_cwd(terminal.id())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| terminal.cwd().display().to_string())
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
@@ -294,18 +1223,24 @@ mod tests {
     // Mock implementations
     // =========================================================================
 
+    /// Arguments recorded for a single `spawn` call: (id, argv, cwd, size, env).
+    type SpawnCall = (TerminalId, Vec<String>, PathBuf, TerminalSize, Vec<(String, String)>);
+
     /// Records all calls made to the PtyPort methods for assertion.
     /// Uses Arc<Mutex<...>> for shared call tracking (Send + Sync safe).
     #[derive(Clone)]
     struct MockPtyPort {
-        spawn_calls: Arc<Mutex<Vec<(TerminalId, String, PathBuf, TerminalSize)>>>,
+        spawn_calls: Arc<Mutex<Vec<SpawnCall>>>,
         kill_calls: Arc<Mutex<Vec<TerminalId>>>,
+        close_calls: Arc<Mutex<Vec<(TerminalId, Duration)>>>,
+        signal_calls: Arc<Mutex<Vec<(TerminalId, Signal)>>>,
         write_calls: Arc<Mutex<Vec<(TerminalId, Vec<u8>)>>>,
         resize_calls: Arc<Mutex<Vec<(TerminalId, TerminalSize)>>>,
         read_results: Arc<Mutex<HashMap<u32, Result<Vec<u8>, AppError>>>>,
         try_wait_results: Arc<Mutex<HashMap<u32, Result<Option<i32>, AppError>>>>,
+        foreground: Arc<Mutex<HashMap<u32, ForegroundProcess>>>,
         spawn_should_fail: bool,
-        kill_should_fail: bool,
+        close_should_fail: bool,
     }
 
     impl MockPtyPort {
@@ -313,12 +1248,15 @@ mod tests {
             Self {
                 spawn_calls: Arc::new(Mutex::new(Vec::new())),
                 kill_calls: Arc::new(Mutex::new(Vec::new())),
+                close_calls: Arc::new(Mutex::new(Vec::new())),
+                signal_calls: Arc::new(Mutex::new(Vec::new())),
                 write_calls: Arc::new(Mutex::new(Vec::new())),
                 resize_calls: Arc::new(Mutex::new(Vec::new())),
                 read_results: Arc::new(Mutex::new(HashMap::new())),
                 try_wait_results: Arc::new(Mutex::new(HashMap::new())),
+                foreground: Arc::new(Mutex::new(HashMap::new())),
                 spawn_should_fail: false,
-                kill_should_fail: false,
+                close_should_fail: false,
             }
         }
 
@@ -327,8 +1265,8 @@ mod tests {
             self
         }
 
-        fn with_kill_failure(mut self) -> Self {
-            self.kill_should_fail = true;
+        fn with_close_failure(mut self) -> Self {
+            self.close_should_fail = true;
             self
         }
 
@@ -351,8 +1289,9 @@ mod tests {
         fn spawn(
             &mut self,
             id: TerminalId,
-            shell: &str,
+            argv: &[String],
             cwd: &Path,
+            env: &[(String, String)],
             size: TerminalSize,
         ) -> Result<(), AppError> {
             if self.spawn_should_fail {
@@ -363,9 +1302,10 @@ mod tests {
             }
             self.spawn_calls.lock().unwrap().push((
                 id,
-                shell.to_string(),
+                argv.to_vec(),
                 cwd.to_path_buf(),
                 size,
+                env.to_vec(),
             ));
             Ok(())
         }
@@ -399,19 +1339,33 @@ mod tests {
                 .unwrap_or(Ok(None))
         }
 
-        fn kill(&mut self, id: TerminalId) -> Result<(), AppError> {
-            self.kill_calls.lock().unwrap().push(id);
-            if self.kill_should_fail {
+        fn foreground_process(&mut self, id: TerminalId) -> Result<Option<ForegroundProcess>, AppError> {
+            Ok(self.foreground.lock().unwrap().get(&id.value()).cloned())
+        }
+
+        fn signal(&mut self, id: TerminalId, signal: Signal) -> Result<(), AppError> {
+            self.signal_calls.lock().unwrap().push((id, signal));
+            Ok(())
+        }
+
+        fn close(&mut self, id: TerminalId, grace: Duration) -> Result<(), AppError> {
+            self.close_calls.lock().unwrap().push((id, grace));
+            if self.close_should_fail {
                 return Err(AppError::PtyIo {
                     id,
                     source: std::io::Error::new(
                         std::io::ErrorKind::Other,
-                        "mock kill failure",
+                        "mock close failure",
                     ),
                 });
             }
             Ok(())
         }
+
+        fn kill(&mut self, id: TerminalId) -> Result<(), AppError> {
+            self.kill_calls.lock().unwrap().push(id);
+            Ok(())
+        }
     }
 
     /// Records all calls made to the ScreenPort methods for assertion.
@@ -425,6 +1379,7 @@ mod tests {
         cells: HashMap<u32, Vec<Vec<Cell>>>,
         create_should_fail: bool,
         pending_notifications: HashMap<u32, Vec<NotificationEvent>>,
+        cwds: HashMap<u32, String>,
     }
 
     impl MockScreenPort {
@@ -437,6 +1392,15 @@ mod tests {
                 cells: HashMap::new(),
                 create_should_fail: false,
                 pending_notifications: HashMap::new(),
+                cwds: HashMap::new(),
+            }
+        }
+
+        /// Write `text` into row `row` of a terminal's grid.
+        fn set_row_text(&mut self, id: TerminalId, row: usize, text: &str) {
+            let grid = self.cells.get_mut(&id.value()).unwrap();
+            for (col, ch) in text.chars().enumerate() {
+                grid[row][col].ch = ch;
             }
         }
 
@@ -496,8 +1460,8 @@ mod tests {
             Ok(false)
         }
 
-        fn get_cwd(&self, _id: TerminalId) -> Result<Option<String>, AppError> {
-            Ok(None)
+        fn get_cwd(&self, id: TerminalId) -> Result<Option<String>, AppError> {
+            Ok(self.cwds.get(&id.value()).cloned())
         }
 
         fn drain_notifications(&mut self, id: TerminalId) -> Result<Vec<NotificationEvent>, AppError> {
@@ -516,6 +1480,10 @@ mod tests {
             Ok(0)
         }
 
+        fn get_scrolled_off(&self, _id: TerminalId) -> Result<usize, AppError> {
+            Ok(0)
+        }
+
         fn is_alternate_screen(&self, _id: TerminalId) -> Result<bool, AppError> {
             Ok(false)
         }
@@ -524,6 +1492,14 @@ mod tests {
             Ok(CursorStyle::DefaultUserShape)
         }
 
+        fn get_mouse_mode(&self, _id: TerminalId) -> Result<MouseMode, AppError> {
+            Ok(MouseMode::Off)
+        }
+
+        fn get_keyboard_flags(&self, _id: TerminalId) -> Result<u16, AppError> {
+            Ok(0)
+        }
+
         fn drain_pending_responses(&mut self, _id: TerminalId) -> Result<Vec<Vec<u8>>, AppError> {
             Ok(vec![])
         }
@@ -532,8 +1508,12 @@ mod tests {
             Ok(vec![])
         }
 
-        fn get_row_cells(&mut self, _id: TerminalId, _abs_row: usize) -> Result<Vec<Cell>, AppError> {
-            Ok(vec![])
+        fn get_row_cells(&mut self, id: TerminalId, abs_row: usize) -> Result<Vec<Cell>, AppError> {
+            self.cells
+                .get(&id.value())
+                .and_then(|grid| grid.get(abs_row))
+                .cloned()
+                .ok_or(AppError::ScreenNotFound(id))
         }
     }
 
@@ -672,187 +1652,565 @@ mod tests {
     }
 
     // =========================================================================
-    // Tests: close_active_terminal
+    // Tests: create_terminal_with_options
     // =========================================================================
 
     #[test]
-    fn close_active_terminal_with_no_active_returns_error() {
-        let mut uc = make_usecase();
+    fn create_terminal_default_spawns_interactive_shell() {
+        let pty = MockPtyPort::new();
+        let spawn_calls = pty.spawn_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
 
-        let result = uc.close_active_terminal();
-        assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), AppError::NoActiveTerminal));
+        uc.create_terminal(None, default_size()).unwrap();
+
+        let calls = spawn_calls.lock().unwrap();
+        assert_eq!(calls[0].1.len(), 1, "argv should only contain the shell");
+        // Only the terminal's own identity is added to the environment
+        assert_eq!(
+            calls[0].4,
+            vec![
+                (TERMINAL_ID_ENV.to_string(), "1".to_string()),
+                (TERMINAL_NAME_ENV.to_string(), "term-1".to_string()),
+            ]
+        );
     }
 
     #[test]
-    fn close_active_terminal_removes_from_list() {
-        let mut uc = make_usecase();
-        let size = default_size();
-
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+    fn create_terminal_with_options_runs_command_via_shell() {
+        let pty = MockPtyPort::new();
+        let spawn_calls = pty.spawn_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let opts = SpawnOptions {
+            command: Some("cargo watch -x test".to_string()),
+            ..Default::default()
+        };
 
-        // active is index 1 (t2)
-        uc.close_active_terminal().unwrap();
+        uc.create_terminal_with_options(None, &opts, default_size())
+            .unwrap();
 
-        assert_eq!(uc.get_terminals().len(), 1);
-        assert_eq!(uc.get_terminals()[0].name(), "t1");
+        let calls = spawn_calls.lock().unwrap();
+        let argv = &calls[0].1;
+        assert_eq!(argv.len(), 3);
+        assert_eq!(argv[1], "-c");
+        assert_eq!(argv[2], "cargo watch -x test");
     }
 
     #[test]
-    fn close_active_terminal_calls_kill_for_running_terminal() {
+    fn create_terminal_with_options_overrides_cwd() {
         let pty = MockPtyPort::new();
-        let kill_calls = pty.kill_calls.clone();
-        let screen = MockScreenPort::new();
-        let mut uc = make_usecase_with_ports(pty, screen);
-        let size = default_size();
+        let spawn_calls = pty.spawn_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let opts = SpawnOptions {
+            cwd: Some(PathBuf::from("/var/log")),
+            ..Default::default()
+        };
 
-        uc.create_terminal(None, size).unwrap();
-        uc.close_active_terminal().unwrap();
+        let id = uc
+            .create_terminal_with_options(None, &opts, default_size())
+            .unwrap();
 
-        let calls = kill_calls.lock().unwrap();
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0], TerminalId::new(1));
+        assert_eq!(spawn_calls.lock().unwrap()[0].2, PathBuf::from("/var/log"));
+        let terminal = uc.get_terminal_by_id(id).unwrap();
+        assert_eq!(terminal.cwd(), Path::new("/var/log"));
     }
 
     #[test]
-    fn close_active_terminal_sets_active_none_when_list_empty() {
-        let mut uc = make_usecase();
-        let size = default_size();
+    fn create_terminal_with_options_passes_env() {
+        let pty = MockPtyPort::new();
+        let spawn_calls = pty.spawn_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let opts = SpawnOptions {
+            env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+            ..Default::default()
+        };
 
-        uc.create_terminal(None, size).unwrap();
-        uc.close_active_terminal().unwrap();
+        uc.create_terminal_with_options(None, &opts, default_size())
+            .unwrap();
 
-        assert_eq!(uc.get_active_index(), None);
-        assert!(uc.get_terminals().is_empty());
+        let calls = spawn_calls.lock().unwrap();
+        assert_eq!(
+            calls[0].4,
+            vec![
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                (TERMINAL_ID_ENV.to_string(), "1".to_string()),
+                (TERMINAL_NAME_ENV.to_string(), "term-1".to_string()),
+            ]
+        );
     }
 
     #[test]
-    fn close_active_terminal_adjusts_index_when_at_end() {
-        let mut uc = make_usecase();
-        let size = default_size();
+    fn create_terminal_with_options_spawn_failure_adds_nothing() {
+        let pty = MockPtyPort::new().with_spawn_failure();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let opts = SpawnOptions {
+            command: Some("false".to_string()),
+            ..Default::default()
+        };
 
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
-        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+        let result = uc.create_terminal_with_options(None, &opts, default_size());
+        assert!(matches!(result, Err(AppError::PtySpawn(_))));
+        assert!(uc.get_terminals().is_empty());
+    }
 
-        // active_index is 2 (t3, last element)
-        assert_eq!(uc.get_active_index(), Some(2));
-        uc.close_active_terminal().unwrap();
+    // =========================================================================
+    // Tests: open_session
+    // =========================================================================
 
-        // After removing last element, index should adjust to new last
-        assert_eq!(uc.get_active_index(), Some(1));
-        assert_eq!(uc.get_active_terminal().unwrap().name(), "t2");
+    fn session_entry(name: &str, command: Option<&str>, memo: Option<&str>) -> TerminalSpec {
+        TerminalSpec {
+            name: Some(name.to_string()),
+            spawn: SpawnOptions {
+                command: command.map(|c| c.to_string()),
+                ..Default::default()
+            },
+            memo: memo.map(|m| m.to_string()),
+            scrollback: Vec::new(),
+            group: None,
+        }
     }
 
     #[test]
-    fn close_active_terminal_keeps_index_when_not_at_end() {
+    fn open_session_creates_terminals_in_order() {
         let mut uc = make_usecase();
-        let size = default_size();
+        let spec = SessionSpec {
+            terminals: vec![
+                session_entry("api", Some("cargo run"), None),
+                session_entry("frontend", Some("npm run dev"), None),
+                session_entry("agent", None, None),
+            ],
+            active: None,
+            ..Default::default()
+        };
 
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
-        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+        let ids = uc.open_session(&spec, default_size()).unwrap();
 
-        // Select first terminal
-        uc.select_by_index(0);
-        assert_eq!(uc.get_active_index(), Some(0));
+        assert_eq!(ids.len(), 3);
+        let names: Vec<&str> = uc.get_terminals().iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["api", "frontend", "agent"]);
+        assert_eq!(uc.get_active_index(), Some(2));
+    }
 
-        uc.close_active_terminal().unwrap();
+    #[test]
+    fn open_session_passes_spawn_options() {
+        let pty = MockPtyPort::new();
+        let spawn_calls = pty.spawn_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let mut entry = session_entry("api", Some("cargo run"), None);
+        entry.spawn.cwd = Some(PathBuf::from("/srv/api"));
+        let spec = SessionSpec { terminals: vec![entry], ..Default::default() };
 
-        // index 0 still valid, now points to what was t2
-        assert_eq!(uc.get_active_index(), Some(0));
-        assert_eq!(uc.get_active_terminal().unwrap().name(), "t2");
+        uc.open_session(&spec, default_size()).unwrap();
+
+        let calls = spawn_calls.lock().unwrap();
+        assert_eq!(calls[0].1.last().unwrap(), "cargo run");
+        assert_eq!(calls[0].2, PathBuf::from("/srv/api"));
     }
 
     #[test]
-    fn close_active_terminal_for_middle_element() {
+    fn open_session_sets_memos() {
         let mut uc = make_usecase();
-        let size = default_size();
-
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
-        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+        let spec = SessionSpec {
+            terminals: vec![
+                session_entry("api", None, Some("port 8080")),
+                session_entry("web", None, None),
+            ],
+            active: None,
+            ..Default::default()
+        };
 
-        // Select middle terminal
-        uc.select_by_index(1);
-        uc.close_active_terminal().unwrap();
+        uc.open_session(&spec, default_size()).unwrap();
 
-        // index 1 still valid, now points to what was t3
-        assert_eq!(uc.get_terminals().len(), 2);
-        assert_eq!(uc.get_active_index(), Some(1));
-        assert_eq!(uc.get_active_terminal().unwrap().name(), "t3");
+        assert_eq!(uc.get_terminals()[0].memo(), "port 8080");
+        assert!(!uc.get_terminals()[1].has_memo());
     }
 
     #[test]
-    fn close_active_terminal_completes_cleanup_even_when_kill_fails() {
-        let pty = MockPtyPort::new().with_kill_failure();
-        let screen = MockScreenPort::new();
-        let mut uc = make_usecase_with_ports(pty, screen);
-        let size = default_size();
-
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+    fn open_session_selects_active_entry() {
+        let mut uc = make_usecase();
+        let spec = SessionSpec {
+            terminals: vec![
+                session_entry("api", None, None),
+                session_entry("web", None, None),
+            ],
+            active: Some(0),
+            ..Default::default()
+        };
 
-        // active is t2 (index 1). kill() will fail, but close should still complete.
-        let result = uc.close_active_terminal();
-        assert!(result.is_ok());
+        uc.open_session(&spec, default_size()).unwrap();
 
-        // Terminal must be removed from the list despite kill failure
-        assert_eq!(uc.get_terminals().len(), 1);
-        assert_eq!(uc.get_active_terminal().unwrap().name(), "t1");
-        assert_eq!(uc.get_active_index(), Some(0));
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "api");
     }
 
     #[test]
-    fn close_active_terminal_with_kill_failure_leaves_no_ghost() {
-        let pty = MockPtyPort::new().with_kill_failure();
-        let screen = MockScreenPort::new();
-        let mut uc = make_usecase_with_ports(pty, screen);
-        let size = default_size();
+    fn open_session_empty_spec_creates_nothing() {
+        let mut uc = make_usecase();
 
-        uc.create_terminal(Some("only".to_string()), size).unwrap();
-        uc.close_active_terminal().unwrap();
+        let ids = uc.open_session(&SessionSpec::default(), default_size()).unwrap();
 
+        assert!(ids.is_empty());
         assert!(uc.get_terminals().is_empty());
         assert_eq!(uc.get_active_index(), None);
     }
 
     #[test]
-    fn close_active_terminal_calls_kill_even_for_exited_terminal() {
-        let pty = MockPtyPort::new();
-        let kill_calls = pty.kill_calls.clone();
-        let screen = MockScreenPort::new();
-        let mut uc = make_usecase_with_ports(pty, screen);
-        let size = default_size();
+    fn open_session_replays_scrollback_dimmed() {
+        let mut uc = make_usecase();
+        let mut entry = session_entry("api", None, None);
+        entry.scrollback = vec!["$ cargo run".to_string(), "listening".to_string()];
+        let spec = SessionSpec { terminals: vec![entry], ..Default::default() };
 
-        let id = uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        let ids = uc.open_session(&spec, default_size()).unwrap();
 
-        // Simulate exit: set try_wait result and poll to mark terminal as Exited
-        uc.pty_port.set_try_wait_result(id, Ok(Some(0)));
-        uc.poll_all().unwrap();
-        assert!(!uc.get_terminals()[0].status().is_running());
+        let calls = &uc.screen_port().process_calls;
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, ids[0]);
+        assert_eq!(calls[0].1, b"\x1b[2m$ cargo run\r\nlistening\x1b[0m\r\n".to_vec());
+    }
 
-        // Close the exited terminal — kill() must still be called to clean up PTY resources
-        uc.close_active_terminal().unwrap();
+    #[test]
+    fn open_session_without_scrollback_does_not_touch_screen() {
+        let mut uc = make_usecase();
+        let spec = SessionSpec {
+            terminals: vec![session_entry("api", None, None)],
+            ..Default::default()
+        };
 
-        let calls = kill_calls.lock().unwrap();
-        assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0], id);
+        uc.open_session(&spec, default_size()).unwrap();
+
+        assert!(uc.screen_port().process_calls.is_empty());
+    }
+
+    #[test]
+    fn open_session_propagates_spawn_error() {
+        let pty = MockPtyPort::new().with_spawn_failure();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let spec = SessionSpec {
+            terminals: vec![session_entry("api", None, None)],
+            active: None,
+            ..Default::default()
+        };
+
+        let result = uc.open_session(&spec, default_size());
+        assert!(matches!(result, Err(AppError::PtySpawn(_))));
     }
 
     // =========================================================================
-    // Tests: select_next / select_prev
+    // Tests: snapshot
     // =========================================================================
 
     #[test]
-    fn select_next_wraps_around() {
+    fn snapshot_empty_usecase() {
+        let mut uc = make_usecase();
+        let spec = uc.snapshot(10);
+        assert!(spec.terminals.is_empty());
+        assert_eq!(spec.active, None);
+    }
+
+    #[test]
+    fn snapshot_records_names_memos_and_active() {
         let mut uc = make_usecase();
         let size = default_size();
+        uc.create_terminal(Some("api".to_string()), size).unwrap();
+        uc.set_active_memo("port 8080".to_string()).unwrap();
+        uc.create_terminal(Some("web".to_string()), size).unwrap();
+        uc.select_by_index(0);
 
-        uc.create_terminal(Some("t1".to_string()), size).unwrap();
-        uc.create_terminal(Some("t2".to_string()), size).unwrap();
-        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+        let spec = uc.snapshot(0);
+
+        assert_eq!(spec.terminals.len(), 2);
+        assert_eq!(spec.terminals[0].name.as_deref(), Some("api"));
+        assert_eq!(spec.terminals[0].memo.as_deref(), Some("port 8080"));
+        assert_eq!(spec.terminals[1].memo, None);
+        assert_eq!(spec.active, Some(0));
+        assert!(spec.terminals[0].spawn.command.is_none());
+    }
+
+    #[test]
+    fn snapshot_prefers_osc7_cwd() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.create_terminal(None, default_size()).unwrap();
+        uc.screen_port_mut().cwds.insert(id.value(), "/home/user/project".to_string());
+
+        let spec = uc.snapshot(0);
+
+        assert_eq!(spec.terminals[0].spawn.cwd, Some(PathBuf::from("/home/user/project")));
+        assert_eq!(spec.terminals[1].spawn.cwd, Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn snapshot_keeps_trailing_output_lines() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.screen_port_mut().set_row_text(id, 0, "one");
+        uc.screen_port_mut().set_row_text(id, 1, "two");
+        uc.screen_port_mut().set_row_text(id, 2, "three");
+
+        let spec = uc.snapshot(2);
+
+        assert_eq!(spec.terminals[0].scrollback, vec!["two".to_string(), "three".to_string()]);
+    }
+
+    #[test]
+    fn snapshot_zero_lines_skips_output() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.screen_port_mut().set_row_text(id, 0, "one");
+
+        let spec = uc.snapshot(0);
+
+        assert!(spec.terminals[0].scrollback.is_empty());
+    }
+
+    #[test]
+    fn snapshot_skips_exited_terminals() {
+        let pty = MockPtyPort::new();
+        let pty_handle = pty.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let id = uc.create_terminal(Some("gone".to_string()), default_size()).unwrap();
+        uc.create_terminal(Some("alive".to_string()), default_size()).unwrap();
+        pty_handle.set_try_wait_result(id, Ok(Some(0)));
+        uc.poll_all().unwrap();
+
+        let spec = uc.snapshot(0);
+
+        assert_eq!(spec.terminals.len(), 1);
+        assert_eq!(spec.terminals[0].name.as_deref(), Some("alive"));
+        assert_eq!(spec.active, Some(0));
+    }
+
+    // =========================================================================
+    // Tests: screen_frame
+    // =========================================================================
+
+    #[test]
+    fn screen_frame_empty_usecase() {
+        let uc = make_usecase();
+        let frame = uc.screen_frame();
+        assert!(frame.windows.is_empty());
+        assert_eq!(frame.active, None);
+        assert!(frame.cells.is_empty());
+    }
+
+    #[test]
+    fn screen_frame_lists_windows_and_active_screen() {
+        let mut uc = make_usecase();
+        let first = uc.create_terminal(Some("api".to_string()), default_size()).unwrap();
+        let second = uc.create_terminal(Some("web".to_string()), default_size()).unwrap();
+        uc.set_active_memo("port 8080".to_string()).unwrap();
+        uc.screen_port_mut().set_row_text(second, 0, "ready");
+        uc.screen_port_mut().cwds.insert(first.value(), "/srv/api".to_string());
+
+        let frame = uc.screen_frame();
+
+        assert_eq!(frame.windows.len(), 2);
+        assert_eq!(frame.windows[0].name, "api");
+        assert_eq!(frame.windows[0].cwd, "/srv/api");
+        assert_eq!(frame.windows[1].memo, "port 8080");
+        assert_eq!(frame.active, Some(second.value()));
+        let row: String = frame.cells[0].iter().take(5).map(|c| c.ch).collect();
+        assert_eq!(row, "ready");
+    }
+
+    #[test]
+    fn screen_frame_reports_unread_notification_summary() {
+        let mut uc = make_usecase();
+        let first = uc.create_terminal(None, default_size()).unwrap();
+        uc.create_terminal(None, default_size()).unwrap();
+        uc.screen_port_mut()
+            .pending_notifications
+            .insert(first.value(), vec![NotificationEvent::Bell]);
+        uc.poll_all().unwrap();
+
+        let frame = uc.screen_frame();
+
+        assert_eq!(frame.windows[0].notification.as_deref(), Some("Bell"));
+        assert_eq!(frame.windows[1].notification, None);
+    }
+
+    // =========================================================================
+    // Tests: close_active_terminal
+    // =========================================================================
+
+    #[test]
+    fn close_active_terminal_with_no_active_returns_error() {
+        let mut uc = make_usecase();
+
+        let result = uc.close_active_terminal();
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), AppError::NoActiveTerminal));
+    }
+
+    #[test]
+    fn close_active_terminal_removes_from_list() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+
+        // active is index 1 (t2)
+        uc.close_active_terminal().unwrap();
+
+        assert_eq!(uc.get_terminals().len(), 1);
+        assert_eq!(uc.get_terminals()[0].name(), "t1");
+    }
+
+    #[test]
+    fn close_active_terminal_closes_running_terminal_gracefully() {
+        let pty = MockPtyPort::new();
+        let close_calls = pty.close_calls.clone();
+        let kill_calls = pty.kill_calls.clone();
+        let screen = MockScreenPort::new();
+        let mut uc = make_usecase_with_ports(pty, screen);
+        let size = default_size();
+
+        uc.create_terminal(None, size).unwrap();
+        uc.close_active_terminal().unwrap();
+
+        let calls = close_calls.lock().unwrap();
+        assert_eq!(*calls, vec![(TerminalId::new(1), CLOSE_GRACE)]);
+        assert!(kill_calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn close_active_terminal_sets_active_none_when_list_empty() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(None, size).unwrap();
+        uc.close_active_terminal().unwrap();
+
+        assert_eq!(uc.get_active_index(), None);
+        assert!(uc.get_terminals().is_empty());
+    }
+
+    #[test]
+    fn close_active_terminal_adjusts_index_when_at_end() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+
+        // active_index is 2 (t3, last element)
+        assert_eq!(uc.get_active_index(), Some(2));
+        uc.close_active_terminal().unwrap();
+
+        // After removing last element, index should adjust to new last
+        assert_eq!(uc.get_active_index(), Some(1));
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "t2");
+    }
+
+    #[test]
+    fn close_active_terminal_keeps_index_when_not_at_end() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+
+        // Select first terminal
+        uc.select_by_index(0);
+        assert_eq!(uc.get_active_index(), Some(0));
+
+        uc.close_active_terminal().unwrap();
+
+        // index 0 still valid, now points to what was t2
+        assert_eq!(uc.get_active_index(), Some(0));
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "t2");
+    }
+
+    #[test]
+    fn close_active_terminal_for_middle_element() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+        uc.create_terminal(Some("t3".to_string()), size).unwrap();
+
+        // Select middle terminal
+        uc.select_by_index(1);
+        uc.close_active_terminal().unwrap();
+
+        // index 1 still valid, now points to what was t3
+        assert_eq!(uc.get_terminals().len(), 2);
+        assert_eq!(uc.get_active_index(), Some(1));
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "t3");
+    }
+
+    #[test]
+    fn close_active_terminal_completes_cleanup_even_when_close_fails() {
+        let pty = MockPtyPort::new().with_close_failure();
+        let screen = MockScreenPort::new();
+        let mut uc = make_usecase_with_ports(pty, screen);
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+
+        // active is t2 (index 1). close() will fail, but removal should still complete.
+        let result = uc.close_active_terminal();
+        assert!(result.is_ok());
+
+        // Terminal must be removed from the list despite close failure
+        assert_eq!(uc.get_terminals().len(), 1);
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "t1");
+        assert_eq!(uc.get_active_index(), Some(0));
+    }
+
+    #[test]
+    fn close_active_terminal_with_close_failure_leaves_no_ghost() {
+        let pty = MockPtyPort::new().with_close_failure();
+        let screen = MockScreenPort::new();
+        let mut uc = make_usecase_with_ports(pty, screen);
+        let size = default_size();
+
+        uc.create_terminal(Some("only".to_string()), size).unwrap();
+        uc.close_active_terminal().unwrap();
+
+        assert!(uc.get_terminals().is_empty());
+        assert_eq!(uc.get_active_index(), None);
+    }
+
+    #[test]
+    fn close_active_terminal_calls_close_even_for_exited_terminal() {
+        let pty = MockPtyPort::new();
+        let close_calls = pty.close_calls.clone();
+        let screen = MockScreenPort::new();
+        let mut uc = make_usecase_with_ports(pty, screen);
+        let size = default_size();
+
+        let id = uc.create_terminal(Some("t1".to_string()), size).unwrap();
+
+        // Simulate exit: set try_wait result and poll to mark terminal as Exited
+        uc.pty_port.set_try_wait_result(id, Ok(Some(0)));
+        uc.poll_all().unwrap();
+        assert!(!uc.get_terminals()[0].status().is_running());
+
+        // Close the exited terminal — close() must still be called to clean up PTY resources
+        uc.close_active_terminal().unwrap();
+
+        let calls = close_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, id);
+    }
+
+    // =========================================================================
+    // Tests: select_next / select_prev
+    // =========================================================================
+
+    #[test]
+    fn select_next_wraps_around() {
+        let mut uc = make_usecase();
+        let size = default_size();
+
+        uc.create_terminal(Some("t1".to_string()), size).unwrap();
+        uc.create_terminal(Some("t2".to_string()), size).unwrap();
+        uc.create_terminal(Some("t3".to_string()), size).unwrap();
 
         // Currently at index 2
         assert_eq!(uc.get_active_index(), Some(2));
@@ -1024,6 +2382,32 @@ mod tests {
         assert_eq!(calls[1].1, new_size);
     }
 
+    #[test]
+    fn resize_terminal_resizes_only_that_terminal() {
+        let pty = MockPtyPort::new();
+        let pty_resize_calls = pty.resize_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let size = default_size();
+
+        let _id1 = uc.create_terminal(None, size).unwrap();
+        let id2 = uc.create_terminal(None, size).unwrap();
+
+        let pane = TerminalSize::new(40, 20);
+        uc.resize_terminal(id2, pane).unwrap();
+
+        let calls = pty_resize_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0], (id2, pane));
+        assert_eq!(uc.screen_port.resize_calls, vec![(id2, pane)]);
+    }
+
+    #[test]
+    fn resize_terminal_unknown_id_is_not_found() {
+        let mut uc = make_usecase();
+        let result = uc.resize_terminal(TerminalId::new(999), default_size());
+        assert!(matches!(result, Err(AppError::TerminalNotFound(_))));
+    }
+
     #[test]
     fn resize_all_on_empty_is_ok() {
         let mut uc = make_usecase();
@@ -1153,6 +2537,22 @@ mod tests {
         assert!(calls.is_empty());
     }
 
+    #[test]
+    fn poll_all_records_output_time() {
+        let pty = MockPtyPort::new();
+        let screen = MockScreenPort::new();
+        let mut uc = make_usecase_with_ports(pty, screen);
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        uc.poll_all().unwrap();
+        assert!(uc.get_terminals()[0].last_output().is_none());
+
+        uc.pty_port.set_read_result(id, Ok(b"hi".to_vec()));
+        let before = Instant::now();
+        uc.poll_all().unwrap();
+        assert!(uc.get_terminals()[0].last_output().is_some_and(|t| t >= before));
+    }
+
     #[test]
     fn poll_all_preserves_exit_code_across_multiple_cycles() {
         let pty = MockPtyPort::new();
@@ -1197,6 +2597,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn poll_all_keeps_output_of_watched_terminals_only() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        let a = uc.create_terminal(None, size).unwrap();
+        let b = uc.create_terminal(None, size).unwrap();
+        uc.pty_port.set_read_result(a, Ok(b"from a".to_vec()));
+        uc.pty_port.set_read_result(b, Ok(b"from b".to_vec()));
+
+        uc.poll_all().unwrap();
+        assert!(uc.take_watched_output().is_empty());
+
+        uc.set_watched_output(HashSet::from([b]));
+        uc.pty_port.set_read_result(a, Ok(b"from a".to_vec()));
+        uc.pty_port.set_read_result(b, Ok(b"from b".to_vec()));
+        uc.poll_all().unwrap();
+        assert_eq!(uc.take_watched_output(), vec![(b, b"from b".to_vec())]);
+        assert!(uc.take_watched_output().is_empty());
+    }
+
+    #[test]
+    fn set_watched_output_drops_output_of_unwatched_terminals() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.pty_port.set_read_result(id, Ok(b"x".to_vec()));
+        uc.set_watched_output(HashSet::from([id]));
+        uc.poll_all().unwrap();
+
+        uc.set_watched_output(HashSet::new());
+        assert!(uc.take_watched_output().is_empty());
+    }
+
     #[test]
     fn poll_all_on_empty_is_ok() {
         let mut uc = make_usecase();
@@ -1581,7 +3013,50 @@ mod tests {
     }
 
     // =========================================================================
-    // Tests: rename_active_terminal
+    // Tests: notify_terminal
+    // =========================================================================
+
+    fn external(body: &str) -> NotificationEvent {
+        NotificationEvent::External { title: "Claude Code".to_string(), body: body.to_string() }
+    }
+
+    #[test]
+    fn notify_terminal_marks_inactive_terminal_and_queues_desktop_notification() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        let id1 = uc.create_terminal(Some("agent".to_string()), size).unwrap();
+        let _id2 = uc.create_terminal(Some("shell".to_string()), size).unwrap();
+
+        uc.notify_terminal(id1, external("needs input")).unwrap();
+
+        assert_eq!(uc.get_terminals()[0].last_notification(), Some(&external("needs input")));
+        assert_eq!(
+            uc.take_pending_notifications(),
+            vec![("agent".to_string(), external("needs input"))]
+        );
+    }
+
+    #[test]
+    fn notify_terminal_on_active_terminal_skips_sidebar_mark() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(Some("agent".to_string()), default_size()).unwrap();
+
+        uc.notify_terminal(id, external("done")).unwrap();
+
+        assert_eq!(uc.get_terminals()[0].last_notification(), None);
+        assert_eq!(uc.take_pending_notifications().len(), 1);
+    }
+
+    #[test]
+    fn notify_terminal_nonexistent_returns_terminal_not_found() {
+        let mut uc = make_usecase();
+        let result = uc.notify_terminal(TerminalId::new(9), external("x"));
+        assert!(matches!(result, Err(AppError::TerminalNotFound(_))));
+        assert!(uc.take_pending_notifications().is_empty());
+    }
+
+    // =========================================================================
+    // Tests: rename_active_terminal
     // =========================================================================
 
     #[test]
@@ -1681,7 +3156,7 @@ mod tests {
         let id = TerminalId::new(100);
         let size = default_size();
         uc.pty_port_mut()
-            .spawn(id, "/bin/sh", &PathBuf::from("/tmp"), size)
+            .spawn(id, &["/bin/sh".to_string()], &PathBuf::from("/tmp"), &[], size)
             .unwrap();
 
         // Verify the call was recorded
@@ -1762,9 +3237,9 @@ mod tests {
     // =========================================================================
 
     #[test]
-    fn close_by_id_removes_terminal_and_calls_kill_and_remove() {
+    fn close_by_id_removes_terminal_and_calls_close_and_remove() {
         let pty = MockPtyPort::new();
-        let kill_calls = pty.kill_calls.clone();
+        let close_calls = pty.close_calls.clone();
         let screen = MockScreenPort::new();
         let mut uc = make_usecase_with_ports(pty, screen);
         let size = default_size();
@@ -1778,10 +3253,9 @@ mod tests {
         assert_eq!(uc.get_terminals().len(), 1);
         assert_eq!(uc.get_terminals()[0].name(), "t2");
 
-        // kill and remove were called
-        let kills = kill_calls.lock().unwrap();
-        assert_eq!(kills.len(), 1);
-        assert_eq!(kills[0], id1);
+        // close and remove were called
+        let closes = close_calls.lock().unwrap();
+        assert_eq!(*closes, vec![(id1, CLOSE_GRACE)]);
 
         assert_eq!(uc.screen_port.remove_calls.len(), 1);
         assert_eq!(uc.screen_port.remove_calls[0], id1);
@@ -1879,9 +3353,9 @@ mod tests {
     }
 
     #[test]
-    fn close_by_id_calls_kill_even_for_exited_terminal() {
+    fn close_by_id_calls_close_even_for_exited_terminal() {
         let pty = MockPtyPort::new();
-        let kill_calls = pty.kill_calls.clone();
+        let close_calls = pty.close_calls.clone();
         let screen = MockScreenPort::new();
         let mut uc = make_usecase_with_ports(pty, screen);
         let size = default_size();
@@ -1894,12 +3368,12 @@ mod tests {
         uc.poll_all().unwrap();
         assert!(!uc.get_terminals()[0].status().is_running());
 
-        // Close the exited terminal — kill() must still be called to clean up PTY resources
+        // Close the exited terminal — close() must still be called to clean up PTY resources
         uc.close_by_id(id1).unwrap();
 
-        let calls = kill_calls.lock().unwrap();
+        let calls = close_calls.lock().unwrap();
         assert_eq!(calls.len(), 1);
-        assert_eq!(calls[0], id1);
+        assert_eq!(calls[0].0, id1);
         assert_eq!(uc.get_terminals().len(), 1);
     }
 
@@ -1939,6 +3413,97 @@ mod tests {
         ));
     }
 
+    // =========================================================================
+    // Tests: resolve_target
+    // =========================================================================
+
+    fn resolve(uc: &TerminalUsecase<MockPtyPort, MockScreenPort>, spec: &str) -> Result<Vec<u32>, AppError> {
+        let ids = uc.resolve_target(&TargetSpec::parse(spec).unwrap(), None)?;
+        Ok(ids.iter().map(|id| id.value()).collect())
+    }
+
+    #[test]
+    fn resolve_target_by_name_and_pattern() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        uc.create_terminal(Some("api-server".to_string()), size).unwrap();
+        uc.create_terminal(Some("agent-1".to_string()), size).unwrap();
+        uc.create_terminal(Some("agent-2".to_string()), size).unwrap();
+
+        assert_eq!(resolve(&uc, "api-server").unwrap(), vec![1]);
+        assert_eq!(resolve(&uc, "agent-*").unwrap(), vec![2, 3]);
+        assert!(matches!(resolve(&uc, "db"), Err(AppError::NoMatchingTerminal(_))));
+        assert!(matches!(resolve(&uc, "web-*"), Err(AppError::NoMatchingTerminal(_))));
+    }
+
+    #[test]
+    fn resolve_target_duplicate_name_is_ambiguous() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        uc.create_terminal(Some("build".to_string()), size).unwrap();
+        uc.create_terminal(Some("build".to_string()), size).unwrap();
+
+        let err = resolve(&uc, "build").unwrap_err();
+        assert!(matches!(err, AppError::AmbiguousTarget { .. }));
+        assert!(err.to_string().contains("1, 2"), "got: {err}");
+    }
+
+    #[test]
+    fn resolve_target_id_is_returned_as_is() {
+        let uc = make_usecase();
+        // Existence is checked by the command itself
+        assert_eq!(resolve(&uc, "42").unwrap(), vec![42]);
+    }
+
+    #[test]
+    fn resolve_target_active_and_last() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        assert!(matches!(resolve(&uc, "@active"), Err(AppError::NoActiveTerminal)));
+        assert!(matches!(resolve(&uc, "@last"), Err(AppError::NoLastTerminal)));
+
+        let id1 = uc.create_terminal(None, size).unwrap();
+        let id2 = uc.create_terminal(None, size).unwrap();
+        assert_eq!(resolve(&uc, "@active").unwrap(), vec![id2.value()]);
+        assert_eq!(resolve(&uc, "@last").unwrap(), vec![id1.value()]);
+
+        uc.select_by_id(id1).unwrap();
+        assert_eq!(resolve(&uc, "@last").unwrap(), vec![id2.value()]);
+
+        // A closed terminal is no longer @last
+        uc.close_by_id(id2).unwrap();
+        assert!(matches!(resolve(&uc, "@last"), Err(AppError::NoLastTerminal)));
+    }
+
+    #[test]
+    fn resolve_target_caller() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        assert_eq!(uc.resolve_target(&TargetSpec::Caller, Some(id)).unwrap(), vec![id]);
+        assert!(matches!(
+            uc.resolve_target(&TargetSpec::Caller, None),
+            Err(AppError::NoCallerTerminal)
+        ));
+    }
+
+    #[test]
+    fn resolve_target_list_is_the_union_of_its_items() {
+        let mut uc = make_usecase();
+        let size = default_size();
+        uc.create_terminal(Some("build".to_string()), size).unwrap();
+        uc.create_terminal(Some("agent-1".to_string()), size).unwrap();
+        uc.create_terminal(Some("agent-2".to_string()), size).unwrap();
+
+        let list = |items: &[&str]| TargetSpec::List(items.iter().map(|s| TargetSpec::parse(s).unwrap()).collect());
+        let ids = |spec: &TargetSpec| -> Result<Vec<u32>, AppError> {
+            Ok(uc.resolve_target(spec, None)?.iter().map(|id| id.value()).collect())
+        };
+        // In the order given, without duplicates
+        assert_eq!(ids(&list(&["3", "build", "agent-*"])).unwrap(), vec![3, 1, 2]);
+        assert!(matches!(ids(&list(&["build", "db"])), Err(AppError::NoMatchingTerminal(_))));
+    }
+
     // =========================================================================
     // Tests: rename_by_id
     // =========================================================================
@@ -1970,4 +3535,758 @@ mod tests {
             AppError::TerminalNotFound(_)
         ));
     }
+
+    // =========================================================================
+    // Tests: set_badge_by_id
+    // =========================================================================
+
+    #[test]
+    fn set_badge_by_id_sets_and_clears_badge() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        let badge = StatusBadge { text: "thinking".to_string(), icon: Some("⏳".to_string()), color: Color::Indexed(3) };
+
+        uc.set_badge_by_id(id, Some(badge.clone())).unwrap();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().badge(), Some(&badge));
+        assert_eq!(uc.screen_frame().windows[0].badge, Some(badge));
+
+        uc.set_badge_by_id(id, None).unwrap();
+        assert!(uc.get_terminal_by_id(id).unwrap().badge().is_none());
+    }
+
+    #[test]
+    fn set_badge_by_id_nonexistent_returns_terminal_not_found() {
+        let mut uc = make_usecase();
+        let result = uc.set_badge_by_id(TerminalId::new(999), None);
+        assert!(matches!(result, Err(AppError::TerminalNotFound(_))));
+    }
+
+    // =========================================================================
+    // Tests: foreground process
+    // =========================================================================
+
+    fn foreground(pid: u32, name: &str, is_terminal_process: bool) -> ForegroundProcess {
+        ForegroundProcess { pid, name: name.to_string(), cmdline: name.to_string(), is_terminal_process }
+    }
+
+    #[test]
+    fn refresh_foreground_marks_shell_at_prompt_idle() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        let fg = uc.pty_port.foreground.clone();
+
+        fg.lock().unwrap().insert(id.value(), foreground(10, "zsh", true));
+        uc.refresh_foreground();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Idle);
+
+        fg.lock().unwrap().insert(id.value(), foreground(11, "vim", false));
+        uc.refresh_foreground();
+        let t = uc.get_terminal_by_id(id).unwrap();
+        assert_eq!(t.status(), &TerminalStatus::Running);
+        assert_eq!(t.foreground().unwrap().name, "vim");
+        assert_eq!(uc.screen_frame().windows[0].process.as_deref(), Some("vim"));
+    }
+
+    #[test]
+    fn refresh_foreground_never_idles_command_terminal() {
+        let mut uc = make_usecase();
+        let opts = SpawnOptions { command: Some("cargo watch".to_string()), ..Default::default() };
+        let id = uc.create_terminal_with_options(None, &opts, default_size()).unwrap();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().command(), Some("cargo watch"));
+
+        uc.pty_port.foreground.lock().unwrap().insert(id.value(), foreground(10, "sh", true));
+        uc.refresh_foreground();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Running);
+    }
+
+    #[test]
+    fn poll_all_refreshes_foreground() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.pty_port.foreground.lock().unwrap().insert(id.value(), foreground(10, "bash", true));
+
+        uc.poll_all().unwrap();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Idle);
+    }
+
+    // =========================================================================
+    // Tests: respawn and restart policy
+    // =========================================================================
+
+    fn exit_terminal(uc: &mut TerminalUsecase<MockPtyPort, MockScreenPort>, id: TerminalId, code: i32) {
+        uc.pty_port.set_try_wait_result(id, Ok(Some(code)));
+        uc.poll_all().unwrap();
+    }
+
+    #[test]
+    fn respawn_by_id_restarts_exited_terminal_in_place() {
+        let mut uc = make_usecase();
+        let opts = SpawnOptions {
+            command: Some("npm run dev".to_string()),
+            env: vec![("PORT".to_string(), "3000".to_string())],
+            ..Default::default()
+        };
+        let id = uc.create_terminal_with_options(Some("web".to_string()), &opts, default_size()).unwrap();
+        uc.set_active_memo("frontend".to_string()).unwrap();
+        exit_terminal(&mut uc, id, 1);
+
+        uc.respawn_by_id(id, false).unwrap();
+
+        let t = uc.get_terminal_by_id(id).unwrap();
+        assert_eq!(t.status(), &TerminalStatus::Running);
+        assert_eq!((t.name(), t.memo()), ("web", "frontend"));
+        assert_eq!(uc.get_terminals().len(), 1);
+
+        let spawns = uc.pty_port.spawn_calls.lock().unwrap();
+        assert_eq!(spawns.len(), 2);
+        assert_eq!(spawns[1].0, id);
+        assert_eq!(spawns[1].1, spawns[0].1);
+        assert_eq!(spawns[1].3, default_size());
+        assert!(spawns[1].4.contains(&("PORT".to_string(), "3000".to_string())));
+        drop(spawns);
+        assert_eq!(*uc.pty_port.close_calls.lock().unwrap(), vec![(id, CLOSE_GRACE)]);
+
+        // Old output stays; a separator marks where the new process starts
+        let (sep_id, separator) = uc.screen_port.process_calls.last().unwrap();
+        assert_eq!(*sep_id, id);
+        assert!(String::from_utf8_lossy(separator).contains("respawned"));
+        assert!(uc.screen_port.remove_calls.is_empty());
+    }
+
+    #[test]
+    fn respawn_by_id_running_terminal_needs_kill() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        let result = uc.respawn_by_id(id, false);
+        assert!(matches!(result, Err(AppError::StillRunning(_))));
+        assert_eq!(uc.pty_port.spawn_calls.lock().unwrap().len(), 1);
+
+        uc.respawn_by_id(id, true).unwrap();
+        assert_eq!(uc.pty_port.spawn_calls.lock().unwrap().len(), 2);
+        // Same hangup and grace period as closing the terminal
+        assert_eq!(*uc.pty_port.close_calls.lock().unwrap(), vec![(id, CLOSE_GRACE)]);
+        assert!(uc.pty_port.kill_calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn respawn_by_id_close_failure_is_reported() {
+        let mut uc = make_usecase_with_ports(MockPtyPort::new().with_close_failure(), MockScreenPort::new());
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        let result = uc.respawn_by_id(id, true);
+        assert!(matches!(result, Err(AppError::PtyIo { .. })));
+        assert_eq!(uc.pty_port.spawn_calls.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn respawn_by_id_nonexistent_returns_terminal_not_found() {
+        let mut uc = make_usecase();
+        let result = uc.respawn_by_id(TerminalId::new(9), true);
+        assert!(matches!(result, Err(AppError::TerminalNotFound(_))));
+    }
+
+    #[test]
+    fn restart_policy_respawns_after_backoff() {
+        let mut uc = make_usecase();
+        let opts = SpawnOptions {
+            command: Some("cargo watch".to_string()),
+            restart: RestartPolicy::OnFailure,
+            ..Default::default()
+        };
+        let id = uc.create_terminal_with_options(None, &opts, default_size()).unwrap();
+        exit_terminal(&mut uc, id, 101);
+
+        // Not yet: the first restart waits a second
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Exited(101));
+        assert!(uc.get_terminal_by_id(id).unwrap().restart_at().is_some());
+
+        uc.run_due_restarts(Instant::now() + Duration::from_secs(2));
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Running);
+        assert_eq!(uc.pty_port.spawn_calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn restart_policy_on_failure_ignores_clean_exit() {
+        let mut uc = make_usecase();
+        let opts = SpawnOptions { restart: RestartPolicy::OnFailure, ..Default::default() };
+        let id = uc.create_terminal_with_options(None, &opts, default_size()).unwrap();
+        exit_terminal(&mut uc, id, 0);
+
+        uc.run_due_restarts(Instant::now() + Duration::from_secs(120));
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().status(), &TerminalStatus::Exited(0));
+        assert!(uc.get_terminal_by_id(id).unwrap().restart_at().is_none());
+    }
+
+    #[test]
+    fn restart_that_fails_to_spawn_is_retried_later() {
+        let mut uc = make_usecase();
+        let opts = SpawnOptions { restart: RestartPolicy::Always, ..Default::default() };
+        let id = uc.create_terminal_with_options(None, &opts, default_size()).unwrap();
+        exit_terminal(&mut uc, id, 0);
+
+        uc.pty_port.spawn_should_fail = true;
+        let now = Instant::now() + Duration::from_secs(2);
+        uc.run_due_restarts(now);
+        let t = uc.get_terminal_by_id(id).unwrap();
+        assert_eq!(t.status(), &TerminalStatus::Exited(-1));
+        assert_eq!(t.restart_at(), Some(now + Duration::from_secs(2)));
+    }
+
+    // =========================================================================
+    // Tests: signals
+    // =========================================================================
+
+    #[test]
+    fn signal_by_id_forwards_to_pty() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        uc.signal_by_id(id, Signal::Int).unwrap();
+        uc.signal_active(Signal::Stop).unwrap();
+
+        let calls = uc.pty_port.signal_calls.lock().unwrap();
+        assert_eq!(*calls, vec![(id, Signal::Int), (id, Signal::Stop)]);
+    }
+
+    #[test]
+    fn signal_by_id_rejects_exited_and_unknown_terminals() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        exit_terminal(&mut uc, id, 0);
+
+        assert!(matches!(uc.signal_by_id(id, Signal::Term), Err(AppError::NotRunning(_))));
+        assert!(matches!(
+            uc.signal_by_id(TerminalId::new(99), Signal::Term),
+            Err(AppError::TerminalNotFound(_))
+        ));
+        assert!(uc.pty_port.signal_calls.lock().unwrap().is_empty());
+    }
+
+    // =========================================================================
+    // Tests: logging
+    // =========================================================================
+
+    use crate::interface_adapter::port::log_port::tests::{LogWrites, MockLogPort};
+
+    fn make_usecase_with_log() -> (TerminalUsecase<MockPtyPort, MockScreenPort>, LogWrites) {
+        let mut uc = make_usecase();
+        let log = MockLogPort::new();
+        let written = Arc::clone(&log.written);
+        uc.set_log_port(Box::new(log));
+        (uc, written)
+    }
+
+    #[test]
+    fn start_logging_without_log_port_is_unavailable() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        let result = uc.start_logging(id, &LogOptions::default());
+        assert!(matches!(result.unwrap_err(), AppError::LogUnavailable));
+    }
+
+    #[test]
+    fn start_logging_unknown_terminal_returns_error() {
+        let (mut uc, _) = make_usecase_with_log();
+
+        let result = uc.start_logging(TerminalId::new(99), &LogOptions::default());
+        assert!(matches!(result.unwrap_err(), AppError::TerminalNotFound(_)));
+    }
+
+    #[test]
+    fn start_logging_passes_terminal_name_and_options() {
+        let (mut uc, _) = make_usecase_with_log();
+        let id = uc.create_terminal(Some("agent".to_string()), default_size()).unwrap();
+        let options = LogOptions { mode: LogMode::Raw, max_bytes: 1024 };
+
+        let status = uc.start_logging(id, &options).unwrap();
+        assert_eq!(status.mode, LogMode::Raw);
+        assert_eq!(status.max_bytes, 1024);
+        assert!(status.path.to_string_lossy().contains("agent"));
+        assert_eq!(uc.logging_status(id).unwrap(), Some(status));
+    }
+
+    #[test]
+    fn poll_all_writes_output_of_logged_terminals_only() {
+        let (mut uc, written) = make_usecase_with_log();
+        let logged = uc.create_terminal(None, default_size()).unwrap();
+        let other = uc.create_terminal(None, default_size()).unwrap();
+        uc.start_logging(logged, &LogOptions::default()).unwrap();
+        uc.pty_port.set_read_result(logged, Ok(b"build ok".to_vec()));
+        uc.pty_port.set_read_result(other, Ok(b"noise".to_vec()));
+
+        uc.poll_all().unwrap();
+
+        let written = written.lock().unwrap();
+        assert_eq!(written.as_slice(), &[(logged, b"build ok".to_vec())]);
+    }
+
+    #[test]
+    fn poll_all_stops_logging_on_write_failure() {
+        let mut uc = make_usecase();
+        let mut log = MockLogPort::new();
+        log.fail_writes = true;
+        uc.set_log_port(Box::new(log));
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.start_logging(id, &LogOptions::default()).unwrap();
+        uc.pty_port.set_read_result(id, Ok(b"x".to_vec()));
+
+        uc.poll_all().unwrap();
+
+        assert_eq!(uc.logging_status(id).unwrap(), None);
+        // Output still reaches the screen
+        assert_eq!(uc.screen_port.process_calls.len(), 1);
+        assert!(uc.get_terminals()[0].status().is_running());
+    }
+
+    #[test]
+    fn stop_logging_reports_whether_it_was_active() {
+        let (mut uc, _) = make_usecase_with_log();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.start_logging(id, &LogOptions::default()).unwrap();
+
+        assert!(uc.stop_logging(id).unwrap());
+        assert!(!uc.stop_logging(id).unwrap());
+        assert_eq!(uc.logging_status(id).unwrap(), None);
+    }
+
+    #[test]
+    fn stop_logging_unknown_terminal_returns_error() {
+        let (mut uc, _) = make_usecase_with_log();
+        assert!(matches!(
+            uc.stop_logging(TerminalId::new(7)).unwrap_err(),
+            AppError::TerminalNotFound(_)
+        ));
+        assert!(uc.logging_status(TerminalId::new(7)).is_err());
+    }
+
+    #[test]
+    fn close_by_id_stops_logging() {
+        let (mut uc, written) = make_usecase_with_log();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.start_logging(id, &LogOptions::default()).unwrap();
+
+        uc.close_by_id(id).unwrap();
+
+        // The log port no longer writes for the closed id
+        uc.log_port.as_mut().unwrap().write(id, b"late").unwrap();
+        assert!(written.lock().unwrap().is_empty());
+    }
+
+    // =========================================================================
+    // Tests: recording
+    // =========================================================================
+
+    use crate::interface_adapter::port::recorder_port::tests::{
+        MockRecorderPort, RecordedEvent, RecordedEvents,
+    };
+
+    fn make_usecase_with_recorder() -> (TerminalUsecase<MockPtyPort, MockScreenPort>, RecordedEvents) {
+        let mut uc = make_usecase();
+        let recorder = MockRecorderPort::new();
+        let events = Arc::clone(&recorder.events);
+        uc.set_recorder_port(Box::new(recorder));
+        (uc, events)
+    }
+
+    #[test]
+    fn start_recording_without_recorder_is_unavailable() {
+        let mut uc = make_usecase();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        assert!(matches!(
+            uc.start_recording(id, None).unwrap_err(),
+            AppError::RecordUnavailable
+        ));
+    }
+
+    #[test]
+    fn start_recording_unknown_terminal_returns_error() {
+        let (mut uc, _) = make_usecase_with_recorder();
+        assert!(matches!(
+            uc.start_recording(TerminalId::new(42), None).unwrap_err(),
+            AppError::TerminalNotFound(_)
+        ));
+    }
+
+    #[test]
+    fn recording_captures_output_and_resize() {
+        let (mut uc, events) = make_usecase_with_recorder();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        let path = uc.start_recording(id, Some(Path::new("/tmp/a.cast"))).unwrap();
+        assert_eq!(uc.recording_path(id), Some(path));
+
+        uc.pty_port.set_read_result(id, Ok(b"out".to_vec()));
+        uc.poll_all().unwrap();
+        uc.resize_all(TerminalSize::new(100, 30)).unwrap();
+
+        assert_eq!(
+            events.lock().unwrap().as_slice(),
+            &[
+                (id, RecordedEvent::Output(b"out".to_vec())),
+                (id, RecordedEvent::Resize(TerminalSize::new(100, 30))),
+            ]
+        );
+    }
+
+    #[test]
+    fn stop_recording_returns_file_once() {
+        let (mut uc, _) = make_usecase_with_recorder();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        let path = uc.start_recording(id, None).unwrap();
+
+        assert_eq!(uc.stop_recording(id).unwrap(), Some(path));
+        assert_eq!(uc.stop_recording(id).unwrap(), None);
+        assert!(uc.recording_path(id).is_none());
+    }
+
+    #[test]
+    fn toggle_active_recording_starts_and_stops() {
+        let (mut uc, _) = make_usecase_with_recorder();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+
+        uc.toggle_active_recording().unwrap();
+        assert!(uc.recording_path(id).is_some());
+        uc.toggle_active_recording().unwrap();
+        assert!(uc.recording_path(id).is_none());
+    }
+
+    #[test]
+    fn toggle_active_recording_without_terminal_fails() {
+        let (mut uc, _) = make_usecase_with_recorder();
+        assert!(matches!(
+            uc.toggle_active_recording().unwrap_err(),
+            AppError::NoActiveTerminal
+        ));
+    }
+
+    #[test]
+    fn close_active_terminal_stops_recording() {
+        let (mut uc, _) = make_usecase_with_recorder();
+        let id = uc.create_terminal(None, default_size()).unwrap();
+        uc.start_recording(id, None).unwrap();
+
+        uc.close_active_terminal().unwrap();
+
+        assert!(uc.recording_path(id).is_none());
+    }
+
+    // =========================================================================
+    // Tests: reorder, sort and filter
+    // =========================================================================
+
+    fn names(uc: &TerminalUsecase<MockPtyPort, MockScreenPort>) -> Vec<&str> {
+        uc.get_terminals().iter().map(|t| t.name()).collect()
+    }
+
+    fn visible_names(uc: &TerminalUsecase<MockPtyPort, MockScreenPort>) -> Vec<&str> {
+        uc.visible_terminals().into_iter().map(|t| t.name()).collect()
+    }
+
+    fn make_usecase_with(names: &[&str]) -> (TerminalUsecase<MockPtyPort, MockScreenPort>, Vec<TerminalId>) {
+        let mut uc = make_usecase();
+        let ids = names
+            .iter()
+            .map(|name| uc.create_terminal(Some(name.to_string()), default_size()).unwrap())
+            .collect();
+        (uc, ids)
+    }
+
+    fn text_filter(text: &str) -> TerminalFilter {
+        TerminalFilter { status: StatusFilter::All, text: text.to_string() }
+    }
+
+    #[test]
+    fn move_by_stops_at_the_ends_and_keeps_active_terminal() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b", "c"]);
+        uc.move_by(ids[2], -1).unwrap();
+        assert_eq!(names(&uc), ["a", "c", "b"]);
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[2]);
+
+        uc.move_by(ids[2], -5).unwrap();
+        assert_eq!(names(&uc), ["c", "a", "b"]);
+        uc.move_by(ids[0], 1).unwrap();
+        assert_eq!(names(&uc), ["c", "b", "a"]);
+        assert_eq!(uc.get_active_index(), Some(0));
+
+        assert!(matches!(uc.move_by(TerminalId::new(99), 1), Err(AppError::TerminalNotFound(_))));
+    }
+
+    #[test]
+    fn move_by_steps_over_hidden_terminals() {
+        let (mut uc, ids) = make_usecase_with(&["api", "web", "api-test"]);
+        uc.set_filter(text_filter("api"));
+        assert_eq!(visible_names(&uc), ["api", "api-test"]);
+
+        uc.move_by(ids[2], -1).unwrap();
+        assert_eq!(names(&uc), ["api-test", "api", "web"]);
+        uc.move_active(1).unwrap();
+        assert_eq!(names(&uc), ["api", "api-test", "web"]);
+    }
+
+    #[test]
+    fn move_to_and_swap_keep_active_terminal() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b", "c"]);
+        uc.move_to(ids[0], 10).unwrap();
+        assert_eq!(names(&uc), ["b", "c", "a"]);
+        uc.swap(ids[1], ids[2]).unwrap();
+        assert_eq!(names(&uc), ["c", "b", "a"]);
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[2]);
+        assert!(matches!(uc.swap(ids[0], TerminalId::new(99)), Err(AppError::TerminalNotFound(_))));
+    }
+
+    #[test]
+    fn sort_terminals_by_each_key() {
+        let (mut uc, ids) = make_usecase_with(&["web", "Api", "db"]);
+        uc.sort_terminals(SortKey::Name);
+        assert_eq!(names(&uc), ["Api", "db", "web"]);
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "db");
+
+        uc.screen_port_mut().cwds.insert(ids[0].value(), "/a".to_string());
+        uc.sort_terminals(SortKey::Cwd);
+        assert_eq!(names(&uc), ["web", "Api", "db"]);
+
+        uc.pty_port.set_read_result(ids[2], Ok(b"x".to_vec()));
+        uc.poll_all().unwrap();
+        uc.sort_terminals(SortKey::Activity);
+        assert_eq!(names(&uc), ["db", "web", "Api"]);
+
+        uc.notify_terminal(ids[1], NotificationEvent::Bell).unwrap();
+        uc.sort_terminals(SortKey::Unread);
+        assert_eq!(names(&uc), ["Api", "db", "web"]);
+        assert_eq!(uc.get_active_terminal().unwrap().name(), "db");
+    }
+
+    #[test]
+    fn set_filter_moves_active_to_first_shown_terminal() {
+        let (mut uc, ids) = make_usecase_with(&["api", "web", "api-test"]);
+        uc.select_by_id(ids[1]).unwrap();
+
+        uc.set_filter(text_filter("api"));
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[0]);
+        assert_eq!(visible_names(&uc), ["api", "api-test"]);
+        assert_eq!(uc.visible_active_index(), Some(0));
+
+        // Nothing matches: the active terminal stays and is still shown
+        uc.set_filter(text_filter("nothing"));
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[0]);
+        assert_eq!(visible_names(&uc), ["api"]);
+
+        uc.set_filter(TerminalFilter::default());
+        assert_eq!(visible_names(&uc), ["api", "web", "api-test"]);
+    }
+
+    #[test]
+    fn selection_skips_hidden_terminals() {
+        let (mut uc, ids) = make_usecase_with(&["api", "web", "api-test"]);
+        uc.pty_port.set_try_wait_result(ids[1], Ok(Some(0)));
+        uc.poll_all().unwrap();
+        uc.set_filter(TerminalFilter { status: StatusFilter::Running, text: String::new() });
+
+        uc.select_next();
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[0]);
+        uc.select_prev();
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[2]);
+        uc.select_by_index(0);
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[0]);
+        uc.select_by_index(2);
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[0]);
+    }
+
+    #[test]
+    fn screen_frame_lists_only_shown_terminals() {
+        let (mut uc, _) = make_usecase_with(&["api", "web"]);
+        uc.set_filter(text_filter("we"));
+
+        let frame = uc.screen_frame();
+        assert_eq!(frame.windows.iter().map(|w| w.name.as_str()).collect::<Vec<_>>(), ["web"]);
+        assert_eq!(frame.filter, text_filter("we"));
+    }
+
+    // =========================================================================
+    // Tests: groups
+    // =========================================================================
+
+    fn group(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    #[test]
+    fn set_group_keeps_groups_together_below_ungrouped_terminals() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b", "c", "d"]);
+        uc.set_group(ids[0], group("api")).unwrap();
+        assert_eq!(names(&uc), ["b", "c", "d", "a"]);
+        uc.set_group(ids[2], group("web")).unwrap();
+        uc.set_group(ids[1], group("api")).unwrap();
+        assert_eq!(names(&uc), ["d", "a", "b", "c"]);
+        assert_eq!(uc.get_active_terminal().unwrap().id(), ids[3]);
+
+        // Ungrouping moves a terminal back among the ungrouped ones
+        uc.set_group(ids[2], None).unwrap();
+        assert_eq!(names(&uc), ["d", "c", "a", "b"]);
+        assert!(matches!(uc.set_group(TerminalId::new(99), None), Err(AppError::TerminalNotFound(_))));
+    }
+
+    #[test]
+    fn created_terminal_stays_above_groups() {
+        let (mut uc, ids) = make_usecase_with(&["a"]);
+        uc.set_group(ids[0], group("api")).unwrap();
+        let id = uc.create_terminal(Some("b".to_string()), default_size()).unwrap();
+        assert_eq!(names(&uc), ["b", "a"]);
+        uc.set_active_group(group("api")).unwrap();
+        assert_eq!(uc.get_terminal_by_id(id).unwrap().group(), Some("api"));
+        assert_eq!(names(&uc), ["a", "b"]);
+    }
+
+    #[test]
+    fn collapsed_group_hides_all_but_the_active_terminal() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b", "c"]);
+        uc.set_group(ids[1], group("api")).unwrap();
+        uc.set_group(ids[2], group("api")).unwrap();
+
+        assert!(uc.set_group_collapsed("api", None).unwrap());
+        assert_eq!(visible_names(&uc), ["a", "c"]);
+        let header = GroupHeader { name: "api".to_string(), count: 2, collapsed: true, at: 1 };
+        assert_eq!(uc.group_headers(), vec![header.clone()]);
+
+        uc.select_by_id(ids[0]).unwrap();
+        assert_eq!(visible_names(&uc), ["a"]);
+        assert_eq!(uc.group_headers(), vec![header]);
+
+        uc.select_by_id(ids[2]).unwrap();
+        uc.toggle_active_group().unwrap();
+        assert_eq!(visible_names(&uc), ["a", "b", "c"]);
+        assert!(matches!(uc.set_group_collapsed("web", None), Err(AppError::NoMatchingTerminal(_))));
+    }
+
+    #[test]
+    fn group_headers_follow_the_filter() {
+        let (mut uc, ids) = make_usecase_with(&["shell", "api", "web"]);
+        uc.set_group(ids[1], group("backend")).unwrap();
+        uc.set_group(ids[2], group("frontend")).unwrap();
+        uc.select_by_id(ids[0]).unwrap();
+
+        uc.set_filter(text_filter("backend"));
+        assert_eq!(visible_names(&uc), ["shell", "api"]);
+        let headers = uc.group_headers();
+        assert_eq!(headers.len(), 1);
+        assert_eq!((headers[0].name.as_str(), headers[0].at), ("backend", 1));
+    }
+
+    #[test]
+    fn moves_and_sorts_stay_within_a_group() {
+        let (mut uc, ids) = make_usecase_with(&["d", "c", "b", "a"]);
+        uc.set_group(ids[1], group("api")).unwrap();
+        uc.set_group(ids[2], group("api")).unwrap();
+        assert_eq!(names(&uc), ["d", "a", "c", "b"]);
+
+        uc.move_by(ids[2], -5).unwrap();
+        assert_eq!(names(&uc), ["d", "a", "b", "c"]);
+        uc.move_to(ids[2], 10).unwrap();
+        assert_eq!(names(&uc), ["d", "a", "c", "b"]);
+        assert!(matches!(uc.swap(ids[0], ids[1]), Err(AppError::DifferentGroups(_, _))));
+        uc.swap(ids[1], ids[2]).unwrap();
+        assert_eq!(names(&uc), ["d", "a", "b", "c"]);
+
+        uc.sort_terminals(SortKey::Name);
+        assert_eq!(names(&uc), ["a", "d", "b", "c"]);
+    }
+
+    #[test]
+    fn resolve_group_target() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b", "c"]);
+        uc.set_group(ids[0], group("api")).unwrap();
+        uc.set_group(ids[2], group("api")).unwrap();
+        assert_eq!(resolve(&uc, "#api").unwrap(), vec![1, 3]);
+        assert!(matches!(resolve(&uc, "#web"), Err(AppError::NoMatchingTerminal(_))));
+    }
+
+    #[test]
+    fn snapshot_and_open_session_keep_groups() {
+        let (mut uc, ids) = make_usecase_with(&["a", "b"]);
+        uc.set_group(ids[0], group("api")).unwrap();
+        let spec = uc.snapshot(0);
+        assert_eq!(spec.terminals[0].group, None);
+        assert_eq!(spec.terminals[1].group, group("api"));
+
+        let mut restored = make_usecase();
+        restored.open_session(&spec, default_size()).unwrap();
+        assert_eq!(names(&restored), ["b", "a"]);
+        assert_eq!(restored.get_terminals()[1].group(), Some("api"));
+        assert_eq!(restored.screen_frame().groups.len(), 1);
+    }
+
+    // =========================================================================
+    // Tests: synchronized input
+    // =========================================================================
+
+    type Writes = Arc<Mutex<Vec<(TerminalId, Vec<u8>)>>>;
+
+    fn make_usecase_with_writes(names: &[&str]) -> (TerminalUsecase<MockPtyPort, MockScreenPort>, Vec<TerminalId>, Writes) {
+        let pty = MockPtyPort::new();
+        let writes = pty.write_calls.clone();
+        let mut uc = make_usecase_with_ports(pty, MockScreenPort::new());
+        let ids = names
+            .iter()
+            .map(|name| uc.create_terminal(Some(name.to_string()), default_size()).unwrap())
+            .collect();
+        (uc, ids, writes)
+    }
+
+    fn written_to(writes: &Writes) -> Vec<TerminalId> {
+        writes.lock().unwrap().drain(..).map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn sync_off_writes_only_to_the_active_terminal() {
+        let (mut uc, ids, writes) = make_usecase_with_writes(&["a", "b"]);
+        uc.set_marked(ids[0], Some(true)).unwrap();
+        assert!(uc.sync_targets().is_empty());
+        uc.write_to_active(b"x").unwrap();
+        assert_eq!(written_to(&writes), [ids[1]]);
+    }
+
+    #[test]
+    fn sync_without_marks_writes_to_every_shown_terminal() {
+        let (mut uc, ids, writes) = make_usecase_with_writes(&["a", "b", "c"]);
+        assert!(uc.set_sync_input(None));
+        uc.set_filter(text_filter("c"));
+        assert_eq!(uc.sync_targets(), [ids[2]]);
+        uc.set_filter(TerminalFilter::default());
+
+        uc.write_to_active(b"x").unwrap();
+        assert_eq!(written_to(&writes), ids);
+        assert!(uc.screen_frame().sync);
+    }
+
+    #[test]
+    fn sync_with_marks_writes_to_marked_and_active_terminals() {
+        let (mut uc, ids, writes) = make_usecase_with_writes(&["a", "b", "c", "d"]);
+        uc.set_sync_input(Some(true));
+        assert!(uc.set_marked(ids[0], None).unwrap());
+        uc.set_marked(ids[2], Some(true)).unwrap();
+        assert_eq!(uc.sync_targets(), [ids[0], ids[2], ids[3]]);
+
+        uc.write_to_active(b"x").unwrap();
+        assert_eq!(written_to(&writes), [ids[0], ids[2], ids[3]]);
+
+        // Input typed into an unmarked terminal stays there
+        uc.select_by_id(ids[1]).unwrap();
+        uc.write_input(ids[3], b"y").unwrap();
+        assert_eq!(written_to(&writes), [ids[3]]);
+        uc.toggle_active_mark().unwrap();
+        assert!(uc.get_terminal_by_id(ids[1]).unwrap().is_marked());
+        assert!(matches!(uc.set_marked(TerminalId::new(99), None), Err(AppError::TerminalNotFound(_))));
+    }
+
+    #[test]
+    fn sync_skips_exited_terminals() {
+        let (mut uc, ids, writes) = make_usecase_with_writes(&["a", "b", "c"]);
+        uc.set_sync_input(Some(true));
+        uc.terminals[1].mark_exited(0);
+        uc.write_to_active(b"x").unwrap();
+        assert_eq!(written_to(&writes), [ids[0], ids[2]]);
+        let frame = uc.screen_frame();
+        assert!(frame.windows.iter().all(|w| !w.marked));
+    }
 }